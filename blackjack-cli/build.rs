@@ -0,0 +1,29 @@
+use std::process::Command;
+
+/// Embeds the git version of the source, so every recorded run says which code produced it.
+fn main() {
+    let version = Command::new("git")
+        .args(["describe", "--always", "--dirty"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map_or_else(
+            || "unknown".to_string(),
+            |version| version.trim().to_string(),
+        );
+    println!("cargo:rustc-env=BLACKJACK_GIT_VERSION={version}");
+    println!("cargo:rerun-if-changed=../.git/HEAD");
+    println!("cargo:rerun-if-changed=../.git/index");
+    #[cfg(feature = "grpc")]
+    compile_player_service();
+}
+
+/// Generates the remote player's gRPC service, with a vendored protoc so none needs installing.
+#[cfg(feature = "grpc")]
+fn compile_player_service() {
+    let protoc =
+        protoc_bin_vendored::protoc_bin_path().expect("protoc is vendored for this platform");
+    std::env::set_var("PROTOC", protoc);
+    tonic_prost_build::compile_protos("proto/player.proto").expect("the player service compiles");
+}
@@ -0,0 +1,115 @@
+use std::fmt::Write;
+
+use blackjack_core::chart::{action_symbol, worth_symbol, StrategyChart, UPCARDS};
+use blackjack_core::game::HandAction;
+
+const HIGHLIGHT: &str = "\x1b[1;30;43m";
+const RESET: &str = "\x1b[0m";
+
+/// Returns the colors a cell with the action is drawn in, as on a printed chart.
+const fn action_color(action: HandAction) -> &'static str {
+    match action {
+        HandAction::Hit => "\x1b[30;101m",
+        HandAction::Stand => "\x1b[30;103m",
+        HandAction::Double => "\x1b[30;102m",
+        HandAction::Split => "\x1b[30;106m",
+        HandAction::Surrender => "\x1b[30;47m",
+    }
+}
+
+/// Renders the chart as a grid, with each cell colored by its action,
+/// followed by a legend of the actions.
+pub fn render_chart(chart: &StrategyChart, color: bool) -> String {
+    let mut output = String::new();
+    write!(output, "   ").unwrap();
+    for upcard in UPCARDS {
+        write!(output, " {} ", worth_symbol(upcard)).unwrap();
+    }
+    writeln!(output).unwrap();
+    for row in &chart.rows {
+        write!(output, "{:<3}", format!("{}:", row.hand)).unwrap();
+        for action in row.actions {
+            let symbol = action_symbol(action);
+            if color {
+                write!(output, "{} {symbol} {RESET}", action_color(action)).unwrap();
+            } else {
+                write!(output, " {symbol} ").unwrap();
+            }
+        }
+        writeln!(output).unwrap();
+    }
+    writeln!(output).unwrap();
+    let legend = [
+        HandAction::Hit,
+        HandAction::Stand,
+        HandAction::Double,
+        HandAction::Split,
+        HandAction::Surrender,
+    ]
+    .map(|action| {
+        let symbol = action_symbol(action);
+        if color {
+            format!("{} {symbol} {RESET} {action:?}", action_color(action))
+        } else {
+            format!("{symbol} {action:?}")
+        }
+    });
+    writeln!(output, "{}", legend.join("  ")).unwrap();
+    output
+}
+
+/// Renders the second chart as a grid, highlighting the cells where it differs from the first,
+/// followed by a list of every difference.
+pub fn render_diff(
+    left: &StrategyChart,
+    left_name: &str,
+    right: &StrategyChart,
+    right_name: &str,
+    color: bool,
+) -> String {
+    let differences = left.differences(right);
+    let mut output = String::new();
+    write!(output, "   ").unwrap();
+    for upcard in UPCARDS {
+        write!(output, " {}", worth_symbol(upcard)).unwrap();
+    }
+    writeln!(output).unwrap();
+    for row in &right.rows {
+        write!(output, "{:<3}", format!("{}:", row.hand)).unwrap();
+        for (upcard, action) in UPCARDS.into_iter().zip(row.actions) {
+            let differs = differences
+                .iter()
+                .any(|&(hand, u, _, _)| hand == row.hand && u == upcard);
+            let symbol = action_symbol(action);
+            match (differs, color) {
+                (true, true) => write!(output, " {HIGHLIGHT}{symbol}{RESET}"),
+                (true, false) => write!(output, "*{symbol}"),
+                (false, _) => write!(output, " {symbol}"),
+            }
+            .unwrap();
+        }
+        writeln!(output).unwrap();
+    }
+    writeln!(output).unwrap();
+    if differences.is_empty() {
+        writeln!(output, "{left_name} and {right_name} agree on every hand.").unwrap();
+    } else {
+        writeln!(
+            output,
+            "{} differences from {left_name} to {right_name}:",
+            differences.len()
+        )
+        .unwrap();
+        for (hand, upcard, from, to) in differences {
+            writeln!(
+                output,
+                "  {hand} vs {}: {} -> {}",
+                worth_symbol(upcard),
+                action_symbol(from),
+                action_symbol(to)
+            )
+            .unwrap();
+        }
+    }
+    output
+}
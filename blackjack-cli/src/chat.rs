@@ -0,0 +1,195 @@
+//! A table shared by everyone in a chat channel, played with commands such as `!bet 100` or `!hit`.
+//! The table knows nothing of the chat service: it takes the text of each message and returns
+//! the lines to send back, with the cards drawn as symbols, e.g. "[Q♠]".
+
+use std::mem;
+
+use blackjack_core::betting::BetPresets;
+use blackjack_core::ev;
+use blackjack_core::game::Table;
+use blackjack_core::i18n::SuitStyle;
+use blackjack_core::narration::Narrator;
+use blackjack_core::state::GameState;
+use blackjack_core::table_spec::TableSpec;
+use blackjack_protocol::answer::action_name;
+use blackjack_protocol::{parse_answer, AnswerError};
+
+/// A table played by a chat channel.
+#[derive(Debug)]
+pub struct ChatTable {
+    spec: TableSpec,
+    chips: u32,
+    table: Table,
+    state: GameState,
+    narrator: Narrator,
+    presets: BetPresets,
+}
+
+impl ChatTable {
+    pub fn new(spec: TableSpec, chips: u32, mut narrator: Narrator, presets: BetPresets) -> Self {
+        narrator.catalog.suit_style = SuitStyle::Symbols;
+        let table = Self::open(&spec, chips);
+        Self {
+            spec,
+            chips,
+            state: table.betting(),
+            table,
+            narrator,
+            presets,
+        }
+    }
+
+    fn open(spec: &TableSpec, chips: u32) -> Table {
+        let mut table = spec.table(chips);
+        table.record_events = true;
+        table
+    }
+
+    /// Plays a chat message, returning the lines to send back.
+    /// Messages that aren't commands are ignored.
+    pub fn command(&mut self, message: &str) -> Vec<String> {
+        let Some(command) = message.trim().strip_prefix('!') else {
+            return Vec::new();
+        };
+        let mut words = command.split_whitespace().map(str::to_ascii_lowercase);
+        let name = words.next().unwrap_or_default();
+        let word = words.next().unwrap_or_default();
+        // Each command is put to the table as the answer it stands for, e.g. "!bet 100" as "100"
+        let answer = match (&self.state, name.as_str()) {
+            (_, "table") => return vec![self.prompt()],
+            (_, "help") => {
+                return vec![
+                "Commands: !bet <chips|same|min|max>, !hit, !stand, !double, !split, !surrender, \
+                     !insure [chips], !yes, !no, !table"
+                    .to_string(),
+            ]
+            }
+            (GameState::Betting { .. }, "bet") => word.as_str(),
+            (GameState::OfferEarlySurrender { .. }, "yes" | "surrender" | "no")
+            | (GameState::OfferInsurance { .. }, "no")
+            | (GameState::PlayPlayerTurn { .. }, _) => name.as_str(),
+            (GameState::OfferInsurance { .. }, "insure") if word.is_empty() => "max",
+            (GameState::OfferInsurance { .. }, "insure") => word.as_str(),
+            _ => "",
+        };
+        let mut lines = Vec::new();
+        let input = match parse_answer(&self.table, &self.state, answer, &self.presets) {
+            Ok(input) => input,
+            Err(AnswerError::NotAllowed(error)) => {
+                return vec![
+                    self.narrator.catalog.error(&error).to_string(),
+                    self.prompt(),
+                ];
+            }
+            Err(AnswerError::Unrecognized(_)) => {
+                return vec![format!("Not now. {}", self.prompt())]
+            }
+        };
+        let state = mem::take(&mut self.state);
+        match self.table.progress(state, Some(input)) {
+            Ok(state) => self.state = state,
+            Err((state, error)) => {
+                self.state = state;
+                lines.push(self.narrator.catalog.error(&error).to_string());
+            }
+        }
+        self.advance(&mut lines);
+        lines
+    }
+
+    /// Deals until the table needs another command, narrating what happens along the way.
+    fn advance(&mut self, lines: &mut Vec<String>) {
+        loop {
+            lines.extend(
+                self.table
+                    .drain_events()
+                    .filter_map(|event| self.narrator.narrate(&event)),
+            );
+            match self.state {
+                GameState::GameOver => {
+                    lines.push(format!(
+                        "The table is out of chips, so a new bankroll of {} is brought out.",
+                        self.chips
+                    ));
+                    self.table = Self::open(&self.spec, self.chips);
+                    self.state = self.table.betting();
+                }
+                GameState::Betting { .. }
+                | GameState::OfferEarlySurrender { .. }
+                | GameState::OfferInsurance { .. }
+                | GameState::PlayPlayerTurn { .. } => break,
+                _ => {
+                    let state = mem::take(&mut self.state);
+                    self.state = match self.table.progress(state, None) {
+                        Ok(state) | Err((state, _)) => state,
+                    };
+                }
+            }
+        }
+        lines.push(self.prompt());
+    }
+
+    /// Says which commands the table is waiting for.
+    fn prompt(&self) -> String {
+        match &self.state {
+            GameState::OfferEarlySurrender { .. } => "Surrender early? !yes or !no".to_string(),
+            GameState::OfferInsurance { player_hand, .. } => {
+                let unseen = self.table.unseen(&self.state);
+                format!(
+                    "Insurance? !insure up to {} chips, or !no (dealer blackjack {:.1}%, EV {:+.3})",
+                    self.table.max_insurance(player_hand),
+                    ev::dealer_blackjack_chance(11, &unseen) * 100.0,
+                    ev::insurance_ev(&unseen)
+                )
+            }
+            GameState::PlayPlayerTurn { player_turn, .. } => {
+                let actions: Vec<_> = self
+                    .table
+                    .allowed_actions(player_turn)
+                    .into_iter()
+                    .map(|action| format!("!{}", action_name(action)))
+                    .collect();
+                format!("Your move: {}", actions.join(", "))
+            }
+            _ => format!("{} chips. Place a bet with !bet <chips>", self.table.chips),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blackjack_core::card::Rank;
+    use blackjack_core::i18n::Locale;
+    use blackjack_core::narration::Personality;
+
+    #[test]
+    fn test_commands() {
+        let narrator = Narrator::new(Personality::Professional, Locale::English);
+        let presets = "big=500".parse().unwrap();
+        let mut table = ChatTable::new("6D".parse().unwrap(), 1000, narrator, presets);
+        assert!(table.command("hello").is_empty());
+        assert!(table.command("!hit")[0].starts_with("Not now."));
+        table
+            .table
+            .shoe
+            .force([Rank::Ten, Rank::Five, Rank::Seven, Rank::Nine]);
+        let lines = table.command("!bet big");
+        assert_eq!(table.table.last_bet(), Some(500));
+        assert_eq!(
+            lines.last().map(String::as_str),
+            Some("Your move: !hit, !stand, !double, !surrender")
+        );
+        assert!(table
+            .command("!split")
+            .last()
+            .unwrap()
+            .starts_with("Your move"));
+        assert!(matches!(table.state, GameState::PlayPlayerTurn { .. }));
+        let lines = table.command("!s");
+        assert!(lines
+            .last()
+            .unwrap()
+            .ends_with("Place a bet with !bet <chips>"));
+    }
+}
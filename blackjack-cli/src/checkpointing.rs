@@ -0,0 +1,172 @@
+//! Plays a simulation at one table round by round, writing its bankroll trajectory and round log
+//! as it goes and saving checkpoints to resume it from if it is interrupted.
+//! A resumed simulation cuts its output files back to how far they had been written at the checkpoint,
+//! so the rounds played after it aren't written twice.
+
+use std::error::Error;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use blackjack_core::checkpoint::Checkpoint;
+use blackjack_core::session::SessionNotes;
+use blackjack_core::simulation::{Progress, Simulator};
+use blackjack_core::strategy::Strategy;
+
+use crate::round_log::RoundLog;
+
+/// Where a simulation writes as it plays, and how it is saved.
+pub struct Outputs {
+    /// The file the bankroll is written to after every round, one per line
+    pub trajectory: Option<PathBuf>,
+    /// The file every round's summary is written to
+    pub round_log: Option<PathBuf>,
+    /// The notes at the head of a new round log, if any
+    pub notes: Option<SessionNotes>,
+    /// The file the simulation is saved to periodically and at the end
+    pub checkpoint: Option<PathBuf>,
+    /// The number of rounds between checkpoints
+    pub checkpoint_every: NonZeroUsize,
+    /// Whether to redraw a progress bar on standard error as the rounds are played
+    pub show_progress: bool,
+}
+
+/// The output files being written.
+struct Writers {
+    trajectory: Option<BufWriter<File>>,
+    round_log: Option<RoundLog>,
+}
+
+/// Plays until the simulation has observed the given number of rounds in all, or the player goes broke,
+/// resuming it first from the given checkpoint, if any. Checkpoints are saved with the seed the run was started with.
+/// An output file the checkpoint recorded is carried on from where it stood; any other is started afresh.
+pub fn run<S: Strategy>(
+    simulator: &mut Simulator<S>,
+    rounds: usize,
+    seed: u64,
+    resume: Option<Checkpoint>,
+    outputs: Outputs,
+    started: Instant,
+) -> Result<(), Box<dyn Error>> {
+    let mut lengths = None;
+    if let Some(checkpoint) = resume {
+        lengths = Some(checkpoint.outputs.clone());
+        simulator.resume(checkpoint)?;
+    }
+    let length = |name: &str| {
+        lengths
+            .as_ref()
+            .and_then(|lengths| lengths.get(name).copied())
+    };
+    let mut writers = Writers {
+        trajectory: match &outputs.trajectory {
+            Some(path) => Some(match length("trajectory") {
+                Some(length) => BufWriter::new(reopen(path, length)?),
+                None => {
+                    let mut file = BufWriter::new(File::create(path)?);
+                    writeln!(file, "{}", simulator.table.chips)?;
+                    file
+                }
+            }),
+            None => None,
+        },
+        round_log: match &outputs.round_log {
+            Some(path) => Some(match length("round_log") {
+                Some(length) => RoundLog::reopen(path, length)?,
+                None => {
+                    let mut log = RoundLog::create(path)?;
+                    if let Some(notes) = &outputs.notes {
+                        log.write_notes(notes)?;
+                    }
+                    log
+                }
+            }),
+            None => None,
+        },
+    };
+    let first_round = simulator.report.rounds_observed;
+    let progress_interval = (rounds / 200).max(1);
+    while simulator.report.rounds_observed < rounds {
+        let playing = simulator.next_round();
+        let round = simulator.report.rounds_observed;
+        if outputs.show_progress
+            && (round.is_multiple_of(progress_interval) || round == rounds || !playing)
+        {
+            let progress =
+                simulator.progress(round - first_round, rounds - first_round, started.elapsed());
+            eprint!("\r{}", progress_bar(&progress));
+        }
+        if let Some(file) = &mut writers.trajectory {
+            writeln!(file, "{}", simulator.table.chips)?;
+        }
+        if let (Some(log), Some(summary)) = (&mut writers.round_log, &simulator.last_round) {
+            log.write(summary)?;
+        }
+        if !playing {
+            break;
+        }
+        if let Some(path) = &outputs.checkpoint {
+            if round.is_multiple_of(outputs.checkpoint_every.get()) {
+                save_checkpoint(path, simulator, seed, &mut writers)?;
+            }
+        }
+    }
+    if let Some(path) = &outputs.checkpoint {
+        save_checkpoint(path, simulator, seed, &mut writers)?;
+    }
+    if let Some(mut file) = writers.trajectory {
+        file.flush()?;
+    }
+    if let Some(log) = writers.round_log {
+        log.finish()?;
+    }
+    if outputs.show_progress {
+        eprintln!();
+    }
+    Ok(())
+}
+
+/// Opens an output file to carry on writing it from the given length, cutting off whatever follows.
+fn reopen(path: &Path, length: u64) -> io::Result<File> {
+    let file = OpenOptions::new().append(true).open(path)?;
+    file.set_len(length)?;
+    Ok(file)
+}
+
+/// Saves the simulation with how far each output file has been written,
+/// writing the checkpoint next to its path first, so an interruption never leaves it half-written.
+fn save_checkpoint<S: Strategy>(
+    path: &Path,
+    simulator: &Simulator<S>,
+    seed: u64,
+    writers: &mut Writers,
+) -> io::Result<()> {
+    let mut checkpoint = simulator.checkpoint();
+    checkpoint.seed = Some(seed);
+    if let Some(file) = &mut writers.trajectory {
+        file.flush()?;
+        let length = file.get_ref().metadata()?.len();
+        checkpoint.outputs.insert("trajectory".to_string(), length);
+    }
+    if let Some(log) = &mut writers.round_log {
+        checkpoint
+            .outputs
+            .insert("round_log".to_string(), log.checkpoint()?);
+    }
+    let temporary = path.with_extension("tmp");
+    fs::write(&temporary, checkpoint.to_string())?;
+    fs::rename(temporary, path)
+}
+
+/// Renders a progress bar followed by the progress, to redraw in place on one line.
+fn progress_bar(progress: &Progress) -> String {
+    const WIDTH: usize = 30;
+    let filled = ((progress.fraction() * WIDTH as f64) as usize).min(WIDTH);
+    format!(
+        "[{}{}] {progress}",
+        "#".repeat(filled),
+        " ".repeat(WIDTH - filled)
+    )
+}
@@ -0,0 +1,229 @@
+//! A table configuration file, as written by `blackjack init`.
+//! The file holds one `key = value` per line: the table in shorthand, its bet limits,
+//! and the stop-win and stop-loss ending each session played there.
+
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+
+use blackjack_core::rules::{BlackjackPayout, DealerDrawing};
+use blackjack_core::table_spec::TableSpec;
+
+/// A table and the limits of the sessions played at it.
+#[derive(Debug, Clone)]
+pub struct TableConfig {
+    /// The table, with its minimum and maximum bets
+    pub table: TableSpec,
+    pub stop_win: Option<u32>,
+    pub stop_loss: Option<u32>,
+}
+
+impl fmt::Display for TableConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "table = {}", self.table)?;
+        let limits = [
+            ("min-bet", self.table.rules.min_bet),
+            ("max-bet", self.table.rules.max_bet),
+            ("stop-win", self.stop_win),
+            ("stop-loss", self.stop_loss),
+        ];
+        for (name, value) in limits {
+            if let Some(value) = value {
+                writeln!(f, "{name} = {value}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for TableConfig {
+    type Err = String;
+
+    /// Parses a configuration file. Blank lines and lines starting with `#` are ignored,
+    /// and any limit left out means there is none.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut config = Self {
+            table: "6D".parse()?,
+            stop_win: None,
+            stop_loss: None,
+        };
+        let mut min_bet = None;
+        let mut max_bet = None;
+        for line in s.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("expected key = value: {line}"))?;
+            let (key, value) = (key.trim(), value.trim());
+            let limit = || {
+                value
+                    .parse::<u32>()
+                    .map_err(|_| format!("invalid {key}: {value}"))
+            };
+            match key {
+                "table" => config.table = value.parse()?,
+                "min-bet" => min_bet = Some(limit()?),
+                "max-bet" => max_bet = Some(limit()?),
+                "stop-win" => config.stop_win = Some(limit()?),
+                "stop-loss" => config.stop_loss = Some(limit()?),
+                _ => return Err(format!("unknown setting: {key}")),
+            }
+        }
+        config.table.rules.min_bet = min_bet;
+        config.table.rules.max_bet = max_bet;
+        Ok(config)
+    }
+}
+
+/// Asks the player about each part of a table in turn.
+pub fn wizard(input: &mut impl BufRead, output: &mut impl Write) -> io::Result<TableConfig> {
+    let mut table: TableSpec = "6D".parse().map_err(io::Error::other)?;
+    let rules = &mut table.rules;
+    table.decks = ask(
+        input,
+        output,
+        "How many decks are in the shoe?",
+        "6",
+        |answer| answer.parse().ok().filter(|&decks| decks > 0),
+    )?;
+    rules.blackjack_payout = ask(
+        input,
+        output,
+        "Does a blackjack pay 3:2 or 6:5?",
+        "3:2",
+        |answer| match answer {
+            "3:2" => Some(BlackjackPayout::ThreeToTwo),
+            "6:5" => Some(BlackjackPayout::SixToFive),
+            _ => None,
+        },
+    )?;
+    let hits = ask(input, output, "Does the dealer hit soft 17?", "no", yes_no)?;
+    rules.dealer_drawing = if hits {
+        DealerDrawing::H17
+    } else {
+        DealerDrawing::S17
+    };
+    rules.double_after_split = ask(
+        input,
+        output,
+        "May the player double after splitting?",
+        "yes",
+        yes_no,
+    )?;
+    let surrender = ask(
+        input,
+        output,
+        "Is surrender late, early, or not offered (none)?",
+        "late",
+        |answer| ["late", "early", "none"].into_iter().find(|&s| s == answer),
+    )?;
+    rules.late_surrender = surrender == "late";
+    rules.early_surrender = surrender == "early";
+    rules.insurance = ask(input, output, "Is insurance offered?", "no", yes_no)?;
+    rules.min_bet = ask(input, output, "What is the minimum bet?", "100", limit)?;
+    rules.max_bet = ask(input, output, "What is the maximum bet?", "none", limit)?;
+    let stop_win = ask(
+        input,
+        output,
+        "Stop a session once up by how many chips?",
+        "none",
+        limit,
+    )?;
+    let stop_loss = ask(
+        input,
+        output,
+        "Stop a session once down by how many chips?",
+        "none",
+        limit,
+    )?;
+    Ok(TableConfig {
+        table,
+        stop_win,
+        stop_loss,
+    })
+}
+
+/// Asks a question until the answer parses. An empty answer, or the end of the input,
+/// takes the default answer, which is shown in brackets.
+pub fn ask<T>(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    question: &str,
+    default: &str,
+    parse: impl Fn(&str) -> Option<T>,
+) -> io::Result<T> {
+    loop {
+        write!(output, "{question} [{default}] ")?;
+        output.flush()?;
+        let mut answer = String::new();
+        let answer = if input.read_line(&mut answer)? == 0 {
+            writeln!(output)?;
+            return parse(default).ok_or_else(|| io::ErrorKind::UnexpectedEof.into());
+        } else if answer.trim().is_empty() {
+            default.to_string()
+        } else {
+            answer.trim().to_ascii_lowercase()
+        };
+        match parse(&answer) {
+            Some(value) => return Ok(value),
+            None => writeln!(output, "Sorry, I didn't understand \"{answer}\".")?,
+        }
+    }
+}
+
+/// Parses a yes or no answer.
+pub fn yes_no(answer: &str) -> Option<bool> {
+    match answer {
+        "y" | "yes" => Some(true),
+        "n" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+/// Parses a limit in chips, where "none" means there is no limit.
+fn limit(answer: &str) -> Option<Option<u32>> {
+    if answer == "none" {
+        Some(None)
+    } else {
+        answer.parse().ok().map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config() {
+        let config: TableConfig =
+            "# A casino downtown\ntable = 2D,H17\nmin-bet = 25\nstop-loss = 500\n"
+                .parse()
+                .unwrap();
+        assert_eq!(config.table.decks, 2);
+        assert_eq!(config.table.rules.min_bet, Some(25));
+        assert_eq!(config.table.rules.max_bet, None);
+        assert_eq!((config.stop_win, config.stop_loss), (None, Some(500)));
+        let written: TableConfig = config.to_string().parse().unwrap();
+        assert_eq!(written.to_string(), config.to_string());
+        assert!("table = 6D\nmin-bet = lots".parse::<TableConfig>().is_err());
+        assert!("tables = 6D".parse::<TableConfig>().is_err());
+
+        // Unclear answers are asked again, and empty answers take the default
+        let mut input = io::Cursor::new("2\n6:5\nmaybe\nyes\n\nnone\n\n25\n1000\n\n");
+        let mut output = Vec::new();
+        let config = wizard(&mut input, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Sorry, I didn't understand \"maybe\"."));
+        let rules = &config.table.rules;
+        assert_eq!(config.table.decks, 2);
+        assert_eq!(rules.blackjack_payout, BlackjackPayout::SixToFive);
+        assert_eq!(rules.dealer_drawing, DealerDrawing::H17);
+        assert!(rules.double_after_split);
+        assert!(!rules.late_surrender && !rules.early_surrender);
+        assert!(!rules.insurance);
+        assert_eq!((rules.min_bet, rules.max_bet), (Some(25), Some(1000)));
+        assert_eq!((config.stop_win, config.stop_loss), (None, None));
+    }
+}
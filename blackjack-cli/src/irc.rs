@@ -0,0 +1,44 @@
+//! A dealer bot for IRC, which joins a channel and deals a shared table to everyone in it.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::TcpStream;
+
+use crate::chat::ChatTable;
+
+/// Connects to an IRC server and deals at the table in a channel until the connection closes.
+pub fn run(server: &str, nick: &str, channel: &str, mut table: ChatTable) -> io::Result<()> {
+    let stream = TcpStream::connect(server)?;
+    let mut output = stream.try_clone()?;
+    write!(
+        output,
+        "NICK {nick}\r\nUSER {nick} 0 * :Blackjack dealer\r\n"
+    )?;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        // Messages may start with the prefix of their sender, which the table doesn't need
+        let line = match line.strip_prefix(':') {
+            Some(line) => line.split_once(' ').map_or("", |(_, line)| line),
+            None => line.as_str(),
+        };
+        let (command, params) = line.split_once(' ').unwrap_or((line, ""));
+        match command {
+            "PING" => write!(output, "PONG {params}\r\n")?,
+            // The server has welcomed the bot
+            "001" => write!(output, "JOIN {channel}\r\n")?,
+            "PRIVMSG" => {
+                let Some((target, message)) = params.split_once(" :") else {
+                    continue;
+                };
+                if !target.eq_ignore_ascii_case(channel) {
+                    continue;
+                }
+                for reply in table.command(message) {
+                    write!(output, "PRIVMSG {channel} :{reply}\r\n")?;
+                }
+            }
+            _ => {}
+        }
+        output.flush()?;
+    }
+    Ok(())
+}
@@ -0,0 +1,198 @@
+//! A leaderboard of the players on the telnet server, built from every session they have played.
+//! Sessions are kept in a text file, one `key = value` per line, with a blank line between sessions.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// A player's session at a table, from sitting down to giving up their seat.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Session {
+    pub player: String,
+    /// When the session ended, in seconds since the Unix epoch
+    pub date: u64,
+    /// The player's net result in chips
+    pub net: i64,
+    /// The number of hands played, including split hands
+    pub hands: usize,
+}
+
+impl fmt::Display for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "player = {}", self.player)?;
+        writeln!(f, "date = {}", self.date)?;
+        writeln!(f, "net = {}", self.net)?;
+        writeln!(f, "hands = {}", self.hands)
+    }
+}
+
+impl FromStr for Session {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut values = HashMap::new();
+        for line in s.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("expected key = value: {line}"))?;
+            values.insert(key.trim(), value.trim());
+        }
+        let get = |key: &str| {
+            values
+                .get(key)
+                .copied()
+                .ok_or_else(|| format!("missing {key}"))
+        };
+        fn parse<T: FromStr>(key: &str, value: &str) -> Result<T, String> {
+            value.parse().map_err(|_| format!("invalid {key}: {value}"))
+        }
+        Ok(Self {
+            player: get("player")?.to_string(),
+            date: parse("date", get("date")?)?,
+            net: parse("net", get("net")?)?,
+            hands: parse("hands", get("hands")?)?,
+        })
+    }
+}
+
+/// A player's place on the leaderboard.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Standing {
+    pub player: String,
+    pub sessions: usize,
+    pub hands: usize,
+    /// The player's net result over every session
+    pub net: i64,
+    /// The player's best net result in a session
+    pub best: i64,
+}
+
+/// The file sessions are stored in.
+#[derive(Debug, Clone)]
+pub struct Leaderboard {
+    pub path: PathBuf,
+}
+
+impl Leaderboard {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Returns every session, oldest first. A missing file has no sessions.
+    pub fn load(&self) -> Result<Vec<Session>, String> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(format!("{}: {e}", self.path.display())),
+        };
+        contents
+            .split("\n\n")
+            .filter(|session| !session.trim().is_empty())
+            .map(str::parse)
+            .collect()
+    }
+
+    /// Adds a session to the file.
+    pub fn save(&self, session: &Session) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{session}")
+    }
+
+    /// Returns each player's standing, from the highest net result to the lowest.
+    pub fn standings(&self) -> Result<Vec<Standing>, String> {
+        let mut standings: Vec<Standing> = Vec::new();
+        for session in self.load()? {
+            match standings
+                .iter_mut()
+                .find(|standing| standing.player == session.player)
+            {
+                Some(standing) => {
+                    standing.sessions += 1;
+                    standing.hands += session.hands;
+                    standing.net += session.net;
+                    standing.best = standing.best.max(session.net);
+                }
+                None => standings.push(Standing {
+                    player: session.player,
+                    sessions: 1,
+                    hands: session.hands,
+                    net: session.net,
+                    best: session.net,
+                }),
+            }
+        }
+        standings.sort_by(|a, b| b.net.cmp(&a.net).then(b.best.cmp(&a.best)));
+        Ok(standings)
+    }
+}
+
+/// Renders the standings as a table, one player per line.
+pub fn render(standings: &[Standing]) -> String {
+    let mut table = format!(
+        "{:>4}  {:<16}  {:>8}  {:>8}  {:>10}  {:>10}\n",
+        "Rank", "Player", "Sessions", "Hands", "Net", "Best"
+    );
+    for (rank, standing) in standings.iter().enumerate() {
+        table += &format!(
+            "{:>4}  {:<16}  {:>8}  {:>8}  {:>+10}  {:>+10}\n",
+            rank + 1,
+            standing.player,
+            standing.sessions,
+            standing.hands,
+            standing.net,
+            standing.best
+        );
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process;
+
+    #[test]
+    fn test_leaderboard() {
+        let path = std::env::temp_dir().join(format!("leaderboard-{}.txt", process::id()));
+        let leaderboard = Leaderboard::new(&path);
+        assert_eq!(leaderboard.standings(), Ok(Vec::new()));
+        let sessions = [("duck", 500, 20), ("goose", -200, 10), ("duck", -100, 5)];
+        for (date, &(player, net, hands)) in (1_700_000_000..).zip(&sessions) {
+            let session = Session {
+                player: player.to_string(),
+                date,
+                net,
+                hands,
+            };
+            assert_eq!(session.to_string().parse(), Ok(session.clone()));
+            leaderboard.save(&session).unwrap();
+        }
+        let loaded = leaderboard.load();
+        let standings = leaderboard.standings();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.map(|sessions| sessions.len()), Ok(3));
+        let standings = standings.unwrap();
+        let duck = Standing {
+            player: "duck".to_string(),
+            sessions: 2,
+            hands: 25,
+            net: 400,
+            best: 500,
+        };
+        assert_eq!(standings[0], duck);
+        assert_eq!(standings[1].player, "goose");
+        assert!(render(&standings).contains("   1  duck"));
+        assert!("player = duck\ndate = soon".parse::<Session>().is_err());
+    }
+}
@@ -0,0 +1,1421 @@
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use clap::{Args, Parser, Subcommand};
+
+use blackjack_core::betting::{
+    check_playable, optimal_ramp, BetPresets, BetRamp, BettingStrategy, Camouflage, CoverRules,
+    FlatBet, Kelly, Martingale, WinProgression,
+};
+use blackjack_core::card::shoe::Shoe;
+use blackjack_core::chart::{worth_symbol, StrategyChart};
+use blackjack_core::checkpoint::Checkpoint;
+use blackjack_core::counting::TrueCount;
+use blackjack_core::currency::{self, Currency, InCurrency};
+use blackjack_core::dealer_error::{DealerErrors, SettlementChecker};
+use blackjack_core::ev::DealerOutcomes;
+use blackjack_core::events::GameEvent;
+use blackjack_core::fairness;
+use blackjack_core::game::{BotSeats, Table};
+use blackjack_core::heat::{Heat, HeatModel};
+use blackjack_core::i18n::{Catalog, Locale, SuitStyle};
+use blackjack_core::narration::{Narrator, Personality};
+use blackjack_core::preferences::Preferences;
+use blackjack_core::promotion::Coupon;
+use blackjack_core::rules::{PayoutRounding, Rules};
+use blackjack_core::session::{SessionLimits, SessionNotes};
+use blackjack_core::simulation::{
+    self, Report, RoundSummary, SessionDistribution, Simulator, WarmUp,
+};
+use blackjack_core::statistics::Statistics;
+use blackjack_core::strategy::{BasicStrategy, CountedInsurance, Strategy};
+use blackjack_core::table_spec::TableSpec;
+
+use crate::chat::ChatTable;
+use crate::checkpointing::Outputs;
+use crate::config::TableConfig;
+use crate::leaderboard::Leaderboard;
+use crate::remote::RemoteStrategy;
+use crate::render::RoundStyle;
+use crate::round_log::RoundLog;
+use crate::runs::{Run, RunStore};
+use crate::serve::Seating;
+
+mod chart;
+mod chat;
+mod checkpointing;
+mod config;
+mod irc;
+mod leaderboard;
+mod remote;
+mod render;
+mod round_log;
+mod runs;
+mod serve;
+
+/// Counts every allocation for the benchmarks.
+#[cfg(feature = "profiling")]
+#[global_allocator]
+static GLOBAL: blackjack_core::profiling::CountingAllocator =
+    blackjack_core::profiling::CountingAllocator;
+
+/// The benchmarks run by the bench command: their names, tables, and the true count the player sits in at, if any.
+const BENCHMARKS: [(&str, &str, Option<f32>); 3] = [
+    ("basic strategy", "6D,H17,DAS,LS,75%", None),
+    ("single deck", "1D,S17,NDAS,NS,50%", None),
+    ("wonging", "6D,H17,DAS,LS,75%", Some(1.0)),
+];
+
+/// The most settlements broken by dealer errors to list after a simulation.
+const VIOLATIONS_SHOWN: usize = 10;
+
+/// Play, simulate, and study Blackjack from the command line.
+#[derive(Debug, Parser)]
+#[command(author, about, version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Print the basic strategy chart for a table, colored by action on a terminal.
+    ///
+    /// Tables are written in shorthand, e.g. "6D,H17,DAS,LS" or "1D,S17,NDAS,NS".
+    Chart {
+        /// the table the chart is generated for.
+        table: TableSpec,
+    },
+    /// Compare the basic strategy charts of two tables, or a chart file against one table.
+    ///
+    /// Tables are written in shorthand, e.g. "6D,H17,DAS,LS" or "1D,S17,NDAS,NS".
+    ChartDiff {
+        /// the table the chart is generated for.
+        table: TableSpec,
+        /// the table to compare against.
+        other: Option<TableSpec>,
+        /// a chart file to compare against the generated chart instead.
+        #[arg(short, long, conflicts_with = "other")]
+        file: Option<PathBuf>,
+    },
+    /// Simulate a player at a table and report the results.
+    Simulate(Box<SimulateArgs>),
+    /// Answer a few questions about a table to write its configuration file,
+    /// then optionally simulate a session there.
+    Init {
+        /// the configuration file to write.
+        #[arg(default_value = "table.conf")]
+        output: PathBuf,
+    },
+    /// Replay a strategy against the cards dealt from real shoes, to see how it would have done.
+    ///
+    /// The transcript lists the cards dealt from each shoe in order, e.g. "A 7 K 10 2 T",
+    /// with a blank line between shoes.
+    Backtest {
+        /// the table the shoes were dealt at, e.g. "6D,H17,DAS,LS".
+        table: TableSpec,
+        /// the transcript of the dealt cards.
+        transcript: PathBuf,
+        /// the player's starting bankroll.
+        #[arg(short, long, default_value_t = 100_000)]
+        chips: u32,
+        #[command(flatten)]
+        betting: BettingArgs,
+        /// show each round as it is dealt: "plain" narrates it, and "box" draws the table,
+        /// falling back to plain when the output isn't a terminal.
+        #[arg(long)]
+        show: Option<RoundStyle>,
+        /// show chips as money: a currency symbol and the worth of a chip, e.g. "$0.25" or "€5".
+        #[arg(long)]
+        currency: Option<Currency>,
+        /// the time in ms each state of a round is shown for in the box-drawn table.
+        #[arg(long, default_value_t = 300, requires = "show")]
+        pace: u64,
+    },
+    /// Host a table for a player in another process, which makes every decision.
+    ///
+    /// The table and the player first greet each other with their protocol version, e.g. "blackjack 2".
+    /// The player is asked for each decision on its standard input, one numbered line per question,
+    /// and answers on its standard output with the same number, e.g. "2 hit".
+    /// With the grpc feature, the player can instead serve `Player` from proto/player.proto.
+    /// Late or illegal decisions are made by the table: it stands and declines any offers.
+    Remote {
+        /// the table, e.g. "6D,H17,DAS,LS".
+        table: TableSpec,
+        /// the player's command, e.g. "-- python3 player.py".
+        #[arg(last = true)]
+        #[cfg_attr(not(feature = "grpc"), arg(required = true))]
+        #[cfg_attr(feature = "grpc", arg(required_unless_present = "grpc"))]
+        command: Vec<String>,
+        /// the URL of a player serving the gRPC service, e.g. "http://localhost:50051".
+        #[cfg(feature = "grpc")]
+        #[arg(long, conflicts_with = "command")]
+        grpc: Option<String>,
+        /// the number of rounds to deal.
+        #[arg(short, long, default_value_t = 1000)]
+        rounds: usize,
+        /// the proportion of the shoe dealt before shuffling, e.g. 0.75.
+        /// Overrides the table's own, written like "75%".
+        #[arg(short, long, value_parser = parse_penetration)]
+        penetration: Option<f32>,
+        /// the player's starting bankroll.
+        #[arg(short, long, default_value_t = 100_000)]
+        chips: u32,
+        /// the time in ms the player has for each decision.
+        #[arg(long, default_value_t = 1000)]
+        deadline: u64,
+    },
+    /// Host tables for players connecting over telnet, each playing at a table of their own.
+    ///
+    /// The dealer narrates each round, and the player answers the prompts one line at a time.
+    Serve {
+        /// the table, e.g. "6D,H17,DAS,LS".
+        table: TableSpec,
+        /// the port to listen on.
+        #[arg(long, default_value_t = 2323)]
+        port: u16,
+        /// the port spectators may connect to, to watch any table.
+        #[arg(long)]
+        watch_port: Option<u16>,
+        /// the time in seconds a disconnected player's seat is kept for them.
+        #[arg(long, default_value_t = 60)]
+        grace: u64,
+        /// the file to keep the players' sessions in, for the leaderboard.
+        #[arg(long)]
+        leaderboard: Option<PathBuf>,
+        /// the address to listen on.
+        #[arg(long, default_value = "127.0.0.1")]
+        address: String,
+        /// each player's starting bankroll.
+        #[arg(short, long, default_value_t = 1000)]
+        chips: u32,
+        /// the personality of the dealer.
+        #[arg(long, default_value_t = Personality::Professional)]
+        personality: Personality,
+        /// bots seated beside each player, playing hands of their own, as a number and optionally their strategy:
+        /// basic (the default), mimic (hits below 17 like the dealer), or never-bust.
+        /// May be given more than once, e.g. "--bots 2 --bots 1:mimic".
+        #[arg(long)]
+        bots: Vec<BotSeats>,
+        /// a preferences file making each player's routine decisions, e.g. "auto-rebet = true".
+        #[arg(long)]
+        preferences: Option<PathBuf>,
+    },
+    /// Show the leaderboard of the players on the server, from the best net result to the worst.
+    Leaderboard {
+        /// the file the server keeps the players' sessions in.
+        path: PathBuf,
+    },
+    /// Check a shoe dealt by the server against the seed revealed after it, and deal it again.
+    Verify {
+        /// the seed revealed when the shoe was shuffled.
+        seed: u64,
+        /// the commitment published before the shoe was dealt.
+        #[arg(long)]
+        commitment: Option<String>,
+        /// the number of decks in the shoe.
+        #[arg(short, long, default_value_t = 6)]
+        decks: u8,
+        /// the number of cards to deal from the shoe.
+        #[arg(long, default_value_t = 20)]
+        cards: usize,
+    },
+    /// Deal a table shared by everyone in an IRC channel, who play it with commands such as "!bet 100" or "!hit".
+    Irc {
+        /// the table, e.g. "6D,H17,DAS,LS".
+        table: TableSpec,
+        /// the IRC server, e.g. "irc.libera.chat:6667".
+        #[arg(long)]
+        server: String,
+        /// the channel to deal in, e.g. "#blackjack".
+        #[arg(long)]
+        channel: String,
+        /// the dealer's nickname.
+        #[arg(long, default_value = "dealer")]
+        nick: String,
+        /// the table's starting bankroll.
+        #[arg(short, long, default_value_t = 1000)]
+        chips: u32,
+        /// the personality of the dealer.
+        #[arg(long, default_value_t = Personality::Professional)]
+        personality: Personality,
+        /// bets the channel may place by name, e.g. "small=25,big=max", as in "!bet big".
+        #[arg(long, default_value_t)]
+        bet_presets: BetPresets,
+    },
+    /// Recompute the statistics of simulations from their round logs, pooling them together.
+    Stats {
+        /// the round logs, as written by simulate --round-log, compressed with gzip or not.
+        #[arg(required = true)]
+        histories: Vec<PathBuf>,
+        /// only count the rounds from this round number on.
+        #[arg(long)]
+        from_round: Option<usize>,
+        /// only count the rounds up to this round number.
+        #[arg(long)]
+        to_round: Option<usize>,
+        /// only count the round logs of sessions with this tag, ignoring case. May be given more than once.
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+        /// only count the round logs of sessions started on or after this day, e.g. "2024-03-01", in UTC.
+        #[arg(long, value_parser = parse_date)]
+        since: Option<u64>,
+        /// only count the round logs of sessions started on or before this day, e.g. "2024-03-31", in UTC.
+        #[arg(long, value_parser = parse_date)]
+        until: Option<u64>,
+        /// only count the round logs of sessions played under the rules of this table, e.g. "6D,H17,DAS".
+        /// The number of decks, limits and seats don't matter, only the rules which change how the hands play out.
+        #[arg(long)]
+        rules: Option<TableSpec>,
+        /// show chips as money: a currency symbol and the worth of a chip, e.g. "$0.25" or "€5".
+        #[arg(long)]
+        currency: Option<Currency>,
+    },
+    /// List the recorded simulation runs, or compare some of them side by side.
+    Runs {
+        /// the runs to compare, by ID.
+        ids: Vec<usize>,
+        #[command(flatten)]
+        store: StoreArgs,
+    },
+    /// Measure how often each true count comes up and the edge at each,
+    /// then compute the bet ramp that wins the most for a bankroll and risk of ruin.
+    BetSpread {
+        /// the table to simulate, e.g. "6D,H17,DAS,LS".
+        #[arg(default_value = "6D")]
+        table: TableSpec,
+        /// the number of rounds to simulate.
+        #[arg(short, long, default_value_t = 500_000)]
+        rounds: usize,
+        /// the proportion of the shoe dealt before shuffling, e.g. 0.75.
+        /// Overrides the table's own, written like "75%".
+        #[arg(short, long, value_parser = parse_penetration)]
+        penetration: Option<f32>,
+        /// the bankroll the ramp is sized for.
+        #[arg(short, long, default_value_t = 100_000)]
+        bankroll: u32,
+        /// the acceptable risk of losing the whole bankroll.
+        #[arg(long, default_value_t = 0.05)]
+        ror: f64,
+        /// bets are rounded down to a multiple of this unit.
+        #[arg(short, long, default_value_t = 25)]
+        unit: u32,
+        /// write the ramp to this file, for a count-based betting strategy to use.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Measure what rounding payouts to whole chips costs the player, e.g. 3:2 on odd bets rounded down,
+    /// by dealing the same shoes under each rounding policy with a flat bet.
+    Rounding {
+        /// the table to simulate, e.g. "6D,H17,DAS,LS".
+        #[arg(default_value = "6D")]
+        table: TableSpec,
+        /// the number of rounds to simulate under each policy.
+        #[arg(short, long, default_value_t = 1_000_000)]
+        rounds: usize,
+        /// the proportion of the shoe dealt before shuffling, e.g. 0.75.
+        /// Overrides the table's own, written like "75%".
+        #[arg(short, long, value_parser = parse_penetration)]
+        penetration: Option<f32>,
+        /// the flat bet on every round. Odd bets lose the most to rounding. Lowers the table minimum if need be.
+        #[arg(short, long, default_value_t = 5)]
+        bet: u32,
+        /// shuffle with this seed. Every policy is dealt the same shoes either way.
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+    },
+    /// Print the chance of each way the dealer's hand finishes under each upcard, dealt from a full shoe.
+    ///
+    /// The dealer has peeked under a ten or an ace, so those upcards leave out the dealer's blackjack.
+    Dealer {
+        /// the table, e.g. "6D,H17".
+        #[arg(default_value = "6D")]
+        table: TableSpec,
+    },
+    /// Measure how fast a few typical simulations run and, when built with the profiling feature,
+    /// how many allocations each round makes.
+    Bench {
+        /// the number of rounds to simulate in each benchmark.
+        #[arg(short, long, default_value_t = 1_000_000)]
+        rounds: usize,
+        /// shuffle with this seed, to deal the same cards every time.
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+    },
+}
+
+#[derive(Debug, Args)]
+struct SimulateArgs {
+    /// the table to simulate, e.g. "6D,H17,DAS,LS". Defaults to six decks.
+    table: Option<TableSpec>,
+    /// a configuration file, as written by init, for the table and session limits.
+    /// The stop-win and stop-loss options override those in the file.
+    #[arg(long, conflicts_with = "table")]
+    config: Option<PathBuf>,
+    /// the number of rounds to simulate.
+    #[arg(short, long, default_value_t = 100_000)]
+    rounds: usize,
+    /// the proportion of the shoe dealt before shuffling, e.g. 0.75.
+    /// Overrides the table's own, written like "75%".
+    #[arg(short, long, value_parser = parse_penetration)]
+    penetration: Option<f32>,
+    /// the player's starting bankroll.
+    #[arg(short, long, default_value_t = 100_000)]
+    chips: u32,
+    /// play this many rounds, or "shoe" for the first shoe, before keeping any results,
+    /// so the fresh shoe and bankroll don't skew them.
+    #[arg(long, conflicts_with = "sessions")]
+    warm_up: Option<WarmUp>,
+    #[command(flatten)]
+    betting: BettingArgs,
+    #[command(flatten)]
+    cover: CoverArgs,
+    /// take full insurance at this Hi-Lo true count or higher, on tables offering it ("INS").
+    #[arg(long, allow_negative_numbers = true)]
+    insure_at: Option<f32>,
+    /// always take even money on a blackjack, on tables offering insurance ("INS").
+    #[arg(long)]
+    even_money: bool,
+    /// take full insurance whenever the exact chance of dealer blackjack makes it a winning bet,
+    /// on tables offering it ("INS").
+    #[arg(long)]
+    insure_favorable: bool,
+    /// draw heat from the pit when raising bets, adding this much for every doubling,
+    /// and end the session with a back-off once the heat reaches 1.
+    #[arg(long)]
+    heat: Option<f64>,
+    /// the share of the heat which cools off every round.
+    #[arg(long, default_value_t = HeatModel::default().cooling, requires = "heat")]
+    heat_cooling: f64,
+    /// have the dealer make mistakes now and then, with the chance of each, e.g. "mispay=0.01,flash=0.02",
+    /// and check every settlement against the rules, reporting the mistakes caught.
+    #[arg(long, conflicts_with_all = ["tables", "sessions"])]
+    dealer_errors: Option<DealerErrors>,
+    /// redeem a promotional coupon on every round, to measure what it is worth:
+    /// "match:25" matches the bet up to 25 at even money, "blackjack:25" pays a blackjack 2:1 on up to 25.
+    #[arg(long)]
+    coupon: Option<Coupon>,
+    /// end each session after this many rounds, moving to a fresh table for the next.
+    #[arg(long)]
+    session_rounds: Option<usize>,
+    /// end the session once the player is up by this many chips.
+    #[arg(long)]
+    stop_win: Option<u32>,
+    /// end the session once the player is down by this many chips.
+    #[arg(long)]
+    stop_loss: Option<u32>,
+    /// write the bankroll after every round to this file, one per line.
+    #[arg(long)]
+    trajectory: Option<PathBuf>,
+    /// write a summary of every round to this file as JSON Lines,
+    /// compressed with gzip if the file name ends in ".gz".
+    /// Names ending in ".pb" or ".pb.gz" are written as length-delimited protobuf instead,
+    /// when built with the protobuf feature.
+    #[arg(long)]
+    round_log: Option<PathBuf>,
+    /// a note on the session, kept at the head of its round log, e.g. "cards dealt at the Golden Nugget".
+    #[arg(long, requires = "round_log")]
+    note: Option<String>,
+    /// tag the session in its round log, e.g. with the casino or a label for its rules,
+    /// to pick it out with stats --tag. May be given more than once.
+    #[arg(long = "tag", requires = "round_log")]
+    tags: Vec<String>,
+    /// periodically save the simulation to this file, to resume it if interrupted.
+    #[arg(long)]
+    checkpoint: Option<PathBuf>,
+    /// the number of rounds between checkpoints.
+    #[arg(long, default_value = "1000000", requires = "checkpoint")]
+    checkpoint_every: NonZeroUsize,
+    /// resume the simulation saved in this checkpoint, playing until the total number of rounds.
+    /// The table, strategy, and betting options should be the same as when it was saved.
+    #[arg(long)]
+    resume: Option<PathBuf>,
+    /// don't show a progress bar.
+    #[arg(short, long)]
+    quiet: bool,
+    /// show chips as money: a currency symbol and the worth of a chip, e.g. "$0.25" or "€5".
+    #[arg(long)]
+    currency: Option<Currency>,
+    /// simulate this many independent tables at once, each with the starting bankroll,
+    /// splitting the rounds between them and pooling the results.
+    #[arg(short, long, default_value_t = 1, conflicts_with_all = ["trajectory", "round_log", "checkpoint", "resume"])]
+    tables: usize,
+    /// run this many independent sessions of up to the given number of rounds each,
+    /// every one with the starting bankroll and a fresh shoe,
+    /// and report the distribution of their results.
+    #[arg(long, conflicts_with_all = ["trajectory", "round_log", "checkpoint", "resume", "tables"])]
+    sessions: Option<usize>,
+    /// shuffle with this seed, to deal the same cards again. Defaults to a random seed.
+    #[arg(long)]
+    seed: Option<u64>,
+    /// don't record the run in the store of past runs.
+    #[arg(long)]
+    no_record: bool,
+    #[command(flatten)]
+    store: StoreArgs,
+}
+
+/// Where past runs are stored.
+#[derive(Debug, Args)]
+struct StoreArgs {
+    /// the file past runs are stored in. Defaults to ~/.blackjack/runs.txt.
+    #[arg(long = "runs", env = "BLACKJACK_RUNS")]
+    path: Option<PathBuf>,
+}
+
+impl StoreArgs {
+    fn store(&self) -> RunStore {
+        self.path.as_ref().map_or_else(
+            || {
+                let home = env::var_os("HOME").map_or_else(PathBuf::new, PathBuf::from);
+                RunStore::new(home.join(".blackjack").join("runs.txt"))
+            },
+            RunStore::new,
+        )
+    }
+}
+
+/// How the simulated player bets. Without any of these, the table minimum is bet flat.
+#[derive(Debug, Args)]
+#[group(multiple = false)]
+struct BettingArgs {
+    /// bet the same amount every round.
+    #[arg(long)]
+    bet: Option<u32>,
+    /// bet this fraction of the Kelly bet for the edge at the current true count.
+    #[arg(long)]
+    kelly: Option<f64>,
+    /// bet by true count according to a ramp file, as written by bet-spread.
+    #[arg(long)]
+    ramp: Option<PathBuf>,
+    /// double the bet after every loss, starting from this bet.
+    #[arg(long)]
+    martingale: Option<u32>,
+    /// double the bet after each win for up to three wins, starting from this bet.
+    #[arg(long)]
+    paroli: Option<u32>,
+    /// bet 1, 3, 2, then 6 times this bet as long as the player keeps winning.
+    #[arg(long)]
+    one_three_two_six: Option<u32>,
+    /// how the true count is worked out for the ramp and insurance: the decks remaining,
+    /// "exact", to the nearest "half" deck, or to the nearest whole "deck",
+    /// and how it is rounded for the ramp, "floor", "round", or "truncate", e.g. "half,floor".
+    #[arg(long, default_value_t = TrueCount::default())]
+    true_count: TrueCount,
+}
+
+/// Cover play, which limits how the bet may change to look less like counting.
+#[derive(Debug, Args)]
+struct CoverArgs {
+    /// never raise the bet to more than this multiple of the last bet.
+    #[arg(long)]
+    max_jump: Option<f64>,
+    /// never raise the bet by more than this many chips in one round.
+    #[arg(long)]
+    max_raise: Option<u32>,
+    /// never lower the bet right after a win.
+    #[arg(long)]
+    hold_after_win: bool,
+}
+
+impl CoverArgs {
+    /// The cover rules, if any were given.
+    fn rules(&self) -> Option<CoverRules> {
+        let rules = CoverRules {
+            max_jump: self.max_jump,
+            max_raise: self.max_raise,
+            hold_after_win: self.hold_after_win,
+        };
+        (rules != CoverRules::default()).then_some(rules)
+    }
+
+    /// Describes the cover rules, for the record of a run.
+    fn describe(&self) -> String {
+        let mut description = String::new();
+        if let Some(max_jump) = self.max_jump {
+            description += &format!(", max jump {max_jump}x");
+        }
+        if let Some(max_raise) = self.max_raise {
+            description += &format!(", max raise {max_raise}");
+        }
+        if self.hold_after_win {
+            description += ", hold after win";
+        }
+        description
+    }
+}
+
+impl BettingArgs {
+    /// Describes the betting strategy, for the record of a run.
+    fn describe(&self, rules: &Rules) -> String {
+        if let Some(bet) = self.bet {
+            format!("flat {bet}")
+        } else if let Some(fraction) = self.kelly {
+            format!("kelly {fraction}")
+        } else if let Some(ramp) = &self.ramp {
+            if self.true_count == TrueCount::default() {
+                format!("ramp {}", ramp.display())
+            } else {
+                format!("ramp {} ({} true count)", ramp.display(), self.true_count)
+            }
+        } else if let Some(base) = self.martingale {
+            format!("martingale {base}")
+        } else if let Some(base) = self.paroli {
+            format!("paroli {base}")
+        } else if let Some(base) = self.one_three_two_six {
+            format!("1-3-2-6 {base}")
+        } else {
+            format!("flat {}", FlatBet::minimum(rules).0)
+        }
+    }
+
+    /// The betting strategy for a player with the given bankroll.
+    /// Fails if the table can't be played with the bankroll, or the flat bet doesn't fit the table.
+    fn strategy(
+        &self,
+        rules: &Rules,
+        chips: u32,
+    ) -> Result<Box<dyn BettingStrategy + Send>, Box<dyn Error>> {
+        check_playable(rules, chips)?;
+        Ok(if let Some(bet) = self.bet {
+            Box::new(FlatBet::for_table(Some(bet), rules, chips)?)
+        } else if let Some(fraction) = self.kelly {
+            Box::new(Kelly::new(fraction))
+        } else if let Some(ramp) = &self.ramp {
+            Box::new(BetRamp {
+                true_count: self.true_count,
+                ..fs::read_to_string(ramp)?.parse()?
+            })
+        } else if let Some(base) = self.martingale {
+            Box::new(Martingale::new(base))
+        } else if let Some(base) = self.paroli {
+            Box::new(WinProgression::paroli(base))
+        } else if let Some(base) = self.one_three_two_six {
+            Box::new(WinProgression::one_three_two_six(base))
+        } else {
+            Box::new(FlatBet::for_table(None, rules, chips)?)
+        })
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    match cli.command {
+        Command::Chart { table } => {
+            let chart = StrategyChart::generate(&table.rules, table.decks);
+            print!(
+                "{}",
+                chart::render_chart(&chart, io::stdout().is_terminal())
+            );
+        }
+        Command::ChartDiff { table, other, file } => {
+            let generated = StrategyChart::generate(&table.rules, table.decks);
+            let (left, left_name) = match (other, file) {
+                (Some(other), _) => (
+                    StrategyChart::generate(&other.rules, other.decks),
+                    other.to_string(),
+                ),
+                (None, Some(file)) => (
+                    fs::read_to_string(&file)?.parse()?,
+                    file.display().to_string(),
+                ),
+                (None, None) => return Err("expected another table or a chart file".into()),
+            };
+            print!(
+                "{}",
+                chart::render_diff(
+                    &left,
+                    &left_name,
+                    &generated,
+                    &table.to_string(),
+                    io::stdout().is_terminal()
+                )
+            );
+        }
+        Command::Simulate(args) => simulate(*args)?,
+        Command::Init { output } => {
+            let mut stdin = io::stdin().lock();
+            let mut stdout = io::stdout();
+            let config = config::wizard(&mut stdin, &mut stdout)?;
+            fs::write(&output, config.to_string())?;
+            println!("Wrote {}:\n{config}", output.display());
+            let question = "Simulate a session at this table now?";
+            if config::ask(&mut stdin, &mut stdout, question, "no", config::yes_no)? {
+                let rounds = config::ask(
+                    &mut stdin,
+                    &mut stdout,
+                    "How many rounds?",
+                    "100",
+                    |answer| answer.parse::<usize>().ok().filter(|&rounds| rounds > 0),
+                )?;
+                let args = Cli::try_parse_from([
+                    "blackjack".as_ref(),
+                    "simulate".as_ref(),
+                    "--config".as_ref(),
+                    output.as_os_str(),
+                    "--rounds".as_ref(),
+                    rounds.to_string().as_ref(),
+                ])?;
+                if let Command::Simulate(args) = args.command {
+                    simulate(*args)?;
+                }
+            }
+        }
+        Command::Backtest {
+            table,
+            transcript,
+            chips,
+            betting,
+            show,
+            currency,
+            pace,
+        } => {
+            let shoe = Shoe::recorded(table.decks, &fs::read_to_string(transcript)?)?;
+            let strategy = BasicStrategy.with_betting(betting.strategy(&table.rules, chips)?);
+            let mut simulator =
+                Simulator::with_strategy(Table::new(chips, shoe, table.rules.clone()), strategy);
+            match show {
+                Some(style) => {
+                    let style = if io::stdout().is_terminal() {
+                        style
+                    } else {
+                        RoundStyle::Plain
+                    };
+                    show_rounds(&mut simulator, style, Duration::from_millis(pace));
+                }
+                None => while !simulator.table.shoe.recording_over() && simulator.next_round() {},
+            }
+            let currency = currency.as_ref();
+            print!("{}", InCurrency::new(&simulator.report, currency));
+            println!(
+                "Final Bankroll: {}",
+                currency::chips(f64::from(simulator.table.chips), 0, currency)
+            );
+        }
+        Command::Remote {
+            table,
+            command,
+            rounds,
+            penetration,
+            chips,
+            deadline,
+            #[cfg(feature = "grpc")]
+            grpc,
+        } => {
+            let table = table.with_penetration(penetration);
+            let deadline = Duration::from_millis(deadline);
+            #[cfg(feature = "grpc")]
+            let strategy = match grpc {
+                Some(url) => RemoteStrategy::connect(&url, deadline)?,
+                None => RemoteStrategy::spawn(&command, deadline)?,
+            };
+            #[cfg(not(feature = "grpc"))]
+            let strategy = RemoteStrategy::spawn(&command, deadline)?;
+            let mut simulator = Simulator::with_strategy(table.table(chips), strategy);
+            simulator.run(rounds);
+            print!("{}", simulator.report);
+            println!("Final Bankroll: {} Chips", simulator.table.chips);
+            println!(
+                "Decisions Made by the Table: {}",
+                simulator.strategy.timeouts
+            );
+        }
+        Command::Serve {
+            table,
+            port,
+            watch_port,
+            grace,
+            leaderboard,
+            address,
+            chips,
+            personality,
+            bots,
+            preferences,
+        } => {
+            let narrator = Narrator::new(personality, Locale::English);
+            let preferences = preferences
+                .as_deref()
+                .map_or_else(|| Ok(Preferences::default()), Preferences::load)?;
+            let seating = Seating {
+                spec: table,
+                chips,
+                bots,
+                preferences,
+            };
+            serve::serve(
+                (address.as_str(), port),
+                watch_port.map(|port| (address.as_str(), port)),
+                &seating,
+                Duration::from_secs(grace),
+                leaderboard.map(Leaderboard::new),
+                &narrator,
+            )?;
+        }
+        Command::Leaderboard { path } => {
+            let standings = Leaderboard::new(path).standings()?;
+            print!("{}", leaderboard::render(&standings));
+        }
+        Command::Verify {
+            seed,
+            commitment,
+            decks,
+            cards,
+        } => {
+            if let Some(commitment) = commitment {
+                if !fairness::verify(seed, &commitment) {
+                    return Err("the seed doesn't match the commitment".into());
+                }
+                println!("The seed matches the commitment.");
+            }
+            let mut catalog = Catalog::new(Locale::English);
+            catalog.suit_style = SuitStyle::Symbols;
+            let dealt: Vec<_> = fairness::deal(decks, seed, cards)
+                .iter()
+                .map(|card| catalog.card(card))
+                .collect();
+            println!("{}", dealt.join(" "));
+        }
+        Command::Irc {
+            table,
+            server,
+            channel,
+            nick,
+            chips,
+            personality,
+            bet_presets,
+        } => {
+            let narrator = Narrator::new(personality, Locale::English);
+            irc::run(
+                &server,
+                &nick,
+                &channel,
+                ChatTable::new(table, chips, narrator, bet_presets),
+            )?;
+        }
+        Command::Stats {
+            histories,
+            from_round,
+            to_round,
+            tags,
+            since,
+            until,
+            rules,
+            currency,
+        } => {
+            // A session starts before the end of the last day
+            let until = until.map(|until| until + 24 * 60 * 60);
+            let rules = rules.map(|table| table.rules.stable_hash());
+            let mut rounds = Vec::new();
+            for path in &histories {
+                let log = RoundLog::read(path)?;
+                let mut lines = log.lines().filter(|line| !line.is_empty()).peekable();
+                let notes = lines
+                    .peek()
+                    .and_then(|line| line.parse::<SessionNotes>().ok());
+                if notes.is_some() {
+                    lines.next();
+                }
+                let notes = notes.unwrap_or_default();
+                if !notes.has_tags(&tags)
+                    || !notes.started_within(since, until)
+                    || rules.is_some_and(|rules| notes.rules != Some(rules))
+                {
+                    continue;
+                }
+                if !notes.note.is_empty() || !notes.tags.is_empty() {
+                    println!(
+                        "{}: {} [{}]",
+                        path.display(),
+                        notes.note,
+                        notes.tags.join(", ")
+                    );
+                }
+                for line in lines {
+                    let round: RoundSummary = line
+                        .parse()
+                        .map_err(|e| format!("{}: {e}", path.display()))?;
+                    if from_round.is_none_or(|from| round.round >= from)
+                        && to_round.is_none_or(|to| round.round <= to)
+                    {
+                        rounds.push(round);
+                    }
+                }
+            }
+            let hands: usize = rounds.iter().map(|round| round.hands).sum();
+            let blackjacks: usize = rounds.iter().map(|round| round.blackjacks).sum();
+            let report = Report::from_rounds(rounds);
+            print!("{}", InCurrency::new(&report, currency.as_ref()));
+            if report.rounds_played > 0 {
+                println!(
+                    "Hands per Round Played: {:.4}",
+                    hands as f64 / report.rounds_played as f64
+                );
+                println!(
+                    "Blackjacks: {blackjacks}, {:.2}% of Rounds Played",
+                    blackjacks as f64 / report.rounds_played as f64 * 100.0
+                );
+            }
+            println!("\nTrue Count   Frequency   Edge");
+            for (count, results) in &report.by_true_count {
+                println!(
+                    "{count:>10}   {:>8.3}%   {:>+6.2}%",
+                    results.rounds as f64 / report.rounds_played as f64 * 100.0,
+                    results.edge() * 100.0
+                );
+            }
+        }
+        Command::Runs { ids, store } => {
+            let runs = store.store().load()?;
+            if ids.is_empty() {
+                print!("{}", runs::render_list(&runs));
+            } else {
+                let runs = ids
+                    .iter()
+                    .map(|id| {
+                        runs.iter()
+                            .find(|run| run.id == *id)
+                            .cloned()
+                            .ok_or_else(|| format!("no run with ID {id}"))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                print!("{}", runs::render_comparison(&runs));
+            }
+        }
+        Command::BetSpread {
+            table,
+            rounds,
+            penetration,
+            bankroll,
+            ror,
+            unit,
+            output,
+        } => {
+            let table = table.with_penetration(penetration);
+            let flat_bet = table.rules.min_bet.unwrap_or(unit);
+            let strategy = BasicStrategy.with_betting(FlatBet(flat_bet));
+            let mut simulator = Simulator::with_strategy(table.table(u32::MAX / 2), strategy);
+            let report = simulator.run(rounds);
+            let ramp = optimal_ramp(&report.by_true_count, bankroll, ror, &table.rules, unit);
+            println!("True Count   Frequency   Edge      Bet");
+            for (count, results) in &report.by_true_count {
+                println!(
+                    "{count:>10}   {:>8.3}%   {:>+6.2}%   {}",
+                    results.rounds as f64 / report.rounds_played as f64 * 100.0,
+                    results.edge() * 100.0,
+                    ramp.bet_for(*count)
+                );
+            }
+            if let Some(output) = output {
+                fs::write(output, ramp.to_string())?;
+            } else {
+                print!("\n{ramp}");
+            }
+        }
+        Command::Rounding {
+            table,
+            rounds,
+            penetration,
+            bet,
+            seed,
+        } => {
+            let mut table = table.with_penetration(penetration);
+            table.rules.min_bet = table.rules.min_bet.map(|min| min.min(bet));
+            table.rules.max_bet = None;
+            let ev = |rounding, bet| {
+                let mut rules = table.rules.clone();
+                rules.payout_rounding = rounding;
+                let shoe = Shoe::seeded(table.decks, table.penetration, seed);
+                let strategy = BasicStrategy.with_betting(FlatBet(bet));
+                let mut simulator =
+                    Simulator::with_strategy(Table::new(u32::MAX / 2, shoe, rules), strategy);
+                simulator.run(rounds).ev_per_round_played()
+            };
+            // Every fractional payout (3:2, 6:5 and surrender) is whole on ten times the bet,
+            // so dealing the same shoes at ten times the bet gives the EV of paying exactly
+            let exact = ev(PayoutRounding::Down, bet * 10) / 10.0;
+            println!("Rounding   EV per Round   Edge       Cost per 100 Rounds");
+            let rows = PayoutRounding::ALL
+                .into_iter()
+                .map(|rounding| (rounding.to_string(), ev(rounding, bet)))
+                .chain([("exact".to_string(), exact)]);
+            for (rounding, ev) in rows {
+                println!(
+                    "{rounding:<8}   {ev:>+12.4}   {:>+7.3}%   {:>19.2}",
+                    ev / f64::from(bet) * 100.0,
+                    (exact - ev) * 100.0
+                );
+            }
+        }
+        Command::Dealer { table } => {
+            let composition = Shoe::new(table.decks, table.penetration).composition();
+            let outcomes = DealerOutcomes::table(&composition, table.rules.dealer_drawing, true);
+            // Only the totals the dealer can finish on get a column
+            let totals: Vec<usize> = (0..22)
+                .filter(|&total| outcomes.iter().any(|outcomes| outcomes.totals[total] > 0.0))
+                .collect();
+            print!("Upcard");
+            for total in &totals {
+                print!("   {total:>6}");
+            }
+            println!("     Bust");
+            for (upcard, outcomes) in (2..=11).zip(outcomes) {
+                print!("{:>6}", worth_symbol(upcard));
+                for &total in &totals {
+                    print!("   {:>5.2}%", outcomes.totals[total] * 100.0);
+                }
+                println!("   {:>5.2}%", outcomes.bust() * 100.0);
+            }
+        }
+        Command::Bench { rounds, seed } => bench(rounds, seed),
+    }
+    Ok(())
+}
+
+/// Runs each benchmark, printing how fast it dealt its rounds and how many allocations each took.
+fn bench(rounds: usize, seed: u64) {
+    println!("Benchmark            Rounds per Second   Allocations per Round");
+    for (name, table, wong_in) in BENCHMARKS {
+        let table: TableSpec = table.parse().expect("benchmark tables are valid");
+        let shoe = Shoe::seeded(table.decks, table.penetration, seed);
+        let mut simulator = Simulator::new(Table::new(u32::MAX / 2, shoe, table.rules));
+        simulator.wong_in = wong_in;
+        // Warm up first, so the buffers a simulation allocates once aren't counted against its rounds
+        simulator.run(rounds / 100);
+        let allocations = blackjack_core::profiling::allocations();
+        let started = Instant::now();
+        simulator.run(rounds);
+        let elapsed = started.elapsed();
+        let per_round = blackjack_core::profiling::allocations()
+            .zip(allocations)
+            .map_or_else(
+                || "not counted".to_string(),
+                |(after, before)| format!("{:.3}", (after - before) as f64 / rounds as f64),
+            );
+        println!(
+            "{name:<18}   {:>17.0}   {per_round:>21}",
+            rounds as f64 / elapsed.as_secs_f64()
+        );
+    }
+}
+
+fn simulate(args: SimulateArgs) -> Result<(), Box<dyn Error>> {
+    let SimulateArgs {
+        table,
+        config,
+        rounds,
+        penetration,
+        chips,
+        warm_up,
+        betting,
+        cover,
+        insure_at,
+        even_money,
+        insure_favorable,
+        heat,
+        heat_cooling,
+        dealer_errors,
+        coupon,
+        session_rounds,
+        stop_win,
+        stop_loss,
+        trajectory,
+        round_log,
+        note,
+        tags,
+        checkpoint,
+        checkpoint_every,
+        resume,
+        quiet,
+        currency,
+        tables,
+        sessions,
+        seed,
+        no_record,
+        store,
+    } = args;
+    let (table, stop_win, stop_loss) = if let Some(config) = config {
+        let config: TableConfig = fs::read_to_string(config)?.parse()?;
+        (
+            config.table,
+            stop_win.or(config.stop_win),
+            stop_loss.or(config.stop_loss),
+        )
+    } else {
+        (
+            table.unwrap_or_else(|| "6D".parse().unwrap()),
+            stop_win,
+            stop_loss,
+        )
+    };
+    let table = table.with_penetration(penetration);
+    let resume: Option<Checkpoint> = match resume {
+        Some(path) => Some(fs::read_to_string(path)?.parse()?),
+        None => None,
+    };
+    // A resumed run keeps the seed it was started with
+    let seed = resume
+        .as_ref()
+        .and_then(|checkpoint| checkpoint.seed)
+        .or(seed);
+    let seed = seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_nanos() as u64)
+    });
+    let mut strategy_name = format!(
+        "basic, {}{}",
+        betting.describe(&table.rules),
+        cover.describe()
+    );
+    if let Some(count) = insure_at {
+        strategy_name += &format!(", insure at {count}");
+    }
+    if even_money {
+        strategy_name += ", even money";
+    }
+    if insure_favorable {
+        strategy_name += ", insure when favorable";
+    }
+    if let Some(sensitivity) = heat {
+        strategy_name += &format!(", heat {sensitivity}");
+    }
+    if let Some(rounds) = session_rounds {
+        strategy_name += &format!(", sessions of {rounds}");
+    }
+    if let Some(sessions) = sessions {
+        strategy_name += &format!(", {sessions} sessions of {rounds}");
+    }
+    if let Some(stop_win) = stop_win {
+        strategy_name += &format!(", stop-win {stop_win}");
+    }
+    if let Some(stop_loss) = stop_loss {
+        strategy_name += &format!(", stop-loss {stop_loss}");
+    }
+    if let Some(warm_up) = warm_up {
+        strategy_name += &format!(", warm-up of {warm_up}");
+    }
+    let date = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs());
+    let record = Run {
+        id: 0,
+        date,
+        version: env!("BLACKJACK_GIT_VERSION").to_string(),
+        table: table.to_string(),
+        rules: runs::rules_hash(&table.rules),
+        strategy: strategy_name,
+        seed,
+        penetration: table.penetration,
+        tables,
+        rounds: 0,
+        duration: 0.0,
+        net: 0,
+        ev: 0.0,
+        error: 0.0,
+    };
+    let started = Instant::now();
+    let new_simulator = |seed: u64| -> Result<_, Box<dyn Error>> {
+        let true_count = betting.true_count;
+        let mut betting = betting.strategy(&table.rules, chips)?;
+        if let Some(rules) = cover.rules() {
+            betting = Box::new(Camouflage::new(betting, rules));
+        }
+        let strategy = BasicStrategy
+            .with_insurance(CountedInsurance {
+                min_true_count: insure_at,
+                even_money,
+                favorable: insure_favorable,
+                true_count,
+            })
+            .with_betting(betting);
+        let mut simulator = Simulator::with_strategy(
+            Table::new(
+                chips,
+                Shoe::seeded(table.decks, table.penetration, seed),
+                table.rules.clone(),
+            ),
+            strategy,
+        );
+        simulator.heat = heat.map(|sensitivity| {
+            Heat::new(HeatModel {
+                sensitivity,
+                cooling: heat_cooling,
+            })
+        });
+        simulator.limits = SessionLimits {
+            rounds: session_rounds,
+            stop_win,
+            stop_loss,
+        };
+        simulator.warm_up = warm_up;
+        simulator.coupon = coupon;
+        Ok(simulator)
+    };
+    if let Some(sessions) = sessions {
+        let mut distribution = SessionDistribution::default();
+        let mut pooled: Option<Report> = None;
+        let mut statistics = Statistics::new();
+        for session in 0..sessions as u64 {
+            let mut simulator = new_simulator(seed.wrapping_add(session))?;
+            let busted = simulator.run_session(rounds);
+            distribution.record(i64::from(simulator.table.chips) - i64::from(chips), busted);
+            statistics.merge(&simulator.table.statistics);
+            match &mut pooled {
+                Some(pooled) => pooled.merge(&simulator.report),
+                None => pooled = Some(simulator.report),
+            }
+        }
+        let report = pooled.unwrap_or_default();
+        print!("{}", InCurrency::new(&report, currency.as_ref()));
+        print!("{}", InCurrency::new(&distribution, currency.as_ref()));
+        print_insurance(&statistics, currency.as_ref());
+        print_coupons(&statistics, currency.as_ref());
+        print_shoes(&statistics);
+        if !no_record {
+            save_run(&store, record, &report, started)?;
+        }
+        return Ok(());
+    }
+    if tables > 1 {
+        let mut simulators = (0..tables as u64)
+            .map(|table| new_simulator(seed.wrapping_add(table)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let report = simulation::run_tables(&mut simulators, rounds);
+        let mut statistics = Statistics::new();
+        for simulator in &simulators {
+            statistics.merge(&simulator.table.statistics);
+        }
+        print!("{}", InCurrency::new(&report, currency.as_ref()));
+        let bankrolls: Vec<String> = simulators
+            .iter()
+            .map(|simulator| {
+                currency::amount(f64::from(simulator.table.chips), 0, currency.as_ref())
+            })
+            .collect();
+        let unit = if currency.is_some() { "" } else { " Chips" };
+        println!("Final Bankrolls: {}{unit}", bankrolls.join(", "));
+        print_insurance(&statistics, currency.as_ref());
+        print_coupons(&statistics, currency.as_ref());
+        print_shoes(&statistics);
+        if !no_record {
+            save_run(&store, record, &report, started)?;
+        }
+        return Ok(());
+    }
+    let mut simulator = new_simulator(seed)?;
+    let checker = dealer_errors.map(|mut errors| {
+        errors.reseed(seed);
+        simulator.table.dealer_errors = Some(errors);
+        let checker = Arc::new(Mutex::new(SettlementChecker::new(
+            simulator.table.rules.clone(),
+        )));
+        simulator
+            .table
+            .observers
+            .push(Box::new(Arc::clone(&checker)));
+        checker
+    });
+    let outputs = Outputs {
+        trajectory,
+        round_log,
+        notes: Some(SessionNotes {
+            note: note.unwrap_or_default(),
+            tags,
+            date: Some(date),
+            rules: Some(simulator.table.rules.stable_hash()),
+        }),
+        checkpoint,
+        checkpoint_every,
+        show_progress: !quiet && io::stderr().is_terminal(),
+    };
+    checkpointing::run(&mut simulator, rounds, seed, resume, outputs, started)?;
+    print!("{}", InCurrency::new(&simulator.report, currency.as_ref()));
+    println!(
+        "Final Bankroll: {}",
+        currency::chips(f64::from(simulator.table.chips), 0, currency.as_ref())
+    );
+    print_insurance(&simulator.table.statistics, currency.as_ref());
+    print_coupons(&simulator.table.statistics, currency.as_ref());
+    print_shoes(&simulator.table.statistics);
+    if let (Some(errors), Some(checker)) = (&simulator.table.dealer_errors, checker) {
+        let checker = checker.lock().unwrap_or_else(PoisonError::into_inner);
+        print_dealer_errors(errors, &checker, currency.as_ref());
+    }
+    if !no_record {
+        save_run(&store, record, &simulator.report, started)?;
+    }
+    Ok(())
+}
+
+/// Back-tests until the recording is over, showing each round as it is dealt.
+fn show_rounds<S: Strategy>(simulator: &mut Simulator<S>, style: RoundStyle, pace: Duration) {
+    let narrator = Narrator::new(Personality::Professional, Locale::English);
+    let mut catalog = Catalog::new(Locale::English);
+    catalog.suit_style = SuitStyle::Symbols;
+    simulator.table.fast_forward = false;
+    simulator.table.record_events = true;
+    let mut round = 0;
+    loop {
+        if simulator.table.shoe.recording_over() {
+            break;
+        }
+        round += 1;
+        println!("Round {round}");
+        // The box is redrawn over itself for every state, so only the round's last state stays on screen
+        let mut lines_drawn = 0;
+        let playing = simulator.next_round_watched(&mut |table, state| {
+            if style != RoundStyle::Box {
+                return;
+            }
+            if let Some(frame) = render::render_box(state, table, &catalog) {
+                if lines_drawn > 0 {
+                    print!("\x1b[{lines_drawn}A\x1b[J");
+                }
+                print!("{frame}");
+                io::stdout().flush().unwrap();
+                lines_drawn = frame.lines().count();
+                thread::sleep(pace);
+            }
+        });
+        for event in simulator.table.drain_events() {
+            if let Some(line) = narrator.narrate(&event) {
+                if style == RoundStyle::Plain || matches!(event, GameEvent::Payout { .. }) {
+                    println!("{line}");
+                }
+            }
+        }
+        println!();
+        if !playing {
+            break;
+        }
+    }
+}
+
+/// Records a finished run in the store, so its settings can be looked up later.
+fn save_run(
+    store: &StoreArgs,
+    mut run: Run,
+    report: &Report,
+    started: Instant,
+) -> Result<(), Box<dyn Error>> {
+    run.rounds = report.rounds_observed;
+    run.duration = started.elapsed().as_secs_f64();
+    run.net = report.net;
+    run.ev = report.ev_per_round_played();
+    run.error = report.standard_error();
+    let id = store.store().save(run)?;
+    eprintln!("Recorded as run {id}");
+    Ok(())
+}
+
+fn print_insurance(statistics: &Statistics, currency: Option<&Currency>) {
+    if let Some(ev) = statistics.insurance_ev() {
+        println!(
+            "Insurance: {} Bets, {} ({:+.2}% EV)",
+            statistics.insurance_bets(),
+            currency::chips(statistics.insurance_net() as f64, 0, currency),
+            ev * 100.0
+        );
+    }
+}
+
+fn print_coupons(statistics: &Statistics, currency: Option<&Currency>) {
+    if let Some(ev) = statistics.coupon_ev() {
+        println!(
+            "Coupons: {} Used, Worth {} Each",
+            statistics.coupons(),
+            currency::chips(ev, 2, currency)
+        );
+    }
+}
+
+fn print_shoes(statistics: &Statistics) {
+    if let Some(cards) = statistics.cards_per_round() {
+        println!("Cards per Round: {cards:.2}");
+    }
+    if let (Some(rounds), Some(penetration)) =
+        (statistics.rounds_per_shoe(), statistics.penetration())
+    {
+        println!(
+            "Shoes: {}, {rounds:.2} Rounds per Shoe, {:.2}% Penetration",
+            statistics.shuffles(),
+            penetration * 100.0
+        );
+    }
+}
+
+/// Prints the mistakes the dealer made, and the settlements the checker caught breaking the rules.
+fn print_dealer_errors(
+    errors: &DealerErrors,
+    checker: &SettlementChecker,
+    currency: Option<&Currency>,
+) {
+    println!(
+        "Dealer Errors: {} Mispays ({}), {} Hole Cards Flashed",
+        errors.mispays,
+        currency::chips(errors.mispaid as f64, 0, currency),
+        errors.flashes
+    );
+    println!(
+        "Settlements Checked: {} Rounds, {} Broke the Rules",
+        checker.rounds,
+        checker.violations.len()
+    );
+    for violation in checker.violations.iter().take(VIOLATIONS_SHOWN) {
+        println!("  {violation}");
+    }
+    if checker.violations.len() > VIOLATIONS_SHOWN {
+        println!("  ...");
+    }
+}
+
+/// Parses a penetration given on the command line, the proportion of the shoe dealt, e.g. 0.75.
+/// Parses a day written as year-month-day, returning the time it starts in seconds since the Unix epoch, in UTC.
+fn parse_date(s: &str) -> Result<u64, String> {
+    let invalid = || format!("expected a day as year-month-day, e.g. 2024-03-01: {s}");
+    let mut parts = s.splitn(3, '-').map(|part| part.parse::<u64>().ok());
+    let (Some(Some(year)), Some(Some(month)), Some(Some(day))) =
+        (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid());
+    };
+    let leap = year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400));
+    let month_days = [
+        31,
+        if leap { 29 } else { 28 },
+        31,
+        30,
+        31,
+        30,
+        31,
+        31,
+        30,
+        31,
+        30,
+        31,
+    ];
+    if year < 1970 || !(1..=12).contains(&month) || day == 0 || day > month_days[month as usize - 1]
+    {
+        return Err(invalid());
+    }
+    let leap_days = |year: u64| year / 4 - year / 100 + year / 400;
+    let days = (year - 1970) * 365 + leap_days(year - 1) - leap_days(1969)
+        + month_days[..month as usize - 1].iter().sum::<u64>()
+        + day
+        - 1;
+    Ok(days * 24 * 60 * 60)
+}
+
+fn parse_penetration(s: &str) -> Result<f32, String> {
+    s.parse()
+        .ok()
+        .filter(|penetration| *penetration > 0.0 && *penetration <= 1.0)
+        .ok_or_else(|| format!("expected a proportion above 0 and up to 1, e.g. 0.75: {s}"))
+}
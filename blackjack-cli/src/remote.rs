@@ -0,0 +1,328 @@
+//! A player in another process, so that strategies written in any language can play at a table.
+//! The table and the player speak the text protocol of the `blackjack-protocol` crate over the player's
+//! standard input and output. Each greets the other with its version of the protocol, e.g. "blackjack 2",
+//! then the table writes a prompt whenever it needs a decision, numbered so that answers can be matched
+//! to questions, e.g.
+//!
+//! ```text
+//! 1 bet chips=100000 min=100 max=none last=none running=0 true=0.0
+//! 2 turn hand=A,7 value=soft-18 dealer=9 allowed=hit,stand,double
+//! 3 insurance hand=10,10 dealer=A max=50
+//! 4 surrender hand=10,6 dealer=A
+//! ```
+//!
+//! and the player answers on its standard output with the same number and its decision,
+//! e.g. "1 200", "2 hit", "3 0", or "4 no".
+//! With the `grpc` feature, the player can instead be a gRPC service implementing `Player` from
+//! `proto/player.proto`, which is sent the same prompts along with a snapshot of the table.
+//! A decision that doesn't arrive before the deadline, or isn't legal, is left to the table,
+//! which stands and declines any offers (the minimum is bet instead of a missing bet).
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use blackjack_core::betting::BetPresets;
+use blackjack_core::card::shoe::Composition;
+use blackjack_core::game::{Input, Table};
+use blackjack_core::state::GameState;
+use blackjack_core::strategy::Strategy;
+#[cfg(feature = "grpc")]
+use blackjack_protocol::Snapshot;
+use blackjack_protocol::{parse_answer, Greeting, Prompt};
+
+/// The gRPC service generated from `proto/player.proto`.
+#[cfg(feature = "grpc")]
+pub mod proto {
+    tonic::include_proto!("blackjack.player");
+}
+
+/// A strategy played by another process, over its standard input and output or over gRPC.
+#[derive(Debug)]
+pub struct RemoteStrategy {
+    player: Player,
+    /// How long the player has for each decision
+    pub deadline: Duration,
+    /// The number of decisions the table made because the player's was late or illegal
+    pub timeouts: usize,
+}
+
+/// How the table reaches the player.
+#[derive(Debug)]
+enum Player {
+    /// A child process, asked numbered questions on its standard input
+    Process {
+        child: Child,
+        stdin: ChildStdin,
+        answers: Receiver<String>,
+        /// The number of the last question asked
+        question: usize,
+    },
+    /// A gRPC service, called on a runtime of the table's own
+    #[cfg(feature = "grpc")]
+    Service {
+        runtime: tokio::runtime::Runtime,
+        client: proto::player_client::PlayerClient<tonic::transport::Channel>,
+    },
+}
+
+impl RemoteStrategy {
+    /// Starts the player's process, with the program followed by its arguments.
+    pub fn spawn(command: &[String], deadline: Duration) -> io::Result<Self> {
+        let (program, args) = command
+            .split_first()
+            .ok_or_else(|| io::Error::other("missing the player's command"))?;
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let stdin = child.stdin.take().expect("the player's stdin is piped");
+        let stdout = child.stdout.take().expect("the player's stdout is piped");
+        // Answers are read on their own thread, so that a silent player can't block the table
+        let (sender, answers) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        let player = Player::Process {
+            child,
+            stdin,
+            answers,
+            question: 0,
+        };
+        Self::greet(player, deadline)
+    }
+
+    /// Connects to a player serving `Player` from `proto/player.proto`, e.g. at "http://localhost:50051".
+    #[cfg(feature = "grpc")]
+    pub fn connect(url: &str, deadline: Duration) -> io::Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let endpoint = tonic::transport::Endpoint::from_shared(url.to_string())
+            .map_err(io::Error::other)?
+            .connect_timeout(deadline);
+        let channel = runtime
+            .block_on(endpoint.connect())
+            .map_err(io::Error::other)?;
+        let client = proto::player_client::PlayerClient::new(channel);
+        Self::greet(Player::Service { runtime, client }, deadline)
+    }
+
+    /// Greets the player, and checks that it greets the table back with a version of the protocol
+    /// the table speaks before the deadline.
+    fn greet(mut player: Player, deadline: Duration) -> io::Result<Self> {
+        let late = || io::Error::other("the player didn't greet the table in time");
+        let greeting = match &mut player {
+            Player::Process { stdin, answers, .. } => {
+                writeln!(stdin, "{}", Greeting::CURRENT)?;
+                stdin.flush()?;
+                answers.recv_timeout(deadline).map_err(|_| late())?
+            }
+            #[cfg(feature = "grpc")]
+            Player::Service { runtime, client } => {
+                let mut request = tonic::Request::new(proto::Greeting {
+                    greeting: Greeting::CURRENT.to_string(),
+                });
+                request.set_timeout(deadline);
+                let greeting = client.greet(request);
+                runtime
+                    .block_on(async { tokio::time::timeout(deadline, greeting).await })
+                    .map_err(|_| late())?
+                    .map_err(io::Error::other)?
+                    .into_inner()
+                    .greeting
+            }
+        };
+        greeting
+            .parse::<Greeting>()
+            .and_then(Greeting::check)
+            .map_err(io::Error::other)?;
+        Ok(Self {
+            player,
+            deadline,
+            timeouts: 0,
+        })
+    }
+
+    /// Asks the player to answer a prompt, returning its answer if it arrives before the deadline.
+    /// Late answers to earlier questions are skipped.
+    #[cfg_attr(not(feature = "grpc"), allow(unused_variables))]
+    fn ask(&mut self, table: &Table, state: &GameState, prompt: &Prompt) -> Option<String> {
+        match &mut self.player {
+            Player::Process {
+                stdin,
+                answers,
+                question,
+                ..
+            } => {
+                *question += 1;
+                writeln!(stdin, "{question} {prompt}").ok()?;
+                stdin.flush().ok()?;
+                let deadline = Instant::now() + self.deadline;
+                loop {
+                    let timeout = deadline.saturating_duration_since(Instant::now());
+                    match answers.recv_timeout(timeout) {
+                        Ok(line) => {
+                            if let Some((number, answer)) = line.trim().split_once(' ') {
+                                if number.parse() == Ok(*question) {
+                                    return Some(answer.to_string());
+                                }
+                            }
+                        }
+                        Err(RecvTimeoutError::Timeout | RecvTimeoutError::Disconnected) => {
+                            return None
+                        }
+                    }
+                }
+            }
+            #[cfg(feature = "grpc")]
+            Player::Service { runtime, client } => {
+                let mut request = tonic::Request::new(proto::Question {
+                    prompt: prompt.to_string(),
+                    snapshot: Snapshot::new(table, state).to_string(),
+                });
+                request.set_timeout(self.deadline);
+                let decision = runtime.block_on(async {
+                    tokio::time::timeout(self.deadline, client.decide(request)).await
+                });
+                Some(decision.ok()?.ok()?.into_inner().answer)
+            }
+        }
+    }
+
+    /// Returns the table's decision in place of the player's, counting it as a timeout.
+    fn time_out(&mut self) -> Input {
+        self.timeouts += 1;
+        Input::Timeout
+    }
+}
+
+impl Strategy for RemoteStrategy {
+    fn input(&mut self, table: &Table, state: &GameState, _: &Composition) -> Option<Input> {
+        let prompt = Prompt::new(table, state)?;
+        let input = self
+            .ask(table, state, &prompt)
+            .and_then(|answer| parse_answer(table, state, &answer, &BetPresets::default()).ok())
+            .filter(|input| table.validate(state, input).is_ok());
+        Some(match (input, state) {
+            (Some(input), _) => input,
+            (None, GameState::Betting { .. }) => {
+                self.timeouts += 1;
+                Input::bet(table.rules.min_bet.unwrap_or(1))
+            }
+            (None, _) => self.time_out(),
+        })
+    }
+}
+
+impl Drop for RemoteStrategy {
+    fn drop(&mut self) {
+        // The player may not notice its input closing, so it is stopped outright
+        match &mut self.player {
+            Player::Process { child, .. } => {
+                let _ = child.kill();
+                let _ = child.wait();
+            }
+            #[cfg(feature = "grpc")]
+            Player::Service { .. } => {}
+        }
+    }
+}
+
+#[cfg(all(test, feature = "grpc"))]
+mod tests {
+    use std::net::SocketAddr;
+
+    use blackjack_core::card::shoe::Shoe;
+    use blackjack_core::rules::Rules;
+    use blackjack_core::simulation::Simulator;
+    use tonic::transport::server::TcpIncoming;
+    use tonic::transport::Server;
+    use tonic::{Request, Response, Status};
+
+    use super::proto::player_server::PlayerServer;
+    use super::*;
+
+    /// A player that bets 100 and stands, taking its time over each decision.
+    struct Stander {
+        delay: Duration,
+    }
+
+    #[tonic::async_trait]
+    impl proto::player_server::Player for Stander {
+        async fn greet(
+            &self,
+            request: Request<proto::Greeting>,
+        ) -> Result<Response<proto::Greeting>, Status> {
+            Ok(Response::new(request.into_inner()))
+        }
+
+        async fn decide(
+            &self,
+            request: Request<proto::Question>,
+        ) -> Result<Response<proto::Decision>, Status> {
+            tokio::time::sleep(self.delay).await;
+            let question = request.into_inner();
+            assert!(question.snapshot.starts_with("snapshot "));
+            let answer = match question.prompt.split(' ').next() {
+                Some("bet") => "100",
+                Some("turn") => "stand",
+                _ => "no",
+            };
+            Ok(Response::new(proto::Decision {
+                answer: answer.to_string(),
+            }))
+        }
+    }
+
+    /// Serves a player on a thread of its own, returning its URL.
+    fn serve(player: Stander) -> String {
+        let (sender, address) = mpsc::channel();
+        thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap();
+            runtime.block_on(async {
+                let incoming = TcpIncoming::bind(SocketAddr::from(([127, 0, 0, 1], 0))).unwrap();
+                sender.send(incoming.local_addr().unwrap()).unwrap();
+                Server::builder()
+                    .add_service(PlayerServer::new(player))
+                    .serve_with_incoming(incoming)
+                    .await
+                    .unwrap();
+            });
+        });
+        format!("http://{}", address.recv().unwrap())
+    }
+
+    #[test]
+    fn test_grpc_player() {
+        let table = || Table::new(10_000, Shoe::new(6, 0.75), Rules::default());
+        let url = serve(Stander {
+            delay: Duration::ZERO,
+        });
+        let strategy = RemoteStrategy::connect(&url, Duration::from_secs(5)).unwrap();
+        let mut simulator = Simulator::with_strategy(table(), strategy);
+        simulator.run(10);
+        assert_eq!(simulator.strategy.timeouts, 0);
+        assert_eq!(simulator.report.rounds_played, 10);
+
+        // A player too slow for the deadline has its decisions made by the table
+        let url = serve(Stander {
+            delay: Duration::from_millis(500),
+        });
+        let strategy = RemoteStrategy::connect(&url, Duration::from_millis(50)).unwrap();
+        let mut simulator = Simulator::with_strategy(table(), strategy);
+        simulator.run(2);
+        assert!(simulator.strategy.timeouts >= 2);
+        assert!(RemoteStrategy::connect("http://127.0.0.1:1", Duration::from_millis(50)).is_err());
+    }
+}
@@ -0,0 +1,182 @@
+use std::fmt::{self, Write};
+use std::str::FromStr;
+
+use blackjack_core::card::hand::{DealerHand, PlayerHand};
+use blackjack_core::game::Table;
+use blackjack_core::i18n::Catalog;
+use blackjack_core::state::GameState;
+
+/// The inner width of the box-drawn table, in characters.
+const WIDTH: usize = 40;
+
+/// How the rounds of a back-test are shown as they are dealt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundStyle {
+    /// A line of narration for everything that happens
+    Plain,
+    /// The table drawn in a box, redrawn in place for every state
+    Box,
+}
+
+impl FromStr for RoundStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "plain" => Ok(Self::Plain),
+            "box" => Ok(Self::Box),
+            _ => Err(format!("unknown round style: {s}")),
+        }
+    }
+}
+
+impl fmt::Display for RoundStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Plain => write!(f, "plain"),
+            Self::Box => write!(f, "box"),
+        }
+    }
+}
+
+/// Returns the dealer's hand, whether the hole card is still face down, and the player's hands,
+/// or `None` in states without cards on the table.
+fn table_cards(state: &GameState) -> Option<(Option<&DealerHand>, bool, Vec<&PlayerHand>)> {
+    Some(match state {
+        GameState::Betting { .. }
+        | GameState::Payout { .. }
+        | GameState::Shuffle
+        | GameState::GameOver => return None,
+        GameState::Dealing {
+            player_hand,
+            dealer_hand,
+            ..
+        } => (dealer_hand.as_ref(), true, player_hand.iter().collect()),
+        GameState::OfferEarlySurrender {
+            player_hand,
+            dealer_hand,
+        }
+        | GameState::OfferInsurance {
+            player_hand,
+            dealer_hand,
+        }
+        | GameState::CheckDealerHoleCard {
+            player_hand,
+            dealer_hand,
+            ..
+        } => (Some(dealer_hand), true, vec![player_hand]),
+        GameState::PlayPlayerTurn {
+            player_turn,
+            dealer_hand,
+            ..
+        }
+        | GameState::PlayerStand {
+            player_turn,
+            dealer_hand,
+            ..
+        }
+        | GameState::PlayerHit {
+            player_turn,
+            dealer_hand,
+            ..
+        }
+        | GameState::PlayerDouble {
+            player_turn,
+            dealer_hand,
+            ..
+        }
+        | GameState::PlayerSplit {
+            player_turn,
+            dealer_hand,
+            ..
+        }
+        | GameState::PlayerSurrender {
+            player_turn,
+            dealer_hand,
+            ..
+        } => (
+            Some(dealer_hand),
+            true,
+            player_turn.all_hands().iter().collect(),
+        ),
+        GameState::DealFirstSplitCard {
+            player_turn,
+            new_hand,
+            dealer_hand,
+            ..
+        }
+        | GameState::DealSecondSplitCard {
+            player_turn,
+            new_hand,
+            dealer_hand,
+            ..
+        } => {
+            let mut hands: Vec<_> = player_turn.all_hands().iter().collect();
+            hands.insert(player_turn.current_hand_index() + 1, new_hand);
+            (Some(dealer_hand), true, hands)
+        }
+        GameState::RevealHoleCard {
+            finished_hands,
+            dealer_hand,
+            ..
+        }
+        | GameState::PlayDealerTurn {
+            finished_hands,
+            dealer_hand,
+            ..
+        }
+        | GameState::RoundOver {
+            finished_hands,
+            dealer_hand,
+            ..
+        } => (Some(dealer_hand), false, finished_hands.iter().collect()),
+    })
+}
+
+/// Draws the table in a box: the dealer's cards on top, each of the player's hands with its bet below,
+/// and the bankroll at the bottom. Returns `None` in states without cards on the table.
+pub fn render_box(state: &GameState, table: &Table, catalog: &Catalog) -> Option<String> {
+    let (dealer_hand, hole_card_down, player_hands) = table_cards(state)?;
+    let mut output = String::new();
+    let row = |output: &mut String, left: &str, right: &str| {
+        let padding = WIDTH.saturating_sub(left.chars().count() + right.chars().count());
+        writeln!(output, "│ {left}{}{right} │", " ".repeat(padding)).unwrap();
+    };
+    let rule = |output: &mut String, left: char, right: char| {
+        writeln!(output, "{left}{}{right}", "─".repeat(WIDTH + 2)).unwrap();
+    };
+    rule(&mut output, '┌', '┐');
+    if let Some(dealer_hand) = dealer_hand {
+        let cards = dealer_hand.cards();
+        let (value, cards) = match (hole_card_down, dealer_hand.hole_card()) {
+            (true, Some(_)) => (
+                format!("Showing {}", dealer_hand.showing()),
+                vec![catalog.card(&cards[0]), "[??]".to_string()],
+            ),
+            _ => (
+                catalog.value(&dealer_hand.value),
+                cards.iter().map(|card| catalog.card(card)).collect(),
+            ),
+        };
+        row(&mut output, "Dealer", &value);
+        row(&mut output, &cards.join(" "), "");
+    }
+    for (i, hand) in player_hands.iter().enumerate() {
+        rule(&mut output, '├', '┤');
+        row(
+            &mut output,
+            &format!("Hand {}  Bet {}", i + 1, hand.bet),
+            &catalog.value(&hand.value),
+        );
+        let cards: Vec<_> = hand.cards.iter().map(|card| catalog.card(card)).collect();
+        row(&mut output, &cards.join(" "), "");
+    }
+    rule(&mut output, '├', '┤');
+    row(
+        &mut output,
+        &format!("Bankroll {}", table.chips),
+        &format!("Wagered {}", state.total_wagered(table.spot_bets())),
+    );
+    rule(&mut output, '└', '┘');
+    Some(output)
+}
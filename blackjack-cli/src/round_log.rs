@@ -0,0 +1,181 @@
+//! A log of every round of a simulation, written as JSON Lines, or as length-delimited protobuf
+//! when built with the protobuf feature. Logs whose names end in ".gz" are compressed with gzip.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Read, Write};
+use std::path::Path;
+
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use blackjack_core::session::SessionNotes;
+use blackjack_core::simulation::RoundSummary;
+
+/// A JSON Lines or protobuf file of round summaries, which may be compressed with gzip on its way to disk.
+pub struct RoundLog {
+    writer: BufWriter<Sink>,
+    protobuf: bool,
+}
+
+/// Where a round log is written: straight to its file, or compressed with gzip.
+enum Sink {
+    Plain(File),
+    Gzip(GzEncoder<File>),
+}
+
+impl Sink {
+    /// The file the log is written to.
+    fn file(&self) -> &File {
+        match self {
+            Self::Plain(file) => file,
+            Self::Gzip(gzip) => gzip.get_ref(),
+        }
+    }
+}
+
+impl Write for Sink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(file) => file.write(buf),
+            Self::Gzip(gzip) => gzip.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Plain(file) => file.flush(),
+            Self::Gzip(gzip) => gzip.flush(),
+        }
+    }
+}
+
+impl RoundLog {
+    /// Creates the log, replacing any log already at the path.
+    pub fn create(path: &Path) -> io::Result<Self> {
+        Self::open(path, File::create(path)?)
+    }
+
+    /// Opens a log to carry on writing it from the given length, cutting off whatever follows,
+    /// e.g. the rounds written after the checkpoint a simulation is resumed from.
+    pub fn reopen(path: &Path, length: u64) -> io::Result<Self> {
+        let file = OpenOptions::new().append(true).open(path)?;
+        file.set_len(length)?;
+        Self::open(path, file)
+    }
+
+    fn open(path: &Path, file: File) -> io::Result<Self> {
+        let name = path.to_string_lossy();
+        let protobuf = name.ends_with(".pb") || name.ends_with(".pb.gz");
+        if protobuf && cfg!(not(feature = "protobuf")) {
+            return Err(io::Error::other(
+                "protobuf round logs need the protobuf feature",
+            ));
+        }
+        let sink = if path.extension().is_some_and(|extension| extension == "gz") {
+            // Appended gzip streams still decompress as one file
+            Sink::Gzip(GzEncoder::new(file, Compression::default()))
+        } else {
+            Sink::Plain(file)
+        };
+        Ok(Self {
+            writer: BufWriter::new(sink),
+            protobuf,
+        })
+    }
+
+    /// Writes the summary of a round to the log.
+    pub fn write(&mut self, summary: &RoundSummary) -> io::Result<()> {
+        #[cfg(feature = "protobuf")]
+        if self.protobuf {
+            let message = blackjack_core::protobuf::encode_round(summary);
+            return blackjack_core::protobuf::write_delimited(&mut self.writer, &message);
+        }
+        #[cfg(not(feature = "protobuf"))]
+        debug_assert!(
+            !self.protobuf,
+            "protobuf logs can't be created without the feature"
+        );
+        writeln!(self.writer, "{}", summary.to_json())
+    }
+
+    /// Writes the session's notes to the head of the log.
+    /// Protobuf logs have no room for notes: they refuse a note or tags, and leave out the date and rules.
+    pub fn write_notes(&mut self, notes: &SessionNotes) -> io::Result<()> {
+        if self.protobuf {
+            if notes.note.is_empty() && notes.tags.is_empty() {
+                return Ok(());
+            }
+            return Err(io::Error::other(
+                "session notes need a JSON Lines round log",
+            ));
+        }
+        writeln!(self.writer, "{}", notes.to_json())
+    }
+
+    /// Writes everything logged so far to disk, returning the length of the file,
+    /// to reopen the log at if the simulation is resumed from here.
+    /// A compressed log finishes its gzip stream, and starts another for the rounds to come.
+    pub fn checkpoint(&mut self) -> io::Result<u64> {
+        self.writer.flush()?;
+        if let Sink::Gzip(gzip) = self.writer.get_mut() {
+            gzip.try_finish()?;
+            let file = gzip.get_ref().try_clone()?;
+            *gzip = GzEncoder::new(file, Compression::default());
+        }
+        Ok(self.writer.get_ref().file().metadata()?.len())
+    }
+
+    /// Reads a whole log, decompressing it if its name ends in ".gz".
+    pub fn read(path: &Path) -> io::Result<String> {
+        if path.extension().is_some_and(|extension| extension == "gz") {
+            let mut log = String::new();
+            MultiGzDecoder::new(File::open(path)?).read_to_string(&mut log)?;
+            Ok(log)
+        } else {
+            fs::read_to_string(path)
+        }
+    }
+
+    /// Flushes the log, finishing its compression if it is compressed.
+    pub fn finish(self) -> io::Result<()> {
+        match self
+            .writer
+            .into_inner()
+            .map_err(io::IntoInnerError::into_error)?
+        {
+            Sink::Plain(mut file) => file.flush(),
+            Sink::Gzip(gzip) => gzip.finish().map(drop),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reopen_compressed() {
+        let path = std::env::temp_dir().join(format!("round-log-{}.jsonl.gz", std::process::id()));
+        let notes = |note: &str| SessionNotes {
+            note: note.to_string(),
+            ..SessionNotes::default()
+        };
+        let mut log = RoundLog::create(&path).unwrap();
+        log.write_notes(&notes("kept")).unwrap();
+        let length = log.checkpoint().unwrap();
+        log.write_notes(&notes("lost")).unwrap();
+        log.finish().unwrap();
+
+        let mut log = RoundLog::reopen(&path, length).unwrap();
+        log.write_notes(&notes("resumed")).unwrap();
+        log.finish().unwrap();
+        let read = RoundLog::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        let notes: Vec<_> = read
+            .lines()
+            .map(|line| line.parse::<SessionNotes>().unwrap().note)
+            .collect();
+        assert_eq!(notes, ["kept", "resumed"]);
+    }
+}
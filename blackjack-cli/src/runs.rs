@@ -0,0 +1,280 @@
+//! A local store of past simulation runs, recording the settings that produced each result.
+//! Runs are kept in a text file, one `key = value` per line, with a blank line between runs.
+
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use blackjack_core::rules::Rules;
+
+/// A simulation run and the settings it was made with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Run {
+    /// The number of the run in its store, counting from 1
+    pub id: usize,
+    /// When the run started, in seconds since the Unix epoch
+    pub date: u64,
+    /// The git version of the code that made the run
+    pub version: String,
+    /// The table, in shorthand
+    pub table: String,
+    /// A hash of every rule of the table, to tell apart tables with the same shorthand
+    pub rules: String,
+    /// The playing and betting strategy
+    pub strategy: String,
+    /// The seed the shoes were shuffled with
+    pub seed: u64,
+    pub penetration: f32,
+    pub tables: usize,
+    pub rounds: usize,
+    /// How long the run took, in seconds
+    pub duration: f64,
+    pub net: i64,
+    /// The expected value per round played
+    pub ev: f64,
+    /// The standard error of the expected value
+    pub error: f64,
+}
+
+/// Returns a hash of the rules which stays the same between builds, unlike the standard hasher.
+pub fn rules_hash(rules: &Rules) -> String {
+    format!("{:016x}", rules.stable_hash())
+}
+
+impl Run {
+    /// The fields of the run by name, in the order they are written.
+    fn fields(&self) -> [(&'static str, String); 14] {
+        [
+            ("id", self.id.to_string()),
+            ("date", self.date.to_string()),
+            ("version", self.version.clone()),
+            ("table", self.table.clone()),
+            ("rules", self.rules.clone()),
+            ("strategy", self.strategy.clone()),
+            ("seed", self.seed.to_string()),
+            ("penetration", self.penetration.to_string()),
+            ("tables", self.tables.to_string()),
+            ("rounds", self.rounds.to_string()),
+            ("duration", format!("{:.3}", self.duration)),
+            ("net", self.net.to_string()),
+            ("ev", self.ev.to_string()),
+            ("error", self.error.to_string()),
+        ]
+    }
+}
+
+impl fmt::Display for Run {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, value) in self.fields() {
+            writeln!(f, "{name} = {value}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Run {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut values = std::collections::HashMap::new();
+        for line in s.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("expected key = value: {line}"))?;
+            values.insert(key.trim(), value.trim());
+        }
+        let get = |key: &str| {
+            values
+                .get(key)
+                .copied()
+                .ok_or_else(|| format!("missing {key}"))
+        };
+        fn parse<T: FromStr>(key: &str, value: &str) -> Result<T, String> {
+            value.parse().map_err(|_| format!("invalid {key}: {value}"))
+        }
+        Ok(Self {
+            id: parse("id", get("id")?)?,
+            date: parse("date", get("date")?)?,
+            version: get("version")?.to_string(),
+            table: get("table")?.to_string(),
+            rules: get("rules")?.to_string(),
+            strategy: get("strategy")?.to_string(),
+            seed: parse("seed", get("seed")?)?,
+            penetration: parse("penetration", get("penetration")?)?,
+            tables: parse("tables", get("tables")?)?,
+            rounds: parse("rounds", get("rounds")?)?,
+            duration: parse("duration", get("duration")?)?,
+            net: parse("net", get("net")?)?,
+            ev: parse("ev", get("ev")?)?,
+            error: parse("error", get("error")?)?,
+        })
+    }
+}
+
+/// The file past runs are stored in.
+#[derive(Debug, Clone)]
+pub struct RunStore {
+    pub path: PathBuf,
+}
+
+impl RunStore {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Returns every run in the store, oldest first. A missing store has no runs.
+    pub fn load(&self) -> Result<Vec<Run>, String> {
+        match fs::read_to_string(&self.path) {
+            Ok(contents) => parse_runs(&contents),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(format!("{}: {e}", self.path.display())),
+        }
+    }
+
+    /// Adds a run to the store, numbering it after the last run. Returns its number.
+    /// The store is locked while the run is numbered and written, so runs saved at once get their own numbers.
+    pub fn save(&self, mut run: Run) -> Result<usize, Box<dyn std::error::Error>> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&self.path)?;
+        file.lock()?;
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)?;
+        run.id = parse_runs(&contents)?.last().map_or(1, |last| last.id + 1);
+        writeln!(file, "{run}")?;
+        Ok(run.id)
+    }
+}
+
+/// Parses the runs in a store's contents.
+fn parse_runs(contents: &str) -> Result<Vec<Run>, String> {
+    contents
+        .split("\n\n")
+        .filter(|run| !run.trim().is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+/// Renders a table listing the runs, one per line.
+pub fn render_list(runs: &[Run]) -> String {
+    let mut list = format!(
+        "{:>4}  {:<14}  {:<20}  {:<32}  {:>12}  EV per Round\n",
+        "ID", "Version", "Table", "Strategy", "Rounds"
+    );
+    for run in runs {
+        list += &format!(
+            "{:>4}  {:<14}  {:<20}  {:<32}  {:>12}  {:+.4} ± {:.4}\n",
+            run.id, run.version, run.table, run.strategy, run.rounds, run.ev, run.error
+        );
+    }
+    list
+}
+
+/// Renders the runs side by side, marking the settings that differ between them with `*`.
+pub fn render_comparison(runs: &[Run]) -> String {
+    let fields: Vec<_> = runs.iter().map(Run::fields).collect();
+    let mut comparison = String::new();
+    for row in 0..fields.first().map_or(0, |first| first.len()) {
+        let name = fields[0][row].0;
+        let values: Vec<&str> = fields.iter().map(|run| run[row].1.as_str()).collect();
+        let differs = values.iter().any(|value| *value != values[0]);
+        comparison += &format!("{}{name:<12}", if differs { '*' } else { ' ' });
+        for value in values {
+            comparison += &format!("  {value:<24}");
+        }
+        comparison = comparison.trim_end().to_string() + "\n";
+    }
+    if let Some((first, others)) = runs.split_first() {
+        for run in others {
+            comparison += &format!(
+                "EV difference ({} - {}): {:+.4} ± {:.4} Chips per round\n",
+                run.id,
+                first.id,
+                run.ev - first.ev,
+                run.error.hypot(first.error)
+            );
+        }
+    }
+    comparison
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_runs() {
+        let run = Run {
+            id: 2,
+            date: 1_700_000_000,
+            version: "v0.1.0-3-gabcdef".to_string(),
+            table: "6D,S17,DAS,LS".to_string(),
+            rules: rules_hash(&Rules::default()),
+            strategy: "basic".to_string(),
+            seed: 7,
+            penetration: 0.75,
+            tables: 4,
+            rounds: 1_000_000,
+            duration: 1.5,
+            net: -4_200,
+            ev: -0.0042,
+            error: 0.0011,
+        };
+        assert_eq!(run.to_string().parse(), Ok(run.clone()));
+        assert!("id = 1\nseed = 7".parse::<Run>().is_err());
+        assert!("id 1".parse::<Run>().is_err());
+
+        let other = Run {
+            id: 3,
+            seed: 8,
+            ev: -0.0052,
+            ..run.clone()
+        };
+        let comparison = render_comparison(&[run, other]);
+        assert!(comparison.contains("*seed"));
+        assert!(comparison.contains(" table"));
+        assert!(comparison.contains("EV difference (3 - 2): -0.0010"));
+    }
+
+    #[test]
+    fn test_save_concurrently() {
+        let path = std::env::temp_dir().join(format!("runs-{}.txt", std::process::id()));
+        let store = RunStore::new(&path);
+        let run = Run {
+            id: 0,
+            date: 1_700_000_000,
+            version: "v0.1.0".to_string(),
+            table: "6D".to_string(),
+            rules: rules_hash(&Rules::default()),
+            strategy: "basic".to_string(),
+            seed: 7,
+            penetration: 0.75,
+            tables: 1,
+            rounds: 1_000,
+            duration: 0.1,
+            net: 0,
+            ev: 0.0,
+            error: 0.0,
+        };
+        let mut ids: Vec<usize> = std::thread::scope(|scope| {
+            let saves: Vec<_> = (0..8)
+                .map(|_| scope.spawn(|| store.save(run.clone()).unwrap()))
+                .collect();
+            saves.into_iter().map(|save| save.join().unwrap()).collect()
+        });
+        let stored: Vec<usize> = store.load().unwrap().iter().map(|run| run.id).collect();
+        fs::remove_file(&path).unwrap();
+        ids.sort_unstable();
+        assert_eq!(ids, (1..=8).collect::<Vec<_>>());
+        assert_eq!(stored.len(), 8);
+    }
+}
@@ -0,0 +1,925 @@
+//! A text server for playing Blackjack over telnet (or netcat), with a table of its own for each connection.
+//! The dealer narrates each round and the player answers the prompts one line at a time.
+//! Spectators may connect on a port of their own to watch any table, without any say in its play,
+//! and are told nothing of cards dealt face down until they are turned over.
+//!
+//! A player who disconnects keeps their seat for a grace period. The table finishes their round
+//! for them, as if every decision had timed out, and saves a snapshot of their chips and the shoe,
+//! which they can return to with the seat code they were given when they sat down.
+//!
+//! Every shoe is provably fair: the seed its cards are drawn with is committed to by its hash
+//! before the shoe is dealt, and revealed when it is shuffled away (see `blackjack verify`).
+//!
+//! Answers are checked strictly. Long lines, answers sent faster than the rate limit allows,
+//! and answers that don't fit the question are rejected with a protocol error, and a player
+//! who keeps sending them is removed from the table.
+//!
+//! A program may play instead of a person, such as the GUI attaching as a thin client, by greeting the server
+//! with the version of the text protocol it speaks (see the `blackjack-protocol` crate) in answer to the first question.
+//! The server greets it back, and from then on sends it the protocol's messages instead of plain text:
+//! narration, events, a snapshot of the table whenever it changes, prompts for each decision, and errors.
+//! It leaves by answering a bet with "q".
+//!
+//! The server seats a limited number of players, and a table takes a limited number of spectators.
+//! A player who doesn't answer for a long while is treated as disconnected,
+//! and a spectator who stops reading is dropped rather than holding up the table.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::mem;
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use blackjack_core::betting::BetPresets;
+use blackjack_core::card::Visibility;
+use blackjack_core::checkpoint::Checkpoint;
+use blackjack_core::events::GameEvent;
+use blackjack_core::fairness;
+use blackjack_core::game::{BotSeats, HandAction, Input, Table};
+use blackjack_core::narration::Narrator;
+use blackjack_core::preferences::Preferences;
+use blackjack_core::simulation::Report;
+use blackjack_core::state::GameState;
+use blackjack_core::statistics::Statistics;
+use blackjack_core::table_spec::TableSpec;
+use blackjack_protocol::{parse_answer, AnswerError, Event, Greeting, Message, Prompt, Snapshot};
+
+use crate::leaderboard::{self, Leaderboard, Session};
+
+/// The longest answer read from a player, in bytes.
+const MAX_LINE: usize = 64;
+
+/// The number of answers a player may send at once, and the rate at which more are allowed, per second.
+const BURST: f64 = 5.0;
+const RATE: f64 = 2.0;
+
+/// The number of protocol errors in a row after which a player is removed from the table.
+const MAX_STRIKES: usize = 10;
+
+/// The most players seated at once, counting the seats kept for disconnected players.
+const MAX_PLAYERS: usize = 64;
+
+/// The most spectators watching a table at once.
+const MAX_SPECTATORS: usize = 16;
+
+/// How long a player may take to answer before they are treated as disconnected.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// How long a write to a spectator may block before the spectator is dropped,
+/// so that one who stops reading can't hold up the table.
+const SPECTATOR_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The table each player sits down at.
+#[derive(Debug, Clone)]
+pub struct Seating {
+    pub spec: TableSpec,
+    /// Each player's starting bankroll
+    pub chips: u32,
+    /// The bots seated beside each player
+    pub bots: Vec<BotSeats>,
+    /// The preferences every player's routine decisions are made with
+    pub preferences: Preferences,
+}
+
+impl Seating {
+    /// Returns a new table with the player's bankroll and the bots seated, recording its events.
+    fn table(&self) -> Table {
+        let mut table = self.spec.table(self.chips);
+        table.record_events = true;
+        table.seat_bots(&self.bots);
+        table
+    }
+}
+
+/// The spectators of each open table, by table number.
+type Spectators = Arc<Mutex<BTreeMap<usize, Vec<TcpStream>>>>;
+
+/// A seat kept for a disconnected player.
+#[derive(Debug)]
+struct Reservation {
+    player: String,
+    /// When the seat is given up
+    expires: Instant,
+    /// A snapshot of the table to resume from
+    snapshot: Checkpoint,
+}
+
+/// The seats kept for disconnected players, by seat code.
+type Reservations = Arc<Mutex<HashMap<String, Reservation>>>;
+
+/// Serves a table to everyone who connects, and lets spectators connecting to the watch address
+/// watch them, until the server is stopped. Disconnected players keep their seat for `grace`.
+/// Each session is added to the leaderboard, if there is one, once its seat is given up.
+/// # Errors
+/// Returns an error if the bots don't all fit at the table, or an address can't be listened on.
+pub fn serve(
+    address: impl ToSocketAddrs,
+    watch_address: Option<impl ToSocketAddrs>,
+    seating: &Seating,
+    grace: Duration,
+    leaderboard: Option<Leaderboard>,
+    narrator: &Narrator,
+) -> io::Result<()> {
+    let seated = seating.table().bots.len();
+    if seated < seating.bots.iter().map(|seats| seats.count).sum() {
+        let error = format!("at most {seated} bots fit beside the player");
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, error));
+    }
+    let listener = TcpListener::bind(address)?;
+    eprintln!("Serving Blackjack on {}", listener.local_addr()?);
+    let spectators = Spectators::default();
+    let reservations = Reservations::default();
+    if let Some(watch_address) = watch_address {
+        let listener = TcpListener::bind(watch_address)?;
+        eprintln!("Spectators may watch on {}", listener.local_addr()?);
+        let spectators = spectators.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().map_while(Result::ok) {
+                let spectators = spectators.clone();
+                thread::spawn(move || watch(stream, &spectators));
+            }
+        });
+    }
+    let players = Arc::new(AtomicUsize::new(0));
+    for (number, stream) in (1..).zip(listener.incoming()) {
+        let mut stream = stream?;
+        let Some(seat) = Occupied::take(&players) else {
+            let _ = writeln!(stream, "The tables are full. Please try again later.\r");
+            continue;
+        };
+        let seating = seating.clone();
+        let narrator = narrator.clone();
+        let spectators = spectators.clone();
+        let reservations = reservations.clone();
+        let leaderboard = leaderboard.clone();
+        thread::spawn(move || {
+            // The seat is given back once the thread ends, however the player left
+            let _seat = seat;
+            let peer = stream.peer_addr().map(|peer| peer.to_string());
+            let peer = peer.unwrap_or_default();
+            let Ok(mut player) = Player::new(stream) else {
+                return;
+            };
+            let Ok((code, name, mut table)) =
+                player.sit(&seating, &reservations, leaderboard.as_ref())
+            else {
+                return;
+            };
+            let record = move |name: String, table_chips: u32, statistics: &Statistics| {
+                // Players who never played a hand don't make the leaderboard
+                let Some(leaderboard) = leaderboard
+                    .as_ref()
+                    .filter(|_| statistics.hands_played() > 0)
+                else {
+                    return;
+                };
+                let session = Session {
+                    player: name,
+                    date: SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map_or(0, |date| date.as_secs()),
+                    net: i64::from(table_chips) - i64::from(seating.chips),
+                    hands: statistics.hands_played(),
+                };
+                if let Err(e) = leaderboard.save(&session) {
+                    eprintln!("{}: {e}", leaderboard.path.display());
+                }
+            };
+            eprintln!("{peer} ({name}) sat down at table {number}");
+            spectators.lock().unwrap().insert(number, Vec::new());
+            let departure = play(
+                player,
+                &mut table,
+                &narrator,
+                &seating.preferences,
+                |line| {
+                    // The spectators are written to outside the lock, so a slow one only holds up this table,
+                    // and spectators who have gone are dropped from it
+                    let mut watching = spectators
+                        .lock()
+                        .unwrap()
+                        .get_mut(&number)
+                        .map(mem::take)
+                        .unwrap_or_default();
+                    watching.retain_mut(|spectator| writeln!(spectator, "{line}\r").is_ok());
+                    if let Some(joined) = spectators.lock().unwrap().get_mut(&number) {
+                        joined.append(&mut watching);
+                    }
+                },
+            );
+            spectators.lock().unwrap().remove(&number);
+            if departure == Departure::Disconnected {
+                let snapshot = Checkpoint {
+                    seed: None,
+                    chips: table.chips,
+                    last_bet: table.last_bet(),
+                    last_net: table.last_net(),
+                    coupon: table.coupon,
+                    heat: None,
+                    heat_last_bet: None,
+                    seated: true,
+                    session_rounds: 0,
+                    session_chips: table.chips,
+                    shoe: table.shoe.clone(),
+                    statistics: table.statistics.clone(),
+                    report: Report::default(),
+                    strategy: BTreeMap::new(),
+                    outputs: BTreeMap::new(),
+                };
+                let reservation = Reservation {
+                    player: name,
+                    expires: Instant::now() + grace,
+                    snapshot,
+                };
+                reservations
+                    .lock()
+                    .unwrap()
+                    .insert(code.clone(), reservation);
+                eprintln!("{peer} disconnected, keeping their seat");
+                // The session ends when the seat is given up, unless the player has returned
+                // (and maybe been disconnected again, keeping their seat for longer)
+                thread::sleep(grace);
+                let mut reservations = reservations.lock().unwrap();
+                let expired = reservations
+                    .get(&code)
+                    .is_some_and(|seat| seat.expires <= Instant::now());
+                let reservation = expired.then(|| reservations.remove(&code)).flatten();
+                drop(reservations);
+                if let Some(Reservation {
+                    player, snapshot, ..
+                }) = reservation
+                {
+                    record(player, snapshot.chips, &snapshot.statistics);
+                }
+                return;
+            }
+            if departure == Departure::Removed {
+                eprintln!("{peer} was removed for sending too many rejected answers");
+            } else {
+                eprintln!("{peer} left");
+            }
+            record(name, table.chips, &table.statistics);
+        });
+    }
+    Ok(())
+}
+
+/// One of the server's seats for players, given back when dropped.
+struct Occupied(Arc<AtomicUsize>);
+
+impl Occupied {
+    /// Takes a seat, if fewer than `MAX_PLAYERS` are taken.
+    fn take(players: &Arc<AtomicUsize>) -> Option<Self> {
+        players
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |taken| {
+                (taken < MAX_PLAYERS).then_some(taken + 1)
+            })
+            .ok()
+            .map(|_| Self(Arc::clone(players)))
+    }
+}
+
+impl Drop for Occupied {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Asks a spectator which table to watch, and adds them to its spectators.
+fn watch(mut stream: TcpStream, spectators: &Spectators) -> io::Result<()> {
+    stream.set_read_timeout(Some(IDLE_TIMEOUT))?;
+    let mut lines = BufReader::new(stream.try_clone()?).lines();
+    loop {
+        let tables: Vec<_> = spectators
+            .lock()
+            .unwrap()
+            .keys()
+            .map(usize::to_string)
+            .collect();
+        if tables.is_empty() {
+            write!(stream, "No tables are open. Press enter to look again: ")?;
+        } else {
+            let tables = tables.join(", ");
+            write!(
+                stream,
+                "Open tables: {tables}. Which would you like to watch? "
+            )?;
+        }
+        stream.flush()?;
+        let Some(line) = lines.next().transpose()? else {
+            return Ok(());
+        };
+        let Ok(number) = line.trim().parse() else {
+            continue;
+        };
+        let mut tables = spectators.lock().unwrap();
+        let Some(watching) = tables.get_mut(&number) else {
+            continue;
+        };
+        if watching.len() >= MAX_SPECTATORS {
+            drop(tables);
+            writeln!(
+                stream,
+                "Table {number} has all the spectators it can take.\r"
+            )?;
+            continue;
+        }
+        stream.set_write_timeout(Some(SPECTATOR_TIMEOUT))?;
+        writeln!(stream, "Watching table {number}.\r")?;
+        watching.push(stream);
+        return Ok(());
+    }
+}
+
+/// Narrates an event for spectators, who don't see cards dealt face down.
+fn narrate_for_spectators(narrator: &Narrator, event: &GameEvent) -> Option<String> {
+    match event {
+        GameEvent::PlayerCard {
+            visibility: Visibility::FaceDown,
+            ..
+        } => Some(narrator.catalog.get("narration.face-down-card").to_string()),
+        event => narrator.narrate(event),
+    }
+}
+
+/// Why an answer from a player was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ProtocolError {
+    /// The answer was longer than `MAX_LINE`
+    LineTooLong,
+    /// The answer wasn't text
+    NotText,
+    /// The answer came faster than the rate limit allows
+    TooFast,
+    /// The answer doesn't answer the question asked
+    Answer(AnswerError),
+}
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LineTooLong => write!(f, "answers must be at most {MAX_LINE} characters"),
+            Self::NotText => write!(f, "answers must be text"),
+            Self::TooFast => write!(f, "too many answers, slow down"),
+            Self::Answer(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+/// A player's connection.
+struct Player {
+    input: BufReader<TcpStream>,
+    output: TcpStream,
+    /// The answers the player may send before being rate limited
+    tokens: f64,
+    refilled: Instant,
+    /// The number of protocol errors in a row
+    strikes: usize,
+    /// Whether the player greeted the server, and is sent protocol messages instead of plain text
+    speaks_protocol: bool,
+}
+
+impl Player {
+    fn new(stream: TcpStream) -> io::Result<Self> {
+        stream.set_read_timeout(Some(IDLE_TIMEOUT))?;
+        stream.set_write_timeout(Some(IDLE_TIMEOUT))?;
+        Ok(Self {
+            input: BufReader::new(stream.try_clone()?),
+            output: stream,
+            tokens: BURST,
+            refilled: Instant::now(),
+            strikes: 0,
+            speaks_protocol: false,
+        })
+    }
+
+    fn say(&mut self, line: &str) -> io::Result<()> {
+        if self.speaks_protocol {
+            self.send(&Message::Say(line.to_string()))
+        } else {
+            writeln!(self.output, "{line}\r")
+        }
+    }
+
+    fn send(&mut self, message: &Message) -> io::Result<()> {
+        writeln!(self.output, "{message}\r")
+    }
+
+    /// Tells the player an answer couldn't be played, as an error message if they speak the protocol.
+    fn say_error(&mut self, error: &str) -> io::Result<()> {
+        if self.speaks_protocol {
+            self.send(&Message::Error(error.to_string()))
+        } else {
+            self.say(error)
+        }
+    }
+
+    /// Returns a question answered in words, as a prompt if the player speaks the protocol.
+    fn question(&self, text: &str) -> String {
+        if self.speaks_protocol {
+            Prompt::Text(text.to_string()).to_string()
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// Reads a line, reading no more than `MAX_LINE` bytes of it into memory.
+    /// Returns `None` at the end of the input.
+    fn read_line(&mut self) -> io::Result<Option<Result<String, ProtocolError>>> {
+        let mut line = Vec::new();
+        let limit = MAX_LINE as u64 + 1;
+        if (&mut self.input).take(limit).read_until(b'\n', &mut line)? == 0 {
+            return Ok(None);
+        }
+        if line.len() > MAX_LINE && line.last() != Some(&b'\n') {
+            // The rest of the line is skipped
+            while !line.is_empty() && line.last() != Some(&b'\n') {
+                line.clear();
+                (&mut self.input).take(limit).read_until(b'\n', &mut line)?;
+            }
+            return Ok(Some(Err(ProtocolError::LineTooLong)));
+        }
+        Ok(Some(
+            String::from_utf8(line).map_err(|_| ProtocolError::NotText),
+        ))
+    }
+
+    /// Takes one of the player's tokens for an answer, returning whether there was one.
+    fn take_token(&mut self) -> bool {
+        let now = Instant::now();
+        let refill = now.duration_since(self.refilled).as_secs_f64() * RATE;
+        self.tokens = (self.tokens + refill).min(BURST);
+        self.refilled = now;
+        if self.tokens < 1.0 {
+            return false;
+        }
+        self.tokens -= 1.0;
+        true
+    }
+
+    /// Asks the player a question, returning their answer, or `None` if they have gone.
+    /// Prompts are sent on a line of their own to players who speak the protocol.
+    fn ask(&mut self, prompt: &str) -> Option<Result<String, ProtocolError>> {
+        if self.speaks_protocol {
+            writeln!(self.output, "{prompt}\r").ok()?;
+        } else {
+            write!(self.output, "{prompt} ").ok()?;
+        }
+        self.output.flush().ok()?;
+        let answer = self.read_line().ok()??;
+        if !self.take_token() {
+            return Some(Err(ProtocolError::TooFast));
+        }
+        Some(answer.map(|answer| answer.trim().to_ascii_lowercase()))
+    }
+
+    /// Tells the player why their answer was rejected, returning whether they have had too many strikes.
+    fn reject(&mut self, error: &ProtocolError) -> bool {
+        self.strikes += 1;
+        let _ = if self.speaks_protocol {
+            self.send(&Message::Error(error.to_string()))
+        } else {
+            self.say(&format!("Error: {error}."))
+        };
+        self.strikes >= MAX_STRIKES
+    }
+
+    /// Seats the player at a new table, or at their reserved one if they give its seat code.
+    /// A player who greets the server instead is greeted back, if it speaks their version of the protocol,
+    /// and asked again. Returns the seat code, the player's name, and the table.
+    fn sit(
+        &mut self,
+        seating: &Seating,
+        reservations: &Reservations,
+        leaderboard: Option<&Leaderboard>,
+    ) -> io::Result<(String, String, Table)> {
+        let prompt = "Enter your seat code to return to your table, or press enter for a new one:";
+        let mut code = self
+            .ask(prompt)
+            .ok_or(io::ErrorKind::UnexpectedEof)?
+            .unwrap_or_default();
+        if let Ok(greeting) = code.parse::<Greeting>() {
+            // The prompt was left waiting for an answer on its line
+            writeln!(self.output, "\r")?;
+            self.speaks_protocol = true;
+            if let Err(error) = greeting.check() {
+                self.send(&Message::Error(error.clone()))?;
+                return Err(io::Error::new(io::ErrorKind::InvalidData, error));
+            }
+            self.send(&Message::Greeting(Greeting::CURRENT))?;
+            code = self
+                .ask(&self.question(prompt))
+                .ok_or(io::ErrorKind::UnexpectedEof)?
+                .unwrap_or_default();
+        }
+        let reserved = reservations.lock().unwrap().remove(&code);
+        let mut table = seating.table();
+        if let Some(reservation) = reserved.filter(|seat| seat.expires > Instant::now()) {
+            table.chips = reservation.snapshot.chips;
+            table.shoe = reservation.snapshot.shoe;
+            table.statistics = reservation.snapshot.statistics;
+            self.say(&format!("Welcome back, {}.", reservation.player))?;
+            return Ok((code, reservation.player, table));
+        }
+        let name = self
+            .ask(&self.question("What's your name?"))
+            .ok_or(io::ErrorKind::UnexpectedEof)?
+            .unwrap_or_default();
+        let name: String = name
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+            .take(16)
+            .collect();
+        let name = if name.is_empty() {
+            "guest".to_string()
+        } else {
+            name
+        };
+        if let Some(leaderboard) = leaderboard {
+            let standings = leaderboard.standings().unwrap_or_default();
+            if !standings.is_empty() {
+                for line in leaderboard::render(&standings[..standings.len().min(5)]).lines() {
+                    self.say(line)?;
+                }
+            }
+        }
+        let code = seat_code();
+        self.say(&format!(
+            "Your seat code is {code}. If you're disconnected, use it to return to your table."
+        ))?;
+        Ok((code, name, table))
+    }
+}
+
+/// Returns a new seat code: 128 bits from the operating system's source of randomness, in hex,
+/// so that a seat can't be taken over by guessing its code.
+fn seat_code() -> String {
+    format!(
+        "{:016x}{:016x}",
+        fairness::random_seed(),
+        fairness::random_seed()
+    )
+}
+
+/// How a player's time at a table ended.
+#[derive(Debug, PartialEq, Eq)]
+enum Departure {
+    /// The player left, or went broke
+    Left,
+    /// The player's connection was lost, and their round finished without them
+    Disconnected,
+    /// The player was removed for sending too many answers that were rejected,
+    /// and their round finished without them
+    Removed,
+}
+
+/// Plays at the table with one connection until the player leaves, goes broke, or is disconnected,
+/// passing what spectators may see of the table to `spectate`.
+/// The preferences make the player's routine decisions, and hold back the ones they confirm.
+/// A disconnected or removed player's round is finished as if every decision had timed out.
+fn play(
+    mut player: Player,
+    table: &mut Table,
+    narrator: &Narrator,
+    preferences: &Preferences,
+    mut spectate: impl FnMut(&str),
+) -> Departure {
+    let catalog = &narrator.catalog;
+    let mut connected = true;
+    let mut removed = false;
+    // A returning player's shoe is shuffled, so that every shoe they play is committed to
+    let mut seed = fairness::random_seed();
+    table.shoe.shuffle();
+    table.shoe.reseed(seed);
+    let commitment = fairness::commitment(seed);
+    let mut announcements = vec![format!("This shoe is committed to as {commitment}.")];
+    let mut state = table.betting();
+    let mut snapshot = None;
+    loop {
+        for line in announcements.drain(..) {
+            connected = connected && player.say(&line).is_ok();
+            spectate(&line);
+        }
+        let mut shuffled = false;
+        for event in table.drain_events() {
+            if let Some(line) = narrator.narrate(&event) {
+                connected = connected && player.say(&line).is_ok();
+            }
+            if player.speaks_protocol {
+                for event in Event::from_game(&event) {
+                    connected = connected && player.send(&Message::Event(event)).is_ok();
+                }
+            }
+            if let Some(line) = narrate_for_spectators(narrator, &event) {
+                spectate(&line);
+            }
+            shuffled |= event == GameEvent::Shuffled;
+        }
+        if player.speaks_protocol {
+            // The table is sent whenever it changes, before the prompt it is asked about
+            let current = Snapshot::new(table, &state);
+            if snapshot.as_ref() != Some(&current) {
+                connected = connected && player.send(&Message::Snapshot(current.clone())).is_ok();
+                snapshot = Some(current);
+            }
+        }
+        if shuffled {
+            announcements.push(format!(
+                "The last shoe was dealt with the seed {seed}, committed to as {}.",
+                fairness::commitment(seed)
+            ));
+            seed = fairness::random_seed();
+            table.shoe.reseed(seed);
+            announcements.push(format!(
+                "The next shoe is committed to as {}.",
+                fairness::commitment(seed)
+            ));
+            continue;
+        }
+        let prompt = match &state {
+            GameState::GameOver => return Departure::Left,
+            GameState::Betting { .. } if removed => {
+                table.withdraw_bets();
+                return Departure::Removed;
+            }
+            GameState::Betting { .. } if !connected => {
+                table.withdraw_bets();
+                return Departure::Disconnected;
+            }
+            state if player.speaks_protocol => Prompt::new(table, state)
+                .as_ref()
+                .map_or_else(String::new, Prompt::to_string),
+            GameState::Betting { last_bet: None } => format!(
+                "\r\n{} chips. Enter your bet, or (q) to leave:",
+                table.chips
+            ),
+            GameState::Betting {
+                last_bet: Some(last_bet),
+            } => format!(
+                "\r\n{} chips. Enter your bet, (same) to bet {last_bet} again, or (q) to leave:",
+                table.chips
+            ),
+            GameState::OfferEarlySurrender { .. } => catalog.get("prompt.surrender").to_string(),
+            GameState::OfferInsurance { player_hand, .. } => {
+                let max = table.max_insurance(player_hand);
+                catalog.format("prompt.insurance", &[("max", &max), ("input", &"")])
+            }
+            GameState::PlayPlayerTurn { player_turn, .. } => {
+                let actions: Vec<_> = preferences
+                    .offered_actions(table, player_turn)
+                    .into_iter()
+                    .map(|action| catalog.action(action))
+                    .collect();
+                format!("{}:", actions.join(", "))
+            }
+            _ => String::new(),
+        };
+        let input = if prompt.is_empty() {
+            None
+        } else if !connected || removed {
+            Some(Input::Timeout)
+        } else if let Some(input) = preferences.auto_input(&state) {
+            Some(input)
+        } else {
+            let Some(answer) = player.ask(&prompt) else {
+                connected = false;
+                continue;
+            };
+            if answer.as_deref() == Ok("q") && matches!(state, GameState::Betting { .. }) {
+                table.withdraw_bets();
+                return Departure::Left;
+            }
+            let presets = BetPresets::default();
+            match answer.and_then(|answer| {
+                parse_answer(table, &state, &answer, &presets).map_err(ProtocolError::Answer)
+            }) {
+                Ok(input) => {
+                    player.strikes = 0;
+                    let confirmed = match confirmation(preferences, &state, input) {
+                        Some(action) => {
+                            let action = catalog.action(action);
+                            let question = catalog.format("prompt.confirm", &[("action", &action)]);
+                            player.ask(&player.question(&question))
+                        }
+                        None => Some(Ok("y".to_string())),
+                    };
+                    match confirmed {
+                        Some(Ok(answer)) if answer == "y" => Some(input),
+                        Some(_) => continue,
+                        None => {
+                            connected = false;
+                            continue;
+                        }
+                    }
+                }
+                Err(error) => {
+                    removed = player.reject(&error);
+                    continue;
+                }
+            }
+        };
+        let current = mem::take(&mut state);
+        state = match table.progress(current, input) {
+            Ok(state) => state,
+            Err((state, error)) => {
+                if input.is_some() {
+                    connected = connected && player.say_error(catalog.error(&error)).is_ok();
+                }
+                state
+            }
+        };
+    }
+}
+
+/// Returns the action the player must confirm before the input is played, if the preferences
+/// hold it back: a double down or surrender, including an early one.
+fn confirmation(preferences: &Preferences, state: &GameState, input: Input) -> Option<HandAction> {
+    let action = match (state, input) {
+        (GameState::OfferEarlySurrender { .. }, Input::Choice { choice: true, .. }) => {
+            HandAction::Surrender
+        }
+        (_, Input::Action { action, .. }) => action,
+        _ => return None,
+    };
+    preferences.confirm_required(action).then_some(action)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    /// Connects a player to a socket on the local machine, returning the player and the client's end.
+    fn connect() -> (Player, TcpStream) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (server, _) = listener.accept().unwrap();
+        (Player::new(server).unwrap(), client)
+    }
+
+    #[test]
+    fn test_sit() {
+        let seating = Seating {
+            spec: "6D".parse().unwrap(),
+            chips: 1000,
+            bots: vec!["1".parse().unwrap(), "1:mimic".parse().unwrap()],
+            preferences: Preferences::default(),
+        };
+        let reservations = Reservations::default();
+        let (mut player, mut client) = connect();
+        client.write_all(b"\r\nduck!\r\n").unwrap();
+        let (code, name, table) = player.sit(&seating, &reservations, None).unwrap();
+        assert_eq!(code.len(), 32);
+        assert!(code.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(name, "duck");
+        assert_eq!((table.chips, table.bots.len()), (1000, 2));
+
+        // The seat code brings a returning player back to their table
+        let mut snapshot = Checkpoint {
+            seed: None,
+            chips: 1500,
+            last_bet: None,
+            last_net: None,
+            coupon: None,
+            heat: None,
+            heat_last_bet: None,
+            seated: true,
+            session_rounds: 0,
+            session_chips: 1500,
+            shoe: table.shoe.clone(),
+            statistics: Statistics::new(),
+            report: Report::default(),
+            strategy: BTreeMap::new(),
+            outputs: BTreeMap::new(),
+        };
+        let reservation = Reservation {
+            player: name,
+            expires: Instant::now() + Duration::from_secs(60),
+            snapshot: snapshot.clone(),
+        };
+        reservations
+            .lock()
+            .unwrap()
+            .insert(code.clone(), reservation);
+        let (mut player, mut client) = connect();
+        writeln!(client, "{code}\r").unwrap();
+        let (_, name, table) = player.sit(&seating, &reservations, None).unwrap();
+        assert_eq!((name.as_str(), table.chips), ("duck", 1500));
+        assert!(reservations.lock().unwrap().is_empty());
+
+        // An expired seat is given up, and the player sits at a new table
+        snapshot.chips = 2000;
+        let reservation = Reservation {
+            player: "duck".to_string(),
+            expires: Instant::now(),
+            snapshot,
+        };
+        reservations
+            .lock()
+            .unwrap()
+            .insert(code.clone(), reservation);
+        let (mut player, mut client) = connect();
+        write!(client, "{code}\r\ngoose\r\n").unwrap();
+        let (new_code, name, table) = player.sit(&seating, &reservations, None).unwrap();
+        assert_ne!(new_code, code);
+        assert_eq!((name.as_str(), table.chips), ("goose", 1000));
+        assert_ne!(seat_code(), seat_code());
+
+        // A program greeting the server is greeted back and sent protocol messages
+        let (mut player, mut client) = connect();
+        write!(client, "{}\r\n\r\nswan\r\n", Greeting::CURRENT).unwrap();
+        let (_, name, _) = player.sit(&seating, &reservations, None).unwrap();
+        assert_eq!(name, "swan");
+        drop(player);
+        let messages: Vec<_> = BufReader::new(client)
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|line| line.parse::<Message>().ok())
+            .collect();
+        assert_eq!(messages[0], Message::Greeting(Greeting::CURRENT));
+        assert!(matches!(&messages[1], Message::Prompt(Prompt::Text(_))));
+        assert_eq!(
+            messages[2],
+            Message::Prompt(Prompt::Text("What's your name?".to_string()))
+        );
+        assert!(matches!(&messages[3], Message::Say(line) if line.starts_with("Your seat code")));
+
+        // and turned away if it speaks another version
+        let (mut player, mut client) = connect();
+        write!(client, "{}\r\n", Greeting { version: 0 }).unwrap();
+        assert!(player.sit(&seating, &reservations, None).is_err());
+    }
+
+    #[test]
+    fn test_removed_mid_round() {
+        use blackjack_core::card::shoe::Shoe;
+        use blackjack_core::card::Rank;
+        use blackjack_core::i18n::Locale;
+        use blackjack_core::narration::Personality;
+        use blackjack_core::rules::Rules;
+
+        // A player removed during their turn has the round finished for them, as if they had left
+        let (player, mut client) = connect();
+        let mut table = Table::new(1000, Shoe::new(6, 0.75), Rules::default());
+        table
+            .shoe
+            .force([Rank::Ten, Rank::Nine, Rank::Six, Rank::Seven]);
+        client.write_all(b"100\n").unwrap();
+        for _ in 0..MAX_STRIKES {
+            client.write_all(b"huh\n").unwrap();
+        }
+        let narrator = Narrator::new(Personality::Professional, Locale::English);
+        let departure = play(
+            player,
+            &mut table,
+            &narrator,
+            &Preferences::default(),
+            |_| {},
+        );
+        assert_eq!(departure, Departure::Removed);
+        assert_eq!(table.statistics.turns_played(), 1);
+        assert!(table.last_net().is_some());
+    }
+
+    #[test]
+    fn test_seats() {
+        let players = Arc::new(AtomicUsize::new(0));
+        let mut seats: Vec<_> = (0..MAX_PLAYERS)
+            .map_while(|_| Occupied::take(&players))
+            .collect();
+        assert_eq!(seats.len(), MAX_PLAYERS);
+        assert!(Occupied::take(&players).is_none());
+        seats.pop();
+        assert!(Occupied::take(&players).is_some());
+        drop(seats);
+        assert_eq!(players.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_protocol_errors() {
+        let (mut player, mut client) = connect();
+        let long = "1".repeat(MAX_LINE * 2);
+        write!(client, "{long}\n100\n").unwrap();
+        client.write_all(b"\xff\n").unwrap();
+        assert_eq!(player.ask(">"), Some(Err(ProtocolError::LineTooLong)));
+        assert_eq!(player.ask(">"), Some(Ok("100".to_string())));
+        assert_eq!(player.ask(">"), Some(Err(ProtocolError::NotText)));
+
+        // A burst of answers is allowed, then answers are limited to the rate
+        for _ in 0..10 {
+            client.write_all(b"s\n").unwrap();
+        }
+        let answers: Vec<_> = (0..10).map(|_| player.ask(">").unwrap()).collect();
+        assert!(answers[..2].iter().all(Result::is_ok));
+        assert!(answers.contains(&Err(ProtocolError::TooFast)));
+
+        assert!((1..MAX_STRIKES).all(|_| !player.reject(&ProtocolError::TooFast)));
+        assert!(player.reject(&ProtocolError::TooFast));
+        drop(client);
+        assert_eq!(player.ask(">"), None);
+    }
+}
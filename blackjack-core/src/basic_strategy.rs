@@ -6,7 +6,32 @@
 
 use crate::card::hand::{DealerHand, PlayerHand, PlayerTurn};
 use crate::composed;
-use crate::game::{HandAction, Table};
+use crate::game::{HandAction, Input, Table};
+use crate::state::GameState;
+
+/// Returns the input basic strategy would give in the given state,
+/// or None if the state does not require any input.
+#[must_use]
+pub fn input(table: &Table, state: &GameState) -> Option<Input> {
+    match state {
+        GameState::Betting { .. } => Some(Input::bet(bet())),
+        GameState::OfferEarlySurrender {
+            player_hand,
+            dealer_hand,
+        } => Some(Input::choice(surrender_early(
+            table,
+            player_hand,
+            dealer_hand,
+        ))),
+        GameState::OfferInsurance { .. } => Some(Input::bet(bet_insurance())),
+        GameState::PlayPlayerTurn {
+            player_turn,
+            dealer_hand,
+            ..
+        } => Some(Input::action(play_hand(table, player_turn, dealer_hand))),
+        _ => None,
+    }
+}
 
 #[must_use]
 pub const fn bet() -> u32 {
@@ -50,12 +75,10 @@ pub fn surrender_early(table: &Table, player_hand: &PlayerHand, dealer_hand: &De
 
 /// Source: <https://wizardofodds.com/games/blackjack/surrender/>
 fn surrender_early_hard(player_hand: &PlayerHand, dealer_hand: &DealerHand) -> bool {
-    match (player_hand.value.total, dealer_hand.showing()) {
-        (5..=7, 11) => true,
-        (12..=17, 11) => true,
-        (14..=16, 10) => true,
-        _ => false,
-    }
+    matches!(
+        (player_hand.value.total, dealer_hand.showing()),
+        (5..=7, 11) | (12..=17, 11) | (14..=16, 10)
+    )
 }
 
 /// Source: <https://wizardofodds.com/games/blackjack/surrender/>
@@ -93,9 +116,9 @@ pub fn play_hand(table: &Table, player_hands: &PlayerTurn, dealer_hand: &DealerH
         player_hands.current_hand().value.soft,
         table.check_split_allowed(player_hands).is_ok(),
     ) {
-        (false, false) => make_move_hard(table, &player_hands.current_hand(), dealer_hand),
-        (true, false) => make_move_soft(&player_hands.current_hand(), dealer_hand),
-        (_, true) => make_move_splittable(&player_hands.current_hand(), dealer_hand),
+        (false, false) => make_move_hard(table, player_hands.current_hand(), dealer_hand),
+        (true, false) => make_move_soft(player_hands.current_hand(), dealer_hand),
+        (_, true) => make_move_splittable(player_hands.current_hand(), dealer_hand),
     };
     match preferred {
         PreferredAction::Stand => HandAction::Stand,
@@ -117,7 +140,7 @@ pub fn play_hand(table: &Table, player_hands: &PlayerTurn, dealer_hand: &DealerH
         }
         PreferredAction::SurrenderOrHit => {
             if table
-                .check_surrender_allowed(&player_hands.current_hand())
+                .check_surrender_allowed(player_hands.current_hand())
                 .is_ok()
             {
                 HandAction::Surrender
@@ -127,7 +150,7 @@ pub fn play_hand(table: &Table, player_hands: &PlayerTurn, dealer_hand: &DealerH
         }
         PreferredAction::SurrenderOrStand => {
             if table
-                .check_surrender_allowed(&player_hands.current_hand())
+                .check_surrender_allowed(player_hands.current_hand())
                 .is_ok()
             {
                 HandAction::Surrender
@@ -137,7 +160,7 @@ pub fn play_hand(table: &Table, player_hands: &PlayerTurn, dealer_hand: &DealerH
         }
         PreferredAction::SurrenderOrSplit => {
             if table
-                .check_surrender_allowed(&player_hands.current_hand())
+                .check_surrender_allowed(player_hands.current_hand())
                 .is_ok()
             {
                 HandAction::Surrender
@@ -214,6 +237,7 @@ fn make_move_hard(
 
 fn make_move_soft(player_hand: &PlayerHand, dealer_hand: &DealerHand) -> PreferredAction {
     match (player_hand.value.total, dealer_hand.showing()) {
+        (12, 2..=11) => PreferredAction::Hit, // A pair of aces that can't be split
         (13 | 14, 2..=4) => PreferredAction::Hit,
         (13 | 14, 5 | 6) => PreferredAction::DoubleOrHit,
         (15 | 16, 2 | 3) => PreferredAction::Hit,
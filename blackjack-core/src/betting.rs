@@ -0,0 +1,903 @@
+//! Betting strategies decide how much the player bets on each round,
+//! independently of how the hands are played.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::card::shoe::Composition;
+use crate::counting::{self, TrueCount};
+use crate::game::{Input, Table};
+use crate::rules::Rules;
+use crate::state::GameState;
+use crate::strategy::Strategy;
+
+/// Decides the bet for the next round.
+pub trait BettingStrategy {
+    /// Returns the bet for the next round at the table.
+    fn bet(&mut self, table: &Table) -> u32;
+
+    /// Learns the net result of the last round bet on, for strategies which depend on it.
+    fn settle(&mut self, _net: i64) {}
+
+    /// Saves what the strategy has learned from the rounds so far, e.g. the step of a progression,
+    /// as `key = value` pairs for a checkpoint.
+    fn save(&self, _state: &mut BTreeMap<String, String>) {}
+
+    /// Restores what the strategy had learned from the pairs it saved.
+    /// # Errors
+    /// Returns an error if a saved value is missing or invalid.
+    fn restore(&mut self, _state: &BTreeMap<String, String>) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+impl<B: BettingStrategy + ?Sized> BettingStrategy for Box<B> {
+    fn bet(&mut self, table: &Table) -> u32 {
+        (**self).bet(table)
+    }
+
+    fn settle(&mut self, net: i64) {
+        (**self).settle(net);
+    }
+
+    fn save(&self, state: &mut BTreeMap<String, String>) {
+        (**self).save(state);
+    }
+
+    fn restore(&mut self, state: &BTreeMap<String, String>) -> Result<(), String> {
+        (**self).restore(state)
+    }
+}
+
+/// Returns the value saved under the key, parsed.
+/// # Errors
+/// Returns an error if there is no such value or it is invalid.
+fn saved<T: FromStr>(state: &BTreeMap<String, String>, key: &str) -> Result<T, String> {
+    let value = state.get(key).ok_or_else(|| format!("missing {key}"))?;
+    value.parse().map_err(|_| format!("invalid {key}: {value}"))
+}
+
+/// Bets the same amount every round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlatBet(pub u32);
+
+impl FlatBet {
+    /// Bets the table minimum, or a chip at a table without one.
+    #[must_use]
+    pub fn minimum(rules: &Rules) -> Self {
+        Self(rules.min_bet.unwrap_or(1))
+    }
+
+    /// Bets the given amount at a table with the given bankroll, or the table minimum if no amount is given.
+    /// # Errors
+    /// Returns an error if the table can't be played with the bankroll,
+    /// or if the bet is outside the table limits or more than the bankroll.
+    pub fn for_table(bet: Option<u32>, rules: &Rules, chips: u32) -> Result<Self, String> {
+        check_playable(rules, chips)?;
+        let Some(bet) = bet else {
+            return Ok(Self::minimum(rules));
+        };
+        match (rules.min_bet, rules.max_bet) {
+            _ if bet == 0 => Err("the bet must be at least one chip".to_string()),
+            (Some(min), _) if bet < min => Err(format!(
+                "a bet of {bet} is below the table minimum of {min}"
+            )),
+            (_, Some(max)) if bet > max => Err(format!(
+                "a bet of {bet} is above the table maximum of {max}"
+            )),
+            _ if bet > chips => Err(format!(
+                "a bet of {bet} is more than the bankroll of {chips}"
+            )),
+            _ => Ok(Self(bet)),
+        }
+    }
+}
+
+/// Checks that a player with the given bankroll can place a bet at a table with the given rules.
+/// # Errors
+/// Returns an error if the table maximum is below the minimum,
+/// or if the bankroll can't cover the minimum bet.
+pub fn check_playable(rules: &Rules, chips: u32) -> Result<(), String> {
+    let min = rules.min_bet.unwrap_or(1);
+    match rules.max_bet {
+        Some(max) if max < min => Err(format!(
+            "the table maximum of {max} is below the table minimum of {min}"
+        )),
+        _ if chips < min => Err(format!(
+            "a bankroll of {chips} can't cover the table minimum of {min}"
+        )),
+        _ => Ok(()),
+    }
+}
+
+impl BettingStrategy for FlatBet {
+    fn bet(&mut self, _: &Table) -> u32 {
+        self.0
+    }
+}
+
+/// Doubles the bet after every loss and goes back to the base bet after a win (a negative progression).
+/// Pushes leave the bet as it is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Martingale {
+    /// The bet at the start of the progression
+    pub base: u32,
+    next: u32,
+}
+
+impl Martingale {
+    #[must_use]
+    pub const fn new(base: u32) -> Self {
+        Self { base, next: base }
+    }
+}
+
+impl BettingStrategy for Martingale {
+    fn bet(&mut self, _: &Table) -> u32 {
+        self.next
+    }
+
+    fn settle(&mut self, net: i64) {
+        match net {
+            ..0 => self.next = self.next.saturating_mul(2),
+            0 => {}
+            1.. => self.next = self.base,
+        }
+    }
+
+    fn save(&self, state: &mut BTreeMap<String, String>) {
+        state.insert("martingale.next".to_string(), self.next.to_string());
+    }
+
+    fn restore(&mut self, state: &BTreeMap<String, String>) -> Result<(), String> {
+        self.next = saved(state, "martingale.next")?;
+        Ok(())
+    }
+}
+
+/// Bets a sequence of multiples of the base bet, moving to the next after every win,
+/// and starting over after a loss or once the sequence is complete (a positive progression).
+/// Pushes leave the bet as it is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WinProgression {
+    /// The bet each multiple is applied to
+    pub base: u32,
+    /// The multiple of the base bet for each step in the sequence
+    pub multiples: Vec<u32>,
+    step: usize,
+}
+
+impl WinProgression {
+    #[must_use]
+    pub const fn new(base: u32, multiples: Vec<u32>) -> Self {
+        Self {
+            base,
+            multiples,
+            step: 0,
+        }
+    }
+
+    /// The Paroli system, which doubles the bet after each win for up to three wins.
+    #[must_use]
+    pub fn paroli(base: u32) -> Self {
+        Self::new(base, vec![1, 2, 4])
+    }
+
+    /// The 1-3-2-6 system.
+    #[must_use]
+    pub fn one_three_two_six(base: u32) -> Self {
+        Self::new(base, vec![1, 3, 2, 6])
+    }
+}
+
+impl BettingStrategy for WinProgression {
+    fn bet(&mut self, _: &Table) -> u32 {
+        self.base
+            .saturating_mul(self.multiples.get(self.step).copied().unwrap_or(1))
+    }
+
+    fn settle(&mut self, net: i64) {
+        match net {
+            ..0 => self.step = 0,
+            0 => {}
+            1.. => self.step = (self.step + 1) % self.multiples.len().max(1),
+        }
+    }
+
+    fn save(&self, state: &mut BTreeMap<String, String>) {
+        state.insert("progression.step".to_string(), self.step.to_string());
+    }
+
+    fn restore(&mut self, state: &BTreeMap<String, String>) -> Result<(), String> {
+        self.step = saved(state, "progression.step")?;
+        Ok(())
+    }
+}
+
+/// Bets according to the Hi-Lo true count, rounded to a whole number by the ramp's convention.
+/// Each step is the bet for its true count and above, up to the next step.
+/// Counts below the first step get the first step's bet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BetRamp {
+    /// The steps of the ramp as (true count, bet), sorted by true count
+    pub steps: Vec<(i32, u32)>,
+    /// How the true count is worked out, rounding down the exact count unless told otherwise
+    pub true_count: TrueCount,
+}
+
+impl BetRamp {
+    /// Returns the bet for the given true count.
+    #[must_use]
+    pub fn bet_for(&self, true_count: i32) -> u32 {
+        self.steps
+            .iter()
+            .take_while(|&&(count, _)| count <= true_count)
+            .last()
+            .or_else(|| self.steps.first())
+            .map_or(0, |&(_, bet)| bet)
+    }
+}
+
+impl BettingStrategy for BetRamp {
+    fn bet(&mut self, table: &Table) -> u32 {
+        self.bet_for(self.true_count.index(&table.shoe.composition()))
+    }
+}
+
+impl fmt::Display for BetRamp {
+    /// Ramps are displayed as one "true count: bet" line per step.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# true count: bet")?;
+        for (count, bet) in &self.steps {
+            writeln!(f, "{count}: {bet}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for BetRamp {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut steps = Vec::new();
+        for line in s.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (count, bet) = line
+                .split_once(':')
+                .ok_or_else(|| format!("expected \"true count: bet\", got {line}"))?;
+            let count = count
+                .trim()
+                .parse()
+                .map_err(|_| format!("bad count: {count}"))?;
+            let bet = bet.trim().parse().map_err(|_| format!("bad bet: {bet}"))?;
+            steps.push((count, bet));
+        }
+        if steps.is_empty() {
+            return Err("empty bet ramp".to_string());
+        }
+        steps.sort_unstable();
+        Ok(Self {
+            steps,
+            true_count: TrueCount::default(),
+        })
+    }
+}
+
+/// Bets a fraction of the current bankroll in proportion to the estimated edge (the Kelly criterion).
+/// The edge is estimated from the Hi-Lo true count, which is worth about half a percent per point.
+/// Bets are kept within the table limits and what the player can afford,
+/// and the minimum is bet whenever the player is at a disadvantage.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Kelly {
+    /// The fraction of the full Kelly bet to make, e.g. 0.5 for half Kelly
+    pub fraction: f64,
+    /// The player's edge at a true count of zero
+    pub base_edge: f64,
+    /// The edge gained for every point of true count
+    pub edge_per_count: f64,
+    /// The variance of a round's result per unit bet
+    pub variance: f64,
+}
+
+impl Default for Kelly {
+    fn default() -> Self {
+        Self {
+            fraction: 1.0,
+            base_edge: -0.005,
+            edge_per_count: 0.005,
+            variance: 1.3,
+        }
+    }
+}
+
+impl Kelly {
+    /// Creates a Kelly bettor betting the given fraction of the full Kelly bet.
+    #[must_use]
+    pub fn new(fraction: f64) -> Self {
+        Self {
+            fraction,
+            ..Self::default()
+        }
+    }
+
+    /// The estimated edge at the given true count.
+    #[must_use]
+    pub fn edge(&self, true_count: f32) -> f64 {
+        self.base_edge + self.edge_per_count * f64::from(true_count)
+    }
+
+    /// Returns the bet for the given bankroll and true count under the given rules.
+    #[must_use]
+    pub fn bet_for(&self, bankroll: u32, true_count: f32, rules: &Rules) -> u32 {
+        let min_bet = rules.min_bet.unwrap_or(1);
+        let max_bet = rules.max_bet.unwrap_or(u32::MAX);
+        let edge = self.edge(true_count);
+        let bet = if edge > 0.0 {
+            (f64::from(bankroll) * self.fraction * edge / self.variance) as u32
+        } else {
+            0
+        };
+        bet.clamp(min_bet, max_bet.max(min_bet)).min(bankroll)
+    }
+}
+
+impl BettingStrategy for Kelly {
+    fn bet(&mut self, table: &Table) -> u32 {
+        self.bet_for(table.chips, counting::true_count(&table.shoe), &table.rules)
+    }
+}
+
+/// Limits on how the bet may change from one round to the next,
+/// so a count-based spread looks less like one to the pit (cover play).
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct CoverRules {
+    /// The most the bet may be raised in one round, as a multiple of the last bet
+    pub max_jump: Option<f64>,
+    /// The most the bet may be raised in one round, in chips
+    pub max_raise: Option<u32>,
+    /// Whether the bet is kept at least as high after a win, as if letting the winnings ride
+    pub hold_after_win: bool,
+}
+
+/// A betting strategy held to a set of cover rules, to measure what camouflage costs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Camouflage<B> {
+    pub betting: B,
+    pub cover: CoverRules,
+    last_bet: Option<u32>,
+    won_last: bool,
+}
+
+impl<B> Camouflage<B> {
+    pub const fn new(betting: B, cover: CoverRules) -> Self {
+        Self {
+            betting,
+            cover,
+            last_bet: None,
+            won_last: false,
+        }
+    }
+}
+
+impl<B: BettingStrategy> BettingStrategy for Camouflage<B> {
+    fn bet(&mut self, table: &Table) -> u32 {
+        let mut bet = self.betting.bet(table);
+        if let Some(last_bet) = self.last_bet {
+            if self.cover.hold_after_win && self.won_last {
+                bet = bet.max(last_bet);
+            }
+            if let Some(max_jump) = self.cover.max_jump {
+                bet = bet.min((f64::from(last_bet) * max_jump) as u32);
+            }
+            if let Some(max_raise) = self.cover.max_raise {
+                bet = bet.min(last_bet.saturating_add(max_raise));
+            }
+        }
+        self.last_bet = Some(bet);
+        bet
+    }
+
+    fn settle(&mut self, net: i64) {
+        self.won_last = net > 0;
+        self.betting.settle(net);
+    }
+
+    fn save(&self, state: &mut BTreeMap<String, String>) {
+        if let Some(last_bet) = self.last_bet {
+            state.insert("cover.last_bet".to_string(), last_bet.to_string());
+        }
+        state.insert("cover.won_last".to_string(), self.won_last.to_string());
+        self.betting.save(state);
+    }
+
+    fn restore(&mut self, state: &BTreeMap<String, String>) -> Result<(), String> {
+        self.last_bet = state
+            .contains_key("cover.last_bet")
+            .then(|| saved(state, "cover.last_bet"))
+            .transpose()?;
+        self.won_last = saved(state, "cover.won_last")?;
+        self.betting.restore(state)
+    }
+}
+
+/// A playing strategy combined with a betting strategy, which decides every bet.
+/// The player never bets more than the table maximum or what they have left.
+/// The betting strategy learns each round's own net result, so tips don't count against it.
+#[derive(Debug, Clone)]
+pub struct WithBetting<S, B> {
+    pub strategy: S,
+    pub betting: B,
+    /// Whether the last bet placed is still to be settled with the round's result
+    unsettled: bool,
+}
+
+impl<S, B> WithBetting<S, B> {
+    pub const fn new(strategy: S, betting: B) -> Self {
+        Self {
+            strategy,
+            betting,
+            unsettled: false,
+        }
+    }
+}
+
+impl<S: Strategy, B: BettingStrategy> Strategy for WithBetting<S, B> {
+    fn input(
+        &mut self,
+        table: &Table,
+        state: &GameState,
+        composition: &Composition,
+    ) -> Option<Input> {
+        match state {
+            GameState::Betting { .. } => {
+                if let Some(net) = table.last_net().filter(|_| self.unsettled) {
+                    self.betting.settle(net);
+                }
+                self.unsettled = true;
+                // Fast-forwarded bets skip the table's checks, so keep them within its limits here
+                let min_bet = table.rules.min_bet.unwrap_or(1).max(1);
+                let max_bet = table.rules.max_bet.unwrap_or(u32::MAX).max(min_bet);
+                let bet = self
+                    .betting
+                    .bet(table)
+                    .clamp(min_bet, max_bet)
+                    .min(table.chips);
+                Some(Input::bet(bet))
+            }
+            state => self.strategy.input(table, state, composition),
+        }
+    }
+
+    fn save(&self, state: &mut BTreeMap<String, String>) {
+        state.insert("betting.unsettled".to_string(), self.unsettled.to_string());
+        self.strategy.save(state);
+        self.betting.save(state);
+    }
+
+    fn restore(&mut self, state: &BTreeMap<String, String>) -> Result<(), String> {
+        self.unsettled = saved(state, "betting.unsettled")?;
+        self.strategy.restore(state)?;
+        self.betting.restore(state)
+    }
+}
+
+/// The results of the rounds played at one true count, measured per unit of the initial bet.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CountResults {
+    /// The number of rounds played at this count
+    pub rounds: u64,
+    /// The sum of each round's result per unit bet
+    pub units: f64,
+    /// The sum of the squares of each round's result per unit bet
+    pub units_squared: f64,
+}
+
+impl CountResults {
+    /// Adds the rounds recorded in another set of results to these.
+    pub fn merge(&mut self, other: &Self) {
+        self.rounds += other.rounds;
+        self.units += other.units;
+        self.units_squared += other.units_squared;
+    }
+
+    /// Records a round where the given initial bet won (or lost) the given net amount.
+    pub fn record(&mut self, bet: u32, net: i64) {
+        if bet > 0 {
+            let units = net as f64 / f64::from(bet);
+            self.rounds += 1;
+            self.units += units;
+            self.units_squared += units * units;
+        }
+    }
+
+    /// The player's average edge per unit bet at this count.
+    #[must_use]
+    pub fn edge(&self) -> f64 {
+        if self.rounds == 0 {
+            0.0
+        } else {
+            self.units / self.rounds as f64
+        }
+    }
+
+    /// The variance of a round's result per unit bet at this count.
+    #[must_use]
+    pub fn variance(&self) -> f64 {
+        if self.rounds == 0 {
+            0.0
+        } else {
+            self.units_squared / self.rounds as f64 - self.edge().powi(2)
+        }
+    }
+}
+
+/// The fewest rounds at a count for its edge to be trusted when sizing bets.
+const MIN_ROUNDS: u64 = 100;
+
+/// Computes the bet ramp that maximizes the win rate for the given bankroll and risk of ruin,
+/// from the results measured at each true count.
+/// Counts with a positive edge are bet in proportion to edge over variance,
+/// scaled so the bankroll covers the target risk of ruin. Other counts get the minimum bet.
+/// Bets are rounded down to a multiple of the unit and kept within the table limits,
+/// and never decrease as the count rises.
+#[must_use]
+pub fn optimal_ramp(
+    results: &BTreeMap<i32, CountResults>,
+    bankroll: u32,
+    risk_of_ruin: f64,
+    rules: &Rules,
+    unit: u32,
+) -> BetRamp {
+    let unit = unit.max(1);
+    let min_bet = rules.min_bet.unwrap_or(unit);
+    let max_bet = rules.max_bet.unwrap_or(u32::MAX);
+    // With bets proportional to edge over variance, the risk of ruin is exp(-2 * bankroll / scale)
+    let scale = 2.0 * f64::from(bankroll) / -risk_of_ruin.clamp(f64::MIN_POSITIVE, 1.0).ln();
+    let mut steps: Vec<(i32, u32)> = Vec::new();
+    for (&count, result) in results {
+        let bet = if result.rounds >= MIN_ROUNDS && result.edge() > 0.0 && result.variance() > 0.0 {
+            let optimal = scale * result.edge() / result.variance();
+            (optimal as u32 / unit * unit).clamp(min_bet, max_bet.max(min_bet))
+        } else {
+            min_bet
+        };
+        let previous = steps.last().map_or(0, |&(_, bet)| bet);
+        let bet = bet.max(previous);
+        if bet != previous || steps.is_empty() {
+            steps.push((count, bet));
+        }
+    }
+    if steps.is_empty() {
+        steps.push((0, min_bet));
+    }
+    BetRamp {
+        steps,
+        true_count: TrueCount::default(),
+    }
+}
+
+/// A bet named by what it is rather than how many chips it is, worked out when it is placed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BetPreset {
+    /// The same bet as on the last round
+    Same,
+    /// The table minimum
+    Min,
+    /// The table maximum, or every chip the player has if that is less
+    Max,
+    /// A fixed number of chips
+    Chips(u32),
+}
+
+impl BetPreset {
+    /// Returns the bet the preset stands for in the given state,
+    /// or None if the table isn't taking bets or there is no last bet to repeat.
+    #[must_use]
+    pub fn bet(self, table: &Table, state: &GameState) -> Option<u32> {
+        let GameState::Betting { last_bet } = state else {
+            return None;
+        };
+        match self {
+            Self::Same => *last_bet,
+            Self::Min => Some(table.rules.min_bet.unwrap_or(1)),
+            Self::Max => Some(
+                table
+                    .rules
+                    .max_bet
+                    .map_or(table.chips, |max| max.min(table.chips)),
+            ),
+            Self::Chips(chips) => Some(chips),
+        }
+    }
+}
+
+impl fmt::Display for BetPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Same => write!(f, "same"),
+            Self::Min => write!(f, "min"),
+            Self::Max => write!(f, "max"),
+            Self::Chips(chips) => write!(f, "{chips}"),
+        }
+    }
+}
+
+impl FromStr for BetPreset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "same" | "rebet" => Ok(Self::Same),
+            "min" => Ok(Self::Min),
+            "max" => Ok(Self::Max),
+            chips => chips
+                .parse()
+                .map(Self::Chips)
+                .map_err(|_| format!("unknown bet: {s}")),
+        }
+    }
+}
+
+/// Bets the player has named, e.g. "small=25,big=max".
+/// Every name also stands for itself, so "same", "max" or "100" need no preset of their own.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BetPresets(BTreeMap<String, BetPreset>);
+
+impl BetPresets {
+    /// Returns the preset with the given name.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<BetPreset> {
+        let name = name.trim().to_ascii_lowercase();
+        self.0.get(&name).copied().or_else(|| name.parse().ok())
+    }
+
+    /// Returns the input placing the named bet in the given state.
+    #[must_use]
+    pub fn input(&self, name: &str, table: &Table, state: &GameState) -> Option<Input> {
+        self.get(name)?.bet(table, state).map(Input::bet)
+    }
+}
+
+impl fmt::Display for BetPresets {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let presets: Vec<_> = self
+            .0
+            .iter()
+            .map(|(name, preset)| format!("{name}={preset}"))
+            .collect();
+        write!(f, "{}", presets.join(","))
+    }
+}
+
+impl FromStr for BetPresets {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut presets = BTreeMap::new();
+        for preset in s
+            .split(',')
+            .map(str::trim)
+            .filter(|preset| !preset.is_empty())
+        {
+            let (name, bet) = preset
+                .split_once('=')
+                .ok_or_else(|| format!("expected \"name=bet\", got {preset}"))?;
+            let name = name.trim().to_ascii_lowercase();
+            if name.is_empty() {
+                return Err(format!("unnamed bet: {preset}"));
+            }
+            presets.insert(name, bet.parse()?);
+        }
+        Ok(Self(presets))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::shoe::Shoe;
+
+    #[test]
+    fn test_optimal_ramp() {
+        let mut results = BTreeMap::new();
+        for (count, edge) in [(-1, -0.02), (0, -0.005), (1, 0.0), (2, 0.01), (3, 0.015)] {
+            let result: &mut CountResults = results.entry(count).or_default();
+            for i in 0..1000 {
+                let net = if i % 2 == 0 { 1000 } else { -1000 };
+                result.record(1000, net + (edge * 1000.0) as i64);
+            }
+        }
+        let ramp = optimal_ramp(&results, 100_000, 0.05, &Rules::default(), 25);
+        assert_eq!(ramp.bet_for(-5), 100);
+        assert_eq!(ramp.bet_for(1), 100);
+        assert!(ramp.bet_for(2) > 100);
+        assert!(ramp.bet_for(3) > ramp.bet_for(2));
+        assert_eq!(ramp.to_string().parse(), Ok(ramp));
+        // With no table minimum, a unit above the maximum is bet throughout
+        let rules = Rules {
+            min_bet: None,
+            max_bet: Some(10),
+            ..Rules::default()
+        };
+        let ramp = optimal_ramp(&results, 100_000, 0.05, &rules, 25);
+        assert_eq!(ramp.bet_for(-5), 25);
+        assert_eq!(ramp.bet_for(3), 25);
+    }
+
+    #[test]
+    fn test_true_count_conventions() {
+        use crate::counting::{DeckEstimate, Rounding};
+        // A deck with every low card dealt: +20 with 32 cards, or 0.62 decks, left
+        let composition = Composition::from([0, 0, 0, 0, 0, 4, 4, 4, 4, 4, 4, 4, 4]);
+        let ramp = BetRamp {
+            steps: vec![(0, 100), (30, 500), (40, 1000)],
+            true_count: TrueCount::default(),
+        };
+        assert_eq!(ramp.true_count.index(&composition), 32);
+        assert_eq!(ramp.bet_for(ramp.true_count.index(&composition)), 500);
+        let half_deck: TrueCount = "half".parse().unwrap();
+        assert_eq!(half_deck.decks, DeckEstimate::HalfDeck);
+        assert_eq!(ramp.bet_for(half_deck.index(&composition)), 1000);
+        let full_deck: TrueCount = "deck,round".parse().unwrap();
+        assert_eq!(full_deck.index(&composition), 20);
+        assert_eq!(full_deck.to_string(), "deck,round");
+
+        assert_eq!(Rounding::Floor.apply(-0.5), -1);
+        assert_eq!(Rounding::Round.apply(2.5), 3);
+        assert_eq!(Rounding::Truncate.apply(-0.5), 0);
+        assert!("ceiling".parse::<TrueCount>().is_err());
+    }
+
+    #[test]
+    fn test_kelly() {
+        let rules = Rules {
+            max_bet: Some(5000),
+            ..Rules::default()
+        };
+        let half_kelly = Kelly::new(0.5);
+        assert_eq!(half_kelly.bet_for(100_000, 0.0, &rules), 100);
+        assert_eq!(half_kelly.bet_for(100_000, 3.0, &rules), 384);
+        assert_eq!(half_kelly.bet_for(1_000_000, 20.0, &rules), 5000);
+        assert_eq!(half_kelly.bet_for(50, 3.0, &rules), 50);
+    }
+
+    #[test]
+    fn test_camouflage() {
+        let table = Table::new(1000, Shoe::new(1, 0.75), Rules::default());
+        let mut camouflage = Camouflage::new(
+            Martingale::new(100),
+            CoverRules {
+                max_jump: Some(2.0),
+                max_raise: Some(150),
+                hold_after_win: true,
+            },
+        );
+        let mut bets = Vec::new();
+        for net in [-1, -1, -1, 1, -1, 1, 0] {
+            bets.push(camouflage.bet(&table));
+            camouflage.settle(net);
+        }
+        // The martingale wants 100, 200, 400, 800, then 100 after the win
+        assert_eq!(bets, [100, 200, 350, 500, 500, 200, 200]);
+    }
+
+    #[test]
+    fn test_progressions() {
+        let table = Table::new(1000, Shoe::new(1, 0.75), Rules::default());
+        let mut martingale = Martingale::new(100);
+        let mut one_three_two_six = WinProgression::one_three_two_six(100);
+        let mut martingale_bets = Vec::new();
+        let mut sequence_bets = Vec::new();
+        for net in [-1, -1, 0, 1, 1, 1, 1, 1, -1] {
+            martingale_bets.push(martingale.bet(&table));
+            sequence_bets.push(one_three_two_six.bet(&table));
+            martingale.settle(net);
+            one_three_two_six.settle(net);
+        }
+        assert_eq!(
+            martingale_bets,
+            [100, 200, 400, 400, 100, 100, 100, 100, 100]
+        );
+        assert_eq!(sequence_bets, [100, 100, 100, 100, 300, 200, 600, 100, 300]);
+    }
+
+    #[test]
+    fn test_bets_within_table_limits() {
+        use crate::strategy::BasicStrategy;
+        let rules = Rules {
+            min_bet: Some(25),
+            max_bet: Some(500),
+            ..Rules::default()
+        };
+        let table = Table::new(1000, Shoe::new(1, 0.75), rules);
+        let state = table.betting();
+        let composition = table.shoe.composition();
+        let mut below = WithBetting::new(BasicStrategy, Martingale::new(10));
+        let mut above = WithBetting::new(BasicStrategy, Martingale::new(800));
+        let mut zero = WithBetting::new(
+            BasicStrategy,
+            Camouflage::new(
+                Martingale::new(100),
+                CoverRules {
+                    max_jump: Some(0.5),
+                    max_raise: None,
+                    hold_after_win: false,
+                },
+            ),
+        );
+        zero.betting.last_bet = Some(25);
+        assert_eq!(
+            below.input(&table, &state, &composition),
+            Some(Input::bet(25))
+        );
+        assert_eq!(
+            above.input(&table, &state, &composition),
+            Some(Input::bet(500))
+        );
+        assert_eq!(
+            zero.input(&table, &state, &composition),
+            Some(Input::bet(25))
+        );
+    }
+
+    #[test]
+    fn test_flat_bet_for_table() {
+        let rules = Rules {
+            min_bet: Some(25),
+            max_bet: Some(500),
+            ..Rules::default()
+        };
+        assert_eq!(FlatBet::for_table(None, &rules, 1000), Ok(FlatBet(25)));
+        assert_eq!(
+            FlatBet::for_table(Some(100), &rules, 1000),
+            Ok(FlatBet(100))
+        );
+        assert!(FlatBet::for_table(Some(10), &rules, 1000).is_err());
+        assert!(FlatBet::for_table(Some(600), &rules, 1000).is_err());
+        assert!(FlatBet::for_table(Some(200), &rules, 100).is_err());
+        assert!(FlatBet::for_table(None, &rules, 20).is_err());
+        let no_limits = Rules {
+            min_bet: None,
+            max_bet: None,
+            ..Rules::default()
+        };
+        assert_eq!(FlatBet::for_table(None, &no_limits, 1), Ok(FlatBet(1)));
+        assert!(FlatBet::for_table(Some(0), &no_limits, 1).is_err());
+        let unplayable = Rules {
+            max_bet: Some(10),
+            ..rules
+        };
+        assert!(check_playable(&unplayable, 1000).is_err());
+    }
+
+    #[test]
+    fn test_bet_presets() {
+        let rules = Rules {
+            min_bet: Some(25),
+            max_bet: Some(500),
+            ..Rules::default()
+        };
+        let mut table = Table::new(300, Shoe::new(1, 0.75), rules);
+        let presets: BetPresets = "small=min, big=max, usual=50".parse().unwrap();
+        assert_eq!(presets.to_string().parse(), Ok(presets.clone()));
+        let state = table.betting();
+        assert_eq!(presets.input("small", &table, &state), Some(Input::bet(25)));
+        assert_eq!(presets.input("BIG", &table, &state), Some(Input::bet(300)));
+        assert_eq!(presets.input("75", &table, &state), Some(Input::bet(75)));
+        assert_eq!(presets.input("same", &table, &state), None);
+        assert_eq!(presets.input("huge", &table, &state), None);
+
+        let input = presets.input("usual", &table, &state);
+        assert!(matches!(
+            table.progress(state, input),
+            Ok(GameState::Dealing { .. })
+        ));
+        assert_eq!(table.betting(), GameState::Betting { last_bet: Some(50) });
+        let state = table.betting();
+        assert_eq!(presets.input("same", &table, &state), Some(Input::bet(50)));
+        assert!("big=huge".parse::<BetPresets>().is_err());
+    }
+}
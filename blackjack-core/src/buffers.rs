@@ -0,0 +1,98 @@
+//! Buffers recycled from settled rounds, so a table dealing round after round doesn't allocate:
+//! the cards of every hand, and the player's hands, are handed back to the table once a round is paid out
+//! and reused for the next round's hands.
+
+use crate::card::hand::PlayerHand;
+use crate::card::Card;
+use crate::state::FinishedRound;
+
+/// The number of cards a new hand has room for, enough for most hands before they need to grow.
+pub const HAND_CAPACITY: usize = 4;
+
+/// The most buffers of each kind kept for reuse, so a round with many splits doesn't hold on to memory for good.
+const MAX_POOLED: usize = 16;
+
+/// The buffers left over from the rounds settled so far, ready for the next round's hands.
+#[derive(Debug, Default)]
+pub struct RoundBuffers {
+    cards: Vec<Vec<Card>>,
+    hands: Vec<Vec<PlayerHand>>,
+}
+
+impl RoundBuffers {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            cards: Vec::new(),
+            hands: Vec::new(),
+        }
+    }
+
+    /// The number of buffers kept for reuse.
+    #[must_use]
+    pub fn pooled(&self) -> usize {
+        self.cards.len() + self.hands.len()
+    }
+
+    /// Returns an empty buffer for the cards of a hand.
+    pub fn cards(&mut self) -> Vec<Card> {
+        self.cards
+            .pop()
+            .unwrap_or_else(|| Vec::with_capacity(HAND_CAPACITY))
+    }
+
+    /// Returns an empty buffer for the player's hands.
+    pub fn hands(&mut self) -> Vec<PlayerHand> {
+        self.hands.pop().unwrap_or_default()
+    }
+
+    /// Takes back the buffers of a settled round.
+    pub fn recycle(&mut self, round: FinishedRound) {
+        let mut hands = round.hands;
+        for hand in hands.drain(..) {
+            self.recycle_cards(hand.cards);
+        }
+        self.recycle_cards(round.dealer_hand.into_cards());
+        if self.hands.len() < MAX_POOLED {
+            self.hands.push(hands);
+        }
+    }
+
+    fn recycle_cards(&mut self, mut cards: Vec<Card>) {
+        if self.cards.len() < MAX_POOLED {
+            cards.clear();
+            self.cards.push(cards);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::basic_strategy;
+    use crate::card::shoe::Shoe;
+    use crate::game::{Input, Table};
+    use crate::rules::Rules;
+    use crate::state::GameState;
+
+    #[test]
+    fn test_round_buffers() {
+        let mut buffers = RoundBuffers::new();
+        let cards = buffers.cards();
+        assert!(cards.is_empty() && cards.capacity() >= HAND_CAPACITY);
+
+        // Played step by step, each round is paid out from the payout state, which hands its buffers back
+        let mut table = Table::new(100_000, Shoe::seeded(6, 0.75, 1), Rules::default());
+        let mut state = GameState::default();
+        let mut rounds = 0;
+        while rounds < 3 {
+            let input = match state {
+                GameState::Betting { .. } => Some(Input::bet(100)),
+                _ => basic_strategy::input(&table, &state),
+            };
+            rounds += usize::from(matches!(state, GameState::Payout { .. }));
+            state = table.progress(state, input).unwrap();
+        }
+        assert!(table.pooled_buffers() >= 3);
+    }
+}
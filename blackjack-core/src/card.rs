@@ -1,6 +1,7 @@
 //! This module contains the types and functions for working with cards in a game of blackjack.
 
 use std::fmt;
+use std::str::FromStr;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Suit {
@@ -10,6 +11,20 @@ pub enum Suit {
     Spades,
 }
 
+impl Suit {
+    /// Returns the symbol printed on cards of this suit, e.g. '♣'.
+    /// Each suit has its own shape, so the symbols can be told apart without color.
+    #[must_use]
+    pub const fn symbol(&self) -> char {
+        match self {
+            Self::Clubs => '♣',
+            Self::Diamonds => '♦',
+            Self::Hearts => '♥',
+            Self::Spades => '♠',
+        }
+    }
+}
+
 impl fmt::Display for Suit {
     /// Suits are displayed as their name, e.g. "Clubs", "Diamonds", "Hearts", "Spades"
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -60,7 +75,48 @@ impl fmt::Display for Rank {
     }
 }
 
+impl FromStr for Rank {
+    type Err = String;
+
+    /// Parses a rank as written on a card, e.g. "2", "10" or "T", "J", "A".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "2" => Ok(Self::Two),
+            "3" => Ok(Self::Three),
+            "4" => Ok(Self::Four),
+            "5" => Ok(Self::Five),
+            "6" => Ok(Self::Six),
+            "7" => Ok(Self::Seven),
+            "8" => Ok(Self::Eight),
+            "9" => Ok(Self::Nine),
+            "10" | "T" => Ok(Self::Ten),
+            "J" => Ok(Self::Jack),
+            "Q" => Ok(Self::Queen),
+            "K" => Ok(Self::King),
+            "A" => Ok(Self::Ace),
+            _ => Err(format!("unknown rank: {s}")),
+        }
+    }
+}
+
 impl Rank {
+    /// All ranks, in the order they are sorted in a deck.
+    pub const ALL: [Self; 13] = [
+        Self::Two,
+        Self::Three,
+        Self::Four,
+        Self::Five,
+        Self::Six,
+        Self::Seven,
+        Self::Eight,
+        Self::Nine,
+        Self::Ten,
+        Self::Jack,
+        Self::Queen,
+        Self::King,
+        Self::Ace,
+    ];
+
     /// Returns how much a card with this rank is worth in the game.
     /// All face cards are worth 10, and aces are worth 11.
     #[must_use]
@@ -78,6 +134,26 @@ impl Rank {
             Self::Ace => 11,
         }
     }
+
+    /// Returns the rank as written on a card, e.g. "2", "10", "J", "A".
+    #[must_use]
+    pub const fn symbol(&self) -> &'static str {
+        match self {
+            Self::Two => "2",
+            Self::Three => "3",
+            Self::Four => "4",
+            Self::Five => "5",
+            Self::Six => "6",
+            Self::Seven => "7",
+            Self::Eight => "8",
+            Self::Nine => "9",
+            Self::Ten => "10",
+            Self::Jack => "J",
+            Self::Queen => "Q",
+            Self::King => "K",
+            Self::Ace => "A",
+        }
+    }
 }
 
 /// A card is a combination of a rank and a suit.
@@ -88,6 +164,14 @@ pub struct Card {
     pub suit: Suit,
 }
 
+/// Whether a card is dealt face up, for the whole table to see, or face down.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Visibility {
+    #[default]
+    FaceUp,
+    FaceDown,
+}
+
 impl fmt::Display for Card {
     /// Cards are displayed as "a Rank of Suit", e.g. "a Two of Clubs"
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -136,11 +220,12 @@ pub mod hand {
     use std::fmt;
     use std::ops::AddAssign;
 
-    use crate::card::{Card, Rank};
-    use crate::rules::{BlackjackPayout, DealerSoft17Action};
+    use crate::buffers::HAND_CAPACITY;
+    use crate::card::{Card, Rank, Visibility};
+    use crate::rules::{BlackjackPayout, DealerDrawing, PayoutRounding, Rules};
 
     /// Represents the game value of a hand, e.g. "Soft 20"
-    #[derive(Debug, Default, PartialEq, Eq)]
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
     pub struct Value {
         /// Whether the hand has an ace that is currently worth 11
         pub soft: bool,
@@ -204,6 +289,40 @@ pub mod hand {
         Surrendered,
     }
 
+    /// How a finished hand turned out, decided when the round is settled.
+    /// Unlike [`Status`], this takes the dealer's hand into account.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum RoundOutcome {
+        /// The player won with a blackjack
+        WinBlackjack,
+        /// The player beat the dealer's total, or the dealer busted
+        Win,
+        /// The player tied with the dealer, including when both have blackjack
+        Push,
+        /// The dealer beat the player's total
+        Lose,
+        /// The dealer had blackjack
+        LoseToBlackjack,
+        /// The player surrendered before the dealer checked for blackjack
+        SurrenderEarly,
+        /// The player surrendered after the dealer checked for blackjack
+        SurrenderLate,
+        /// The player busted
+        Bust,
+        /// The player won by drawing enough cards without busting (no table rules offer this yet)
+        CharlieWin,
+        /// The dealer busted with 22, which pushes under the table rules
+        Push22,
+    }
+
+    impl RoundOutcome {
+        /// Returns whether the player won the hand.
+        #[must_use]
+        pub const fn is_win(self) -> bool {
+            matches!(self, Self::WinBlackjack | Self::Win | Self::CharlieWin)
+        }
+    }
+
     /// Represents the dealer's hand.
     #[derive(Debug, PartialEq, Eq)]
     pub struct DealerHand {
@@ -213,8 +332,10 @@ pub mod hand {
         pub status: Status,
         /// The cards in this hand (non-empty at all times)
         cards: Vec<Card>,
-        /// Whether the dealer stands or hits on soft 17
-        soft_17_action: DealerSoft17Action,
+        /// When the dealer stops drawing
+        drawing: DealerDrawing,
+        /// Whether the dealer flashed their hole card to the player by mistake
+        pub flashed: bool,
     }
 
     impl AddAssign<Card> for DealerHand {
@@ -224,37 +345,75 @@ pub mod hand {
             self.value += &rhs;
             self.cards.push(rhs);
             self.status = match (self.value.soft, self.value.total) {
-                (true, 17) if self.hits_on_soft_17() => Status::InPlay,
                 (true, 21) if self.cards.len() == 2 => Status::Blackjack,
-                (_, 17..=21) => Status::Stood,
                 (_, 22..) => Status::Bust,
+                _ if self.drawing.stands_on(self.value) => Status::Stood,
                 _ => Status::InPlay,
             };
         }
     }
 
     impl DealerHand {
-        /// Creates a new dealer hand with the given card and soft 17 behavior.
+        /// Creates a new dealer hand with the given card, drawing by the given rule.
+        #[must_use]
+        pub fn new(card: Card, drawing: DealerDrawing) -> Self {
+            Self::with_buffer(card, drawing, Vec::with_capacity(HAND_CAPACITY))
+        }
+
+        /// Creates a new dealer hand like `new`, keeping its cards in the given empty buffer.
         #[must_use]
-        pub fn new(card: Card, soft_17_action: DealerSoft17Action) -> Self {
+        pub fn with_buffer(card: Card, drawing: DealerDrawing, mut cards: Vec<Card>) -> Self {
+            debug_assert!(cards.is_empty(), "cannot reuse a buffer holding cards");
+            cards.push(card);
             Self {
-                value: Value::from(&card),
+                value: Value::from(&cards[0]),
                 status: Status::InPlay,
-                cards: vec![card],
-                soft_17_action,
+                cards,
+                drawing,
+                flashed: false,
             }
         }
 
+        /// Takes the dealer's cards, to reuse their buffer.
+        #[must_use]
+        pub fn into_cards(self) -> Vec<Card> {
+            self.cards
+        }
+
+        /// Returns the dealer's cards, starting with the up card.
+        #[must_use]
+        pub fn cards(&self) -> &[Card] {
+            &self.cards
+        }
+
+        /// Returns the dealer's hole card, if it has been dealt.
+        #[must_use]
+        pub fn hole_card(&self) -> Option<&Card> {
+            self.cards.get(1)
+        }
+
+        /// Returns the dealer's hole card if they flashed it, so the player has seen it.
+        #[must_use]
+        pub fn flashed_hole_card(&self) -> Option<&Card> {
+            self.hole_card().filter(|_| self.flashed)
+        }
+
         /// Returns the worth of the dealer's up card, which is what the player must base their decisions on.
         #[must_use]
         pub fn showing(&self) -> u8 {
             self.cards[0].rank.worth()
         }
 
+        /// Returns when the dealer stops drawing.
+        #[must_use]
+        pub const fn drawing(&self) -> DealerDrawing {
+            self.drawing
+        }
+
         /// Returns whether the dealer hits on soft 17.
         #[must_use]
-        pub fn hits_on_soft_17(&self) -> bool {
-            self.soft_17_action == DealerSoft17Action::Hit
+        pub const fn hits_on_soft_17(&self) -> bool {
+            self.drawing.hits_soft_17()
         }
     }
 
@@ -271,6 +430,14 @@ pub mod hand {
         pub cards: Vec<Card>,
         /// The player's winnings on this hand
         pub winnings: u32,
+        /// How this hand turned out, once the round is settled
+        pub outcome: Option<RoundOutcome>,
+        /// Whether the hand's opening cards lie face down on the table
+        pub visibility: Visibility,
+        /// Whether the player surrendered this hand before the dealer checked for blackjack
+        surrendered_early: bool,
+        /// Whether this hand was split off another, rather than dealt to a spot
+        split_off: bool,
     }
 
     impl AddAssign<Card> for PlayerHand {
@@ -292,12 +459,24 @@ pub mod hand {
         /// Creates a new player hand with the given card and bet.
         #[must_use]
         pub fn new(card: Card, bet: u32) -> Self {
+            Self::with_buffer(card, bet, Vec::with_capacity(HAND_CAPACITY))
+        }
+
+        /// Creates a new player hand like `new`, keeping its cards in the given empty buffer.
+        #[must_use]
+        pub fn with_buffer(card: Card, bet: u32, mut cards: Vec<Card>) -> Self {
+            debug_assert!(cards.is_empty(), "cannot reuse a buffer holding cards");
+            cards.push(card);
             Self {
                 bet,
-                value: Value::from(&card),
+                value: Value::from(&cards[0]),
                 status: Status::InPlay,
-                cards: vec![card],
+                cards,
                 winnings: 0,
+                outcome: None,
+                visibility: Visibility::FaceUp,
+                surrendered_early: false,
+                split_off: false,
             }
         }
 
@@ -335,10 +514,26 @@ pub mod hand {
         /// Will panic if the hand is not a pair.
         #[must_use]
         pub fn split(&mut self) -> Self {
+            self.split_into(Vec::with_capacity(HAND_CAPACITY))
+        }
+
+        /// Splits the hand like `split`, keeping the new hand's cards in the given empty buffer.
+        /// # Panics
+        /// Will panic if the hand is not a pair.
+        #[must_use]
+        pub fn split_into(&mut self, cards: Vec<Card>) -> Self {
             debug_assert!(self.is_pair(), "cannot split hand that is not a pair");
             let split_card = self.cards.pop().expect("Hand must be a pair"); // Remove the second card
             self.value = Value::from(&self.cards[0]); // The value of this hand is now the first card
-            Self::new(split_card, self.bet) // Create a new hand with the second card
+            let mut hand = Self::with_buffer(split_card, self.bet, cards); // Create a new hand with the second card
+            hand.split_off = true;
+            hand
+        }
+
+        /// Returns whether this hand was split off another, rather than dealt to a spot.
+        #[must_use]
+        pub const fn is_split_off(&self) -> bool {
+            self.split_off
         }
 
         /// The player surrenders this hand.
@@ -351,6 +546,12 @@ pub mod hand {
             self.status = Status::Surrendered;
         }
 
+        /// The player surrenders this hand before the dealer checks for blackjack.
+        pub fn surrender_early(&mut self) {
+            self.surrender();
+            self.surrendered_early = true;
+        }
+
         /// Returns the number of cards in this hand.
         #[must_use]
         pub fn size(&self) -> usize {
@@ -364,115 +565,200 @@ pub mod hand {
             self.size() == 2 && self.cards[0].rank == self.cards[1].rank
         }
 
-        /// Calculates the winnings for this hand based on the dealer's hand.
+        /// Settles this hand against the dealer's hand, recording its winnings and outcome.
+        /// This method should only be called once the dealer's hand is in a terminal state.
+        pub fn settle(&mut self, dealer_hand: &DealerHand, rules: &Rules) {
+            self.winnings = self.calculate_winnings(dealer_hand, rules);
+            self.outcome = Some(self.calculate_outcome(dealer_hand, rules));
+            // The dealer turns every hand face up to settle it
+            self.visibility = Visibility::FaceUp;
+        }
+
+        /// Decides how this hand turned out against the dealer's hand.
         /// This method should only be called once the dealer's hand is in a terminal state.
         #[must_use]
-        pub fn calculate_winnings(
-            &self,
-            dealer_hand: &DealerHand,
-            blackjack_payout: BlackjackPayout,
-        ) -> u32 {
+        pub fn calculate_outcome(&self, dealer_hand: &DealerHand, rules: &Rules) -> RoundOutcome {
             match (&self.status, &dealer_hand.status) {
-                (Status::Surrendered, _) => self.payout_surrender(), // Player surrender
-                (Status::Blackjack, Status::Blackjack) => self.payout_push(), // Blackjack push
-                (Status::Blackjack, _) => self.payout_blackjack(blackjack_payout), // Blackjack win
-                (_, Status::Blackjack) | (Status::Bust, _) => self.payout_loss(), // Dealer blackjack or player bust
-                (_, Status::Bust) => self.payout_win(),                           // Dealer bust
+                (Status::Surrendered, _) if self.surrendered_early => RoundOutcome::SurrenderEarly,
+                (Status::Surrendered, _) => RoundOutcome::SurrenderLate,
+                (Status::Blackjack, Status::Blackjack) => RoundOutcome::Push,
+                (Status::Blackjack, _) => RoundOutcome::WinBlackjack,
+                (Status::Bust, _) => RoundOutcome::Bust,
+                (_, Status::Blackjack) => RoundOutcome::LoseToBlackjack,
+                (_, Status::Bust) if rules.dealer_push_22 && dealer_hand.value.total == 22 => {
+                    RoundOutcome::Push22
+                }
+                (_, Status::Bust) => RoundOutcome::Win,
                 _ => match self.value.total.cmp(&dealer_hand.value.total) {
-                    Ordering::Greater => self.payout_win(), // Player win
-                    Ordering::Equal => self.payout_push(),  // Push
-                    Ordering::Less => self.payout_loss(),   // Dealer win
+                    Ordering::Greater => RoundOutcome::Win,
+                    Ordering::Equal if rules.push_policy.dealer_wins_tie(self.value.total) => {
+                        RoundOutcome::Lose
+                    }
+                    Ordering::Equal => RoundOutcome::Push,
+                    Ordering::Less => RoundOutcome::Lose,
                 },
             }
         }
 
-        /// Calculates the winnings for a blackjack win based on whether the game pays 3:2 or 6:5.
-        const fn payout_blackjack(&self, payout: BlackjackPayout) -> u32 {
-            match payout {
-                BlackjackPayout::ThreeToTwo => self.bet + self.bet * 3 / 2,
-                BlackjackPayout::SixToFive => self.bet + self.bet * 6 / 5,
-            }
+        /// Calculates the winnings for this hand based on the dealer's hand.
+        /// This method should only be called once the dealer's hand is in a terminal state.
+        #[must_use]
+        pub fn calculate_winnings(&self, dealer_hand: &DealerHand, rules: &Rules) -> u32 {
+            self.calculate_winnings_on(self.bet, dealer_hand, rules)
         }
 
-        /// Calculates the winnings for a normal win, which is double the bet.
-        const fn payout_win(&self) -> u32 {
-            self.bet * 2
+        /// Calculates the winnings for some other wager riding on the outcome of this hand,
+        /// such as a back bet. The wager does not follow a double down.
+        /// This method should only be called once the dealer's hand is in a terminal state.
+        #[must_use]
+        pub fn calculate_winnings_on(
+            &self,
+            bet: u32,
+            dealer_hand: &DealerHand,
+            rules: &Rules,
+        ) -> u32 {
+            match self.calculate_outcome(dealer_hand, rules) {
+                RoundOutcome::WinBlackjack => {
+                    payout_blackjack(bet, rules.blackjack_payout, rules.payout_rounding)
+                }
+                RoundOutcome::Win | RoundOutcome::CharlieWin => payout_win(bet),
+                RoundOutcome::Push | RoundOutcome::Push22 => payout_push(bet),
+                RoundOutcome::SurrenderEarly | RoundOutcome::SurrenderLate => {
+                    payout_surrender(bet, rules.payout_rounding)
+                }
+                RoundOutcome::Lose | RoundOutcome::LoseToBlackjack | RoundOutcome::Bust => {
+                    payout_loss(bet)
+                }
+            }
         }
+    }
 
-        /// Calculates the winnings for a push, which is the same as the bet.
-        const fn payout_push(&self) -> u32 {
-            self.bet
+    /// Calculates the winnings for a blackjack win based on whether the game pays 3:2 or 6:5.
+    const fn payout_blackjack(bet: u32, payout: BlackjackPayout, rounding: PayoutRounding) -> u32 {
+        match payout {
+            BlackjackPayout::ThreeToTwo => bet + rounding.apply(bet, 3, 2),
+            BlackjackPayout::SixToFive => bet + rounding.apply(bet, 6, 5),
         }
+    }
 
-        /// Calculates the winnings for a surrender, which is half the bet.
-        const fn payout_surrender(&self) -> u32 {
-            self.bet / 2
-        }
+    /// Calculates the winnings for a normal win, which is double the bet.
+    const fn payout_win(bet: u32) -> u32 {
+        bet * 2
+    }
 
-        /// Calculates the winnings for a loss, which is 0.
-        const fn payout_loss(&self) -> u32 {
-            0
-        }
+    /// Calculates the winnings for a push, which is the same as the bet.
+    const fn payout_push(bet: u32) -> u32 {
+        bet
+    }
+
+    /// Calculates the winnings for a surrender, which is half the bet.
+    const fn payout_surrender(bet: u32, rounding: PayoutRounding) -> u32 {
+        rounding.apply(bet, 1, 2)
+    }
+
+    /// Calculates the winnings for a loss, which is 0.
+    const fn payout_loss(_bet: u32) -> u32 {
+        0
     }
 
     /// All the player's hands in a round of blackjack.
-    /// This always starts with just one hand, but the player might split it into arbitrarily many.
-    /// Split hands are pushed onto the vec.
-    /// The player plays each hand in turn, and the hands are resolved in the order they were split.
+    /// This starts with one hand for each spot the player bet on, usually just one,
+    /// but the player might split them into arbitrarily many.
+    /// Split hands are inserted right after the hand they were split from,
+    /// so each spot's hands are played before the next spot's.
+    /// The player plays each hand in turn, and the hands are resolved in that order.
     #[derive(Debug, PartialEq, Eq)]
     pub struct PlayerTurn {
         /// The hands in the player's turn, initially just their starting hand.
         /// This will only grow in size if the player splits.
         hands: Vec<PlayerHand>,
-        /// The index of the hand the player is currently playing.
-        /// u8 is more than sufficient for the number of hands the player could realistically split
-        current_hand_index: usize,
+        /// The index of the hand the player is currently playing, below `PlayerTurn::MAX_HANDS`
+        current_hand_index: u8,
+        /// The number of spots the player bet on, each dealt a hand of its own
+        spots: u8,
     }
 
     /// Convenience implementation to convert a player hand into a player turn.
     impl From<PlayerHand> for PlayerTurn {
         fn from(hand: PlayerHand) -> Self {
-            Self {
-                hands: vec![hand],
-                current_hand_index: 0,
-            }
+            Self::from_spots(vec![hand])
         }
     }
 
     impl PlayerTurn {
+        /// The most hands a player turn can hold, so that every hand's index fits in a u8.
+        pub const MAX_HANDS: u8 = u8::MAX;
+
+        /// Creates a player turn from the hands dealt to each spot the player bet on.
+        /// # Panics
+        /// Will panic if there are more than `MAX_HANDS` hands.
+        pub fn from_spots(hands: Vec<PlayerHand>) -> Self {
+            Self {
+                spots: u8::try_from(hands.len()).expect("more spots than a player turn can hold"),
+                hands,
+                current_hand_index: 0,
+            }
+        }
         /// Returns a mutable reference to the current hand.
         pub fn current_hand_mut(&mut self) -> &mut PlayerHand {
-            &mut self.hands[self.current_hand_index]
+            &mut self.hands[usize::from(self.current_hand_index)]
         }
         /// Returns a reference to the current hand.
         pub fn current_hand(&self) -> &PlayerHand {
-            &self.hands[self.current_hand_index]
+            &self.hands[self.current_hand_index()]
+        }
+        /// Returns the index of the current hand, in the order the hands are played.
+        pub fn current_hand_index(&self) -> usize {
+            usize::from(self.current_hand_index)
         }
         /// Returns the total number of hands belonging to the player.
         pub fn hands(&self) -> u8 {
-            self.hands.len() as u8
+            u8::try_from(self.hands.len()).expect("more hands than a player turn can hold")
+        }
+        /// Returns the number of spots the player bet on.
+        pub fn spots(&self) -> u8 {
+            self.spots
+        }
+        /// Returns the number of times the player has split this turn.
+        pub fn splits(&self) -> u8 {
+            self.hands() - self.spots
+        }
+        /// Returns all the player's hands, in the order they are played.
+        pub fn all_hands(&self) -> &[PlayerHand] {
+            &self.hands
+        }
+        /// Returns the total bet on all the player's hands, including doubles.
+        pub fn total_bet(&self) -> u32 {
+            self.hands.iter().map(|hand| hand.bet).sum()
         }
 
-        /// Adds a new (split) hand to the player's turn.
-        /// The player may not play this hand immediately, so it is deferred until later.
+        /// Adds a new (split) hand to the player's turn, right after the current hand.
+        /// The player may not play this hand immediately, so it is deferred until the current hand is finished.
+        /// # Panics
+        /// Will panic if the turn already holds `MAX_HANDS` hands.
         pub fn defer(&mut self, hand: PlayerHand) {
-            self.hands.push(hand);
+            assert!(
+                self.hands() < Self::MAX_HANDS,
+                "more hands than a player turn can hold"
+            );
+            self.hands.insert(self.current_hand_index() + 1, hand);
         }
 
         /// Continue playing on the next hand which is still in-play.
         /// If the current hand is still in-play, then nothing happens,
         /// otherwise the index is incremented until a hand is found that is still in-play.
         /// This ensures that finished hands are not played again, and we eventually
-        /// play all hands to completion in the order they were split.
+        /// play all hands to completion in order.
         /// If there are no more hands to play, Self is deconstructed and Err(hands) is returned.
         pub fn continue_playing(mut self) -> Result<Self, Vec<PlayerHand>> {
             if let Some(position) = self
                 .hands
                 .iter()
-                .skip(self.current_hand_index)
+                .skip(self.current_hand_index())
                 .position(|hand| hand.status == Status::InPlay)
             {
-                self.current_hand_index += position;
+                self.current_hand_index = u8::try_from(self.current_hand_index() + position)
+                    .expect("more hands than a player turn can hold");
                 Ok(self)
             } else {
                 Err(self.hands)
@@ -519,10 +805,13 @@ pub mod hand {
 }
 
 pub mod shoe {
-    use rand::rng;
+    use std::collections::VecDeque;
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
     use rand_distr::{weighted::WeightedTreeIndex, Distribution};
 
-    use crate::card::Card;
+    use crate::card::{Card, Rank};
 
     /// A shoe is a container that contains multiple decks of cards.
     #[derive(Debug, Clone)]
@@ -534,7 +823,14 @@ pub mod shoe {
         /// The proportion of cards to play before shuffling
         pub max_penetration: f32,
         /// Weighted distribution to draw random cards from the shoe without replacement.
-        dist: WeightedTreeIndex<u8>,
+        dist: WeightedTreeIndex<u16>,
+        /// The random number generator cards are drawn with, kept so its state can be saved
+        pub(crate) rng: ChaCha12Rng,
+        /// The cards recorded from real shoes still to be dealt, one shoe after another,
+        /// or None if cards are dealt at random
+        recorded: Option<VecDeque<VecDeque<Rank>>>,
+        /// The ranks of the next cards to deal, forced for analysis ahead of any others
+        pub(crate) forced: VecDeque<Rank>,
     }
 
     impl Shoe {
@@ -545,46 +841,418 @@ pub mod shoe {
         /// Panics if the number of decks is 0
         #[must_use]
         pub fn new(decks: u8, shuffle_threshold: f32) -> Self {
+            Self::with_rng(decks, shuffle_threshold, ChaCha12Rng::from_os_rng())
+        }
+
+        /// Create a new shoe which deals the same cards every time for the same seed.
+        ///
+        /// # Panics
+        ///
+        /// Panics if the number of decks is 0
+        #[must_use]
+        pub fn seeded(decks: u8, shuffle_threshold: f32, seed: u64) -> Self {
+            Self::with_rng(decks, shuffle_threshold, ChaCha12Rng::seed_from_u64(seed))
+        }
+
+        fn with_rng(decks: u8, shuffle_threshold: f32, rng: ChaCha12Rng) -> Self {
             Self {
                 decks,
                 cards_drawn: 0,
                 max_penetration: shuffle_threshold,
-                dist: WeightedTreeIndex::new([decks; 52]).unwrap(),
+                dist: WeightedTreeIndex::new([u16::from(decks); 52]).unwrap(),
+                rng,
+                recorded: None,
+                forced: VecDeque::new(),
+            }
+        }
+
+        /// Create a shoe which deals the cards recorded from real shoes, in order,
+        /// to see how a strategy would have done on them.
+        /// The transcript lists the ranks dealt from each shoe, e.g. "A 7 K 10 2 T",
+        /// with a blank line between shoes. Suits may be given, e.g. "AS 10H", but are ignored.
+        /// Each shoe is shuffled once its recorded cards run out.
+        /// If a shoe runs out mid-round, or a card is recorded more often than the decks allow,
+        /// random cards are dealt instead.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if a card in the transcript cannot be read.
+        ///
+        /// # Panics
+        ///
+        /// Panics if the number of decks is 0
+        pub fn recorded(decks: u8, transcript: &str) -> Result<Self, String> {
+            let mut shoes = VecDeque::new();
+            let mut shoe = VecDeque::new();
+            for line in transcript.lines().map(str::trim) {
+                if line.starts_with('#') {
+                    continue;
+                }
+                if line.is_empty() {
+                    if !shoe.is_empty() {
+                        shoes.push_back(std::mem::take(&mut shoe));
+                    }
+                    continue;
+                }
+                for card in line.split([' ', ',', '\t']).filter(|card| !card.is_empty()) {
+                    let rank = card.parse().or_else(|_| {
+                        // Drop the suit, which comes last
+                        let suit = card.char_indices().last().map_or(0, |(i, _)| i);
+                        card[..suit].parse()
+                    });
+                    shoe.push_back(rank.map_err(|_| format!("unknown card: {card}"))?);
+                }
+            }
+            if !shoe.is_empty() {
+                shoes.push_back(shoe);
             }
+            Ok(Self {
+                recorded: Some(shoes),
+                ..Self::new(decks, 1.0)
+            })
+        }
+
+        /// Returns whether every recorded shoe has been dealt.
+        /// Shoes dealing at random are never over.
+        #[must_use]
+        pub fn recording_over(&self) -> bool {
+            self.recorded.as_ref().is_some_and(VecDeque::is_empty)
+        }
+
+        /// Restores a shoe with the given number of each card left in it, by ordinal.
+        pub(crate) fn restore(
+            decks: u8,
+            shuffle_threshold: f32,
+            weights: [u16; 52],
+            rng: ChaCha12Rng,
+        ) -> Result<Self, String> {
+            let dist = WeightedTreeIndex::new(weights).map_err(|e| e.to_string())?;
+            let cards_drawn = (u16::from(decks) * 52)
+                .checked_sub(weights.iter().sum())
+                .ok_or("more cards than decks in the shoe")?;
+            Ok(Self {
+                decks,
+                cards_drawn,
+                max_penetration: shuffle_threshold,
+                dist,
+                rng,
+                recorded: None,
+                forced: VecDeque::new(),
+            })
         }
 
-        /// Draws a random card from the shoe.
+        /// Returns the number of each card left in the shoe, by ordinal.
+        pub(crate) fn weights(&self) -> [u16; 52] {
+            std::array::from_fn(|ordinal| self.dist.get(ordinal))
+        }
+
+        /// Forces the next cards dealt to be of the given ranks, in order,
+        /// after any cards already forced, to explore "what if" rounds on an otherwise normal shoe.
+        /// Each forced card is taken out of the shoe as it is dealt, so the rest of the shoe is unchanged,
+        /// and a random card is dealt instead if none of its rank are left.
+        /// Forced cards outlast a shuffle.
+        pub fn force(&mut self, ranks: impl IntoIterator<Item = Rank>) {
+            self.forced.extend(ranks);
+        }
+
+        /// Returns the ranks of the cards forced to be dealt next, in order.
+        #[must_use]
+        pub const fn forced(&self) -> &VecDeque<Rank> {
+            &self.forced
+        }
+
+        /// Draws a random card from the shoe, unless one has been forced or recorded.
         /// The card is removed from the shoe, and the distribution is updated to reflect the new weight.
-        /// If the last card is drawn, the shoe is shuffled.
+        /// If the shoe has run out mid-round, it is shuffled before the card is drawn.
         pub fn draw_card(&mut self) -> Card {
-            let ordinal = self.dist.sample(&mut rng());
-            self.cards_drawn += 1;
-            let new_weight = self.dist.get(ordinal) - 1;
-            // Update the distribution to reflect the new weight of the removed card
-            if self.dist.update(ordinal, new_weight).is_err() {
-                // The update failed, so we must have drawn the last card
-                debug_assert_eq!(self.cards_drawn, self.decks as u16 * 52, "last card drawn");
+            if self.cards_remaining() == 0 {
                 self.shuffle();
             }
+            let rank = self.forced.pop_front().or_else(|| {
+                self.recorded
+                    .as_mut()
+                    .and_then(VecDeque::front_mut)
+                    .and_then(VecDeque::pop_front)
+            });
+            let ordinal = rank
+                .and_then(|rank| {
+                    let first = rank as usize * 4; // Ordinals are sorted by rank, then suit
+                    (first..first + 4).find(|&ordinal| self.dist.get(ordinal) > 0)
+                })
+                .unwrap_or_else(|| self.dist.sample(&mut self.rng));
+            self.cards_drawn += 1;
+            let new_weight = self.dist.get(ordinal) - 1;
+            // Update the distribution to reflect the new weight of the removed card.
+            // The weights may all be zero once the last card is drawn.
+            self.dist
+                .update(ordinal, new_weight)
+                .expect("a drawn card's weight is one less than before");
             Card::from_ordinal(ordinal)
         }
 
+        /// Returns the number of cards left in the shoe.
+        #[must_use]
+        pub fn cards_remaining(&self) -> u16 {
+            self.decks as u16 * 52 - self.cards_drawn
+        }
+
+        /// Returns the number of decks left in the shoe, e.g. 2.5 for 130 cards.
+        #[must_use]
+        pub fn decks_remaining(&self) -> f32 {
+            f32::from(self.cards_remaining()) / 52.0
+        }
+
+        /// Returns the number of cards of the given rank left in the shoe.
+        #[must_use]
+        pub fn remaining(&self, rank: &Rank) -> u16 {
+            let first = rank.clone() as usize * 4; // Ordinals are sorted by rank, then suit
+            (first..first + 4)
+                .map(|ordinal| self.dist.get(ordinal))
+                .sum()
+        }
+
+        /// Returns the number of cards of each rank left in the shoe.
+        #[must_use]
+        pub fn composition(&self) -> Composition {
+            Composition {
+                counts: Rank::ALL.map(|rank| self.remaining(&rank)),
+            }
+        }
+
+        /// Checks if no cards have been drawn since the shoe was last shuffled.
+        #[must_use]
+        pub const fn is_fresh(&self) -> bool {
+            self.cards_drawn == 0
+        }
+
         /// Checks if the shoe needs to be shuffled.
+        /// A recorded shoe needs to be shuffled once its recorded cards run out.
         #[must_use]
         pub fn needs_shuffle(&self) -> bool {
+            if let Some(shoes) = &self.recorded {
+                return shoes.front().is_none_or(VecDeque::is_empty);
+            }
             let penetration = f32::from(self.cards_drawn) / f32::from(self.decks as u16 * 52);
             penetration >= self.max_penetration
         }
 
         /// Shuffles the shoe.
         /// All cards are returned to the shoe, and the distribution is updated to reflect the new weights.
+        /// A recorded shoe moves on to the next recorded shoe.
         ///
         /// # Panics
         ///
         /// Panics if the number of decks is 0
         pub fn shuffle(&mut self) {
+            if let Some(shoes) = &mut self.recorded {
+                shoes.pop_front();
+            }
             self.cards_drawn = 0;
-            self.dist = WeightedTreeIndex::new([self.decks; 52]).unwrap();
+            // Refill the distribution in place rather than building a new one, so shuffling doesn't allocate
+            for ordinal in 0..52 {
+                self.dist
+                    .update(ordinal, u16::from(self.decks))
+                    .expect("a full shoe's weights are valid");
+            }
+        }
+
+        /// Draws the cards from now on with a new seed.
+        /// A freshly shuffled shoe then deals the same cards as `Shoe::seeded` with the seed.
+        pub fn reseed(&mut self, seed: u64) {
+            self.rng = ChaCha12Rng::seed_from_u64(seed);
+        }
+    }
+
+    /// The number of cards of each rank left in a shoe.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Composition {
+        /// The count for each rank, in the order of `Rank::ALL`
+        counts: [u16; 13],
+    }
+
+    impl From<[u16; 13]> for Composition {
+        /// Creates a composition from the count for each rank, in the order of `Rank::ALL`.
+        fn from(counts: [u16; 13]) -> Self {
+            Self { counts }
+        }
+    }
+
+    impl Composition {
+        /// Returns the number of cards of the given rank.
+        #[must_use]
+        pub fn remaining(&self, rank: &Rank) -> u16 {
+            self.counts[rank.clone() as usize]
+        }
+
+        /// Returns the number of cards worth the given amount, where an ace is worth 11.
+        #[must_use]
+        pub fn remaining_worth(&self, worth: u8) -> u16 {
+            Rank::ALL
+                .iter()
+                .filter(|rank| rank.worth() == worth)
+                .map(|rank| self.remaining(rank))
+                .sum()
+        }
+
+        /// Returns the total number of cards.
+        #[must_use]
+        pub fn total(&self) -> u16 {
+            self.counts.iter().sum()
+        }
+
+        /// Returns the number of decks the cards make up, e.g. 2.5 for 130 cards.
+        #[must_use]
+        pub fn decks_remaining(&self) -> f32 {
+            f32::from(self.total()) / 52.0
+        }
+
+        /// Adds a card back, such as one that has been drawn but not yet seen.
+        pub fn put_back(&mut self, card: &Card) {
+            self.counts[card.rank.clone() as usize] += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::hand::{DealerHand, PlayerHand, RoundOutcome};
+    use super::shoe::Shoe;
+    use super::*;
+    use crate::rules::{BlackjackPayout, DealerDrawing, PayoutRounding, PushPolicy, Rules};
+
+    #[test]
+    fn test_recorded_shoe() {
+        let mut shoe = Shoe::recorded(1, "# two shoes\nA KS 10h\n5, 5\n\n2\n").unwrap();
+        let ranks: Vec<Rank> = (0..5).map(|_| shoe.draw_card().rank).collect();
+        assert_eq!(
+            ranks,
+            [Rank::Ace, Rank::King, Rank::Ten, Rank::Five, Rank::Five]
+        );
+        assert!(shoe.needs_shuffle());
+        shoe.shuffle();
+        assert!(!shoe.needs_shuffle());
+        assert_eq!(shoe.draw_card().rank, Rank::Two);
+        shoe.shuffle();
+        assert!(shoe.recording_over());
+        assert!(Shoe::recorded(1, "A X").is_err());
+    }
+
+    #[test]
+    fn test_forced_cards() {
+        let mut shoe = Shoe::seeded(1, 0.75, 1);
+        shoe.force([Rank::Ace, Rank::Ace, Rank::Seven]);
+        let ranks: Vec<Rank> = (0..3).map(|_| shoe.draw_card().rank).collect();
+        assert_eq!(ranks, [Rank::Ace, Rank::Ace, Rank::Seven]);
+        assert_eq!(shoe.remaining(&Rank::Ace), 2);
+        assert!(shoe.forced().is_empty());
+    }
+
+    #[test]
+    fn test_draw_past_last_card() {
+        let mut shoe = Shoe::seeded(1, 1.0, 1);
+        for _ in 0..52 {
+            shoe.draw_card();
+        }
+        assert_eq!(shoe.cards_remaining(), 0);
+        shoe.draw_card();
+        assert_eq!(shoe.cards_remaining(), 51);
+    }
+
+    #[test]
+    fn test_push_22() {
+        let card = |rank| Card {
+            rank,
+            suit: Suit::Spades,
+        };
+        let mut hand = PlayerHand::new(card(Rank::Ten), 100);
+        hand += card(Rank::Eight);
+        hand.stand();
+        let mut blackjack = PlayerHand::new(card(Rank::Ace), 100);
+        blackjack += card(Rank::King);
+        let mut dealer_hand = DealerHand::new(card(Rank::Ten), DealerDrawing::S17);
+        dealer_hand += card(Rank::Six);
+        dealer_hand += card(Rank::Six);
+        let mut rules = Rules::default();
+        assert_eq!(
+            hand.calculate_outcome(&dealer_hand, &rules),
+            RoundOutcome::Win
+        );
+        rules.dealer_push_22 = true;
+        assert_eq!(
+            hand.calculate_outcome(&dealer_hand, &rules),
+            RoundOutcome::Push22
+        );
+        assert_eq!(hand.calculate_winnings(&dealer_hand, &rules), 100);
+        assert_eq!(blackjack.calculate_winnings(&dealer_hand, &rules), 250);
+    }
+
+    #[test]
+    fn test_push_policy() {
+        let card = |rank| Card {
+            rank,
+            suit: Suit::Spades,
+        };
+        let stood = |first, second| {
+            let mut hand = PlayerHand::new(card(first), 100);
+            hand += card(second);
+            hand.stand();
+            hand
+        };
+        let mut dealer_18 = DealerHand::new(card(Rank::Ten), DealerDrawing::S17);
+        dealer_18 += card(Rank::Eight);
+        let mut dealer_20 = DealerHand::new(card(Rank::Ten), DealerDrawing::S17);
+        dealer_20 += card(Rank::Queen);
+        let (hand_18, hand_20) = (stood(Rank::Nine, Rank::Nine), stood(Rank::King, Rank::Jack));
+        let mut rules = Rules::default();
+        assert_eq!(hand_18.calculate_winnings(&dealer_18, &rules), 100);
+        rules.push_policy = "TL19".parse().unwrap();
+        assert_eq!(hand_18.calculate_winnings(&dealer_18, &rules), 0);
+        assert_eq!(hand_20.calculate_winnings(&dealer_20, &rules), 100);
+        rules.push_policy = PushPolicy::TiesLose;
+        assert_eq!(
+            hand_20.calculate_outcome(&dealer_20, &rules),
+            RoundOutcome::Lose
+        );
+    }
+
+    #[test]
+    fn test_payout_rounding() {
+        let card = |rank| Card {
+            rank,
+            suit: Suit::Spades,
+        };
+        let mut blackjack = PlayerHand::new(card(Rank::Ace), 15);
+        blackjack += card(Rank::King);
+        let mut dealer_hand = DealerHand::new(card(Rank::Ten), DealerDrawing::S17);
+        dealer_hand += card(Rank::Seven);
+        let mut rules = Rules::default();
+        // 3:2 on 15 is 22.5
+        let payouts = PayoutRounding::ALL.map(|rounding| {
+            rules.payout_rounding = rounding;
+            blackjack.calculate_winnings(&dealer_hand, &rules)
+        });
+        assert_eq!(payouts, [37, 38, 38]);
+        rules.blackjack_payout = BlackjackPayout::SixToFive;
+        // 6:5 on 15 is exactly 18
+        assert_eq!(blackjack.calculate_winnings(&dealer_hand, &rules), 33);
+        assert_eq!(PayoutRounding::Down.apply(15, 1, 2), 7);
+        assert_eq!(PayoutRounding::Nearest.apply(14, 6, 5), 17);
+        assert_eq!(PayoutRounding::Up.apply(14, 6, 5), 17);
+        assert_eq!(PayoutRounding::Up.apply(15, 1, 2), 8);
+    }
+
+    #[test]
+    fn test_composition() {
+        let mut shoe = Shoe::seeded(2, 0.75, 1);
+        assert_eq!(shoe.cards_remaining(), 104);
+        assert!((shoe.decks_remaining() - 2.0).abs() < f32::EPSILON);
+        let drawn: Vec<Card> = (0..26).map(|_| shoe.draw_card()).collect();
+        let composition = shoe.composition();
+        assert_eq!(composition.total(), shoe.cards_remaining());
+        assert!((composition.decks_remaining() - 1.5).abs() < f32::EPSILON);
+        for rank in &Rank::ALL {
+            let seen = drawn.iter().filter(|card| card.rank == *rank).count();
+            assert_eq!(usize::from(composition.remaining(rank)) + seen, 8);
         }
     }
 }
@@ -0,0 +1,276 @@
+//! Strategy charts: the recommended action for every starting hand against every dealer upcard.
+//! Charts can be generated from basic strategy for a set of rules, or written by hand as text,
+//! one row per hand, e.g. `A7: D D D D D S S H H H`.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::basic_strategy;
+use crate::card::hand::{DealerHand, PlayerHand, PlayerTurn};
+use crate::card::shoe::Shoe;
+use crate::card::{Card, Rank, Suit};
+use crate::game::{HandAction, Table};
+use crate::rules::Rules;
+
+/// The dealer upcards in chart order, by worth (an ace is 11).
+pub const UPCARDS: [u8; 10] = [2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
+
+/// A starting hand, as it appears on a chart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChartHand {
+    /// Two different cards without an ace, by total
+    Hard(u8),
+    /// An ace and another card, by total
+    Soft(u8),
+    /// Two cards of the same worth, by the worth of one card
+    Pair(u8),
+}
+
+impl ChartHand {
+    /// Every hand on a chart, in chart order.
+    pub fn all() -> impl Iterator<Item = Self> {
+        (5..=19)
+            .map(Self::Hard)
+            .chain((13..=20).map(Self::Soft))
+            .chain((2..=11).map(Self::Pair))
+    }
+
+    /// The chart hand a player's hand is played as: a pair if it is two cards of the same worth,
+    /// or its hard or soft total otherwise, however many cards it has.
+    #[must_use]
+    pub fn of(hand: &PlayerHand) -> Self {
+        match hand.cards.as_slice() {
+            [first, second] if first.rank.worth() == second.rank.worth() => {
+                Self::Pair(first.rank.worth())
+            }
+            _ if hand.value.soft => Self::Soft(hand.value.total),
+            _ => Self::Hard(hand.value.total),
+        }
+    }
+
+    /// The two cards making up this hand.
+    const fn cards(self) -> (u8, u8) {
+        match self {
+            Self::Hard(total @ ..=11) => (2, total - 2),
+            Self::Hard(total) => (10, total - 10),
+            Self::Soft(total) => (11, total - 11),
+            Self::Pair(worth) => (worth, worth),
+        }
+    }
+}
+
+impl fmt::Display for ChartHand {
+    /// Hands are displayed as their total if hard, or as their cards otherwise, e.g. "16", "A7", "88"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Hard(total) => write!(f, "{total}"),
+            Self::Soft(total) => write!(f, "A{}", worth_symbol(total - 11)),
+            Self::Pair(worth) => write!(f, "{0}{0}", worth_symbol(*worth)),
+        }
+    }
+}
+
+impl FromStr for ChartHand {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ChartHand::all()
+            .find(|hand| hand.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown hand: {s}"))
+    }
+}
+
+/// Returns the symbol for a card worth, e.g. "T" for 10 or "A" for 11.
+#[must_use]
+pub fn worth_symbol(worth: u8) -> String {
+    match worth {
+        10 => "T".to_string(),
+        11 => "A".to_string(),
+        worth => worth.to_string(),
+    }
+}
+
+/// Returns the letter for an action on a chart.
+#[must_use]
+pub const fn action_symbol(action: HandAction) -> char {
+    match action {
+        HandAction::Stand => 'S',
+        HandAction::Hit => 'H',
+        HandAction::Double => 'D',
+        HandAction::Split => 'P',
+        HandAction::Surrender => 'R',
+    }
+}
+
+fn parse_action(symbol: &str) -> Result<HandAction, String> {
+    match symbol {
+        "S" | "s" => Ok(HandAction::Stand),
+        "H" | "h" => Ok(HandAction::Hit),
+        "D" | "d" => Ok(HandAction::Double),
+        "P" | "p" => Ok(HandAction::Split),
+        "R" | "r" => Ok(HandAction::Surrender),
+        _ => Err(format!("unknown action: {symbol}")),
+    }
+}
+
+/// Returns a card worth the given amount, where an ace is worth 11.
+fn card_worth(worth: u8) -> Card {
+    let rank = match worth {
+        2 => Rank::Two,
+        3 => Rank::Three,
+        4 => Rank::Four,
+        5 => Rank::Five,
+        6 => Rank::Six,
+        7 => Rank::Seven,
+        8 => Rank::Eight,
+        9 => Rank::Nine,
+        10 => Rank::Ten,
+        _ => Rank::Ace,
+    };
+    Card {
+        rank,
+        suit: Suit::Spades,
+    }
+}
+
+/// One row of a chart: the action for a hand against each upcard in `UPCARDS` order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChartRow {
+    pub hand: ChartHand,
+    pub actions: [HandAction; 10],
+}
+
+/// The recommended action for every starting hand against every dealer upcard.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StrategyChart {
+    pub rows: Vec<ChartRow>,
+}
+
+impl StrategyChart {
+    /// Generates the basic strategy chart for a table with the given rules and number of decks.
+    #[must_use]
+    pub fn generate(rules: &Rules, decks: u8) -> Self {
+        let table = Table::new(u32::MAX / 2, Shoe::new(decks, 0.75), rules.clone());
+        let rows = ChartHand::all()
+            .map(|hand| {
+                let (first, second) = hand.cards();
+                let mut player_hand = PlayerHand::new(card_worth(first), 100);
+                player_hand += card_worth(second);
+                let player_turn = PlayerTurn::from(player_hand);
+                let actions = UPCARDS.map(|upcard| {
+                    let dealer_hand = DealerHand::new(card_worth(upcard), rules.dealer_drawing);
+                    basic_strategy::play_hand(&table, &player_turn, &dealer_hand)
+                });
+                ChartRow { hand, actions }
+            })
+            .collect();
+        Self { rows }
+    }
+
+    /// Returns the action for the hand against the upcard, if the chart covers it.
+    #[must_use]
+    pub fn action(&self, hand: ChartHand, upcard: u8) -> Option<HandAction> {
+        let column = UPCARDS.iter().position(|&u| u == upcard)?;
+        self.rows
+            .iter()
+            .find(|row| row.hand == hand)
+            .map(|row| row.actions[column])
+    }
+
+    /// Returns every hand and upcard where the two charts recommend different actions,
+    /// along with the action from this chart and the other.
+    /// Hands missing from either chart are not compared.
+    #[must_use]
+    pub fn differences(&self, other: &Self) -> Vec<(ChartHand, u8, HandAction, HandAction)> {
+        let mut differences = Vec::new();
+        for row in &self.rows {
+            let Some(other_row) = other.rows.iter().find(|r| r.hand == row.hand) else {
+                continue;
+            };
+            for (i, upcard) in UPCARDS.into_iter().enumerate() {
+                if row.actions[i] != other_row.actions[i] {
+                    differences.push((row.hand, upcard, row.actions[i], other_row.actions[i]));
+                }
+            }
+        }
+        differences
+    }
+}
+
+impl fmt::Display for StrategyChart {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "   ")?;
+        for upcard in UPCARDS {
+            write!(f, " {}", worth_symbol(upcard))?;
+        }
+        writeln!(f)?;
+        for row in &self.rows {
+            write!(f, "{:<3}", format!("{}:", row.hand))?;
+            for action in row.actions {
+                write!(f, " {}", action_symbol(action))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for StrategyChart {
+    type Err = String;
+
+    /// Parses a chart with one `hand: actions` row per line.
+    /// Lines without a colon, such as the header, and lines starting with `#` are ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rows = Vec::new();
+        for line in s.lines().map(str::trim) {
+            if line.starts_with('#') {
+                continue;
+            }
+            let Some((hand, actions)) = line.split_once(':') else {
+                continue;
+            };
+            let hand = hand.trim().parse()?;
+            let actions = actions
+                .split_whitespace()
+                .map(parse_action)
+                .collect::<Result<Vec<_>, _>>()?;
+            let actions = actions
+                .try_into()
+                .map_err(|_| format!("expected 10 actions for {hand}"))?;
+            rows.push(ChartRow { hand, actions });
+        }
+        Ok(Self { rows })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::DealerDrawing;
+
+    #[test]
+    fn test_chart() {
+        let s17 = StrategyChart::generate(&Rules::default(), 6);
+        assert_eq!(s17.rows.len(), 33);
+        assert_eq!(s17.action(ChartHand::Pair(11), 6), Some(HandAction::Split));
+        assert_eq!(
+            s17.action(ChartHand::Hard(16), 10),
+            Some(HandAction::Surrender)
+        );
+        assert_eq!(s17.to_string().parse(), Ok(s17.clone()));
+
+        let h17 = StrategyChart::generate(
+            &Rules {
+                dealer_drawing: DealerDrawing::H17,
+                ..Rules::default()
+            },
+            6,
+        );
+        assert!(s17.differences(&h17).contains(&(
+            ChartHand::Hard(11),
+            11,
+            HandAction::Hit,
+            HandAction::Double
+        )));
+    }
+}
@@ -0,0 +1,354 @@
+//! Checkpoints of a simulation, so long runs can be resumed after an interruption.
+//! A checkpoint saves everything that changes as rounds are played: the player's chips and last bet,
+//! the shoe (including the state of its random number generator), the statistics, the report,
+//! the current session (its heat, its limits so far and any coupon left on the table),
+//! and what the strategy has learned, e.g. where its betting progression stands.
+//! The table's rules and the strategy itself are not saved, and must be set up the same way to resume.
+//!
+//! Checkpoints are written as text, one `key = value` per line.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::str::FromStr;
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+
+use crate::betting::CountResults;
+use crate::card::shoe::Shoe;
+use crate::promotion::Coupon;
+use crate::simulation::Report;
+use crate::statistics::Statistics;
+
+/// The state of a simulation between two rounds.
+#[derive(Debug, Clone)]
+pub struct Checkpoint {
+    /// The seed the simulation's first shoe was shuffled with, if known, to record a resumed run under
+    pub seed: Option<u64>,
+    pub chips: u32,
+    /// The bet the player placed on the last round, if any
+    pub last_bet: Option<u32>,
+    /// The player's net result on the last round settled, if any
+    pub last_net: Option<i64>,
+    /// The coupon a push left on the table, to redeem on the next round played
+    pub coupon: Option<Coupon>,
+    /// The heat on the player, if the simulation draws heat
+    pub heat: Option<f64>,
+    /// The bet the heat was last measured from, if any
+    pub heat_last_bet: Option<u32>,
+    /// Whether the player played the last round, rather than sitting it out
+    pub seated: bool,
+    /// The number of rounds dealt in the current session
+    pub session_rounds: usize,
+    /// The player's chips at the start of the current session
+    pub session_chips: u32,
+    pub shoe: Shoe,
+    pub statistics: Statistics,
+    pub report: Report,
+    /// What the strategy has learned from the rounds so far, as it saved it
+    pub strategy: BTreeMap<String, String>,
+    /// How far each of the simulation's output files had been written, in bytes, by name,
+    /// so a resumed simulation can cut off what was written after the checkpoint
+    pub outputs: BTreeMap<String, u64>,
+}
+
+impl fmt::Display for Checkpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# Blackjack simulation checkpoint")?;
+        if let Some(seed) = self.seed {
+            writeln!(f, "seed = {seed}")?;
+        }
+        writeln!(f, "chips = {}", self.chips)?;
+        if let Some(last_bet) = self.last_bet {
+            writeln!(f, "last_bet = {last_bet}")?;
+        }
+        if let Some(last_net) = self.last_net {
+            writeln!(f, "last_net = {last_net}")?;
+        }
+        if let Some(coupon) = self.coupon {
+            writeln!(f, "coupon = {coupon}")?;
+        }
+        if let Some(heat) = self.heat {
+            writeln!(f, "heat.level = {heat}")?;
+        }
+        if let Some(last_bet) = self.heat_last_bet {
+            writeln!(f, "heat.last_bet = {last_bet}")?;
+        }
+        writeln!(f, "session.seated = {}", self.seated)?;
+        writeln!(f, "session.rounds = {}", self.session_rounds)?;
+        writeln!(f, "session.chips = {}", self.session_chips)?;
+        writeln!(f, "shoe.decks = {}", self.shoe.decks)?;
+        writeln!(f, "shoe.penetration = {}", self.shoe.max_penetration)?;
+        let weights = self.shoe.weights().map(|weight| weight.to_string());
+        writeln!(f, "shoe.cards = {}", weights.join(" "))?;
+        let seed: String = self
+            .shoe
+            .rng
+            .get_seed()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        writeln!(f, "shoe.seed = {seed}")?;
+        writeln!(f, "shoe.stream = {}", self.shoe.rng.get_stream())?;
+        writeln!(f, "shoe.position = {}", self.shoe.rng.get_word_pos())?;
+        for (name, value) in self.statistics.clone().counters_mut() {
+            writeln!(f, "statistics.{name} = {value}")?;
+        }
+        let report = &self.report;
+        writeln!(f, "report.rounds_observed = {}", report.rounds_observed)?;
+        writeln!(f, "report.rounds_played = {}", report.rounds_played)?;
+        writeln!(f, "report.net = {}", report.net)?;
+        writeln!(f, "report.net_squared = {}", report.net_squared)?;
+        writeln!(f, "report.tips = {}", report.tips)?;
+        writeln!(f, "report.lowest_bankroll = {}", report.lowest_bankroll)?;
+        writeln!(f, "report.highest_bankroll = {}", report.highest_bankroll)?;
+        writeln!(f, "report.sessions = {}", report.sessions)?;
+        writeln!(f, "report.back_offs = {}", report.back_offs)?;
+        writeln!(f, "report.stop_wins = {}", report.stop_wins)?;
+        writeln!(f, "report.stop_losses = {}", report.stop_losses)?;
+        for (count, results) in &report.by_true_count {
+            writeln!(
+                f,
+                "report.count.{count} = {} {} {}",
+                results.rounds, results.units, results.units_squared
+            )?;
+        }
+        for (key, value) in &self.strategy {
+            writeln!(f, "strategy.{key} = {value}")?;
+        }
+        for (name, length) in &self.outputs {
+            writeln!(f, "output.{name} = {length}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Checkpoint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let values: HashMap<&str, &str> = s
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                line.split_once('=')
+                    .map(|(key, value)| (key.trim(), value.trim()))
+                    .ok_or_else(|| format!("expected key = value: {line}"))
+            })
+            .collect::<Result<_, _>>()?;
+        let get = |key: &str| {
+            values
+                .get(key)
+                .copied()
+                .ok_or_else(|| format!("missing {key}"))
+        };
+        fn parse<T: FromStr>(key: &str, value: &str) -> Result<T, String> {
+            value.parse().map_err(|_| format!("invalid {key}: {value}"))
+        }
+
+        let weights = get("shoe.cards")?
+            .split_whitespace()
+            .map(|weight| parse("shoe.cards", weight))
+            .collect::<Result<Vec<u16>, _>>()?
+            .try_into()
+            .map_err(|_| "expected 52 numbers for shoe.cards".to_string())?;
+        let seed = get("shoe.seed")?;
+        let seed: [u8; 32] = (0..seed.len())
+            .step_by(2)
+            .map(|i| {
+                seed.get(i..i + 2)
+                    .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                    .ok_or_else(|| format!("invalid shoe.seed: {seed}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .try_into()
+            .map_err(|_| format!("invalid shoe.seed: {seed}"))?;
+        let mut rng = ChaCha12Rng::from_seed(seed);
+        rng.set_stream(parse("shoe.stream", get("shoe.stream")?)?);
+        rng.set_word_pos(parse("shoe.position", get("shoe.position")?)?);
+        let shoe = Shoe::restore(
+            parse("shoe.decks", get("shoe.decks")?)?,
+            parse("shoe.penetration", get("shoe.penetration")?)?,
+            weights,
+            rng,
+        )?;
+
+        let mut statistics = Statistics::new();
+        for (name, value) in statistics.counters_mut() {
+            let key = format!("statistics.{name}");
+            *value = parse(&key, get(&key)?)?;
+        }
+
+        let mut report = Report {
+            rounds_observed: parse("report.rounds_observed", get("report.rounds_observed")?)?,
+            rounds_played: parse("report.rounds_played", get("report.rounds_played")?)?,
+            net: parse("report.net", get("report.net")?)?,
+            net_squared: parse("report.net_squared", get("report.net_squared")?)?,
+            tips: parse("report.tips", get("report.tips")?)?,
+            lowest_bankroll: parse("report.lowest_bankroll", get("report.lowest_bankroll")?)?,
+            highest_bankroll: parse("report.highest_bankroll", get("report.highest_bankroll")?)?,
+            sessions: parse("report.sessions", get("report.sessions")?)?,
+            back_offs: parse("report.back_offs", get("report.back_offs")?)?,
+            stop_wins: parse("report.stop_wins", get("report.stop_wins")?)?,
+            stop_losses: parse("report.stop_losses", get("report.stop_losses")?)?,
+            ..Report::default()
+        };
+        for (key, value) in &values {
+            let Some(count) = key.strip_prefix("report.count.") else {
+                continue;
+            };
+            let results: Vec<&str> = value.split_whitespace().collect();
+            let [rounds, units, units_squared] = results[..] else {
+                return Err(format!("expected 3 numbers for {key}"));
+            };
+            report.by_true_count.insert(
+                parse(key, count)?,
+                CountResults {
+                    rounds: parse(key, rounds)?,
+                    units: parse(key, units)?,
+                    units_squared: parse(key, units_squared)?,
+                },
+            );
+        }
+
+        let strategy = values
+            .iter()
+            .filter_map(|(key, value)| {
+                let key = key.strip_prefix("strategy.")?;
+                Some((key.to_string(), (*value).to_string()))
+            })
+            .collect();
+        let outputs = values
+            .iter()
+            .filter_map(|(key, value)| Some((key.strip_prefix("output.")?, key, value)))
+            .map(|(name, key, value)| Ok((name.to_string(), parse(key, value)?)))
+            .collect::<Result<_, String>>()?;
+
+        Ok(Self {
+            seed: values
+                .get("seed")
+                .map(|value| parse("seed", value))
+                .transpose()?,
+            chips: parse("chips", get("chips")?)?,
+            last_bet: values
+                .get("last_bet")
+                .map(|value| parse("last_bet", value))
+                .transpose()?,
+            last_net: values
+                .get("last_net")
+                .map(|value| parse("last_net", value))
+                .transpose()?,
+            coupon: values
+                .get("coupon")
+                .map(|value| value.parse())
+                .transpose()?,
+            heat: values
+                .get("heat.level")
+                .map(|value| parse("heat.level", value))
+                .transpose()?,
+            heat_last_bet: values
+                .get("heat.last_bet")
+                .map(|value| parse("heat.last_bet", value))
+                .transpose()?,
+            seated: parse("session.seated", get("session.seated")?)?,
+            session_rounds: parse("session.rounds", get("session.rounds")?)?,
+            session_chips: parse("session.chips", get("session.chips")?)?,
+            shoe,
+            statistics,
+            report,
+            strategy,
+            outputs,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::betting::{Martingale, WithBetting};
+    use crate::card::shoe::Shoe;
+    use crate::game::Table;
+    use crate::heat::{Heat, HeatModel};
+    use crate::rules::Rules;
+    use crate::session::SessionLimits;
+    use crate::simulation::Simulator;
+    use crate::strategy::BasicStrategy;
+
+    use super::*;
+
+    #[test]
+    fn test_checkpoint() {
+        let table = Table::new(1_000_000, Shoe::seeded(6, 0.75, 42), Rules::default());
+        let mut simulator = Simulator::new(table);
+        simulator.run(500);
+        let checkpoint: Checkpoint = simulator.checkpoint().to_string().parse().unwrap();
+        assert_eq!(checkpoint.report, simulator.report);
+        assert_eq!(checkpoint.statistics, simulator.table.statistics);
+
+        let table = Table::new(0, Shoe::new(1, 0.5), Rules::default());
+        let mut resumed = Simulator::new(table);
+        resumed.resume(checkpoint).unwrap();
+        simulator.run(500);
+        resumed.run(500);
+        assert_eq!(resumed.report, simulator.report);
+        assert_eq!(resumed.table.chips, simulator.table.chips);
+    }
+
+    #[test]
+    fn test_checkpoint_progression() {
+        // The progression carries on where it stood, rather than starting over at the base bet
+        let strategy = || WithBetting::new(BasicStrategy, Martingale::new(1));
+        let table = Table::new(1_000_000, Shoe::seeded(6, 0.75, 42), Rules::default());
+        let mut simulator = Simulator::with_strategy(table, strategy());
+        simulator.run(500);
+        let mut checkpoint = simulator.checkpoint();
+        checkpoint.outputs.insert("trajectory".to_string(), 1234);
+        let checkpoint: Checkpoint = checkpoint.to_string().parse().unwrap();
+        assert_eq!(checkpoint.outputs["trajectory"], 1234);
+
+        let table = Table::new(0, Shoe::new(1, 0.5), Rules::default());
+        let mut resumed = Simulator::with_strategy(table, strategy());
+        resumed.resume(checkpoint).unwrap();
+        simulator.run(500);
+        resumed.run(500);
+        assert_eq!(resumed.report, simulator.report);
+        assert_eq!(resumed.table.chips, simulator.table.chips);
+    }
+
+    #[test]
+    fn test_checkpoint_session() {
+        // The heat, the session so far, the seat and a pushed coupon carry over too
+        let simulator = || {
+            let rules = Rules {
+                no_mid_shoe_entry: true,
+                ..Rules::default()
+            };
+            let table = Table::new(1_000_000, Shoe::seeded(6, 0.75, 42), rules);
+            let strategy = WithBetting::new(BasicStrategy, Martingale::new(100));
+            let mut simulator = Simulator::with_strategy(table, strategy);
+            simulator.wong_in = Some(0.0);
+            simulator.heat = Some(Heat::new(HeatModel::default()));
+            simulator.limits = SessionLimits {
+                rounds: Some(150),
+                stop_win: Some(2000),
+                stop_loss: Some(3000),
+            };
+            simulator.coupon = Some(Coupon::MatchPlay(100));
+            simulator
+        };
+        let mut uninterrupted = simulator();
+        uninterrupted.run(1000);
+
+        let mut interrupted = simulator();
+        for _ in 0..20 {
+            interrupted.run(50);
+            let checkpoint = interrupted.checkpoint().to_string().parse().unwrap();
+            interrupted = simulator();
+            interrupted.resume(checkpoint).unwrap();
+        }
+        assert!(uninterrupted.report.back_offs > 0);
+        assert_eq!(interrupted.report, uninterrupted.report);
+        assert_eq!(interrupted.table.chips, uninterrupted.table.chips);
+        assert_eq!(interrupted.table.statistics, uninterrupted.table.statistics);
+    }
+}
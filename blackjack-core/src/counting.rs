@@ -0,0 +1,171 @@
+//! Card counting with the Hi-Lo system.
+//! Every card that leaves the shoe adjusts the running count by its tag,
+//! so the count can be recovered at any time from the cards still left in the shoe.
+//! The true count divides it by the exact decks remaining, unless a `TrueCount` convention says otherwise.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::card::shoe::{Composition, Shoe};
+use crate::card::Rank;
+
+/// Returns the Hi-Lo tag of a card with the given rank.
+/// Low cards (2-6) are worth +1, neutral cards (7-9) are worth 0, and high cards (10-A) are worth -1.
+#[must_use]
+pub const fn hi_lo(rank: &Rank) -> i32 {
+    match rank.worth() {
+        2..=6 => 1,
+        7..=9 => 0,
+        _ => -1,
+    }
+}
+
+/// Returns the Hi-Lo running count of the cards drawn since the last shuffle.
+#[must_use]
+pub fn running_count(shoe: &Shoe) -> i32 {
+    running_count_of(&shoe.composition())
+}
+
+/// Returns the Hi-Lo true count, which is the running count per deck remaining in the shoe.
+#[must_use]
+pub fn true_count(shoe: &Shoe) -> f32 {
+    true_count_of(&shoe.composition())
+}
+
+/// Returns the Hi-Lo running count of a shoe with the given cards left in it.
+/// A full shoe is balanced, so the running count is the negated sum of the tags still in the shoe.
+#[must_use]
+pub fn running_count_of(composition: &Composition) -> i32 {
+    -Rank::ALL
+        .iter()
+        .map(|rank| hi_lo(rank) * i32::from(composition.remaining(rank)))
+        .sum::<i32>()
+}
+
+/// Returns the Hi-Lo true count of a shoe with the given cards left in it.
+#[must_use]
+pub fn true_count_of(composition: &Composition) -> f32 {
+    let decks_remaining = composition.decks_remaining();
+    if decks_remaining > 0.0 {
+        running_count_of(composition) as f32 / decks_remaining
+    } else {
+        0.0
+    }
+}
+
+/// How the decks remaining are estimated when working out the true count.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DeckEstimate {
+    /// The exact number of cards remaining, in decks
+    #[default]
+    Exact,
+    /// Rounded to the nearest half deck, as when judging the discard tray by eye
+    HalfDeck,
+    /// Rounded to the nearest whole deck
+    FullDeck,
+}
+
+impl DeckEstimate {
+    /// Estimates the decks remaining in a shoe with the given cards left in it.
+    /// Rounded estimates are never less than the smallest step, so there is always something to divide by.
+    #[must_use]
+    pub fn decks_remaining(self, composition: &Composition) -> f32 {
+        let decks = composition.decks_remaining();
+        match self {
+            Self::Exact => decks,
+            Self::HalfDeck => ((decks * 2.0).round() / 2.0).max(0.5),
+            Self::FullDeck => decks.round().max(1.0),
+        }
+    }
+}
+
+/// How the true count is turned into a whole number, e.g. for bet ramps and strategy indices.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    /// Rounded down, so +2.9 is +2 and -0.5 is -1
+    #[default]
+    Floor,
+    /// Rounded to the nearest whole number, halves away from zero
+    Round,
+    /// Rounded towards zero, so -0.5 is 0
+    Truncate,
+}
+
+impl Rounding {
+    /// Rounds the true count to a whole number.
+    #[must_use]
+    pub fn apply(self, true_count: f32) -> i32 {
+        (match self {
+            Self::Floor => true_count.floor(),
+            Self::Round => true_count.round(),
+            Self::Truncate => true_count.trunc(),
+        }) as i32
+    }
+}
+
+/// The convention a true count is worked out with.
+/// Published indices are generated with a particular one, so they should be played with it too.
+/// The default divides by the exact decks remaining and rounds down.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TrueCount {
+    pub decks: DeckEstimate,
+    pub rounding: Rounding,
+}
+
+impl TrueCount {
+    /// Returns the Hi-Lo true count of a shoe with the given cards left in it,
+    /// divided by the estimated decks remaining but not rounded.
+    #[must_use]
+    pub fn of(&self, composition: &Composition) -> f32 {
+        let decks_remaining = self.decks.decks_remaining(composition);
+        if decks_remaining > 0.0 {
+            running_count_of(composition) as f32 / decks_remaining
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns the Hi-Lo true count of a shoe with the given cards left in it, rounded to a whole number.
+    #[must_use]
+    pub fn index(&self, composition: &Composition) -> i32 {
+        self.rounding.apply(self.of(composition))
+    }
+}
+
+impl fmt::Display for TrueCount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let decks = match self.decks {
+            DeckEstimate::Exact => "exact",
+            DeckEstimate::HalfDeck => "half",
+            DeckEstimate::FullDeck => "deck",
+        };
+        let rounding = match self.rounding {
+            Rounding::Floor => "floor",
+            Rounding::Round => "round",
+            Rounding::Truncate => "truncate",
+        };
+        write!(f, "{decks},{rounding}")
+    }
+}
+
+impl FromStr for TrueCount {
+    type Err = String;
+
+    /// Parses a deck estimate ("exact", "half", or "deck") and a rounding ("floor", "round",
+    /// or "truncate"), e.g. "half,floor". Either may be left out for its default.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut true_count = Self::default();
+        for token in s.split([',', ' ']).filter(|token| !token.is_empty()) {
+            match token.to_ascii_lowercase().as_str() {
+                "exact" => true_count.decks = DeckEstimate::Exact,
+                "half" => true_count.decks = DeckEstimate::HalfDeck,
+                "deck" => true_count.decks = DeckEstimate::FullDeck,
+                "floor" => true_count.rounding = Rounding::Floor,
+                "round" => true_count.rounding = Rounding::Round,
+                "truncate" => true_count.rounding = Rounding::Truncate,
+                _ => return Err(format!("unknown true count convention: {token}")),
+            }
+        }
+        Ok(true_count)
+    }
+}
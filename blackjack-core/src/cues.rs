@@ -0,0 +1,117 @@
+//! Sound cues for notable moments in the game, derived from the event stream.
+//! Front-ends decide how a cue is played, e.g. with the terminal bell or a sound file.
+
+use std::io::Write;
+
+use crate::card::hand::Status;
+use crate::events::GameEvent;
+
+/// A notable moment worth an audible cue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cue {
+    /// The player was dealt a blackjack
+    Blackjack,
+    /// One of the player's hands went bust
+    Bust,
+    /// The player won big on a round
+    BigWin,
+}
+
+/// Which moments get a cue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CueSettings {
+    /// Cue when the player is dealt a blackjack
+    pub blackjack: bool,
+    /// Cue when one of the player's hands goes bust
+    pub bust: bool,
+    /// Cue when the player's profit on a round is at least this many chips
+    pub big_win: Option<u32>,
+}
+
+impl Default for CueSettings {
+    fn default() -> Self {
+        Self {
+            blackjack: true,
+            bust: true,
+            big_win: Some(500),
+        }
+    }
+}
+
+impl CueSettings {
+    /// Returns the cue for the event, if it gets one.
+    #[must_use]
+    pub fn cue(&self, event: &GameEvent) -> Option<Cue> {
+        match event {
+            GameEvent::PlayerCard {
+                status: Status::Blackjack,
+                ..
+            } if self.blackjack => Some(Cue::Blackjack),
+            GameEvent::PlayerCard {
+                status: Status::Bust,
+                ..
+            } if self.bust => Some(Cue::Bust),
+            GameEvent::Payout {
+                total_bet,
+                total_winnings,
+            } => self
+                .big_win
+                .filter(|&threshold| total_winnings.saturating_sub(*total_bet) >= threshold)
+                .map(|_| Cue::BigWin),
+            _ => None,
+        }
+    }
+}
+
+/// Plays cues, however the front-end sees fit.
+pub trait CuePlayer {
+    fn play(&mut self, cue: Cue);
+}
+
+/// Plays every cue as the terminal bell.
+#[derive(Debug)]
+pub struct Bell<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> Bell<W> {
+    #[must_use]
+    pub const fn new(writer: W) -> Self {
+        Self { writer }
+    }
+}
+
+impl<W: Write> CuePlayer for Bell<W> {
+    fn play(&mut self, _cue: Cue) {
+        // A missed bell is not worth interrupting the game for
+        let _ = self
+            .writer
+            .write_all(b"\x07")
+            .and_then(|()| self.writer.flush());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cues() {
+        let settings = CueSettings::default();
+        let big_win = GameEvent::Payout {
+            total_bet: 400,
+            total_winnings: 1000,
+        };
+        let small_win = GameEvent::Payout {
+            total_bet: 100,
+            total_winnings: 200,
+        };
+        assert_eq!(settings.cue(&big_win), Some(Cue::BigWin));
+        assert_eq!(settings.cue(&small_win), None);
+        assert_eq!(settings.cue(&GameEvent::Shuffled), None);
+
+        let mut bell = Bell::new(Vec::new());
+        bell.play(Cue::BigWin);
+        assert_eq!(bell.writer, b"\x07");
+    }
+}
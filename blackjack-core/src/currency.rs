@@ -0,0 +1,120 @@
+//! Showing chips as money, e.g. "$12.50" for 50 chips worth 25 cents each.
+//! The conversion happens only when amounts are displayed: the game itself keeps whole chips.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// A currency to show chips in, and what one chip is worth in it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Currency {
+    /// The symbol written before amounts, e.g. "$" or "€"
+    pub symbol: String,
+    /// The worth of one chip in hundredths of the currency, e.g. 25 for chips worth a quarter
+    pub chip_cents: u32,
+}
+
+impl Currency {
+    /// Returns an amount of chips in the currency, to at least two decimal places, e.g. "-$12.50".
+    #[must_use]
+    pub fn format(&self, chips: f64, precision: usize) -> String {
+        let value = chips * f64::from(self.chip_cents) / 100.0;
+        let sign = if value < 0.0 { "-" } else { "" };
+        format!("{sign}{}{:.*}", self.symbol, precision.max(2), value.abs())
+    }
+}
+
+impl fmt::Display for Currency {
+    /// Currencies are displayed as their symbol and the worth of a chip, e.g. "$0.25"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.symbol, f64::from(self.chip_cents) / 100.0)
+    }
+}
+
+impl FromStr for Currency {
+    type Err = String;
+
+    /// Parses a symbol followed by the worth of a chip to the hundredth, e.g. "$0.25" or "€5",
+    /// or a bare symbol for chips worth 1.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let split = s
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(s.len());
+        let (symbol, value) = s.split_at(split);
+        if symbol.is_empty() {
+            return Err(format!("missing currency symbol: {s}"));
+        }
+        let chip_cents = if value.is_empty() {
+            100
+        } else {
+            value
+                .parse::<f64>()
+                .ok()
+                .map(|value| (value * 100.0).round())
+                .filter(|cents| *cents >= 1.0 && *cents <= f64::from(u32::MAX))
+                .ok_or_else(|| format!("invalid chip value: {value}"))? as u32
+        };
+        Ok(Self {
+            symbol: symbol.to_string(),
+            chip_cents,
+        })
+    }
+}
+
+/// Returns an amount of chips in the currency if there is one, or as a bare number of chips otherwise,
+/// e.g. "$12.50" or "50".
+#[must_use]
+pub fn amount(chips: f64, precision: usize, currency: Option<&Currency>) -> String {
+    currency.map_or_else(
+        || format!("{chips:.precision$}"),
+        |currency| currency.format(chips, precision),
+    )
+}
+
+/// Returns an amount of chips in the currency if there is one, or in chips otherwise,
+/// e.g. "$12.50" or "50 Chips".
+#[must_use]
+pub fn chips(chips: f64, precision: usize, currency: Option<&Currency>) -> String {
+    match currency {
+        Some(currency) => currency.format(chips, precision),
+        None => format!("{chips:.precision$} Chips"),
+    }
+}
+
+/// A report displayed with its amounts in a currency, or in chips if there is none.
+/// Reports display in chips by themselves.
+#[derive(Debug, Clone, Copy)]
+pub struct InCurrency<'a, T> {
+    pub value: &'a T,
+    pub currency: Option<&'a Currency>,
+}
+
+impl<'a, T> InCurrency<'a, T> {
+    pub const fn new(value: &'a T, currency: Option<&'a Currency>) -> Self {
+        Self { value, currency }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_currency() {
+        let quarters: Currency = "$0.25".parse().unwrap();
+        assert_eq!(quarters.symbol, "$");
+        assert_eq!(quarters.chip_cents, 25);
+        assert_eq!(quarters.format(50.0, 0), "$12.50");
+        assert_eq!(quarters.format(-3.0, 0), "-$0.75");
+        assert_eq!(quarters.format(0.1236, 4), "$0.0309");
+        assert_eq!(quarters.to_string(), "$0.25");
+        let euros: Currency = "€".parse().unwrap();
+        assert_eq!(euros.format(5.0, 0), "€5.00");
+        assert!("0.25".parse::<Currency>().is_err());
+        assert!("$0".parse::<Currency>().is_err());
+
+        assert_eq!(chips(50.0, 0, None), "50 Chips");
+        assert_eq!(chips(50.0, 0, Some(&quarters)), "$12.50");
+        assert_eq!(amount(0.5, 2, None), "0.50");
+    }
+}
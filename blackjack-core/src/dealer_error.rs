@@ -0,0 +1,244 @@
+//! Dealer errors: a model of the mistakes a real dealer makes now and then, paying a hand the wrong amount
+//! or flashing their hole card, for novelty analysis. The settlement checker recomputes every payout
+//! from the rules, so it catches the mispays, or any other settlement which breaks them.
+
+use std::fmt;
+use std::str::FromStr;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+use crate::card::hand::{PlayerHand, Status};
+use crate::observer::RoundObserver;
+use crate::rules::Rules;
+use crate::state::FinishedRound;
+
+/// How often the dealer makes each kind of mistake, and how many they have made.
+#[derive(Debug, Clone)]
+pub struct DealerErrors {
+    /// The chance of paying one of the round's hands a bet too much or too little
+    pub mispay: f64,
+    /// The chance of flashing the hole card to the player when dealing it
+    pub flash: f64,
+    /// The number of hands mispaid so far
+    pub mispays: u32,
+    /// The chips the player gained from mispays so far, or lost if negative
+    pub mispaid: i64,
+    /// The number of hole cards flashed so far
+    pub flashes: u32,
+    rng: ChaCha12Rng,
+}
+
+impl DealerErrors {
+    #[must_use]
+    pub fn new(mispay: f64, flash: f64) -> Self {
+        Self::with_rng(mispay, flash, ChaCha12Rng::from_os_rng())
+    }
+
+    /// Creates dealer errors which happen the same way every time for the same seed.
+    #[must_use]
+    pub fn seeded(mispay: f64, flash: f64, seed: u64) -> Self {
+        Self::with_rng(mispay, flash, ChaCha12Rng::seed_from_u64(seed))
+    }
+
+    const fn with_rng(mispay: f64, flash: f64, rng: ChaCha12Rng) -> Self {
+        Self {
+            mispay,
+            flash,
+            mispays: 0,
+            mispaid: 0,
+            flashes: 0,
+            rng,
+        }
+    }
+
+    /// Reseeds the errors, to happen the same way every time for the same seed.
+    pub fn reseed(&mut self, seed: u64) {
+        self.rng = ChaCha12Rng::seed_from_u64(seed);
+    }
+
+    /// Whether the dealer flashes the hole card they are dealing.
+    pub fn flashes_hole_card(&mut self) -> bool {
+        let flashed = self.rng.random_bool(self.flash.clamp(0.0, 1.0));
+        self.flashes += u32::from(flashed);
+        flashed
+    }
+
+    /// Maybe mispays one of the settled hands, by paying a bet more, or a bet less if the hand won anything.
+    /// Returns the hand mispaid and the chips it was paid too much, or too little if negative.
+    pub fn mispay(&mut self, hands: &mut [PlayerHand]) -> Option<(usize, i64)> {
+        if hands.is_empty() || !self.rng.random_bool(self.mispay.clamp(0.0, 1.0)) {
+            return None;
+        }
+        let index = self.rng.random_range(0..hands.len());
+        let hand = &mut hands[index];
+        let amount = if hand.winnings > 0 && self.rng.random_bool(0.5) {
+            let short = hand.bet.min(hand.winnings);
+            hand.winnings -= short;
+            -i64::from(short)
+        } else {
+            hand.winnings += hand.bet;
+            i64::from(hand.bet)
+        };
+        self.mispays += 1;
+        self.mispaid += amount;
+        Some((index, amount))
+    }
+}
+
+impl FromStr for DealerErrors {
+    type Err = String;
+
+    /// Parses the chance of each mistake, e.g. "mispay=0.01,flash=0.02".
+    /// Mistakes left out never happen.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (mut mispay, mut flash) = (0.0, 0.0);
+        for part in s.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+            let (name, chance) = part
+                .split_once('=')
+                .ok_or_else(|| format!("expected mistake=chance: {part}"))?;
+            let chance: f64 = chance
+                .trim()
+                .parse()
+                .ok()
+                .filter(|chance| (0.0..=1.0).contains(chance))
+                .ok_or_else(|| format!("invalid chance: {chance}"))?;
+            match name.trim().to_ascii_lowercase().as_str() {
+                "mispay" => mispay = chance,
+                "flash" => flash = chance,
+                _ => return Err(format!("unknown dealer error: {name}")),
+            }
+        }
+        Ok(Self::new(mispay, flash))
+    }
+}
+
+/// A bet settled for a different amount than the rules pay.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Violation {
+    /// The round the bet was settled in, counting from 1
+    pub round: usize,
+    /// The hand the bet was on, or `None` for the insurance bet
+    pub hand: Option<usize>,
+    /// What the bet paid back
+    pub paid: u32,
+    /// What the rules pay back on the bet
+    pub expected: u32,
+}
+
+impl fmt::Display for Violation {
+    /// Violations are displayed as e.g. "Round 12, hand 1: paid 50, expected 100"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.hand {
+            Some(hand) => write!(f, "Round {}, hand {}", self.round, hand + 1)?,
+            None => write!(f, "Round {}, insurance", self.round)?,
+        }
+        write!(f, ": paid {}, expected {}", self.paid, self.expected)
+    }
+}
+
+/// Checks every round the table settles against what the rules pay, keeping each bet settled wrongly.
+#[derive(Debug, Clone)]
+pub struct SettlementChecker {
+    rules: Rules,
+    /// The rounds checked so far
+    pub rounds: usize,
+    /// The bets settled wrongly so far
+    pub violations: Vec<Violation>,
+}
+
+impl SettlementChecker {
+    #[must_use]
+    pub const fn new(rules: Rules) -> Self {
+        Self {
+            rules,
+            rounds: 0,
+            violations: Vec::new(),
+        }
+    }
+}
+
+impl RoundObserver for SettlementChecker {
+    fn on_round(&mut self, round: &FinishedRound) {
+        self.rounds += 1;
+        for (i, hand) in round.hands.iter().enumerate() {
+            let expected = hand.calculate_winnings(&round.dealer_hand, &self.rules);
+            if hand.winnings != expected {
+                self.violations.push(Violation {
+                    round: self.rounds,
+                    hand: Some(i),
+                    paid: hand.winnings,
+                    expected,
+                });
+            }
+        }
+        // Insurance pays 2:1, so a winning insurance bet comes back three times over
+        let expected = if round.dealer_hand.status == Status::Blackjack {
+            round.insurance_bet * 3
+        } else {
+            0
+        };
+        if round.insurance_winnings != expected {
+            self.violations.push(Violation {
+                round: self.rounds,
+                hand: None,
+                paid: round.insurance_winnings,
+                expected,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::basic_strategy;
+    use crate::card::shoe::Shoe;
+    use crate::game::{Input, Table};
+    use crate::state::GameState;
+
+    /// Plays rounds of basic strategy with the given dealer errors, returning the table and what the checker found.
+    fn play(errors: DealerErrors) -> (Table, SettlementChecker) {
+        let checker = Arc::new(Mutex::new(SettlementChecker::new(Rules::default())));
+        let mut table = Table::new(u32::MAX / 2, Shoe::seeded(6, 0.75, 5), Rules::default());
+        table.dealer_errors = Some(errors);
+        table.observers.push(Box::new(Arc::clone(&checker)));
+        let mut state = GameState::default();
+        for _ in 0..2000 {
+            let input = match state {
+                GameState::Betting { .. } => Some(Input::bet(100)),
+                _ => basic_strategy::input(&table, &state),
+            };
+            state = table.progress(state, input).unwrap();
+        }
+        let checker = checker.lock().unwrap().clone();
+        (table, checker)
+    }
+
+    #[test]
+    fn test_dealer_errors() {
+        let errors: DealerErrors = "mispay=0.1, flash=0.5".parse().unwrap();
+        assert_eq!((errors.mispay, errors.flash), (0.1, 0.5));
+        assert!("mispay=2".parse::<DealerErrors>().is_err());
+        assert!("peek=0.1".parse::<DealerErrors>().is_err());
+
+        let (table, checker) = play(DealerErrors::seeded(0.0, 0.0, 1));
+        assert!(checker.rounds > 0);
+        assert!(checker.violations.is_empty());
+        assert_eq!(table.dealer_errors.unwrap().flashes, 0);
+
+        // Every mispay breaks a settlement, and the checker catches each one
+        let (table, checker) = play(DealerErrors::seeded(0.1, 0.5, 1));
+        let errors = table.dealer_errors.unwrap();
+        assert!(errors.mispays > 0 && errors.flashes > 0);
+        assert_eq!(checker.violations.len(), errors.mispays as usize);
+        let mispaid: i64 = checker
+            .violations
+            .iter()
+            .map(|v| i64::from(v.paid) - i64::from(v.expected))
+            .sum();
+        assert_eq!(mispaid, errors.mispaid);
+    }
+}
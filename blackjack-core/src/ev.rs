@@ -0,0 +1,461 @@
+//! Expected values of the player's actions, computed from the cards the player has not seen.
+//! The dealer's outcomes are computed exactly, drawing without replacement.
+//! The player's own draws are taken from the same composition throughout their hand,
+//! which is a close approximation unless the shoe is nearly empty.
+//! Split hands are played out once each, without resplitting.
+//!
+//! Every order of drawing the same cards leaves the dealer with the same hand and the same shoe,
+//! so the dealer's draws are enumerated by the set of cards drawn, packed into a single integer,
+//! and each set is played out only once, however many orders it can be drawn in.
+//! Outcomes are kept as flat rows of chances, which add up many totals at once.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::hash::{BuildHasherDefault, Hasher};
+
+use crate::card::hand::{DealerHand, PlayerHand, PlayerTurn, Value};
+use crate::card::shoe::Composition;
+use crate::game::{HandAction, Table};
+use crate::rules::DealerDrawing;
+
+/// The card worths a hand can draw, where an ace is worth 11.
+pub(crate) const WORTHS: [u8; 10] = [2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
+
+/// Returns the value of a hand after drawing a card with the given worth.
+fn draw(mut value: Value, worth: u8) -> Value {
+    value += Value {
+        soft: worth == 11,
+        total: worth,
+    };
+    value
+}
+
+/// The chance of each way the dealer's hand can finish.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct DealerOutcomes {
+    /// The chance of finishing on each total, by total
+    pub totals: [f64; 22],
+    /// The chance of busting
+    pub bust: f64,
+}
+
+impl DealerOutcomes {
+    /// Computes the dealer's outcomes for the upcard worth, drawing from the given composition.
+    /// If the dealer has peeked, the hole card is known not to give them blackjack.
+    #[must_use]
+    pub fn new(
+        upcard: u8,
+        composition: &Composition,
+        drawing: DealerDrawing,
+        peeked: bool,
+    ) -> Self {
+        let mut counts = WORTHS.map(|worth| composition.remaining_worth(worth));
+        // The solver draws the upcard out of the shoe itself, so it is put back first
+        counts[worth_index(upcard)] += 1;
+        DealerSolver::new(counts, drawing)
+            .outcomes(upcard, peeked)
+            .into()
+    }
+
+    /// Computes the dealer's outcomes for every upcard at once, by worth from 2 to 11,
+    /// drawing the upcard out of the given composition first. Upcards the composition has none of have no outcomes.
+    /// If the dealer peeks, a ten or an ace upcard is known not to give them blackjack.
+    #[must_use]
+    pub fn table(composition: &Composition, drawing: DealerDrawing, peek: bool) -> [Self; 10] {
+        let counts = WORTHS.map(|worth| composition.remaining_worth(worth));
+        let mut solver = DealerSolver::new(counts, drawing);
+        WORTHS.map(|upcard| {
+            if counts[worth_index(upcard)] == 0 {
+                Self::default()
+            } else {
+                solver.outcomes(upcard, peek && upcard >= 10).into()
+            }
+        })
+    }
+
+    /// The chance that the dealer busts.
+    #[must_use]
+    pub const fn bust(&self) -> f64 {
+        self.bust
+    }
+
+    /// The expected value of standing on each total from 0 to 21 against these outcomes, per unit bet.
+    /// Every total is computed at once, from the chances of the dealer finishing below and above it.
+    #[must_use]
+    pub fn stand_evs(&self) -> [f64; 22] {
+        let finished: f64 = self.totals.iter().sum();
+        let mut evs = [0.0; 22];
+        let mut below = 0.0;
+        for (total, chance) in self.totals.iter().enumerate() {
+            let above = finished - below - chance;
+            evs[total] = self.bust + below - above;
+            below += chance;
+        }
+        evs
+    }
+}
+
+impl From<Row> for DealerOutcomes {
+    fn from(row: Row) -> Self {
+        let mut totals = [0.0; 22];
+        totals.copy_from_slice(&row[..BUST]);
+        Self {
+            totals,
+            bust: row[BUST],
+        }
+    }
+}
+
+/// The dealer's outcomes as one flat row: the chance of finishing on each total from 0 to 21, then of busting.
+type Row = [f64; 23];
+
+/// The place of busting in a row of outcomes.
+const BUST: usize = 22;
+
+/// The bits each worth takes in a packed set of cards, enough for more cards of one worth than a hand can hold.
+const BITS_PER_WORTH: u32 = 5;
+
+/// Returns the index of a card worth in `WORTHS`.
+const fn worth_index(worth: u8) -> usize {
+    worth as usize - 2
+}
+
+/// Returns the set of cards with one more card of the worth at the given index.
+const fn with_card(drawn: u64, index: usize) -> u64 {
+    drawn + (1 << (index as u32 * BITS_PER_WORTH))
+}
+
+/// Hashes packed sets of cards, which are already spread over their bits, with a single multiplication.
+#[derive(Default)]
+struct PackedHasher(u64);
+
+impl Hasher for PackedHasher {
+    fn finish(&self) -> u64 {
+        // The high bits are the best mixed, so they are folded into the low bits the table indexes by
+        self.0 ^ (self.0 >> 32)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.write_u64(u64::from(byte));
+        }
+    }
+
+    fn write_u64(&mut self, packed: u64) {
+        self.0 = (self.0 ^ packed).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    }
+}
+
+/// Enumerates the dealer's draws from the cards in a shoe, remembering the outcomes from each set of cards drawn,
+/// so the outcomes for every upcard share the sets they have in common.
+struct DealerSolver {
+    /// The number of cards of each worth in the shoe, before the dealer draws any
+    counts: [u16; 10],
+    drawing: DealerDrawing,
+    /// The outcomes from each set of two or more cards the dealer has to draw to, by the packed set
+    memo: HashMap<u64, Row, BuildHasherDefault<PackedHasher>>,
+}
+
+impl DealerSolver {
+    fn new(counts: [u16; 10], drawing: DealerDrawing) -> Self {
+        Self {
+            counts,
+            drawing,
+            memo: HashMap::default(),
+        }
+    }
+
+    /// The number of cards of each worth left once the given set is drawn.
+    fn remaining(&self, drawn: u64) -> [u16; 10] {
+        let mask = (1 << BITS_PER_WORTH) - 1;
+        std::array::from_fn(|i| {
+            let taken = (drawn >> (i as u32 * BITS_PER_WORTH)) & mask;
+            self.counts[i].saturating_sub(taken as u16)
+        })
+    }
+
+    /// The outcomes for the upcard worth, drawing the upcard and then the rest out of the shoe.
+    /// If the dealer has peeked, the hole card is known not to give them blackjack.
+    fn outcomes(&mut self, upcard: u8, peeked: bool) -> Row {
+        let drawn = with_card(0, worth_index(upcard));
+        let value = Value {
+            soft: upcard == 11,
+            total: upcard,
+        };
+        // The dealer always draws a hole card to their upcard,
+        // but one which would give them blackjack was ruled out by the peek
+        let mut remaining = self.remaining(drawn);
+        for (count, worth) in remaining.iter_mut().zip(WORTHS) {
+            if peeked && draw(value, worth).total == 21 {
+                *count = 0;
+            }
+        }
+        self.draw(drawn, value, &remaining)
+    }
+
+    /// The outcomes from a hand the dealer has to draw to, adding up those after each card they can draw.
+    fn draw(&mut self, drawn: u64, value: Value, remaining: &[u16; 10]) -> Row {
+        let mut row = [0.0; 23];
+        let total: u16 = remaining.iter().sum();
+        if total == 0 {
+            return row;
+        }
+        for (i, &count) in remaining.iter().enumerate() {
+            if count == 0 {
+                continue;
+            }
+            let chance = f64::from(count) / f64::from(total);
+            let next = self.finish(with_card(drawn, i), draw(value, WORTHS[i]));
+            for (outcome, next) in row.iter_mut().zip(next) {
+                *outcome += chance * next;
+            }
+        }
+        row
+    }
+
+    /// The outcomes from a hand of two or more cards.
+    fn finish(&mut self, drawn: u64, value: Value) -> Row {
+        let mut row = [0.0; 23];
+        if value.total > 21 {
+            row[BUST] = 1.0;
+        } else if self.drawing.stands_on(value) {
+            row[usize::from(value.total)] = 1.0;
+        } else if let Some(&known) = self.memo.get(&drawn) {
+            row = known;
+        } else {
+            row = self.draw(drawn, value, &self.remaining(drawn));
+            self.memo.insert(drawn, row);
+        }
+        row
+    }
+}
+
+/// Returns the exact chance that the dealer's hole card gives them blackjack,
+/// drawing it from the cards the player has not seen, before the dealer peeks.
+#[must_use]
+pub fn dealer_blackjack_chance(upcard: u8, composition: &Composition) -> f64 {
+    let completing = match upcard {
+        11 => composition.remaining_worth(10),
+        10 => composition.remaining_worth(11),
+        _ => return 0.0,
+    };
+    f64::from(completing) / f64::from(composition.total().max(1))
+}
+
+/// Returns the expected value of insurance per unit bet while the dealer shows an ace.
+/// Insurance pays 2 to 1, so it is a winning bet once more than a third of the unseen cards are tens.
+#[must_use]
+pub fn insurance_ev(composition: &Composition) -> f64 {
+    let blackjack = dealer_blackjack_chance(11, composition);
+    2.0 * blackjack - (1.0 - blackjack)
+}
+
+/// Computes the expected values of playing a hand against a set of dealer outcomes.
+struct Evaluator {
+    /// The chance of drawing each worth
+    chances: [f64; 10],
+    /// The expected value of standing on each total, per unit bet
+    stands: [f64; 22],
+    double_after_split: bool,
+    /// The expected value of hitting each hand, by total and softness, once computed
+    hits: RefCell<[[Option<f64>; 2]; 22]>,
+}
+
+impl Evaluator {
+    /// The expected value of standing, per unit bet.
+    fn stand(&self, total: u8) -> f64 {
+        self.stands.get(usize::from(total)).copied().unwrap_or(-1.0)
+    }
+
+    /// The expected value of hitting, then playing on as well as possible.
+    fn hit(&self, value: Value) -> f64 {
+        let (total, soft) = (usize::from(value.total), usize::from(value.soft));
+        if let Some(ev) = self.hits.borrow()[total][soft] {
+            return ev;
+        }
+        let ev = self.draw(value, |value| {
+            if value.total > 21 {
+                -1.0
+            } else {
+                self.stand(value.total).max(self.hit(value))
+            }
+        });
+        self.hits.borrow_mut()[total][soft] = Some(ev);
+        ev
+    }
+
+    /// The expected value of doubling, per unit of the original bet.
+    fn double(&self, value: Value) -> f64 {
+        2.0 * self.draw(value, |value| self.stand(value.total))
+    }
+
+    /// The expected value of a split hand starting with a card of the given worth.
+    /// Split aces get one card each.
+    fn split_hand(&self, worth: u8) -> f64 {
+        let value = Value {
+            soft: worth == 11,
+            total: worth,
+        };
+        self.draw(value, |value| {
+            if worth == 11 {
+                self.stand(value.total)
+            } else {
+                let best = self.stand(value.total).max(self.hit(value));
+                if self.double_after_split {
+                    best.max(self.double(value))
+                } else {
+                    best
+                }
+            }
+        })
+    }
+
+    /// The expected value over the next card drawn.
+    fn draw(&self, value: Value, ev: impl Fn(Value) -> f64) -> f64 {
+        WORTHS
+            .iter()
+            .zip(self.chances)
+            .filter(|(_, chance)| *chance > 0.0)
+            .map(|(&worth, chance)| chance * ev(draw(value, worth)))
+            .sum()
+    }
+}
+
+/// The expected value of each action on a hand, per unit of the hand's bet,
+/// whether or not the player is allowed to take it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HandEvs {
+    pub stand: f64,
+    pub hit: f64,
+    pub double: f64,
+    /// The expected value of splitting, if the hand is two cards of the same worth
+    pub split: Option<f64>,
+}
+
+impl HandEvs {
+    /// Returns the expected value of an action, or `None` for splitting a hand which is not a pair.
+    #[must_use]
+    pub const fn of(&self, action: HandAction) -> Option<f64> {
+        match action {
+            HandAction::Stand => Some(self.stand),
+            HandAction::Hit => Some(self.hit),
+            HandAction::Double => Some(self.double),
+            HandAction::Split => self.split,
+            HandAction::Surrender => Some(-0.5),
+        }
+    }
+
+    /// Returns the expected value of each action the player is allowed to take on their current hand.
+    #[must_use]
+    pub fn allowed(&self, table: &Table, player_turn: &PlayerTurn) -> Vec<(HandAction, f64)> {
+        table
+            .allowed_actions(player_turn)
+            .into_iter()
+            .filter_map(|action| Some((action, self.of(action)?)))
+            .collect()
+    }
+}
+
+/// Returns the expected value of each action on a hand, given the cards the player has not seen.
+#[must_use]
+pub fn hand_evs(
+    table: &Table,
+    hand: &PlayerHand,
+    dealer_hand: &DealerHand,
+    composition: &Composition,
+) -> HandEvs {
+    let upcard = dealer_hand.showing();
+    let cards = f64::from(composition.total().max(1));
+    let evaluator = Evaluator {
+        chances: WORTHS.map(|worth| f64::from(composition.remaining_worth(worth)) / cards),
+        stands: DealerOutcomes::new(upcard, composition, dealer_hand.drawing(), upcard >= 10)
+            .stand_evs(),
+        double_after_split: table.rules.double_after_split,
+        hits: RefCell::new([[None; 2]; 22]),
+    };
+    // Two cards of the same worth split the same way, whether or not the rules let them be split
+    let pair = match hand.cards.as_slice() {
+        [first, second] => first.rank.worth() == second.rank.worth(),
+        _ => false,
+    };
+    HandEvs {
+        stand: evaluator.stand(hand.value.total),
+        hit: evaluator.hit(hand.value),
+        double: evaluator.double(hand.value),
+        split: pair.then(|| 2.0 * evaluator.split_hand(hand.cards[0].rank.worth())),
+    }
+}
+
+/// Returns the expected value of each action the player is allowed to take on their current hand,
+/// per unit of the hand's bet, given the cards the player has not seen.
+#[must_use]
+pub fn action_evs(
+    table: &Table,
+    player_turn: &PlayerTurn,
+    dealer_hand: &DealerHand,
+    composition: &Composition,
+) -> Vec<(HandAction, f64)> {
+    hand_evs(table, player_turn.current_hand(), dealer_hand, composition)
+        .allowed(table, player_turn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::shoe::Shoe;
+    use crate::card::{Card, Rank, Suit};
+    use crate::rules::Rules;
+
+    #[test]
+    fn test_ev() {
+        let full = Shoe::new(6, 0.75).composition();
+        let six = DealerOutcomes::new(6, &full, DealerDrawing::S17, false);
+        let ten = DealerOutcomes::new(10, &full, DealerDrawing::S17, true);
+        assert!((six.bust() - 0.42).abs() < 0.01);
+        assert!((ten.bust() - 0.23).abs() < 0.01);
+        let total: f64 = six.totals.iter().sum::<f64>() + six.bust;
+        assert!((total - 1.0).abs() < 1e-9);
+        // A dealer standing on hard 16 busts less often, and sometimes finishes on 16
+        let promo = DealerDrawing { hard: 16, soft: 17 };
+        let six_promo = DealerOutcomes::new(6, &full, promo, false);
+        assert!(six_promo.bust() < six.bust());
+        assert!(six_promo.totals[16] > 0.0);
+
+        // The table draws each upcard out of the shoe, as dealing it does
+        let table = DealerOutcomes::table(&full, DealerDrawing::S17, true);
+        let without_six = Composition::from(
+            Rank::ALL.map(|rank| full.remaining(&rank) - u16::from(rank == Rank::Six)),
+        );
+        assert_eq!(
+            table[4],
+            DealerOutcomes::new(6, &without_six, DealerDrawing::S17, false)
+        );
+        // Below 17, standing only wins when the dealer busts
+        let stands = six.stand_evs();
+        assert!(stands[..17]
+            .iter()
+            .all(|ev| (ev - (2.0 * six.bust() - 1.0)).abs() < 1e-9));
+        assert!(stands[21] > stands[20] && stands[20] > stands[17]);
+
+        let table = Table::new(1000, Shoe::new(6, 0.75), Rules::default());
+        let card = |rank| Card {
+            rank,
+            suit: Suit::Clubs,
+        };
+        let mut hand = PlayerHand::new(card(Rank::Six), 100);
+        hand += card(Rank::Five);
+        let dealer_hand = DealerHand::new(card(Rank::Six), DealerDrawing::S17);
+        let evs = action_evs(&table, &PlayerTurn::from(hand), &dealer_hand, &full);
+        let ev = |action| evs.iter().find(|(a, _)| *a == action).unwrap().1;
+        assert!(ev(HandAction::Double) > ev(HandAction::Hit));
+        assert!(ev(HandAction::Hit) > ev(HandAction::Stand));
+
+        // 96 tens in 312 cards
+        assert!((dealer_blackjack_chance(11, &full) - 96.0 / 312.0).abs() < 1e-9);
+        assert!((dealer_blackjack_chance(10, &full) - 24.0 / 312.0).abs() < 1e-9);
+        assert!(dealer_blackjack_chance(9, &full).abs() < f64::EPSILON);
+        assert!((insurance_ev(&full) - (3.0 * 96.0 / 312.0 - 1.0)).abs() < 1e-9);
+        let rich = Composition::from([0, 0, 0, 0, 0, 0, 0, 0, 4, 4, 4, 4, 4]);
+        assert!(insurance_ev(&rich) > 0.0);
+    }
+}
@@ -0,0 +1,292 @@
+//! A cache of the expected values the EV solver computes, which can be saved and loaded between runs,
+//! so hints and the trainer don't solve the same hand against the same cards twice.
+//! Entries are keyed by a hash of the rules, each worth's share of the cards left in the shoe,
+//! the hand as it appears on a chart, and the dealer's upcard.
+//! Shares are rounded to half a percent, so shoes which differ by a card or two share their entries,
+//! and a lookup is answered with the expected values solved for the first such shoe.
+//! The cache keeps the most recently solved hands, forgetting the oldest once it is full.
+//!
+//! The cache is written as text, one entry per line, oldest first,
+//! e.g. for hard 16 against a ten from a full six-deck shoe:
+//! `f66c43408c65dbb4 15,15,15,15,15,15,15,15,62,15 H16 10 -0.5397347219792548 -0.5397250507590672 -1.0794501015181344 -`,
+//! which gives the expected values of standing, hitting, doubling, and splitting (`-` if the hand isn't a pair).
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::card::hand::{DealerHand, PlayerTurn};
+use crate::card::shoe::Composition;
+use crate::chart::ChartHand;
+use crate::ev::{self, HandEvs, WORTHS};
+use crate::game::{HandAction, Table};
+
+/// The steps each worth's share of the cards left is rounded to, here half a percent.
+const SHARE_STEPS: u32 = 200;
+
+/// The most hands kept, about 5 MB of them.
+const MAX_ENTRIES: usize = 50_000;
+
+/// What a hand's expected values depend on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Key {
+    /// The stable hash of the table's rules
+    rules: u64,
+    /// Each worth's share of the cards left, in `SHARE_STEPS`
+    shares: [u8; 10],
+    hand: ChartHand,
+    upcard: u8,
+}
+
+/// The expected values computed so far, by hand.
+#[derive(Clone, Default, PartialEq)]
+pub struct EvCache {
+    entries: HashMap<Key, HandEvs>,
+    /// The keys of the entries, oldest first
+    order: VecDeque<Key>,
+    /// The number of lookups answered from the cache
+    pub hits: usize,
+    /// The number of lookups which had to be solved
+    pub misses: usize,
+}
+
+impl EvCache {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of hands in the cache.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the expected value of each action the player is allowed to take on their current hand,
+    /// like `ev::action_evs`, solving the hand only if it isn't in the cache.
+    pub fn action_evs(
+        &mut self,
+        table: &Table,
+        player_turn: &PlayerTurn,
+        dealer_hand: &DealerHand,
+        composition: &Composition,
+    ) -> Vec<(HandAction, f64)> {
+        let hand = player_turn.current_hand();
+        let key = Key {
+            rules: table.rules.stable_hash(),
+            shares: shares(composition),
+            hand: ChartHand::of(hand),
+            upcard: dealer_hand.showing(),
+        };
+        let evs = if let Some(&evs) = self.entries.get(&key) {
+            self.hits += 1;
+            evs
+        } else {
+            self.misses += 1;
+            let evs = ev::hand_evs(table, hand, dealer_hand, composition);
+            self.insert(key, evs);
+            evs
+        };
+        evs.allowed(table, player_turn)
+    }
+
+    /// Adds the entries of another cache, keeping this cache's own where both have a hand.
+    pub fn merge(&mut self, other: &Self) {
+        for key in &other.order {
+            if !self.entries.contains_key(key) {
+                self.insert(*key, other.entries[key]);
+            }
+        }
+    }
+
+    /// Adds or replaces an entry, forgetting the oldest once there are more than `MAX_ENTRIES`.
+    fn insert(&mut self, key: Key, evs: HandEvs) {
+        if self.entries.insert(key, evs).is_none() {
+            self.order.push_back(key);
+        }
+        while self.order.len() > MAX_ENTRIES {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+}
+
+/// Returns each worth's share of the cards left, rounded to the nearest step.
+fn shares(composition: &Composition) -> [u8; 10] {
+    let total = u32::from(composition.total()).max(1);
+    WORTHS.map(|worth| {
+        let count = u32::from(composition.remaining_worth(worth));
+        let share = (2 * count * SHARE_STEPS + total) / (2 * total);
+        u8::try_from(share).unwrap_or(u8::MAX)
+    })
+}
+
+impl fmt::Debug for EvCache {
+    /// Caches are debugged by their size, as they may hold many thousands of hands
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EvCache")
+            .field("entries", &self.entries.len())
+            .field("hits", &self.hits)
+            .field("misses", &self.misses)
+            .finish()
+    }
+}
+
+/// Writes a chart hand with its kind, e.g. "H16", "S18", or "P8",
+/// so totals which never appear on a chart can be written too.
+fn write_hand(hand: ChartHand) -> String {
+    match hand {
+        ChartHand::Hard(total) => format!("H{total}"),
+        ChartHand::Soft(total) => format!("S{total}"),
+        ChartHand::Pair(worth) => format!("P{worth}"),
+    }
+}
+
+fn parse_hand(s: &str) -> Option<ChartHand> {
+    let (kind, number) = s.split_at_checked(1)?;
+    let number = number.parse().ok()?;
+    match kind {
+        "H" => Some(ChartHand::Hard(number)),
+        "S" => Some(ChartHand::Soft(number)),
+        "P" => Some(ChartHand::Pair(number)),
+        _ => None,
+    }
+}
+
+impl fmt::Display for EvCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# Blackjack EV cache")?;
+        for key in &self.order {
+            let evs = &self.entries[key];
+            let shares: Vec<String> = key.shares.iter().map(u8::to_string).collect();
+            let split = evs
+                .split
+                .map_or_else(|| "-".to_string(), |ev| ev.to_string());
+            writeln!(
+                f,
+                "{:016x} {} {} {} {} {} {} {split}",
+                key.rules,
+                shares.join(","),
+                write_hand(key.hand),
+                key.upcard,
+                evs.stand,
+                evs.hit,
+                evs.double
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for EvCache {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cache = Self::new();
+        for line in s
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        {
+            let invalid = || format!("invalid EV cache entry: {line}");
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [rules, shares, hand, upcard, stand, hit, double, split] = fields[..] else {
+                return Err(invalid());
+            };
+            let shares: Vec<u8> = shares
+                .split(',')
+                .map(str::parse)
+                .collect::<Result<_, _>>()
+                .map_err(|_| invalid())?;
+            let ev = |field: &str| field.parse::<f64>().map_err(|_| invalid());
+            let key = Key {
+                rules: u64::from_str_radix(rules, 16).map_err(|_| invalid())?,
+                shares: shares.try_into().map_err(|_| invalid())?,
+                hand: parse_hand(hand).ok_or_else(invalid)?,
+                upcard: upcard.parse().map_err(|_| invalid())?,
+            };
+            let evs = HandEvs {
+                stand: ev(stand)?,
+                hit: ev(hit)?,
+                double: ev(double)?,
+                split: if split == "-" { None } else { Some(ev(split)?) },
+            };
+            cache.insert(key, evs);
+        }
+        Ok(cache)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::hand::PlayerHand;
+    use crate::card::shoe::Shoe;
+    use crate::card::{Card, Rank, Suit};
+    use crate::rules::{DealerDrawing, Rules};
+
+    #[test]
+    fn test_ev_cache() {
+        let table = Table::new(1000, Shoe::new(6, 0.75), Rules::default());
+        let full = table.shoe.composition();
+        let card = |rank| Card {
+            rank,
+            suit: Suit::Spades,
+        };
+        let mut pair = PlayerHand::new(card(Rank::Eight), 100);
+        pair += card(Rank::Eight);
+        let player_turn = PlayerTurn::from(pair);
+        let dealer_hand = DealerHand::new(card(Rank::Ten), DealerDrawing::S17);
+
+        let mut cache = EvCache::new();
+        let solved = cache.action_evs(&table, &player_turn, &dealer_hand, &full);
+        assert_eq!(
+            solved,
+            ev::action_evs(&table, &player_turn, &dealer_hand, &full)
+        );
+        assert_eq!(
+            cache.action_evs(&table, &player_turn, &dealer_hand, &full),
+            solved
+        );
+        assert_eq!((cache.len(), cache.hits, cache.misses), (1, 1, 1));
+
+        // Saved and loaded, the cache answers with exactly the same expected values
+        let mut loaded: EvCache = cache.to_string().parse().unwrap();
+        assert_eq!(
+            loaded.action_evs(&table, &player_turn, &dealer_hand, &full),
+            solved
+        );
+        assert_eq!((loaded.hits, loaded.misses), (1, 0));
+        assert!("0 1,2 H16 10 0 0 0 -".parse::<EvCache>().is_err());
+
+        // Other rules are solved on their own
+        let mut h17 = Table::new(1000, Shoe::new(6, 0.75), Rules::default());
+        h17.rules.dealer_drawing = DealerDrawing::H17;
+        loaded.action_evs(&h17, &player_turn, &dealer_hand, &full);
+        assert_eq!((loaded.len(), loaded.misses), (2, 1));
+
+        // A shoe a card short shares its entries, while a shoe short of tens is solved on its own
+        let mut counts = [24; 13];
+        counts[0] -= 1;
+        loaded.action_evs(&table, &player_turn, &dealer_hand, &counts.into());
+        assert_eq!((loaded.len(), loaded.hits), (2, 2));
+        counts[9..].fill(8);
+        loaded.action_evs(&table, &player_turn, &dealer_hand, &counts.into());
+        assert_eq!((loaded.len(), loaded.misses), (3, 2));
+
+        // Once full, the cache forgets its oldest hands first
+        let evs = loaded.entries[&loaded.order[0]];
+        let oldest = loaded.order[0];
+        for rules in 0..MAX_ENTRIES as u64 {
+            loaded.insert(Key { rules, ..oldest }, evs);
+        }
+        assert_eq!(loaded.len(), MAX_ENTRIES);
+        assert!(!loaded.entries.contains_key(&oldest));
+    }
+}
@@ -0,0 +1,67 @@
+//! Events describing what happens at the table, in the order it happens.
+//! Front-ends can record these to narrate, animate, or log the game
+//! without having to work out what changed between two states.
+
+use crate::card::hand::{Status, Value};
+use crate::card::{Card, Visibility};
+use crate::game::HandAction;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameEvent {
+    /// The player placed a bet to start the round.
+    BetPlaced { bet: u32 },
+    /// A backer placed a bet behind the player.
+    BackBetPlaced { backer: usize, bet: u32 },
+    /// The player bet on another spot, counting from 1 after their main hand.
+    SpotBetPlaced { spot: usize, bet: u32 },
+    /// The player tipped the dealer.
+    Tipped { tip: u32 },
+    /// A card was dealt to one of the player's hands, face up or face down.
+    PlayerCard {
+        hand: usize,
+        card: Card,
+        value: Value,
+        status: Status,
+        visibility: Visibility,
+    },
+    /// A face-up card was dealt to the dealer.
+    DealerCard { card: Card, value: Value },
+    /// The dealer dealt their hole card face down.
+    HoleCardDealt,
+    /// The player was offered to surrender before the dealer checks for blackjack.
+    EarlySurrenderOffered,
+    /// The player was offered insurance.
+    InsuranceOffered,
+    /// The player placed an insurance bet, which may be nothing.
+    InsurancePlaced { bet: u32 },
+    /// The dealer flashed their hole card to the player by mistake while dealing it.
+    HoleCardFlashed { card: Card },
+    /// The dealer checked their hole card for blackjack.
+    DealerPeeked { blackjack: bool },
+    /// The player chose an action for one of their hands.
+    PlayerAction { hand: usize, action: HandAction },
+    /// A bot played one of its hands, making the given decisions on it in order.
+    BotPlayed {
+        bot: usize,
+        cards: Vec<Card>,
+        actions: Vec<HandAction>,
+        value: Value,
+        status: Status,
+    },
+    /// A bot's hands were settled, winning it this many chips, or losing them if negative.
+    BotPaid { bot: usize, net: i64 },
+    /// The dealer turned over their hole card.
+    HoleCardRevealed { card: Card, value: Value },
+    /// The dealer finished their hand and the round is over.
+    RoundOver { status: Status, value: Value },
+    /// The dealer mispaid one of the player's hands by mistake, paying it too much, or too little if negative.
+    Mispaid { hand: usize, amount: i64 },
+    /// The player was paid out.
+    Payout { total_bet: u32, total_winnings: u32 },
+    /// The cut card came out of the shoe, so it will be shuffled after this round.
+    CutCardReached,
+    /// The shoe was shuffled.
+    Shuffled,
+    /// The player can no longer afford to play.
+    GameOver,
+}
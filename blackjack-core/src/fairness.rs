@@ -0,0 +1,68 @@
+//! Provably fair shoes, by commit and reveal.
+//! Before a shoe is dealt, the house commits to the seed its cards will be drawn with by publishing
+//! the seed's SHA-256 hash. Once the shoe is shuffled away the seed is revealed, and anyone can check
+//! it against the commitment and deal the shoe again from it to see that the same cards came out.
+//!
+//! The commitment is the hash of the seed written in decimal, so it can also be checked with
+//! standard tools, e.g. `printf 42 | sha256sum`.
+
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use sha2::{Digest, Sha256};
+
+use crate::card::shoe::Shoe;
+use crate::card::Card;
+
+/// Returns a new seed for a shoe, from the operating system's source of randomness.
+#[must_use]
+pub fn random_seed() -> u64 {
+    ChaCha12Rng::from_os_rng().next_u64()
+}
+
+/// Returns the commitment to a seed: the SHA-256 hash of the seed in decimal, in hex.
+#[must_use]
+pub fn commitment(seed: u64) -> String {
+    Sha256::digest(seed.to_string().as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Checks a revealed seed against the commitment published before the shoe was dealt.
+#[must_use]
+pub fn verify(seed: u64, commitment: &str) -> bool {
+    self::commitment(seed).eq_ignore_ascii_case(commitment.trim())
+}
+
+/// Deals the first cards of a shoe drawn with the given seed, in order,
+/// to compare with the cards that were dealt from it.
+#[must_use]
+pub fn deal(decks: u8, seed: u64, cards: usize) -> Vec<Card> {
+    let mut shoe = Shoe::seeded(decks, 1.0, seed);
+    (0..cards).map(|_| shoe.draw_card()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commit_reveal() {
+        assert_eq!(
+            commitment(42),
+            "73475cb40a568e8da8a045ced110137e159f890ac4da883b6b17dc651b3a8049"
+        );
+        assert!(verify(42, &commitment(42)));
+        assert!(!verify(43, &commitment(42)));
+
+        // A shoe reseeded after a shuffle deals the same cards as a new shoe with that seed
+        let mut shoe = Shoe::seeded(6, 0.75, 1);
+        for _ in 0..100 {
+            shoe.draw_card();
+        }
+        shoe.shuffle();
+        shoe.reseed(42);
+        let dealt: Vec<_> = (0..20).map(|_| shoe.draw_card()).collect();
+        assert_eq!(dealt, deal(6, 42, 20));
+    }
+}
@@ -3,15 +3,25 @@
 //! The core logic of the game.
 
 use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
 
+use crate::basic_strategy;
+use crate::buffers::RoundBuffers;
 use crate::card::hand::{DealerHand, PlayerHand, PlayerTurn, Status};
-use crate::card::shoe::Shoe;
-use crate::rules::Rules;
-use crate::state::GameState;
+use crate::card::shoe::{Composition, Shoe};
+use crate::card::{Card, Rank, Visibility};
+use crate::dealer_error::DealerErrors;
+use crate::events::GameEvent;
+use crate::observer::RoundObserver;
+use crate::promotion::Coupon;
+use crate::rules::{Deal, Rules, TimeoutAction};
+use crate::state::{FinishedRound, GameState};
 use crate::statistics::Statistics;
+use crate::timeline::{Timeline, Transition};
 
 /// The player's options for playing their hand
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum HandAction {
     Stand,
     Hit,
@@ -20,90 +30,364 @@ pub enum HandAction {
     Surrender,
 }
 
+/// A seat at the table, which every input is made from.
+/// The player makes every decision on their hand, while backers may only bet behind it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Seat {
+    #[default]
+    Player,
+    /// A backer, by their index at the table
+    Backer(u8),
+}
+
+impl fmt::Display for Seat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Player => write!(f, "Player"),
+            Self::Backer(backer) => write!(f, "Backer {}", backer + 1),
+        }
+    }
+}
+
 /// The game input. Different states require different inputs.
-#[derive(Debug)]
+/// Every input but a timeout says which seat made it, so the table can refuse inputs made
+/// for the wrong player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Input {
-    Bet(u32),
-    Choice(bool),
-    Action(HandAction),
+    /// A bet on the next hand, or an insurance bet. Backers bet behind the player's next hand.
+    Bet {
+        seat: Seat,
+        bet: u32,
+    },
+    Tip {
+        seat: Seat,
+        tip: u32,
+    },
+    /// A bet on another spot for the player's next round, played after their main hand.
+    /// Spots are bet before the main hand, which starts the round.
+    Spot {
+        seat: Seat,
+        bet: u32,
+    },
+    Choice {
+        seat: Seat,
+        choice: bool,
+    },
+    Action {
+        seat: Seat,
+        action: HandAction,
+    },
+    /// The player ran out of time to decide, so the table decides for them.
+    Timeout,
+}
+
+impl Input {
+    /// The player's bet.
+    #[must_use]
+    pub const fn bet(bet: u32) -> Self {
+        Self::Bet {
+            seat: Seat::Player,
+            bet,
+        }
+    }
+
+    /// The player's tip.
+    #[must_use]
+    pub const fn tip(tip: u32) -> Self {
+        Self::Tip {
+            seat: Seat::Player,
+            tip,
+        }
+    }
+
+    /// The player's bet on another spot.
+    #[must_use]
+    pub const fn spot(bet: u32) -> Self {
+        Self::Spot {
+            seat: Seat::Player,
+            bet,
+        }
+    }
+
+    /// The player's choice.
+    #[must_use]
+    pub const fn choice(choice: bool) -> Self {
+        Self::Choice {
+            seat: Seat::Player,
+            choice,
+        }
+    }
+
+    /// The player's action on their current hand.
+    #[must_use]
+    pub const fn action(action: HandAction) -> Self {
+        Self::Action {
+            seat: Seat::Player,
+            action,
+        }
+    }
+
+    /// Returns the same input made from another seat. A timeout stays a timeout.
+    #[must_use]
+    pub const fn with_seat(self, seat: Seat) -> Self {
+        match self {
+            Self::Bet { bet, .. } => Self::Bet { seat, bet },
+            Self::Tip { tip, .. } => Self::Tip { seat, tip },
+            Self::Spot { bet, .. } => Self::Spot { seat, bet },
+            Self::Choice { choice, .. } => Self::Choice { seat, choice },
+            Self::Action { action, .. } => Self::Action { seat, action },
+            Self::Timeout => Self::Timeout,
+        }
+    }
+
+    /// Returns the seat which made the input, if it was made by a seat at all.
+    #[must_use]
+    pub const fn seat(&self) -> Option<Seat> {
+        match *self {
+            Self::Bet { seat, .. }
+            | Self::Tip { seat, .. }
+            | Self::Spot { seat, .. }
+            | Self::Choice { seat, .. }
+            | Self::Action { seat, .. } => Some(seat),
+            Self::Timeout => None,
+        }
+    }
+}
+
+/// A bettor who wagers behind the player's hand without making any decisions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Backer {
+    pub chips: u32, // The backer's chips at this table
+}
+
+/// A computer player seated beside the player, betting the table minimum on a hand of its own
+/// and playing it by its strategy, so that cards leave the shoe as they would at a fuller table.
+/// Bots never run out of chips.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Bot {
+    pub net: i64,              // The bot's winnings at this table, less its bets
+    pub strategy: BotStrategy, // How the bot plays its hands
+}
+
+/// How a bot plays its hands.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BotStrategy {
+    /// Basic strategy, as the player should
+    #[default]
+    Basic,
+    /// Hits below 17 and stands on the rest, like the dealer, without doubling or splitting
+    Mimic,
+    /// Never hits a hand which could bust, only soft hands below 18, without doubling or splitting
+    NeverBust,
+}
+
+impl BotStrategy {
+    /// Returns the bot's action on the hand being played.
+    fn action(
+        self,
+        table: &Table,
+        player_turn: &PlayerTurn,
+        dealer_hand: &DealerHand,
+    ) -> HandAction {
+        let value = player_turn.current_hand().value;
+        let hit = match self {
+            Self::Basic => return basic_strategy::play_hand(table, player_turn, dealer_hand),
+            Self::Mimic => value.total < 17,
+            Self::NeverBust => value.total < 12 || (value.soft && value.total < 18),
+        };
+        if hit {
+            HandAction::Hit
+        } else {
+            HandAction::Stand
+        }
+    }
+}
+
+impl FromStr for BotStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "basic" => Ok(Self::Basic),
+            "mimic" => Ok(Self::Mimic),
+            "never-bust" => Ok(Self::NeverBust),
+            _ => Err(format!("unknown bot strategy: {s}")),
+        }
+    }
+}
+
+impl fmt::Display for BotStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Basic => write!(f, "basic"),
+            Self::Mimic => write!(f, "mimic"),
+            Self::NeverBust => write!(f, "never-bust"),
+        }
+    }
+}
+
+/// A number of bots to seat playing the same strategy, written as "N[:strategy]", e.g. "2" or "1:mimic".
+/// Bots play basic strategy unless told otherwise.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BotSeats {
+    pub count: usize,
+    pub strategy: BotStrategy,
+}
+
+impl FromStr for BotSeats {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (count, strategy) = s.split_once(':').unwrap_or((s, "basic"));
+        Ok(Self {
+            count: count
+                .trim()
+                .parse()
+                .map_err(|_| format!("expected a number of bots, e.g. 2 or 1:mimic: {s}"))?,
+            strategy: strategy.trim().parse()?,
+        })
+    }
+}
+
+impl fmt::Display for BotSeats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.count, self.strategy)
+    }
+}
+
+/// A wager placed behind one of the player's spots by a backer.
+/// It rides on the hand dealt to that spot, staying with it when it is split,
+/// and is resolved with that hand's outcome.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackBet {
+    pub backer: usize, // The index of the backer at the table
+    pub spot: usize,   // The spot backed, where 0 is the player's main hand
+    pub bet: u32,      // The amount wagered
 }
 
 /// The game table. This is where the game is played.
 /// It holds the player's chips, the shoe, and the game rules.
 #[derive(Debug)]
 pub struct Table {
-    pub chips: u32,             // The player's chips at this table
-    pub shoe: Shoe,             // The shoe of cards used in the game
-    pub rules: Rules,           // The table rules
-    pub statistics: Statistics, // The continuous game statistics
+    pub chips: u32,                             // The player's chips at this table
+    pub shoe: Shoe,                             // The shoe of cards used in the game
+    pub rules: Rules,                           // The table rules
+    pub statistics: Statistics,                 // The continuous game statistics
     pub fast_forward: bool, // Fast-forward non-user-facing transitions and skip input checks for faster simulation
+    pub backers: Vec<Backer>, // The bettors who may bet behind the player
+    pub bots: Vec<Bot>,     // The computer players seated beside the player
+    bot_hands: Vec<(usize, Vec<PlayerHand>)>, // The bots' hands in the current round, by bot
+    back_bets: Vec<BackBet>, // The back bets riding on the current round, by the spot they back
+    spot_bets: Vec<u32>,    // The player's bets on extra spots for the current round
+    spot_hands: Vec<PlayerHand>, // The hands dealt to the extra spots, until the player's turn
+    pub record_events: bool, // Record events for front-ends to consume
+    events: Vec<GameEvent>, // The events recorded since they were last drained
+    pub observers: Vec<Box<dyn RoundObserver>>, // Collectors handed every round after the statistics
+    timeline: Timeline,                         // The timestamped transitions of the current round
+    pub(crate) last_bet: Option<u32>,           // The bet the player placed on the last round
+    pub(crate) last_net: Option<i64>,           // The player's net result on the last round settled
+    hole_card: Option<Rank>,                    // The rank forced on the dealer's next hole card
+    pub dealer_errors: Option<DealerErrors>, // The mistakes the dealer makes now and then, if any
+    pub coupon: Option<Coupon>, // The coupon the player redeems with their next bet, if any
+    coupon_in_play: Option<Coupon>, // The coupon redeemed with the bet on the current round
+    buffers: RoundBuffers,      // The buffers of settled rounds, reused for the next round's hands
 }
 
-#[derive(Debug, PartialEq, Eq)]
+/// Why a bet was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
 pub enum BetError {
+    #[error("Bet too low")]
     TooLow,
+    #[error("Bet too high")]
     TooHigh,
+    #[error("Can't afford bet")]
     CantAfford,
+    #[error("No spots left")]
+    NoSpotsLeft,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+/// Why a double down was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
 pub enum DoubleError {
+    #[error("Can't afford double down")]
     CantAfford,
+    #[error("Not two cards")]
     NotTwoCards,
+    #[error("Double after split not allowed")]
     DoubleAfterSplitNotAllowed,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+/// Why a split was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
 pub enum SplitError {
+    #[error("Can't afford split")]
     CantAfford,
+    #[error("Not a pair")]
     NotAPair,
+    #[error("Max splits reached")]
     MaxSplitsReached,
+    #[error("Split aces not allowed")]
     SplitAcesNotAllowed,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+/// Why a surrender was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
 pub enum SurrenderError {
+    #[error("Not two cards")]
     NotTwoCards,
+    #[error("Late surrender not allowed")]
     LateSurrenderNotAllowed,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+/// Why the table refused an input.
+/// Errors about one of the player's hands, or one of the backers, say which one, counting from 0.
+/// The indices are kept small so that the error stays cheap to return alongside the state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
 pub enum Error {
+    #[error("Wrong input")]
     WrongInput,
-    BetError(BetError),
-    DoubleError(DoubleError),
-    SplitError(SplitError),
-    SurrenderError(SurrenderError),
+    /// The player's bet, insurance bet, or tip was refused
+    #[error("{0}")]
+    BetError(#[source] BetError),
+    #[error("Backer {}: {error}", backer + 1)]
+    BackBetError {
+        backer: u8,
+        #[source]
+        error: BetError,
+    },
+    #[error("Hand {}: {error}", hand + 1)]
+    DoubleError {
+        hand: u8,
+        #[source]
+        error: DoubleError,
+    },
+    #[error("Hand {}: {error}", hand + 1)]
+    SplitError {
+        hand: u8,
+        #[source]
+        error: SplitError,
+    },
+    #[error("Hand {}: {error}", hand + 1)]
+    SurrenderError {
+        hand: u8,
+        #[source]
+        error: SurrenderError,
+    },
+    /// A seat tried to join in the middle of a shoe, which the rules do not allow.
+    #[error("No mid-shoe entry")]
+    MidShoeEntry,
+    /// A seat tried to join a table with no free seats.
+    #[error("Table full")]
+    TableFull,
+    /// The input was made from a seat which may not make it, such as a backer trying to play
+    /// the player's hand, or a backer who isn't seated at the table.
+    #[error("{0}: Not your turn")]
+    WrongSeat(Seat),
 }
 
-impl fmt::Display for Error {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::WrongInput => write!(f, "Wrong input"),
-            Self::BetError(err) => match err {
-                BetError::TooLow => write!(f, "Bet too low"),
-                BetError::TooHigh => write!(f, "Bet too high"),
-                BetError::CantAfford => write!(f, "Can't afford bet"),
-            },
-            Self::DoubleError(err) => match err {
-                DoubleError::CantAfford => write!(f, "Can't afford double down"),
-                DoubleError::NotTwoCards => write!(f, "Not two cards"),
-                DoubleError::DoubleAfterSplitNotAllowed => {
-                    write!(f, "Double after split not allowed")
-                }
-            },
-            Self::SplitError(err) => match err {
-                SplitError::CantAfford => write!(f, "Can't afford split"),
-                SplitError::NotAPair => write!(f, "Not a pair"),
-                SplitError::MaxSplitsReached => write!(f, "Max splits reached"),
-                SplitError::SplitAcesNotAllowed => write!(f, "Split aces not allowed"),
-            },
-            Self::SurrenderError(err) => match err {
-                SurrenderError::NotTwoCards => write!(f, "Not two cards"),
-                SurrenderError::LateSurrenderNotAllowed => write!(f, "Late surrender not allowed"),
-            },
-        }
-    }
+/// The index of the hand being played, to say which hand an error is about.
+fn hand_index(player_turn: &PlayerTurn) -> u8 {
+    u8::try_from(player_turn.current_hand_index()).expect("more hands than a player turn can hold")
 }
 
 /// If the player input is invalid, the game cannot progress.
@@ -119,6 +403,43 @@ impl Table {
             rules,
             statistics: Statistics::new(),
             fast_forward: false,
+            backers: Vec::new(),
+            bots: Vec::new(),
+            bot_hands: Vec::new(),
+            back_bets: Vec::new(),
+            spot_bets: Vec::new(),
+            spot_hands: Vec::new(),
+            record_events: false,
+            events: Vec::new(),
+            observers: Vec::new(),
+            timeline: Timeline {
+                transitions: Vec::new(),
+            },
+            last_bet: None,
+            last_net: None,
+            hole_card: None,
+            dealer_errors: None,
+            coupon: None,
+            coupon_in_play: None,
+            buffers: RoundBuffers::new(),
+        }
+    }
+
+    /// The number of buffers kept from settled rounds for the next round's hands.
+    #[must_use]
+    pub fn pooled_buffers(&self) -> usize {
+        self.buffers.pooled()
+    }
+
+    /// Removes and returns the events recorded since the last call, oldest first.
+    pub fn drain_events(&mut self) -> std::vec::Drain<'_, GameEvent> {
+        self.events.drain(..)
+    }
+
+    /// Records an event if recording is enabled.
+    fn emit(&mut self, event: GameEvent) {
+        if self.record_events {
+            self.events.push(event);
         }
     }
 
@@ -128,26 +449,26 @@ impl Table {
     /// Returns Err with the same state if the game could not progress.
     #[rustfmt::skip]
     pub fn progress(&mut self, state: GameState, input: Option<Input>) -> ProgressResult {
+        let input = match input {
+            Some(Input::Timeout) => self.timeout_input(&state),
+            input => input,
+        };
+        if let Some(Err(error)) = input.map(|input| self.check_seat(&state, &input)) {
+            return Err((state, error));
+        }
         match state {
-            GameState::Betting => {
-                if let Some(Input::Bet(bet)) = input {
-                    self.bet(bet)
-                } else {
-                    Err((GameState::Betting, Error::WrongInput))
-                }
-            }
-            GameState::DealFirstPlayerCard { bet } => Ok(self.deal_first_player_card(bet)),
-            GameState::DealFirstDealerCard { player_hand } => {
-                Ok(self.deal_first_dealer_card(player_hand))
-            },
-            GameState::DealSecondPlayerCard { player_hand, dealer_hand } => {
-                Ok(self.deal_second_player_card(player_hand, dealer_hand))
+            GameState::Betting { last_bet } => match input {
+                Some(Input::Bet { seat: Seat::Player, bet }) => self.bet(bet),
+                Some(Input::Bet { seat: Seat::Backer(backer), bet }) => self.back_bet(backer, bet),
+                Some(Input::Tip { tip, .. }) => self.tip(tip),
+                Some(Input::Spot { bet, .. }) => self.spot(bet),
+                _ => Err((GameState::Betting { last_bet }, Error::WrongInput)),
             },
-            GameState::DealHoleCard { player_hand, dealer_hand } => {
-                Ok(self.deal_hole_card(player_hand, dealer_hand))
+            GameState::Dealing { bet, dealt, player_hand, dealer_hand } => {
+                Ok(self.deal(bet, dealt, player_hand, dealer_hand))
             },
             GameState::OfferEarlySurrender { player_hand, dealer_hand } => {
-                if let Some(Input::Choice(early_surrender)) = input {
+                if let Some(Input::Choice { choice: early_surrender, .. }) = input {
                     Ok(self.choose_early_surrender(player_hand, dealer_hand, early_surrender))
                 } else {
                     Err((
@@ -160,7 +481,7 @@ impl Table {
                 }
             }
             GameState::OfferInsurance { player_hand, dealer_hand } => {
-                if let Some(Input::Bet(insurance_bet)) = input {
+                if let Some(Input::Bet { bet: insurance_bet, .. }) = input {
                     self.bet_insurance(player_hand, dealer_hand, insurance_bet)
                 } else {
                     Err((
@@ -176,7 +497,7 @@ impl Table {
                 Ok(self.check_dealer_hole_card(player_hand, dealer_hand, insurance_bet))
             },
             GameState::PlayPlayerTurn { player_turn, dealer_hand, insurance_bet } => {
-                if let Some(Input::Action(action)) = input {
+                if let Some(Input::Action { action, .. }) = input {
                     self.play_player_turn(player_turn, dealer_hand, insurance_bet, action)
                 } else {
                     Err((
@@ -219,7 +540,9 @@ impl Table {
             GameState::RoundOver { finished_hands, dealer_hand, insurance_bet } => {
                 Ok(self.end_round(finished_hands, dealer_hand, insurance_bet))
             },
-            GameState::Payout { total_winnings, .. } => {
+            GameState::Payout { round } => {
+                let total_winnings = round.total_winnings();
+                self.buffers.recycle(round);
                 Ok(self.pay_out_winnings(total_winnings))
             }
             GameState::Shuffle => Ok(self.shuffle_dispenser()),
@@ -227,6 +550,211 @@ impl Table {
         }
     }
 
+    /// Plays the game like `progress`, stamping the transition with the given time.
+    /// Each round's transitions, from the player's bet onwards, are handed over with the
+    /// finished round as its timeline, so the statistics can tell how long the round
+    /// and each decision in it took.
+    /// # Errors
+    /// Returns Err with the same state if the game could not progress.
+    pub fn progress_at(
+        &mut self,
+        state: GameState,
+        input: Option<Input>,
+        at: Duration,
+    ) -> ProgressResult {
+        if matches!(state, GameState::Betting { .. }) {
+            // A round's timeline starts with the bet, leaving out whatever came before it
+            self.timeline.transitions.clear();
+        }
+        self.timeline.transitions.push(Transition::new(&state, at));
+        let result = self.progress(state, input);
+        if result.is_err() {
+            self.timeline.transitions.pop();
+        }
+        result
+    }
+
+    /// Returns the input made on the player's behalf when they run out of time in the given state.
+    /// Offers are declined, and the hand is either stood or played with basic strategy.
+    /// Bets are never placed automatically.
+    fn timeout_input(&self, state: &GameState) -> Option<Input> {
+        match state {
+            GameState::OfferEarlySurrender { .. } => Some(Input::choice(false)),
+            GameState::OfferInsurance { .. } => Some(Input::bet(0)),
+            GameState::PlayPlayerTurn { .. }
+                if self.rules.timeout_action == TimeoutAction::Stand =>
+            {
+                Some(Input::action(HandAction::Stand))
+            }
+            GameState::PlayPlayerTurn { .. } => basic_strategy::input(self, state),
+            _ => None,
+        }
+    }
+
+    /// Returns the cards the player has not seen in the given state: the cards left in the shoe,
+    /// plus the dealer's hole card while it is face down.
+    #[must_use]
+    pub fn unseen(&self, state: &GameState) -> Composition {
+        let mut composition = self.shoe.composition();
+        if let GameState::OfferEarlySurrender { dealer_hand, .. }
+        | GameState::OfferInsurance { dealer_hand, .. }
+        | GameState::PlayPlayerTurn { dealer_hand, .. } = state
+        {
+            if let Some(hole_card) = dealer_hand.hole_card() {
+                composition.put_back(hole_card);
+            }
+        }
+        composition
+    }
+
+    /// Returns the bet the player placed on the last round, if they have placed one.
+    #[must_use]
+    pub const fn last_bet(&self) -> Option<u32> {
+        self.last_bet
+    }
+
+    /// Returns the player's net result on the last round settled, if one has been, not counting tips.
+    #[must_use]
+    pub const fn last_net(&self) -> Option<i64> {
+        self.last_net
+    }
+
+    /// Forces the dealer's hole card on the next round to be of the given rank,
+    /// so "what if" rounds and trainer scenarios can be set up on an otherwise normal table.
+    /// The other opening cards are dealt as usual, unless forced with `Shoe::force`.
+    pub fn force_hole_card(&mut self, rank: Rank) {
+        self.hole_card = Some(rank);
+    }
+
+    /// Returns the state to bet in, remembering the player's last bet so they can bet it again.
+    #[must_use]
+    pub const fn betting(&self) -> GameState {
+        GameState::Betting {
+            last_bet: self.last_bet,
+        }
+    }
+
+    /// Returns whether a new seat may join the table now.
+    /// Without mid-shoe entry, seats may only join before the first card of a shoe is dealt.
+    #[must_use]
+    pub const fn can_join(&self) -> bool {
+        !self.rules.no_mid_shoe_entry || self.shoe.is_fresh()
+    }
+
+    /// Returns the number of seats still free at the table.
+    /// The player's main hand and extra spots each take a seat, as does every backer and bot.
+    #[must_use]
+    pub fn free_seats(&self) -> usize {
+        usize::from(self.rules.max_seats)
+            .saturating_sub(1 + self.spot_bets.len() + self.backers.len() + self.bots.len())
+    }
+
+    /// Returns the player's bets on extra spots for the current round.
+    #[must_use]
+    pub fn spot_bets(&self) -> &[u32] {
+        &self.spot_bets
+    }
+
+    /// Returns the hands dealt to the player's extra spots, until the player's turn takes them up.
+    #[must_use]
+    pub fn spot_hands(&self) -> &[PlayerHand] {
+        &self.spot_hands
+    }
+
+    /// Returns the back bets riding on the current round.
+    #[must_use]
+    pub fn back_bets(&self) -> &[BackBet] {
+        &self.back_bets
+    }
+
+    /// Returns a bot's hands in the current round, which are empty between rounds.
+    #[must_use]
+    pub fn bot_hands(&self, bot: usize) -> &[PlayerHand] {
+        self.bot_hands
+            .iter()
+            .find(|(seated, _)| *seated == bot)
+            .map_or(&[], |(_, hands)| hands)
+    }
+
+    /// Seats a new backer with the given chips, returning the seat they bet from.
+    /// A table seats at most 256 backers, and no more than the rules' seats allow.
+    /// # Errors
+    /// Returns `Error::MidShoeEntry` if the rules do not allow joining in the middle of the shoe,
+    /// or `Error::TableFull` if there are no free seats.
+    pub fn add_backer(&mut self, chips: u32) -> Result<Seat, Error> {
+        if !self.can_join() {
+            return Err(Error::MidShoeEntry);
+        }
+        if self.free_seats() == 0 {
+            return Err(Error::TableFull);
+        }
+        let backer = u8::try_from(self.backers.len()).map_err(|_| Error::WrongInput)?;
+        self.backers.push(Backer { chips });
+        Ok(Seat::Backer(backer))
+    }
+
+    /// Seats a new bot playing the given strategy, returning its index at the table.
+    /// # Errors
+    /// Returns `Error::MidShoeEntry` if the rules do not allow joining in the middle of the shoe,
+    /// or `Error::TableFull` if there are no free seats.
+    pub fn add_bot(&mut self, strategy: BotStrategy) -> Result<usize, Error> {
+        if !self.can_join() {
+            return Err(Error::MidShoeEntry);
+        }
+        if self.free_seats() == 0 {
+            return Err(Error::TableFull);
+        }
+        self.bots.push(Bot { net: 0, strategy });
+        Ok(self.bots.len() - 1)
+    }
+
+    /// Seats the given bots in order, as far as the table's seats allow, returning how many were seated.
+    pub fn seat_bots(&mut self, bots: &[BotSeats]) -> usize {
+        let strategies = bots
+            .iter()
+            .flat_map(|seats| std::iter::repeat_n(seats.strategy, seats.count));
+        strategies
+            .take_while(|strategy| self.add_bot(*strategy).is_ok())
+            .count()
+    }
+
+    /// Returns the bets placed for a round that never started to whoever placed them:
+    /// the backers' back bets and the player's bets on extra spots.
+    /// Front-ends call this when they end a game between rounds, so no bet is lost with it.
+    pub fn withdraw_bets(&mut self) {
+        for back_bet in std::mem::take(&mut self.back_bets) {
+            self.backers[back_bet.backer].chips += back_bet.bet;
+        }
+        self.chips += std::mem::take(&mut self.spot_bets).iter().sum::<u32>();
+    }
+
+    /// Returns the largest insurance bet the player may place on their hand,
+    /// which is half their original bet, or as many chips as they have left if that is less.
+    #[must_use]
+    pub fn max_insurance(&self, player_hand: &PlayerHand) -> u32 {
+        (player_hand.bet / 2).min(self.chips)
+    }
+
+    /// Returns the actions the player is allowed to take on their current hand.
+    /// Hitting and standing are always allowed; the rest depend on the hand and the rules.
+    #[must_use]
+    pub fn allowed_actions(&self, player_turn: &PlayerTurn) -> Vec<HandAction> {
+        let mut allowed_actions = vec![HandAction::Hit, HandAction::Stand];
+        if self.check_double_allowed(player_turn).is_ok() {
+            allowed_actions.push(HandAction::Double);
+        }
+        if self.check_split_allowed(player_turn).is_ok() {
+            allowed_actions.push(HandAction::Split);
+        }
+        if self
+            .check_surrender_allowed(player_turn.current_hand())
+            .is_ok()
+        {
+            allowed_actions.push(HandAction::Surrender);
+        }
+        allowed_actions
+    }
+
     /// A helper function to determine if the player is allowed to double down on their current hand.
     /// The player can double down if their hand consists of two cards, they have enough chips,
     /// and the maximum splits and double after split rules do not prevent them from doing so.
@@ -237,7 +765,7 @@ impl Table {
             Err(DoubleError::NotTwoCards)
         } else if player_turn.current_hand().bet > self.chips {
             Err(DoubleError::CantAfford)
-        } else if player_turn.hands() > 1 && !self.rules.double_after_split {
+        } else if player_turn.splits() > 0 && !self.rules.double_after_split {
             Err(DoubleError::DoubleAfterSplitNotAllowed)
         } else {
             Ok(())
@@ -257,7 +785,8 @@ impl Table {
         } else if self
             .rules
             .max_splits
-            .map_or(false, |max| player_turn.hands() > max)
+            .is_some_and(|max| player_turn.splits() >= max)
+            || player_turn.hands() == PlayerTurn::MAX_HANDS
         {
             Err(SplitError::MaxSplitsReached)
         } else if player_turn.current_hand().value.soft && !self.rules.split_aces {
@@ -282,96 +811,397 @@ impl Table {
         }
     }
 
+    /// Checks whether the input would be accepted in the given state, without playing it.
+    /// Front-ends can use this to disable inputs the player may not make,
+    /// or to reject an input before handing the state over to `progress`.
+    /// States that progress automatically accept no input.
+    /// # Errors
+    /// Returns the error `progress` would return for the input.
+    pub fn validate(&self, state: &GameState, input: &Input) -> Result<(), Error> {
+        let input = match input {
+            Input::Timeout => self.timeout_input(state).ok_or(Error::WrongInput)?,
+            input => *input,
+        };
+        self.check_seat(state, &input)?;
+        match (state, input) {
+            (
+                GameState::Betting { .. },
+                Input::Bet {
+                    seat: Seat::Player,
+                    bet,
+                },
+            ) => self.check_bet(bet, self.chips).map_err(Error::BetError),
+            (
+                GameState::Betting { .. },
+                Input::Bet {
+                    seat: Seat::Backer(backer),
+                    bet,
+                },
+            ) => {
+                let chips = self.backers[usize::from(backer)].chips;
+                self.check_bet(bet, chips)
+                    .map_err(|error| Error::BackBetError { backer, error })
+            }
+            (GameState::Betting { .. }, Input::Tip { tip, .. }) => {
+                self.check_tip(tip).map_err(Error::BetError)
+            }
+            (GameState::Betting { .. }, Input::Spot { bet, .. }) => {
+                self.check_spot(bet).map_err(Error::BetError)
+            }
+            (GameState::OfferEarlySurrender { .. }, Input::Choice { .. }) => Ok(()),
+            (GameState::OfferInsurance { player_hand, .. }, Input::Bet { bet, .. }) => self
+                .check_insurance(player_hand, bet)
+                .map_err(Error::BetError),
+            (GameState::PlayPlayerTurn { player_turn, .. }, Input::Action { action, .. }) => {
+                let hand = hand_index(player_turn);
+                match action {
+                    HandAction::Hit | HandAction::Stand => Ok(()),
+                    HandAction::Double => self
+                        .check_double_allowed(player_turn)
+                        .map_err(|error| Error::DoubleError { hand, error }),
+                    HandAction::Split => self
+                        .check_split_allowed(player_turn)
+                        .map_err(|error| Error::SplitError { hand, error }),
+                    HandAction::Surrender => self
+                        .check_surrender_allowed(player_turn.current_hand())
+                        .map_err(|error| Error::SurrenderError { hand, error }),
+                }
+            }
+            _ => Err(Error::WrongInput),
+        }
+    }
+
+    /// A helper function to determine if the input's seat may make it in the given state.
+    /// The player may make any input, while a seated backer may only bet before the round starts.
+    fn check_seat(&self, state: &GameState, input: &Input) -> Result<(), Error> {
+        match (input.seat(), state, input) {
+            (None | Some(Seat::Player), ..) => Ok(()),
+            (Some(Seat::Backer(backer)), GameState::Betting { .. }, Input::Bet { .. })
+                if usize::from(backer) < self.backers.len() =>
+            {
+                Ok(())
+            }
+            (Some(seat), ..) => Err(Error::WrongSeat(seat)),
+        }
+    }
+
+    /// A helper function to determine if a bet is within the table limits and can be afforded.
+    fn check_bet(&self, bet: u32, chips: u32) -> Result<(), BetError> {
+        match (self.rules.min_bet, self.rules.max_bet) {
+            (Some(min), _) if bet < min => Err(BetError::TooLow),
+            (_, Some(max)) if bet > max => Err(BetError::TooHigh),
+            _ if bet > chips => Err(BetError::CantAfford),
+            _ => Ok(()),
+        }
+    }
+
+    /// A helper function to determine if another spot is free, within the table limits,
+    /// and can be afforded alongside the spots already bet.
+    fn check_spot(&self, bet: u32) -> Result<(), BetError> {
+        if self.free_seats() == 0 {
+            Err(BetError::NoSpotsLeft)
+        } else {
+            self.check_bet(bet, self.chips)
+        }
+    }
+
+    /// A helper function to determine if the player can afford a tip.
+    const fn check_tip(&self, tip: u32) -> Result<(), BetError> {
+        if tip > self.chips {
+            Err(BetError::CantAfford)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// A helper function to determine if an insurance bet is at most half the original bet
+    /// and can be afforded.
+    const fn check_insurance(&self, player_hand: &PlayerHand, bet: u32) -> Result<(), BetError> {
+        if bet > player_hand.bet / 2 {
+            Err(BetError::TooHigh)
+        } else if bet > self.chips {
+            Err(BetError::CantAfford)
+        } else {
+            Ok(())
+        }
+    }
+
     /// The player places a bet to start the round.
     /// The bet must be within the table limits and the player must have enough chips.
-    /// If the bet is valid, the game transitions to dealing the first player card.
+    /// If the bet is valid, the game transitions to dealing the opening cards.
     fn bet(&mut self, bet: u32) -> ProgressResult {
         if self.fast_forward {
             self.chips -= bet;
+            self.last_bet = Some(bet);
+            self.coupon_in_play = self.coupon.take();
+            self.emit(GameEvent::BetPlaced { bet });
             // Simulated bets should already be valid, so we don't need to check them
-            return Ok(self.deal_first_player_card(bet));
+            return Ok(self.deal(bet, 0, None, None));
         }
-        match (self.rules.min_bet, self.rules.max_bet) {
-            (Some(min), _) if bet < min => {
-                Err((GameState::Betting, Error::BetError(BetError::TooLow)))
+        if let Err(error) = self.check_bet(bet, self.chips) {
+            return Err((self.betting(), Error::BetError(error)));
+        }
+        self.chips -= bet;
+        self.last_bet = Some(bet);
+        self.coupon_in_play = self.coupon.take();
+        self.emit(GameEvent::BetPlaced { bet });
+        Ok(GameState::Dealing {
+            bet,
+            dealt: 0,
+            player_hand: None,
+            dealer_hand: None,
+        })
+    }
+
+    /// A backer places a bet behind the player's next hand.
+    /// The bet must be within the table limits and the backer must have enough chips.
+    /// The game stays in the betting state until the player places their own bet.
+    fn back_bet(&mut self, backer: u8, bet: u32) -> ProgressResult {
+        let Some(chips) = self
+            .backers
+            .get(usize::from(backer))
+            .map(|backer| backer.chips)
+        else {
+            return Err((self.betting(), Error::WrongSeat(Seat::Backer(backer))));
+        };
+        if let Err(error) = self.check_bet(bet, chips) {
+            return Err((self.betting(), Error::BackBetError { backer, error }));
+        }
+        let backer = usize::from(backer);
+        self.backers[backer].chips -= bet;
+        self.back_bets.push(BackBet {
+            backer,
+            spot: 0,
+            bet,
+        });
+        self.emit(GameEvent::BackBetPlaced { backer, bet });
+        Ok(self.betting())
+    }
+
+    /// The player bets on another spot for the next round.
+    /// The bet must be within the table limits, a spot must be free, and the player must have
+    /// enough chips. The game stays in the betting state until the player bets on their main hand.
+    fn spot(&mut self, bet: u32) -> ProgressResult {
+        if let Err(error) = self.check_spot(bet) {
+            return Err((self.betting(), Error::BetError(error)));
+        }
+        self.chips -= bet;
+        self.spot_bets.push(bet);
+        self.emit(GameEvent::SpotBetPlaced {
+            spot: self.spot_bets.len(),
+            bet,
+        });
+        Ok(self.betting())
+    }
+
+    /// The player tips the dealer between rounds.
+    /// The tip leaves the player's bankroll for good and is recorded in the statistics.
+    /// The game stays in the betting state until the player places their bet.
+    fn tip(&mut self, tip: u32) -> ProgressResult {
+        if let Err(error) = self.check_tip(tip) {
+            return Err((self.betting(), Error::BetError(error)));
+        }
+        self.chips -= tip;
+        self.statistics.record_tip(tip);
+        self.emit(GameEvent::Tipped { tip });
+        Ok(self.betting())
+    }
+
+    /// The dealer deals the next opening card, to whoever the rules' deal order says is next.
+    /// The player's and dealer's hands are created with their first cards.
+    /// Once all the opening cards are dealt, the round goes on to any offers or the player's turn.
+    fn deal(
+        &mut self,
+        bet: u32,
+        dealt: usize,
+        mut player_hand: Option<PlayerHand>,
+        mut dealer_hand: Option<DealerHand>,
+    ) -> GameState {
+        let sequence = self.rules.deal_order.sequence();
+        if sequence[dealt] == Deal::DealerHoleCard {
+            if let Some(rank) = self.hole_card.take() {
+                self.shoe.forced.push_front(rank);
             }
-            (_, Some(max)) if bet > max => {
-                Err((GameState::Betting, Error::BetError(BetError::TooHigh)))
+        }
+        let card = self.draw_card();
+        match sequence[dealt] {
+            Deal::Player => {
+                let visibility = self.face_down_visibility();
+                let hand = match player_hand.as_mut() {
+                    Some(hand) => {
+                        *hand += card;
+                        hand
+                    }
+                    None => {
+                        let cards = self.buffers.cards();
+                        player_hand.insert(PlayerHand::with_buffer(card, bet, cards))
+                    }
+                };
+                hand.visibility = visibility;
+                self.emit_player_card(0, hand, visibility);
             }
-            _ if bet > self.chips => {
-                Err((GameState::Betting, Error::BetError(BetError::CantAfford)))
+            Deal::DealerUpcard => {
+                let cards = self.buffers.cards();
+                let hand = dealer_hand.insert(DealerHand::with_buffer(
+                    card,
+                    self.rules.dealer_drawing,
+                    cards,
+                ));
+                self.emit_dealer_card(hand);
+            }
+            Deal::DealerHoleCard => {
+                let hand = dealer_hand
+                    .as_mut()
+                    .expect("the upcard is dealt before the hole card");
+                *hand += card;
+                self.emit(GameEvent::HoleCardDealt);
+                if let Some(errors) = &mut self.dealer_errors {
+                    hand.flashed = errors.flashes_hole_card();
+                }
+                if let Some(card) = hand.flashed_hole_card().filter(|_| self.record_events) {
+                    let card = card.clone();
+                    self.emit(GameEvent::HoleCardFlashed { card });
+                }
             }
-            _ => {
-                self.chips -= bet;
-                Ok(GameState::DealFirstPlayerCard { bet })
+        }
+        let dealt = dealt + 1;
+        if dealt < sequence.len() {
+            if self.fast_forward {
+                self.deal(bet, dealt, player_hand, dealer_hand)
+            } else {
+                GameState::Dealing {
+                    bet,
+                    dealt,
+                    player_hand,
+                    dealer_hand,
+                }
             }
+        } else {
+            let player_hand = player_hand.expect("the player is dealt two cards");
+            let dealer_hand = dealer_hand.expect("the dealer is dealt two cards");
+            self.deal_spots();
+            self.deal_bots();
+            self.offer_or_play(player_hand, dealer_hand)
         }
     }
 
-    /// The dealer deals the first card to the player and the player's hand is created.
-    /// Next, the dealer will deal their first card.
-    fn deal_first_player_card(&mut self, bet: u32) -> GameState {
-        let card = self.shoe.draw_card();
-        let player_hand = PlayerHand::new(card, bet);
-        if self.fast_forward {
-            self.deal_first_dealer_card(player_hand)
-        } else {
-            GameState::DealFirstDealerCard { player_hand }
+    /// The dealer deals two cards to each bot, after the player's spots.
+    /// Every bot bets the table minimum on its hand.
+    fn deal_bots(&mut self) {
+        let bet = self.rules.min_bet.unwrap_or(1);
+        for bot in 0..self.bots.len() {
+            let mut hand = PlayerHand::new(self.draw_card(), bet);
+            hand += self.draw_card();
+            self.bot_hands.push((bot, vec![hand]));
         }
     }
 
-    /// The dealer deals the first card to themselves and the dealer's hand is created.
-    /// Next, the dealer will deal the second card to the player.
-    fn deal_first_dealer_card(&mut self, player_hand: PlayerHand) -> GameState {
-        let card = self.shoe.draw_card();
-        let dealer_hand = DealerHand::new(card, self.rules.dealer_soft_17);
-        if self.fast_forward {
-            self.deal_second_player_card(player_hand, dealer_hand)
-        } else {
-            GameState::DealSecondPlayerCard {
-                player_hand,
-                dealer_hand,
+    /// The bots play their hands by their strategies once the player has finished their turn,
+    /// as if they sat after the player. They never surrender early or take insurance.
+    /// Returns whether any bot stood on a hand, which the dealer then plays against.
+    fn play_bots(&mut self, dealer_hand: &DealerHand) -> bool {
+        let mut stood = false;
+        let mut bot_hands = std::mem::take(&mut self.bot_hands);
+        for (bot, hands) in &mut bot_hands {
+            let mut player_turn = PlayerTurn::from_spots(std::mem::take(hands));
+            let mut actions = vec![Vec::new()];
+            *hands = loop {
+                player_turn = match player_turn.continue_playing() {
+                    Ok(player_turn) => player_turn,
+                    Err(hands) => break hands,
+                };
+                let action = self.bots[*bot]
+                    .strategy
+                    .action(self, &player_turn, dealer_hand);
+                actions[player_turn.current_hand_index()].push(action);
+                match action {
+                    HandAction::Stand => player_turn.current_hand_mut().stand(),
+                    HandAction::Hit => *player_turn.current_hand_mut() += self.draw_card(),
+                    HandAction::Double => {
+                        let card = self.draw_card();
+                        player_turn.current_hand_mut().double(card);
+                    }
+                    HandAction::Split => {
+                        let mut new_hand = player_turn.current_hand_mut().split();
+                        *player_turn.current_hand_mut() += self.draw_card();
+                        new_hand += self.draw_card();
+                        player_turn.defer(new_hand);
+                        actions.insert(player_turn.current_hand_index() + 1, Vec::new());
+                    }
+                    HandAction::Surrender => player_turn.current_hand_mut().surrender(),
+                }
+            };
+            stood |= hands.iter().any(|hand| hand.status == Status::Stood);
+            if self.record_events {
+                for (hand, actions) in hands.iter().zip(actions) {
+                    self.emit(GameEvent::BotPlayed {
+                        bot: *bot,
+                        cards: hand.cards.clone(),
+                        actions,
+                        value: hand.value,
+                        status: hand.status.clone(),
+                    });
+                }
             }
         }
+        self.bot_hands = bot_hands;
+        stood
     }
 
-    /// The dealer deals the second card to the player.
-    /// Next, the dealer will deal the second card to themselves, also known as the hole card.
-    fn deal_second_player_card(
-        &mut self,
-        mut player_hand: PlayerHand,
-        dealer_hand: DealerHand,
-    ) -> GameState {
-        player_hand += self.shoe.draw_card();
-        if self.fast_forward {
-            self.deal_hole_card(player_hand, dealer_hand)
-        } else {
-            GameState::DealHoleCard {
-                player_hand,
-                dealer_hand,
-            }
+    /// The dealer deals two cards to each of the player's extra spots, after the opening cards.
+    /// The spot hands are kept aside until the player's turn, which plays them after the main hand.
+    fn deal_spots(&mut self) {
+        for (spot, bet) in self.spot_bets.clone().into_iter().enumerate() {
+            let visibility = self.face_down_visibility();
+            let cards = self.buffers.cards();
+            let mut hand = PlayerHand::with_buffer(self.draw_card(), bet, cards);
+            hand += self.draw_card();
+            hand.visibility = visibility;
+            self.emit_player_card(spot + 1, &hand, visibility);
+            self.spot_hands.push(hand);
         }
     }
 
-    /// The dealer deals the hole card to themselves.
+    /// Returns the main hand followed by the hands dealt to the player's extra spots.
+    fn with_spots(&mut self, player_hand: PlayerHand) -> Vec<PlayerHand> {
+        let mut hands = self.buffers.hands();
+        hands.push(player_hand);
+        hands.append(&mut self.spot_hands);
+        hands
+    }
+
+    /// The opening cards are dealt.
     /// If the dealer is showing a 10 or an Ace, they will check their hole card for Blackjack.
     /// If early surrender or insurance is offered, the game will transition to those states first.
     /// If the dealer cannot have Blackjack or if the player does have Blackjack, the dealer will
-    /// not check their hole card, and will instead let the player play their hand.
-    fn deal_hole_card(
-        &mut self,
-        player_hand: PlayerHand,
-        mut dealer_hand: DealerHand,
-    ) -> GameState {
-        dealer_hand += self.shoe.draw_card();
-        if dealer_hand.showing() < 10 || player_hand.status == Status::Blackjack {
-            self.play_player_turn_or_go_to_dealer_turn(player_hand.into(), dealer_hand, 0)
+    /// not check their hole card, and will instead let the player play their hand,
+    /// unless the player is offered even money on their Blackjack.
+    fn offer_or_play(&mut self, player_hand: PlayerHand, dealer_hand: DealerHand) -> GameState {
+        let blackjacks = player_hand.status == Status::Blackjack
+            && self
+                .spot_hands
+                .iter()
+                .all(|hand| hand.status == Status::Blackjack);
+        if dealer_hand.showing() < 10 || blackjacks {
+            if self.rules.insurance && dealer_hand.showing() == 11 {
+                // Insuring a blackjack is also known as taking even money
+                self.emit(GameEvent::InsuranceOffered);
+                GameState::OfferInsurance {
+                    player_hand,
+                    dealer_hand,
+                }
+            } else {
+                let player_turn = PlayerTurn::from_spots(self.with_spots(player_hand));
+                self.play_player_turn_or_go_to_dealer_turn(player_turn, dealer_hand, 0)
+            }
         } else if self.rules.early_surrender {
+            self.emit(GameEvent::EarlySurrenderOffered);
             GameState::OfferEarlySurrender {
                 player_hand,
                 dealer_hand,
             }
         } else if self.rules.insurance && dealer_hand.showing() == 11 {
+            self.emit(GameEvent::InsuranceOffered);
             GameState::OfferInsurance {
                 player_hand,
                 dealer_hand,
@@ -388,27 +1218,37 @@ impl Table {
     }
 
     /// The player decides whether to surrender early.
-    /// If the player surrenders, their hand is finished and the round is over.
+    /// If the player surrenders, their hand is finished and the round is over,
+    /// unless they have other spots to play, which still wait for the dealer to check their hole card.
     /// Otherwise, if insurance is offered and the dealer is showing an Ace, the player can place
     /// an insurance bet.
     /// Otherwise, the dealer checks their hole card for Blackjack.
     fn choose_early_surrender(
         &mut self,
-        player_hand: PlayerHand,
+        mut player_hand: PlayerHand,
         dealer_hand: DealerHand,
         surrender: bool,
     ) -> GameState {
         if surrender {
-            if self.fast_forward {
-                self.late_surrender(player_hand.into(), dealer_hand, 0)
-            } else {
-                GameState::PlayerSurrender {
-                    player_turn: player_hand.into(),
-                    dealer_hand,
-                    insurance_bet: 0,
-                }
+            self.emit(GameEvent::PlayerAction {
+                hand: 0,
+                action: HandAction::Surrender,
+            });
+            player_hand.surrender_early();
+            if self.spot_hands.is_empty() {
+                return if self.fast_forward {
+                    self.late_surrender(player_hand.into(), dealer_hand, 0)
+                } else {
+                    GameState::PlayerSurrender {
+                        player_turn: player_hand.into(),
+                        dealer_hand,
+                        insurance_bet: 0,
+                    }
+                };
             }
-        } else if self.rules.insurance && dealer_hand.showing() == 11 {
+        }
+        if !surrender && self.rules.insurance && dealer_hand.showing() == 11 {
+            self.emit(GameEvent::InsuranceOffered);
             GameState::OfferInsurance {
                 player_hand,
                 dealer_hand,
@@ -437,25 +1277,19 @@ impl Table {
         if self.fast_forward {
             // Simulated bets should already be valid, so we don't need to check them
             self.chips -= insurance_bet;
+            self.emit(GameEvent::InsurancePlaced { bet: insurance_bet });
             Ok(self.check_dealer_hole_card(player_hand, dealer_hand, insurance_bet))
-        } else if insurance_bet > player_hand.bet / 2 {
-            Err((
-                GameState::OfferInsurance {
-                    player_hand,
-                    dealer_hand,
-                },
-                Error::BetError(BetError::TooHigh),
-            ))
-        } else if insurance_bet > self.chips {
+        } else if let Err(error) = self.check_insurance(&player_hand, insurance_bet) {
             Err((
                 GameState::OfferInsurance {
                     player_hand,
                     dealer_hand,
                 },
-                Error::BetError(BetError::CantAfford),
+                Error::BetError(error),
             ))
         } else {
             self.chips -= insurance_bet;
+            self.emit(GameEvent::InsurancePlaced { bet: insurance_bet });
             Ok(GameState::CheckDealerHoleCard {
                 player_hand,
                 dealer_hand,
@@ -473,19 +1307,23 @@ impl Table {
         dealer_hand: DealerHand,
         insurance_bet: u32,
     ) -> GameState {
-        if dealer_hand.status == Status::Blackjack {
+        let blackjack = dealer_hand.status == Status::Blackjack;
+        self.emit(GameEvent::DealerPeeked { blackjack });
+        let player_hands = self.with_spots(player_hand);
+        if blackjack {
+            self.emit_hole_card(&dealer_hand);
             if self.fast_forward {
-                self.end_round(vec![player_hand], dealer_hand, insurance_bet)
+                self.end_round(player_hands, dealer_hand, insurance_bet)
             } else {
                 GameState::RoundOver {
-                    finished_hands: vec![player_hand],
+                    finished_hands: player_hands,
                     dealer_hand,
                     insurance_bet,
                 }
             }
         } else {
             self.play_player_turn_or_go_to_dealer_turn(
-                player_hand.into(),
+                PlayerTurn::from_spots(player_hands),
                 dealer_hand,
                 insurance_bet,
             )
@@ -503,6 +1341,23 @@ impl Table {
         insurance_bet: u32,
         action: HandAction,
     ) -> ProgressResult {
+        let hand = player_turn.current_hand_index();
+        let result = self.choose_action(player_turn, dealer_hand, insurance_bet, action);
+        if result.is_ok() {
+            self.emit(GameEvent::PlayerAction { hand, action });
+        }
+        result
+    }
+
+    /// Carries out the player's chosen action, if it is allowed.
+    fn choose_action(
+        &mut self,
+        player_turn: PlayerTurn,
+        dealer_hand: DealerHand,
+        insurance_bet: u32,
+        action: HandAction,
+    ) -> ProgressResult {
+        let hand = hand_index(&player_turn);
         match action {
             HandAction::Hit if self.fast_forward => {
                 Ok(self.hit(player_turn, dealer_hand, insurance_bet))
@@ -533,7 +1388,7 @@ impl Table {
                             dealer_hand,
                             insurance_bet,
                         },
-                        Error::DoubleError(err),
+                        Error::DoubleError { hand, error: err },
                     ))
                 } else {
                     self.chips -= player_turn.current_hand().bet;
@@ -557,7 +1412,7 @@ impl Table {
                             dealer_hand,
                             insurance_bet,
                         },
-                        Error::SplitError(err),
+                        Error::SplitError { hand, error: err },
                     ))
                 } else {
                     self.chips -= player_turn.current_hand().bet;
@@ -580,7 +1435,7 @@ impl Table {
                             dealer_hand,
                             insurance_bet,
                         },
-                        Error::SurrenderError(err),
+                        Error::SurrenderError { hand, error: err },
                     ))
                 } else {
                     Ok(GameState::PlayerSurrender {
@@ -601,7 +1456,12 @@ impl Table {
         dealer_hand: DealerHand,
         insurance_bet: u32,
     ) -> GameState {
-        *player_turn.current_hand_mut() += self.shoe.draw_card();
+        *player_turn.current_hand_mut() += self.draw_card();
+        self.emit_player_card(
+            player_turn.current_hand_index(),
+            player_turn.current_hand(),
+            Visibility::FaceUp,
+        );
         self.play_player_turn_or_go_to_dealer_turn(player_turn, dealer_hand, insurance_bet)
     }
 
@@ -625,7 +1485,12 @@ impl Table {
         dealer_hand: DealerHand,
         insurance_bet: u32,
     ) -> GameState {
-        player_turn.current_hand_mut().double(self.shoe.draw_card());
+        player_turn.current_hand_mut().double(self.draw_card());
+        self.emit_player_card(
+            player_turn.current_hand_index(),
+            player_turn.current_hand(),
+            self.face_down_visibility(),
+        );
         self.play_player_turn_or_go_to_dealer_turn(player_turn, dealer_hand, insurance_bet)
     }
 
@@ -637,7 +1502,11 @@ impl Table {
         dealer_hand: DealerHand,
         insurance_bet: u32,
     ) -> GameState {
-        let new_hand = player_turn.current_hand_mut().split();
+        // A face-down pair is turned up to be split
+        player_turn.current_hand_mut().visibility = Visibility::FaceUp;
+        let new_hand = player_turn
+            .current_hand_mut()
+            .split_into(self.buffers.cards());
         if self.fast_forward {
             self.deal_first_split_card(player_turn, new_hand, dealer_hand, insurance_bet)
         } else {
@@ -659,7 +1528,12 @@ impl Table {
         dealer_hand: DealerHand,
         insurance_bet: u32,
     ) -> GameState {
-        *player_turn.current_hand_mut() += self.shoe.draw_card();
+        *player_turn.current_hand_mut() += self.draw_card();
+        self.emit_player_card(
+            player_turn.current_hand_index(),
+            player_turn.current_hand(),
+            Visibility::FaceUp,
+        );
         if self.fast_forward {
             self.deal_second_split_card(player_turn, new_hand, dealer_hand, insurance_bet)
         } else {
@@ -682,7 +1556,12 @@ impl Table {
         dealer_hand: DealerHand,
         insurance_bet: u32,
     ) -> GameState {
-        new_hand += self.shoe.draw_card();
+        new_hand += self.draw_card();
+        self.emit_player_card(
+            player_turn.current_hand_index() + 1,
+            &new_hand,
+            Visibility::FaceUp,
+        );
         player_turn.defer(new_hand);
         self.play_player_turn_or_go_to_dealer_turn(player_turn, dealer_hand, insurance_bet)
     }
@@ -715,9 +1594,12 @@ impl Table {
                 insurance_bet,
             },
             Err(finished_hands) => {
-                // If the player did not explicitly stand on any of their hands,
+                let bots_stood = self.play_bots(&dealer_hand);
+                self.emit_hole_card(&dealer_hand);
+                // If neither the player nor a bot explicitly stood on any of their hands,
                 // the dealer will simply flip their hole card and stand immediately.
                 if dealer_hand.status == Status::InPlay
+                    && !bots_stood
                     && !finished_hands
                         .iter()
                         .any(|hand| hand.status == Status::Stood)
@@ -777,34 +1659,99 @@ impl Table {
         mut dealer_hand: DealerHand,
         insurance_bet: u32,
     ) -> GameState {
-        dealer_hand += self.shoe.draw_card();
+        dealer_hand += self.draw_card();
+        self.emit_dealer_card(&dealer_hand);
         self.play_dealer_turn_or_end_round(finished_hands, dealer_hand, insurance_bet)
     }
 
     /// The round is over.
-    /// The player's total bet and winnings are calculated.
+    /// The player's hands are settled into a finished round, which is handed to the statistics
+    /// and the observers before it is paid out, and back bets are paid to their backers.
     fn end_round(
         &mut self,
         mut finished_hands: Vec<PlayerHand>,
         dealer_hand: DealerHand,
         insurance: u32,
     ) -> GameState {
-        let total_bet = finished_hands.iter().map(|hand| hand.bet).sum::<u32>() + insurance;
+        self.emit(GameEvent::RoundOver {
+            status: dealer_hand.status.clone(),
+            value: dealer_hand.value,
+        });
         for hand in &mut finished_hands {
-            hand.winnings = hand.calculate_winnings(&dealer_hand, self.rules.blackjack_payout);
+            hand.settle(&dealer_hand, &self.rules);
+        }
+        let mispaid = self
+            .dealer_errors
+            .as_mut()
+            .and_then(|errors| errors.mispay(&mut finished_hands));
+        if let Some((hand, amount)) = mispaid {
+            self.emit(GameEvent::Mispaid { hand, amount });
+        }
+        // Each spot's own hand is the one not split off another, ahead of the hands split from it
+        for back_bet in std::mem::take(&mut self.back_bets) {
+            let hand = finished_hands
+                .iter()
+                .filter(|hand| !hand.is_split_off())
+                .nth(back_bet.spot)
+                .expect("every spot backed is dealt a hand");
+            self.backers[back_bet.backer].chips +=
+                hand.calculate_winnings_on(back_bet.bet, &dealer_hand, &self.rules);
+        }
+        // The bots' hands are settled too, including those left unplayed when the dealer had blackjack
+        for (bot, hands) in std::mem::take(&mut self.bot_hands) {
+            let net = hands
+                .iter()
+                .map(|hand| {
+                    i64::from(hand.calculate_winnings(&dealer_hand, &self.rules))
+                        - i64::from(hand.bet)
+                })
+                .sum();
+            self.bots[bot].net += net;
+            self.emit(GameEvent::BotPaid { bot, net });
+        }
+        // Insurance pays 2:1, so a winning insurance bet comes back three times over
+        let insurance_winnings = if insurance > 0 && dealer_hand.status == Status::Blackjack {
+            insurance * 3
+        } else {
+            0
+        };
+        // A coupon rides on the original hand too, and a coupon still good goes back to the player
+        let mut coupon_winnings = 0;
+        if let Some(coupon) = self.coupon_in_play.take() {
+            let result = coupon.settle(&finished_hands[0], &dealer_hand, &self.rules);
+            if result.kept {
+                self.coupon.get_or_insert(coupon);
+            } else {
+                self.statistics.record_coupon(result.winnings);
+            }
+            coupon_winnings = result.winnings;
         }
-        let mut total_winnings = finished_hands.iter().map(|hand| hand.winnings).sum();
-        if dealer_hand.status == Status::Blackjack {
-            total_winnings += insurance * 2;
+        let round = FinishedRound {
+            hands: finished_hands,
+            spots: 1 + std::mem::take(&mut self.spot_bets).len() as u8,
+            dealer_hand,
+            insurance_bet: insurance,
+            insurance_winnings,
+            coupon_winnings,
+            shoe: self.shoe.composition(),
+            timeline: (!self.timeline.transitions.is_empty())
+                .then(|| Box::new(std::mem::take(&mut self.timeline))),
+        };
+        self.last_net = Some(round.net());
+        self.statistics.on_round(&round);
+        for observer in &mut self.observers {
+            observer.on_round(&round);
         }
-        self.statistics.update(finished_hands, dealer_hand);
+        self.emit(GameEvent::Payout {
+            total_bet: round.total_bet(),
+            total_winnings: round.total_winnings(),
+        });
         if self.fast_forward {
+            let total_winnings = round.total_winnings();
+            self.buffers.recycle(round);
             self.pay_out_winnings(total_winnings)
         } else {
-            GameState::Payout {
-                total_bet,
-                total_winnings,
-            }
+            GameState::Payout { round }
         }
     }
 
@@ -818,6 +1765,7 @@ impl Table {
             .min_bet
             .map_or(self.chips == 0, |min| self.chips < min)
         {
+            self.emit(GameEvent::GameOver);
             GameState::GameOver
         } else if self.shoe.needs_shuffle() {
             if self.fast_forward {
@@ -826,15 +1774,73 @@ impl Table {
                 GameState::Shuffle
             }
         } else {
-            GameState::Betting
+            self.betting()
         }
     }
 
     /// The dealer shuffles the shoe.
     /// The game returns to the betting state.
     fn shuffle_dispenser(&mut self) -> GameState {
+        self.statistics.record_shuffle(
+            usize::from(self.shoe.cards_drawn),
+            usize::from(self.shoe.decks) * 52,
+        );
         self.shoe.shuffle();
-        GameState::Betting
+        self.emit(GameEvent::Shuffled);
+        self.betting()
+    }
+
+    /// Draws a card from the shoe, announcing when it brings out the cut card.
+    fn draw_card(&mut self) -> Card {
+        let reached = self.record_events && !self.shoe.needs_shuffle();
+        let card = self.shoe.draw_card();
+        self.statistics.record_card();
+        if reached && self.shoe.needs_shuffle() {
+            self.emit(GameEvent::CutCardReached);
+        }
+        card
+    }
+
+    /// Records the latest card dealt to one of the player's hands.
+    fn emit_player_card(&mut self, hand: usize, player_hand: &PlayerHand, visibility: Visibility) {
+        if self.record_events {
+            self.emit(GameEvent::PlayerCard {
+                hand,
+                card: player_hand.cards[player_hand.size() - 1].clone(),
+                value: player_hand.value,
+                status: player_hand.status.clone(),
+                visibility,
+            });
+        }
+    }
+
+    /// How the player's opening and double-down cards are dealt under the rules.
+    const fn face_down_visibility(&self) -> Visibility {
+        if self.rules.face_down {
+            Visibility::FaceDown
+        } else {
+            Visibility::FaceUp
+        }
+    }
+
+    /// Records the latest card dealt face up to the dealer.
+    fn emit_dealer_card(&mut self, dealer_hand: &DealerHand) {
+        if self.record_events {
+            self.emit(GameEvent::DealerCard {
+                card: dealer_hand.cards()[dealer_hand.cards().len() - 1].clone(),
+                value: dealer_hand.value,
+            });
+        }
+    }
+
+    /// Records the dealer turning over their hole card.
+    fn emit_hole_card(&mut self, dealer_hand: &DealerHand) {
+        if let Some(card) = dealer_hand.hole_card().filter(|_| self.record_events) {
+            self.emit(GameEvent::HoleCardRevealed {
+                card: card.clone(),
+                value: dealer_hand.value,
+            });
+        }
     }
 }
 
@@ -842,6 +1848,8 @@ impl Table {
 mod tests {
     use super::*;
 
+    const BETTING: GameState = GameState::Betting { last_bet: None };
+
     #[test]
     fn test_bet() {
         let mut table = Table::new(
@@ -853,22 +1861,516 @@ mod tests {
                 ..Rules::default()
             },
         );
-        assert_eq!(
-            table.bet(50),
-            Ok(GameState::DealFirstPlayerCard { bet: 50 })
-        );
+        let dealing = |bet| GameState::Dealing {
+            bet,
+            dealt: 0,
+            player_hand: None,
+            dealer_hand: None,
+        };
+        // Refused bets leave the game betting, still remembering the last bet placed
+        let betting = |last_bet| GameState::Betting {
+            last_bet: Some(last_bet),
+        };
+        assert_eq!(table.bet(50), Ok(dealing(50)));
         assert_eq!(
             table.bet(101),
-            Err((GameState::Betting, Error::BetError(BetError::TooHigh)))
+            Err((betting(50), Error::BetError(BetError::TooHigh)))
         );
         assert_eq!(
             table.bet(0),
-            Err((GameState::Betting, Error::BetError(BetError::TooLow)))
+            Err((betting(50), Error::BetError(BetError::TooLow)))
         );
-        assert_eq!(table.bet(1), Ok(GameState::DealFirstPlayerCard { bet: 1 }));
+        assert_eq!(table.bet(1), Ok(dealing(1)));
         assert_eq!(
             table.bet(50),
-            Err((GameState::Betting, Error::BetError(BetError::CantAfford)))
+            Err((betting(1), Error::BetError(BetError::CantAfford)))
+        );
+        assert_eq!(table.last_bet(), Some(1));
+    }
+
+    #[test]
+    fn test_deal_order() {
+        let rules = Rules {
+            deal_order: crate::rules::DealOrder::PlayerFirst,
+            ..Rules::default()
+        };
+        let mut table = Table::new(1000, Shoe::new(4, 0.50), rules);
+        table.record_events = true;
+        let mut state = table.bet(100).unwrap();
+        while let GameState::Dealing { .. } = state {
+            state = table.progress(state, None).unwrap();
+        }
+        let events: Vec<_> = table.drain_events().collect();
+        assert!(matches!(
+            events[..4],
+            [
+                GameEvent::BetPlaced { .. },
+                GameEvent::PlayerCard { .. },
+                GameEvent::PlayerCard { .. },
+                GameEvent::DealerCard { .. },
+            ]
+        ));
+        assert_eq!(events[4], GameEvent::HoleCardDealt);
+
+        // Hand-held games deal the player's opening cards face down
+        table.rules.face_down = true;
+        let mut state = table.bet(100).unwrap();
+        while let GameState::Dealing { .. } = state {
+            state = table.progress(state, None).unwrap();
+        }
+        assert!(table.drain_events().all(|event| !matches!(
+            event,
+            GameEvent::PlayerCard {
+                visibility: Visibility::FaceUp,
+                ..
+            }
+        )));
+    }
+
+    #[test]
+    fn test_force_hole_card() {
+        let mut table = Table::new(1000, Shoe::seeded(6, 0.75, 1), Rules::default());
+        table.shoe.force([Rank::Ten, Rank::Ace, Rank::Nine]);
+        table.force_hole_card(Rank::King);
+        let mut state = table.bet(100).unwrap();
+        while let GameState::Dealing { .. } = state {
+            state = table.progress(state, None).unwrap();
+        }
+        let (GameState::OfferEarlySurrender {
+            player_hand,
+            dealer_hand,
+        }
+        | GameState::OfferInsurance {
+            player_hand,
+            dealer_hand,
+        }
+        | GameState::CheckDealerHoleCard {
+            player_hand,
+            dealer_hand,
+            ..
+        }) = &state
+        else {
+            panic!("the dealer should be showing an ace, not {state:?}");
+        };
+        assert_eq!(player_hand.value.total, 19);
+        assert_eq!(
+            dealer_hand.hole_card().map(|card| card.rank.clone()),
+            Some(Rank::King)
+        );
+        assert!(table.hole_card.is_none());
+        assert!(table.shoe.forced().is_empty());
+    }
+
+    #[test]
+    fn test_spots() {
+        let mut table = Table::new(1000, Shoe::seeded(6, 0.75, 1), Rules::default());
+        table.shoe.force([
+            Rank::Ten,
+            Rank::Nine,
+            Rank::Eight,
+            Rank::Seven,
+            Rank::Five,
+            Rank::Six,
+        ]);
+        assert_eq!(table.progress(BETTING, Some(Input::spot(100))), Ok(BETTING));
+        assert_eq!(table.chips, 900);
+        let mut state = table.progress(BETTING, Some(Input::bet(100))).unwrap();
+        while let GameState::Dealing { .. } = state {
+            assert_eq!(state.total_wagered(table.spot_bets()), 200);
+            state = table.progress(state, None).unwrap();
+        }
+        assert_eq!(state.total_wagered(table.spot_bets()), 200);
+        let GameState::PlayPlayerTurn { player_turn, .. } = &state else {
+            panic!("the dealer can't have blackjack, so the player should play, not {state:?}");
+        };
+        assert_eq!((player_turn.spots(), player_turn.splits()), (2, 0));
+        assert_eq!(player_turn.all_hands()[1].value.total, 11);
+        assert_eq!(player_turn.all_hands()[1].bet, 100);
+        while !matches!(state, GameState::Payout { .. }) {
+            let input = matches!(state, GameState::PlayPlayerTurn { .. })
+                .then_some(Input::action(HandAction::Stand));
+            state = table.progress(state, input).unwrap();
+        }
+        let GameState::Payout { round } = &state else {
+            unreachable!()
+        };
+        assert_eq!((round.spots, round.hands.len()), (2, 2));
+        assert_eq!(table.statistics.extra_spots(), 1);
+
+        for _ in 1..table.rules.max_seats {
+            assert!(table.progress(BETTING, Some(Input::spot(100))).is_ok());
+        }
+        assert_eq!(
+            table.validate(&BETTING, &Input::spot(100)),
+            Err(Error::BetError(BetError::NoSpotsLeft))
+        );
+    }
+
+    #[test]
+    fn test_split_first_spot() {
+        let mut table = Table::new(1000, Shoe::seeded(6, 0.75, 1), Rules::default());
+        table.shoe.force([
+            Rank::Eight,
+            Rank::Nine,
+            Rank::Eight,
+            Rank::Seven,
+            Rank::Five,
+            Rank::Four,
+            Rank::Three,
+            Rank::Two,
+        ]);
+        table.progress(BETTING, Some(Input::spot(100))).unwrap();
+        let mut state = table.progress(BETTING, Some(Input::bet(100))).unwrap();
+        while let GameState::Dealing { .. } = state {
+            state = table.progress(state, None).unwrap();
+        }
+        state = table
+            .progress(state, Some(Input::action(HandAction::Split)))
+            .unwrap();
+        // The hand split off the main hand is played before the extra spot's hand
+        let mut played = Vec::new();
+        while !matches!(state, GameState::Payout { .. }) {
+            if let GameState::PlayPlayerTurn { player_turn, .. } = &state {
+                played.push(player_turn.current_hand().value.total);
+                state = table
+                    .progress(state, Some(Input::action(HandAction::Stand)))
+                    .unwrap();
+            } else {
+                state = table.progress(state, None).unwrap();
+            }
+        }
+        assert_eq!(played, [11, 10, 9]);
+        let GameState::Payout { round } = &state else {
+            unreachable!()
+        };
+        assert_eq!((round.spots, round.hands.len()), (2, 3));
+    }
+
+    #[test]
+    fn test_bots() {
+        let mut table = Table::new(1000, Shoe::seeded(6, 0.75, 1), Rules::default());
+        table.record_events = true;
+        assert_eq!(table.add_bot(BotStrategy::Basic), Ok(0));
+        assert_eq!(table.free_seats(), usize::from(table.rules.max_seats) - 2);
+        // The player busts, but the bot stands on 17, so the dealer still plays out their 16
+        table.shoe.force([
+            Rank::Ten,
+            Rank::Six,
+            Rank::Six,
+            Rank::Ten,
+            Rank::Ten,
+            Rank::Seven,
+            Rank::Ten,
+            Rank::Nine,
+        ]);
+        let mut state = table.progress(BETTING, Some(Input::bet(100))).unwrap();
+        while !matches!(state, GameState::Payout { .. }) {
+            let input = matches!(state, GameState::PlayPlayerTurn { .. })
+                .then_some(Input::action(HandAction::Hit));
+            state = table.progress(state, input).unwrap();
+        }
+        let bet = i64::from(table.rules.min_bet.unwrap_or(1));
+        assert_eq!(
+            table.bots,
+            [Bot {
+                net: bet,
+                strategy: BotStrategy::Basic
+            }]
+        );
+        let events: Vec<_> = table.drain_events().collect();
+        assert!(events.contains(&GameEvent::BotPaid { bot: 0, net: bet }));
+        let played = events.iter().find_map(|event| match event {
+            GameEvent::BotPlayed { actions, value, .. } => Some((actions, value.total)),
+            _ => None,
+        });
+        assert_eq!(played, Some((&vec![HandAction::Stand], 17)));
+        let dealer = events.iter().find_map(|event| match event {
+            GameEvent::RoundOver { status, value } => Some((status, value.total)),
+            _ => None,
+        });
+        assert_eq!(dealer, Some((&Status::Bust, 25)));
+    }
+
+    #[test]
+    fn test_bot_strategies() {
+        use crate::card::Suit;
+        use crate::rules::DealerDrawing;
+
+        let card = |rank| Card {
+            rank,
+            suit: Suit::Spades,
+        };
+
+        assert_eq!(
+            "2".parse(),
+            Ok(BotSeats {
+                count: 2,
+                strategy: BotStrategy::Basic
+            })
+        );
+        assert_eq!(
+            "1:never-bust".parse(),
+            Ok(BotSeats {
+                count: 1,
+                strategy: BotStrategy::NeverBust
+            })
+        );
+        assert!("1:card-counter".parse::<BotSeats>().is_err());
+        assert!("many".parse::<BotSeats>().is_err());
+
+        let mut table = Table::new(1000, Shoe::seeded(6, 0.75, 1), Rules::default());
+        let seated =
+            table.seat_bots(&["1:mimic".parse().unwrap(), "9:never-bust".parse().unwrap()]);
+        assert_eq!(seated, usize::from(table.rules.max_seats) - 1);
+        assert_eq!(table.bots[0].strategy, BotStrategy::Mimic);
+        assert_eq!(table.bots[1].strategy, BotStrategy::NeverBust);
+
+        // Against the dealer's 6, basic strategy stands on 13, the mimic hits it, and never-bust stands
+        let dealer_hand = DealerHand::new(card(Rank::Six), DealerDrawing::S17);
+        let mut hand = PlayerHand::new(card(Rank::Ten), 10);
+        hand += card(Rank::Three);
+        let player_turn = PlayerTurn::from_spots(vec![hand]);
+        let action = |strategy: BotStrategy| strategy.action(&table, &player_turn, &dealer_hand);
+        assert_eq!(action(BotStrategy::Basic), HandAction::Stand);
+        assert_eq!(action(BotStrategy::Mimic), HandAction::Hit);
+        assert_eq!(action(BotStrategy::NeverBust), HandAction::Stand);
+    }
+
+    #[test]
+    fn test_back_bet() {
+        let mut table = Table::new(1000, Shoe::new(4, 0.50), Rules::default());
+        let seat = table.add_backer(150).unwrap();
+        assert_eq!(seat, Seat::Backer(0));
+        table.rules.max_seats = 2;
+        assert_eq!(table.free_seats(), 0);
+        assert_eq!(table.add_backer(150), Err(Error::TableFull));
+        let back_bet = |seat, bet| Some(Input::Bet { seat, bet });
+        assert_eq!(
+            table.progress(BETTING, back_bet(Seat::Backer(1), 100)),
+            Err((BETTING, Error::WrongSeat(Seat::Backer(1))))
+        );
+        assert_eq!(
+            table.progress(BETTING, back_bet(seat, 50)),
+            Err((
+                BETTING,
+                Error::BackBetError {
+                    backer: 0,
+                    error: BetError::TooLow
+                }
+            ))
+        );
+        assert_eq!(table.progress(BETTING, back_bet(seat, 100)), Ok(BETTING));
+        assert_eq!(
+            table.progress(BETTING, back_bet(seat, 100)),
+            Err((
+                BETTING,
+                Error::BackBetError {
+                    backer: 0,
+                    error: BetError::CantAfford
+                }
+            ))
+        );
+        assert_eq!(table.backers[0].chips, 50);
+        assert_eq!(table.chips, 1000);
+
+        // A game ended before the round starts gives the back bet back
+        table.withdraw_bets();
+        assert_eq!(table.backers[0].chips, 150);
+
+        let error = Error::BackBetError {
+            backer: 0,
+            error: BetError::TooLow,
+        };
+        assert_eq!(error.to_string(), "Backer 1: Bet too low");
+        let source = std::error::Error::source(&error).map(ToString::to_string);
+        assert_eq!(source.as_deref(), Some("Bet too low"));
+    }
+
+    #[test]
+    fn test_back_bet_settlement() {
+        use crate::card::Suit;
+        use crate::rules::DealerDrawing;
+
+        let card = |rank| Card {
+            rank,
+            suit: Suit::Spades,
+        };
+        let mut table = Table::new(1000, Shoe::seeded(6, 0.75, 1), Rules::default());
+        let seat = table.add_backer(100).unwrap();
+        let back_bet = Some(Input::Bet { seat, bet: 100 });
+        table.progress(BETTING, back_bet).unwrap();
+        // The hand split off pushes, but the back bet stays on the spot's own hand, which wins
+        let mut original = PlayerHand::new(card(Rank::Eight), 100);
+        original += card(Rank::Eight);
+        let mut split = original.split();
+        original += card(Rank::Ten);
+        original.stand();
+        split += card(Rank::Nine);
+        split.stand();
+        let mut dealer_hand = DealerHand::new(card(Rank::Ten), DealerDrawing::S17);
+        dealer_hand += card(Rank::Seven);
+        table.end_round(vec![original, split], dealer_hand, 0);
+        assert_eq!(table.backers[0].chips, 200);
+    }
+
+    #[test]
+    fn test_tip() {
+        let mut table = Table::new(100, Shoe::new(4, 0.50), Rules::default());
+        assert_eq!(
+            table.tip(101),
+            Err((BETTING, Error::BetError(BetError::CantAfford)))
+        );
+        assert_eq!(table.tip(5), Ok(BETTING));
+        assert_eq!(table.chips, 95);
+        assert_eq!(table.statistics.tips(), 5);
+    }
+
+    #[test]
+    fn test_timeout() {
+        use crate::card::{Card, Rank, Suit};
+        use crate::rules::DealerDrawing;
+
+        let card = |rank| Card {
+            rank,
+            suit: Suit::Spades,
+        };
+        let mut table = Table::new(1000, Shoe::new(4, 0.50), Rules::default());
+        let mut player_hand = PlayerHand::new(card(Rank::Ten), 100);
+        player_hand += card(Rank::Six);
+        let mut dealer_hand = DealerHand::new(card(Rank::Ten), DealerDrawing::S17);
+        dealer_hand += card(Rank::Seven);
+        let state = GameState::PlayPlayerTurn {
+            player_turn: player_hand.into(),
+            dealer_hand,
+            insurance_bet: 0,
+        };
+        assert!(matches!(
+            table.progress(state, Some(Input::Timeout)),
+            Ok(GameState::PlayerStand { .. })
+        ));
+        assert_eq!(
+            table.progress(BETTING, Some(Input::Timeout)),
+            Err((BETTING, Error::WrongInput))
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        use crate::card::{Card, Rank, Suit};
+        use crate::rules::DealerDrawing;
+
+        let card = |rank| Card {
+            rank,
+            suit: Suit::Spades,
+        };
+        let mut table = Table::new(150, Shoe::new(4, 0.50), Rules::default());
+        assert_eq!(table.validate(&BETTING, &Input::bet(100)), Ok(()));
+        assert_eq!(
+            table.validate(&BETTING, &Input::bet(200)),
+            Err(Error::BetError(BetError::CantAfford))
+        );
+        let back_bet = Input::Bet {
+            seat: Seat::Backer(0),
+            bet: 100,
+        };
+        assert_eq!(
+            table.validate(&BETTING, &back_bet),
+            Err(Error::WrongSeat(Seat::Backer(0)))
+        );
+        assert_eq!(
+            table.validate(&BETTING, &Input::Timeout),
+            Err(Error::WrongInput)
+        );
+
+        let mut player_hand = PlayerHand::new(card(Rank::Ten), 100);
+        player_hand += card(Rank::Ten);
+        let mut dealer_hand = DealerHand::new(card(Rank::Ten), DealerDrawing::S17);
+        dealer_hand += card(Rank::Seven);
+        let state = GameState::PlayPlayerTurn {
+            player_turn: player_hand.into(),
+            dealer_hand,
+            insurance_bet: 0,
+        };
+        table.chips = 50;
+        table.add_backer(100).unwrap();
+        let validate = |action| table.validate(&state, &Input::action(action));
+        assert_eq!(validate(HandAction::Hit), Ok(()));
+        assert_eq!(validate(HandAction::Surrender), Ok(()));
+        assert_eq!(
+            validate(HandAction::Split),
+            Err(Error::SplitError {
+                hand: 0,
+                error: SplitError::CantAfford
+            })
+        );
+        assert_eq!(
+            table.validate(&state, &Input::bet(100)),
+            Err(Error::WrongInput)
+        );
+        let backer_action = Input::Action {
+            seat: Seat::Backer(0),
+            action: HandAction::Hit,
+        };
+        assert_eq!(
+            table.validate(&state, &backer_action),
+            Err(Error::WrongSeat(Seat::Backer(0)))
+        );
+
+        // Validating an input agrees with playing it, and leaves the table untouched
+        let error = Error::DoubleError {
+            hand: 0,
+            error: DoubleError::CantAfford,
+        };
+        assert_eq!(validate(HandAction::Double), Err(error));
+        let result = table.progress(state, Some(Input::action(HandAction::Double)));
+        assert!(matches!(result, Err((GameState::PlayPlayerTurn { .. }, e)) if e == error));
+        assert_eq!(table.chips, 50);
+    }
+
+    #[test]
+    fn test_cut_card() {
+        let mut table = Table::new(1000, Shoe::new(1, 0.1), Rules::default());
+        table.record_events = true;
+        for _ in 0..10 {
+            table.draw_card();
+        }
+        let events: Vec<_> = table.drain_events().collect();
+        assert_eq!(events, vec![GameEvent::CutCardReached]);
+    }
+
+    #[test]
+    fn test_settlement() {
+        use crate::card::hand::RoundOutcome;
+        use crate::card::{Card, Rank, Suit};
+        use crate::rules::DealerDrawing;
+
+        let card = |rank| Card {
+            rank,
+            suit: Suit::Spades,
+        };
+        let hand = |first, second| {
+            let mut hand = PlayerHand::new(card(first), 100);
+            hand += card(second);
+            hand.status = Status::Stood;
+            hand
+        };
+        let mut table = Table::new(1000, Shoe::new(4, 0.50), Rules::default());
+        let mut dealer_hand = DealerHand::new(card(Rank::Ten), DealerDrawing::S17);
+        dealer_hand += card(Rank::Nine);
+        let finished_hands = vec![hand(Rank::Ten, Rank::King), hand(Rank::Ten, Rank::Nine)];
+        let GameState::Payout { round } = table.end_round(finished_hands, dealer_hand, 25) else {
+            panic!("the round should be paid out");
+        };
+        assert_eq!((round.total_bet(), round.total_winnings()), (225, 300));
+        assert_eq!(round.shoe, table.shoe.composition());
+        let settlement = round.settlement();
+        assert_eq!(settlement.hands[0].outcome, RoundOutcome::Win);
+        assert_eq!(settlement.hands[0].net(), 100);
+        assert_eq!(settlement.hands[1].outcome, RoundOutcome::Push);
+        let insurance = settlement.insurance.unwrap();
+        assert_eq!(
+            (insurance.outcome, insurance.net()),
+            (RoundOutcome::Lose, -25)
         );
     }
 }
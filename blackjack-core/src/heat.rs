@@ -0,0 +1,104 @@
+//! A model of the attention a card counter draws by spreading their bets, for advantage-play research.
+//! Every raise over the player's last bet adds heat in proportion to how many times the bet doubled,
+//! and heat cools by a fixed share every round dealt. When the heat reaches 1, the player is backed off.
+
+/// How quickly the pit notices the player's bet spread.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeatModel {
+    /// The heat added each time the bet doubles from one round played to the next
+    pub sensitivity: f64,
+    /// The share of the heat that cools off every round
+    pub cooling: f64,
+}
+
+impl Default for HeatModel {
+    fn default() -> Self {
+        Self {
+            sensitivity: 0.2,
+            cooling: 0.05,
+        }
+    }
+}
+
+/// The heat on the player during a session.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heat {
+    pub model: HeatModel,
+    level: f64,
+    last_bet: Option<u32>,
+}
+
+impl Heat {
+    #[must_use]
+    pub const fn new(model: HeatModel) -> Self {
+        Self {
+            model,
+            level: 0.0,
+            last_bet: None,
+        }
+    }
+
+    /// The current heat, where 1 means the player is backed off.
+    #[must_use]
+    pub const fn level(&self) -> f64 {
+        self.level
+    }
+
+    /// Records a round dealt, with the player's bet if they played it.
+    /// Returns whether the player has been backed off.
+    pub fn observe(&mut self, bet: Option<u32>) -> bool {
+        self.level *= 1.0 - self.model.cooling;
+        if let Some(bet) = bet {
+            if let Some(last_bet) = self.last_bet.filter(|&last_bet| last_bet > 0) {
+                let doublings = (f64::from(bet) / f64::from(last_bet)).log2();
+                self.level += self.model.sensitivity * doublings.max(0.0);
+            }
+            self.last_bet = Some(bet);
+        }
+        self.level >= 1.0
+    }
+
+    /// The bet the heat was last measured from, if the player has played this session.
+    #[must_use]
+    pub const fn last_bet(&self) -> Option<u32> {
+        self.last_bet
+    }
+
+    /// Cools off completely, as at the start of a new session.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.model);
+    }
+
+    /// Picks up where a saved session left the heat.
+    pub const fn restore(&mut self, level: f64, last_bet: Option<u32>) {
+        self.level = level;
+        self.last_bet = last_bet;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heat() {
+        let model = HeatModel {
+            sensitivity: 0.3,
+            cooling: 0.0,
+        };
+        let mut flat = Heat::new(model);
+        assert!((0..10).all(|_| !flat.observe(Some(100))));
+        assert!(flat.level().abs() < f64::EPSILON);
+
+        // Two jumps from 1 to 4 units are four doublings, which is enough heat to be backed off
+        let mut spread = Heat::new(model);
+        assert!(!spread.observe(Some(100)));
+        assert!(!spread.observe(Some(400)));
+        assert!((spread.level() - 0.6).abs() < 1e-9);
+        assert!(!spread.observe(Some(100)));
+        assert!(!spread.observe(None));
+        assert!(spread.observe(Some(400)));
+        spread.reset();
+        assert!(spread.level().abs() < f64::EPSILON);
+    }
+}
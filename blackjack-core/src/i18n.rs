@@ -0,0 +1,420 @@
+//! Message catalogs for user-facing text.
+//! Catalogs are plain text files of `key = message` lines, with `{name}` placeholders
+//! filled in when the message is formatted. Translations live in `locales/` and
+//! any key missing from a translation falls back to English.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::card::hand::{RoundOutcome, Value};
+use crate::card::shoe::Shoe;
+use crate::card::{Card, Rank, Suit};
+use crate::currency::Currency;
+use crate::game::{BetError, DoubleError, Error, HandAction, SplitError, SurrenderError};
+use crate::rules::{BlackjackPayout, DealerDrawing, PayoutRounding, PushPolicy, Rules};
+use crate::state::BetResult;
+
+const ENGLISH: &str = include_str!("../locales/en.txt");
+const SPANISH: &str = include_str!("../locales/es.txt");
+
+/// The languages with a built-in catalog.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    English,
+    Spanish,
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "en" | "english" => Ok(Self::English),
+            "es" | "spanish" | "español" => Ok(Self::Spanish),
+            _ => Err(format!("unknown locale: {s}")),
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::English => write!(f, "en"),
+            Self::Spanish => write!(f, "es"),
+        }
+    }
+}
+
+impl Locale {
+    /// The source of the built-in catalog for this locale.
+    const fn source(self) -> &'static str {
+        match self {
+            Self::English => ENGLISH,
+            Self::Spanish => SPANISH,
+        }
+    }
+}
+
+/// How cards are named, e.g. "an Ace of Spades", "[AS]", or "[A♠]".
+/// Letters and symbols tell the suits apart by shape, without relying on color.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SuitStyle {
+    #[default]
+    Names,
+    Letters,
+    Symbols,
+}
+
+impl FromStr for SuitStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "names" => Ok(Self::Names),
+            "letters" => Ok(Self::Letters),
+            "symbols" => Ok(Self::Symbols),
+            _ => Err(format!("unknown suit style: {s}")),
+        }
+    }
+}
+
+impl fmt::Display for SuitStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Names => write!(f, "names"),
+            Self::Letters => write!(f, "letters"),
+            Self::Symbols => write!(f, "symbols"),
+        }
+    }
+}
+
+/// A set of messages in one language, looked up by key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Catalog {
+    messages: HashMap<String, String>,
+    pub suit_style: SuitStyle,
+    /// The currency amounts of chips are shown in, or None to show them as chips
+    pub currency: Option<Currency>,
+}
+
+impl Default for Catalog {
+    fn default() -> Self {
+        Self::new(Locale::English)
+    }
+}
+
+impl Catalog {
+    /// Loads the built-in catalog for the locale, falling back to English for missing keys.
+    #[must_use]
+    pub fn new(locale: Locale) -> Self {
+        let mut catalog = Self {
+            messages: HashMap::new(),
+            suit_style: SuitStyle::default(),
+            currency: None,
+        };
+        catalog.extend(ENGLISH);
+        if locale != Locale::English {
+            catalog.extend(locale.source());
+        }
+        catalog
+    }
+
+    /// Adds or replaces messages from a catalog source.
+    /// Blank lines and lines starting with `#` are ignored.
+    pub fn extend(&mut self, source: &str) {
+        for line in source.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some((key, message)) = line.split_once('=') {
+                self.messages
+                    .insert(key.trim().to_string(), message.trim().to_string());
+            }
+        }
+    }
+
+    /// Returns the message for the key, or the key itself if there is no such message.
+    #[must_use]
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.messages.get(key).map_or(key, String::as_str)
+    }
+
+    /// Returns the message for the key with each `{name}` placeholder replaced by its argument.
+    #[must_use]
+    pub fn format(&self, key: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+        let mut message = self.get(key).to_string();
+        for (name, value) in args {
+            message = message.replace(&format!("{{{name}}}"), &value.to_string());
+        }
+        message
+    }
+
+    /// Returns an amount of chips in the catalog's currency, e.g. "$12.50", or in chips, e.g. "50 chips".
+    #[must_use]
+    pub fn chips(&self, chips: f64) -> String {
+        match &self.currency {
+            Some(currency) => currency.format(chips, 2),
+            None => self.format("chips", &[("amount", &chips)]),
+        }
+    }
+
+    /// Returns an amount of chips in the catalog's currency, e.g. "$12.50", or as a bare number, e.g. "50".
+    #[must_use]
+    pub fn money(&self, chips: f64) -> String {
+        match &self.currency {
+            Some(currency) => currency.format(chips, 2),
+            None => chips.to_string(),
+        }
+    }
+
+    /// Returns the name of the card in the catalog's suit style, e.g. "a Two of Clubs" or "[2♣]".
+    #[must_use]
+    pub fn card(&self, card: &Card) -> String {
+        let suit = match self.suit_style {
+            SuitStyle::Names => return self.card_name(card),
+            SuitStyle::Letters => self
+                .get(match card.suit {
+                    Suit::Clubs => "card.suit-letter.clubs",
+                    Suit::Diamonds => "card.suit-letter.diamonds",
+                    Suit::Hearts => "card.suit-letter.hearts",
+                    Suit::Spades => "card.suit-letter.spades",
+                })
+                .to_string(),
+            SuitStyle::Symbols => card.suit.symbol().to_string(),
+        };
+        self.format(
+            "card.compact",
+            &[("rank", &card.rank.symbol()), ("suit", &suit)],
+        )
+    }
+
+    /// Returns the full name of the card, e.g. "a Two of Clubs".
+    fn card_name(&self, card: &Card) -> String {
+        let rank = match card.rank {
+            Rank::Two => "card.rank.two",
+            Rank::Three => "card.rank.three",
+            Rank::Four => "card.rank.four",
+            Rank::Five => "card.rank.five",
+            Rank::Six => "card.rank.six",
+            Rank::Seven => "card.rank.seven",
+            Rank::Eight => "card.rank.eight",
+            Rank::Nine => "card.rank.nine",
+            Rank::Ten => "card.rank.ten",
+            Rank::Jack => "card.rank.jack",
+            Rank::Queen => "card.rank.queen",
+            Rank::King => "card.rank.king",
+            Rank::Ace => "card.rank.ace",
+        };
+        let suit = match card.suit {
+            Suit::Clubs => "card.suit.clubs",
+            Suit::Diamonds => "card.suit.diamonds",
+            Suit::Hearts => "card.suit.hearts",
+            Suit::Spades => "card.suit.spades",
+        };
+        self.format(
+            "card",
+            &[("rank", &self.get(rank)), ("suit", &self.get(suit))],
+        )
+    }
+
+    /// Returns the value of a hand, e.g. "Soft 20".
+    #[must_use]
+    pub fn value(&self, value: &Value) -> String {
+        let key = if value.soft {
+            "value.soft"
+        } else {
+            "value.hard"
+        };
+        self.format(key, &[("total", &value.total)])
+    }
+
+    /// Returns the prompt for a hand action, e.g. "(h) Hit".
+    #[must_use]
+    pub fn action(&self, action: HandAction) -> &str {
+        self.get(match action {
+            HandAction::Stand => "action.stand",
+            HandAction::Hit => "action.hit",
+            HandAction::Double => "action.double",
+            HandAction::Split => "action.split",
+            HandAction::Surrender => "action.surrender",
+        })
+    }
+
+    /// Returns the result of a bet, e.g. "Win +100", or just "Push" when no chips changed hands.
+    #[must_use]
+    pub fn bet_result(&self, result: &BetResult) -> String {
+        let outcome = self.get(match result.outcome {
+            RoundOutcome::WinBlackjack => "outcome.win-blackjack",
+            RoundOutcome::Win => "outcome.win",
+            RoundOutcome::Push => "outcome.push",
+            RoundOutcome::Lose => "outcome.lose",
+            RoundOutcome::LoseToBlackjack => "outcome.lose-to-blackjack",
+            RoundOutcome::SurrenderEarly => "outcome.surrender-early",
+            RoundOutcome::SurrenderLate => "outcome.surrender-late",
+            RoundOutcome::Bust => "outcome.bust",
+            RoundOutcome::CharlieWin => "outcome.charlie",
+            RoundOutcome::Push22 => "outcome.push-22",
+        });
+        match result.net() {
+            0 => outcome.to_string(),
+            net => format!("{outcome} {net:+}"),
+        }
+    }
+
+    /// Summarizes the rules of a table dealt from the given shoe, one rule per line.
+    #[must_use]
+    pub fn summarize_rules(&self, rules: &Rules, shoe: &Shoe) -> Vec<String> {
+        let percent = format!("{:.0}", shoe.max_penetration * 100.0);
+        let mut summary = vec![
+            self.format("rules.decks", &[("decks", &shoe.decks)]),
+            self.format("rules.penetration", &[("percent", &percent)]),
+            self.get(match rules.blackjack_payout {
+                BlackjackPayout::ThreeToTwo => "rules.payout-3-2",
+                BlackjackPayout::SixToFive => "rules.payout-6-5",
+            })
+            .to_string(),
+            match rules.dealer_drawing {
+                DealerDrawing::S17 => self.get("rules.s17").to_string(),
+                DealerDrawing::H17 => self.get("rules.h17").to_string(),
+                DealerDrawing { hard, soft } => {
+                    self.format("rules.dealer-stands", &[("hard", &hard), ("soft", &soft)])
+                }
+            },
+            self.get(if rules.double_after_split {
+                "rules.das"
+            } else {
+                "rules.ndas"
+            })
+            .to_string(),
+            match rules.max_splits {
+                Some(splits) => self.format("rules.max-splits", &[("splits", &splits)]),
+                None => self.get("rules.unlimited-splits").to_string(),
+            },
+        ];
+        if !rules.split_aces {
+            summary.push(self.get("rules.no-split-aces").to_string());
+        }
+        if rules.early_surrender {
+            summary.push(self.get("rules.early-surrender").to_string());
+        }
+        if rules.late_surrender {
+            summary.push(self.get("rules.late-surrender").to_string());
+        }
+        if !rules.early_surrender && !rules.late_surrender {
+            summary.push(self.get("rules.no-surrender").to_string());
+        }
+        if rules.insurance {
+            summary.push(self.get("rules.insurance").to_string());
+        }
+        summary.push(self.format("rules.seats", &[("seats", &rules.max_seats)]));
+        if rules.dealer_push_22 {
+            summary.push(self.get("rules.push-22").to_string());
+        }
+        match rules.push_policy {
+            PushPolicy::Push => {}
+            PushPolicy::TiesLose => summary.push(self.get("rules.ties-lose").to_string()),
+            PushPolicy::TiesLoseUpTo(total) => {
+                summary.push(self.format("rules.ties-lose-up-to", &[("total", &total)]));
+            }
+        }
+        match rules.payout_rounding {
+            PayoutRounding::Down => {}
+            PayoutRounding::Nearest => summary.push(self.get("rules.round-nearest").to_string()),
+            PayoutRounding::Up => summary.push(self.get("rules.round-up").to_string()),
+        }
+        summary.push(match (rules.min_bet, rules.max_bet) {
+            (Some(min), Some(max)) => self.format("rules.limits", &[("min", &min), ("max", &max)]),
+            (Some(min), None) => self.format("rules.min-bet", &[("min", &min)]),
+            (None, Some(max)) => self.format("rules.max-bet", &[("max", &max)]),
+            (None, None) => self.get("rules.no-limits").to_string(),
+        });
+        summary
+    }
+
+    /// Returns the explanation of why the input was rejected.
+    #[must_use]
+    pub fn error(&self, error: &Error) -> &str {
+        self.get(match error {
+            Error::WrongInput => "error.wrong-input",
+            Error::BetError(error) | Error::BackBetError { error, .. } => match error {
+                BetError::TooLow => "error.bet.too-low",
+                BetError::TooHigh => "error.bet.too-high",
+                BetError::CantAfford => "error.bet.cant-afford",
+                BetError::NoSpotsLeft => "error.bet.no-spots-left",
+            },
+            Error::DoubleError { error, .. } => match error {
+                DoubleError::CantAfford => "error.double.cant-afford",
+                DoubleError::NotTwoCards => "error.double.not-two-cards",
+                DoubleError::DoubleAfterSplitNotAllowed => "error.double.after-split",
+            },
+            Error::SplitError { error, .. } => match error {
+                SplitError::CantAfford => "error.split.cant-afford",
+                SplitError::NotAPair => "error.split.not-a-pair",
+                SplitError::MaxSplitsReached => "error.split.max-splits",
+                SplitError::SplitAcesNotAllowed => "error.split.aces",
+            },
+            Error::SurrenderError { error, .. } => match error {
+                SurrenderError::NotTwoCards => "error.surrender.not-two-cards",
+                SurrenderError::LateSurrenderNotAllowed => "error.surrender.not-allowed",
+            },
+            Error::MidShoeEntry => "error.mid-shoe-entry",
+            Error::TableFull => "error.table-full",
+            Error::WrongSeat(_) => "error.wrong-seat",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog() {
+        let english = Catalog::new(Locale::English);
+        let spanish = Catalog::new(Locale::Spanish);
+        let card = Card {
+            rank: Rank::Ace,
+            suit: Suit::Spades,
+        };
+        assert_eq!(english.card(&card), "an Ace of Spades");
+        assert_eq!(spanish.card(&card), "un As de Picas");
+        let mut letters = Catalog::new(Locale::Spanish);
+        letters.suit_style = SuitStyle::Letters;
+        assert_eq!(letters.card(&card), "[AP]");
+        let mut symbols = Catalog::new(Locale::English);
+        symbols.suit_style = SuitStyle::Symbols;
+        assert_eq!(symbols.card(&card), "[A♠]");
+        assert_eq!(
+            english.error(&Error::BetError(BetError::TooLow)),
+            Error::BetError(BetError::TooLow).to_string()
+        );
+
+        let mut custom = Catalog::new(Locale::Spanish);
+        custom.extend("# partial translation\nnarration.hit = ¡Carta!\n");
+        assert_eq!(custom.get("narration.hit"), "¡Carta!");
+        assert_eq!(custom.get("narration.stand"), "Te plantas.");
+        assert_eq!(custom.get("missing.key"), "missing.key");
+
+        let summary = english.summarize_rules(&Rules::default(), &Shoe::new(6, 0.75));
+        assert_eq!(summary[0], "Decks: 6");
+        assert_eq!(summary[1], "Penetration: 75%");
+        assert!(summary.contains(&"Late surrender".to_string()));
+        assert_eq!(summary.last().unwrap(), "Minimum bet: 100");
+    }
+
+    #[test]
+    fn test_translations_complete() {
+        let english = Catalog::new(Locale::English);
+        let mut spanish = Catalog {
+            messages: HashMap::new(),
+            suit_style: SuitStyle::default(),
+            currency: None,
+        };
+        spanish.extend(SPANISH);
+        for key in english.messages.keys() {
+            assert!(spanish.messages.contains_key(key), "missing {key}");
+        }
+    }
+}
@@ -1,6 +1,34 @@
 pub mod basic_strategy;
+pub mod betting;
+pub mod buffers;
 pub mod card;
+pub mod chart;
+pub mod checkpoint;
+pub mod counting;
+pub mod cues;
+pub mod currency;
+pub mod dealer_error;
+pub mod ev;
+pub mod ev_cache;
+pub mod events;
+pub mod fairness;
 pub mod game;
+pub mod heat;
+pub mod i18n;
+pub mod narration;
+pub mod observer;
+pub mod preferences;
+pub mod profiling;
+pub mod promotion;
+#[cfg(feature = "protobuf")]
+pub mod protobuf;
 pub mod rules;
+pub mod session;
+pub mod simulation;
+pub mod snapshot;
 pub mod state;
 pub mod statistics;
+pub mod strategy;
+pub mod table_spec;
+pub mod timeline;
+pub mod trainer;
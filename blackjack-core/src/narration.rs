@@ -0,0 +1,344 @@
+//! Turns game events into lines of narration for front-ends to display.
+//! The dealer's personality adds flavor to the plain description of what happened.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::card::hand::Status;
+use crate::events::GameEvent;
+use crate::game::HandAction;
+use crate::i18n::{Catalog, Locale};
+
+/// The personality of the dealer doing the talking.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Personality {
+    /// Just the facts
+    #[default]
+    Professional,
+    /// Cheers the player on
+    Friendly,
+    /// Would rather be anywhere else
+    Grumpy,
+}
+
+impl FromStr for Personality {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "professional" => Ok(Self::Professional),
+            "friendly" => Ok(Self::Friendly),
+            "grumpy" => Ok(Self::Grumpy),
+            _ => Err(format!("unknown dealer personality: {s}")),
+        }
+    }
+}
+
+impl fmt::Display for Personality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Professional => write!(f, "professional"),
+            Self::Friendly => write!(f, "friendly"),
+            Self::Grumpy => write!(f, "grumpy"),
+        }
+    }
+}
+
+impl Personality {
+    /// Returns the catalog key of the remark this personality makes about the event, if any.
+    const fn remark(self, event: &GameEvent) -> Option<&'static str> {
+        match (self, event) {
+            (Self::Professional, _) => None,
+            (
+                Self::Friendly,
+                GameEvent::PlayerCard {
+                    status: Status::Blackjack,
+                    ..
+                },
+            ) => Some("remark.friendly.blackjack"),
+            (
+                Self::Friendly,
+                GameEvent::RoundOver {
+                    status: Status::Bust,
+                    ..
+                },
+            ) => Some("remark.friendly.dealer-bust"),
+            (Self::Friendly, GameEvent::Tipped { .. }) => Some("remark.friendly.tip"),
+            (Self::Friendly, GameEvent::Shuffled) => Some("remark.friendly.shuffle"),
+            (
+                Self::Grumpy,
+                GameEvent::PlayerCard {
+                    status: Status::Blackjack,
+                    ..
+                },
+            ) => Some("remark.grumpy.blackjack"),
+            (
+                Self::Grumpy,
+                GameEvent::PlayerCard {
+                    status: Status::Bust,
+                    ..
+                },
+            ) => Some("remark.grumpy.bust"),
+            (
+                Self::Grumpy,
+                GameEvent::PlayerAction {
+                    action: HandAction::Split,
+                    ..
+                },
+            ) => Some("remark.grumpy.split"),
+            (Self::Grumpy, GameEvent::Tipped { .. }) => Some("remark.grumpy.tip"),
+            (Self::Friendly | Self::Grumpy, _) => None,
+        }
+    }
+}
+
+/// Narrates game events in the voice of the dealer.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Narrator {
+    /// The personality of the dealer
+    pub personality: Personality,
+    /// The messages the dealer speaks in
+    pub catalog: Catalog,
+}
+
+impl Narrator {
+    #[must_use]
+    pub fn new(personality: Personality, locale: Locale) -> Self {
+        Self {
+            personality,
+            catalog: Catalog::new(locale),
+        }
+    }
+
+    /// Returns the line of narration for the event, if it is worth mentioning.
+    #[must_use]
+    pub fn narrate(&self, event: &GameEvent) -> Option<String> {
+        let line = self.describe(event)?;
+        Some(match self.personality.remark(event) {
+            Some(remark) => format!("{line} {}", self.catalog.get(remark)),
+            None => line,
+        })
+    }
+
+    /// Returns the plain description of the event, if it is worth mentioning.
+    fn describe(&self, event: &GameEvent) -> Option<String> {
+        let catalog = &self.catalog;
+        let chips = |chips: &u32| catalog.chips(f64::from(*chips));
+        let line = match event {
+            GameEvent::BetPlaced { bet } => {
+                catalog.format("narration.bet-placed", &[("bet", &chips(bet))])
+            }
+            GameEvent::BackBetPlaced { backer, bet } => catalog.format(
+                "narration.back-bet-placed",
+                &[("backer", backer), ("bet", &chips(bet))],
+            ),
+            GameEvent::SpotBetPlaced { spot, bet } => catalog.format(
+                "narration.spot-bet-placed",
+                &[("spot", spot), ("bet", &chips(bet))],
+            ),
+            GameEvent::Tipped { tip } => {
+                catalog.format("narration.tipped", &[("tip", &chips(tip))])
+            }
+            GameEvent::PlayerCard {
+                hand,
+                card,
+                value,
+                status,
+                ..
+            } => {
+                let card = catalog.card(card);
+                let value = catalog.value(value);
+                let mut line = if *hand == 0 {
+                    catalog.format(
+                        "narration.player-card",
+                        &[("card", &card), ("value", &value)],
+                    )
+                } else {
+                    catalog.format(
+                        "narration.split-hand-card",
+                        &[("hand", &(hand + 1)), ("card", &card), ("value", &value)],
+                    )
+                };
+                let outcome = match status {
+                    Status::Blackjack => Some("narration.player-blackjack"),
+                    Status::Bust => Some("narration.player-bust"),
+                    _ => None,
+                };
+                if let Some(outcome) = outcome {
+                    line.push(' ');
+                    line.push_str(catalog.get(outcome));
+                }
+                line
+            }
+            GameEvent::DealerCard { card, value } => catalog.format(
+                "narration.dealer-card",
+                &[
+                    ("card", &catalog.card(card)),
+                    ("value", &catalog.value(value)),
+                ],
+            ),
+            GameEvent::HoleCardDealt => catalog.get("narration.hole-card-dealt").to_string(),
+            GameEvent::EarlySurrenderOffered => {
+                catalog.get("narration.early-surrender-offered").to_string()
+            }
+            GameEvent::InsuranceOffered => catalog.get("narration.insurance-offered").to_string(),
+            GameEvent::InsurancePlaced { bet: 0 } => {
+                catalog.get("narration.insurance-declined").to_string()
+            }
+            GameEvent::InsurancePlaced { bet } => {
+                catalog.format("narration.insurance-placed", &[("bet", &chips(bet))])
+            }
+            GameEvent::HoleCardFlashed { card } => catalog.format(
+                "narration.hole-card-flashed",
+                &[("card", &catalog.card(card))],
+            ),
+            GameEvent::DealerPeeked { blackjack: true } => {
+                catalog.get("narration.dealer-blackjack").to_string()
+            }
+            GameEvent::DealerPeeked { blackjack: false } => {
+                catalog.get("narration.dealer-no-blackjack").to_string()
+            }
+            GameEvent::PlayerAction { action, .. } => catalog
+                .get(match action {
+                    HandAction::Stand => "narration.stand",
+                    HandAction::Hit => "narration.hit",
+                    HandAction::Double => "narration.double",
+                    HandAction::Split => "narration.split",
+                    HandAction::Surrender => "narration.surrender",
+                })
+                .to_string(),
+            GameEvent::BotPlayed {
+                bot,
+                cards,
+                actions,
+                value,
+                status,
+            } => {
+                let bot = bot + 1;
+                let cards: Vec<_> = cards.iter().map(|card| catalog.card(card)).collect();
+                let cards = cards.join(", ");
+                let actions: Vec<_> = actions
+                    .iter()
+                    .map(|action| {
+                        catalog.get(match action {
+                            HandAction::Stand => "narration.bot-stand",
+                            HandAction::Hit => "narration.bot-hit",
+                            HandAction::Double => "narration.bot-double",
+                            HandAction::Split => "narration.bot-split",
+                            HandAction::Surrender => "narration.bot-surrender",
+                        })
+                    })
+                    .collect();
+                let key = match status {
+                    Status::Blackjack => "narration.bot-blackjack",
+                    Status::Bust => "narration.bot-busts",
+                    _ => "narration.bot-played",
+                };
+                catalog.format(
+                    key,
+                    &[
+                        ("bot", &bot),
+                        ("actions", &actions.join(", ")),
+                        ("cards", &cards),
+                        ("value", &catalog.value(value)),
+                    ],
+                )
+            }
+            GameEvent::BotPaid { bot, net } => {
+                let amount = catalog.chips(net.unsigned_abs() as f64);
+                let key = match net.signum() {
+                    1 => "narration.bot-won",
+                    0 => "narration.bot-pushed",
+                    _ => "narration.bot-lost",
+                };
+                catalog.format(key, &[("bot", &(bot + 1)), ("amount", &amount)])
+            }
+            GameEvent::HoleCardRevealed { card, value } => catalog.format(
+                "narration.hole-card-revealed",
+                &[
+                    ("card", &catalog.card(card)),
+                    ("value", &catalog.value(value)),
+                ],
+            ),
+            GameEvent::RoundOver { status, value } => match status {
+                Status::Bust => catalog.get("narration.dealer-busts").to_string(),
+                Status::Stood => {
+                    catalog.format("narration.dealer-stands", &[("total", &value.total)])
+                }
+                _ => return None,
+            },
+            GameEvent::Mispaid { hand, amount } => catalog.format(
+                if *amount > 0 {
+                    "narration.overpaid"
+                } else {
+                    "narration.underpaid"
+                },
+                &[
+                    ("hand", &(hand + 1)),
+                    ("amount", &catalog.chips(amount.unsigned_abs() as f64)),
+                ],
+            ),
+            GameEvent::Payout {
+                total_bet,
+                total_winnings,
+            } => payout(catalog, *total_bet, *total_winnings),
+            GameEvent::CutCardReached => catalog.get("narration.cut-card").to_string(),
+            GameEvent::Shuffled => catalog.get("narration.shuffled").to_string(),
+            GameEvent::GameOver => catalog.get("narration.game-over").to_string(),
+        };
+        Some(line)
+    }
+}
+
+/// Describes the result of a round, e.g. "You win 200 chips (+100)!".
+#[must_use]
+pub fn payout(catalog: &Catalog, total_bet: u32, total_winnings: u32) -> String {
+    let difference = i64::from(total_winnings) - i64::from(total_bet);
+    match difference {
+        1.. => catalog.format(
+            "narration.payout-win",
+            &[
+                ("winnings", &catalog.chips(f64::from(total_winnings))),
+                ("difference", &catalog.money(difference as f64)),
+            ],
+        ),
+        0 => catalog.format(
+            "narration.payout-push",
+            &[("winnings", &catalog.chips(f64::from(total_winnings)))],
+        ),
+        _ if total_winnings > 0 => catalog.format(
+            "narration.payout-partial",
+            &[
+                ("winnings", &catalog.money(f64::from(total_winnings))),
+                ("bet", &catalog.chips(f64::from(total_bet))),
+            ],
+        ),
+        _ => catalog.format(
+            "narration.payout-loss",
+            &[("loss", &catalog.chips(difference.abs() as f64))],
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_narrate() {
+        let tip = GameEvent::Tipped { tip: 5 };
+        assert_eq!(
+            Narrator::new(Personality::Professional, Locale::English).narrate(&tip),
+            Some("You tip the dealer 5 chips.".to_string())
+        );
+        assert_eq!(
+            Narrator::new(Personality::Grumpy, Locale::English).narrate(&tip),
+            Some("You tip the dealer 5 chips. Is that all?".to_string())
+        );
+        assert_eq!(
+            Narrator::new(Personality::Grumpy, Locale::Spanish).narrate(&tip),
+            Some("Das una propina de 5 fichas al crupier. ¿Eso es todo?".to_string())
+        );
+        assert_eq!("Friendly".parse(), Ok(Personality::Friendly));
+    }
+}
@@ -0,0 +1,81 @@
+//! Observers of finished rounds.
+//! Every round the table settles is handed to its statistics and then to each observer plugged
+//! into the table, so custom collectors, such as count trackers or CSV writers,
+//! see exactly the rounds the statistics do.
+
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::state::FinishedRound;
+use crate::statistics::Statistics;
+
+/// Collects something from each round the table settles.
+/// Observers must be `Send` so simulated tables can run on their own threads.
+pub trait RoundObserver: fmt::Debug + Send {
+    fn on_round(&mut self, round: &FinishedRound);
+}
+
+impl RoundObserver for Statistics {
+    fn on_round(&mut self, round: &FinishedRound) {
+        if round.insurance_bet > 0 {
+            self.record_insurance(round.insurance_bet, round.insurance_winnings);
+        }
+        self.record_spots(round.spots);
+        self.update(&round.hands, &round.dealer_hand);
+        if let Some(timeline) = &round.timeline {
+            self.record_timeline(timeline);
+        }
+    }
+}
+
+/// A shared observer, so its owner can read what it collected while the table holds on to it.
+impl<T: RoundObserver> RoundObserver for Arc<Mutex<T>> {
+    fn on_round(&mut self, round: &FinishedRound) {
+        self.lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .on_round(round);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::basic_strategy;
+    use crate::card::shoe::Shoe;
+    use crate::game::{Input, Table};
+    use crate::rules::Rules;
+    use crate::state::GameState;
+
+    /// Counts the rounds and the hands in them.
+    #[derive(Debug, Default)]
+    struct Counter {
+        rounds: usize,
+        hands: usize,
+    }
+
+    impl RoundObserver for Counter {
+        fn on_round(&mut self, round: &FinishedRound) {
+            self.rounds += 1;
+            self.hands += round.hands.len();
+        }
+    }
+
+    #[test]
+    fn test_observer() {
+        let counter = Arc::new(Mutex::new(Counter::default()));
+        let mut table = Table::new(u32::MAX / 2, Shoe::seeded(6, 0.75, 3), Rules::default());
+        table.observers.push(Box::new(Arc::clone(&counter)));
+        let mut state = GameState::default();
+        for _ in 0..500 {
+            let input = match state {
+                GameState::Betting { .. } => Some(Input::bet(100)),
+                _ => basic_strategy::input(&table, &state),
+            };
+            state = table.progress(state, input).unwrap();
+        }
+        let counter = counter.lock().unwrap();
+        assert!(counter.rounds > 0);
+        assert_eq!(counter.rounds, table.statistics.turns_played());
+        assert_eq!(counter.hands, table.statistics.hands_played());
+    }
+}
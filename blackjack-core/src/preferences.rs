@@ -0,0 +1,157 @@
+//! Player preferences that automate routine decisions during manual play,
+//! shared by every front-end the player plays from.
+//! They only act on the player's behalf; the table rules are unaffected.
+//!
+//! A preferences file holds one `preference = true` or `preference = false` per line,
+//! e.g. `auto-stand = true`. Blank lines and lines starting with `#` are ignored,
+//! and any preference left out is off.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::card::hand::PlayerTurn;
+use crate::game::{HandAction, Input, Table};
+use crate::state::GameState;
+
+/// Player preferences that automate routine decisions during manual play.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Preferences {
+    /// Automatically stand on hard 20 or more
+    pub auto_stand: bool,
+    /// Automatically decline insurance
+    pub auto_decline_insurance: bool,
+    /// Automatically repeat the last bet
+    pub auto_rebet: bool,
+    /// Never offer to surrender
+    pub never_surrender: bool,
+    /// Ask for confirmation before surrendering
+    pub confirm_surrender: bool,
+    /// Ask for confirmation before doubling down
+    pub confirm_double: bool,
+}
+
+impl Preferences {
+    /// Reads the preferences from a preferences file.
+    /// # Errors
+    /// Returns an error, naming the file, if it can't be read or isn't a preferences file.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|preferences| preferences.parse())
+            .map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    /// The name of each preference in a preferences file, with a mutable reference to it.
+    fn named(&mut self) -> [(&'static str, &mut bool); 6] {
+        [
+            ("auto-stand", &mut self.auto_stand),
+            ("auto-decline-insurance", &mut self.auto_decline_insurance),
+            ("auto-rebet", &mut self.auto_rebet),
+            ("never-surrender", &mut self.never_surrender),
+            ("confirm-surrender", &mut self.confirm_surrender),
+            ("confirm-double", &mut self.confirm_double),
+        ]
+    }
+
+    /// Returns whether the player must confirm the action before it is taken.
+    #[must_use]
+    pub const fn confirm_required(&self, action: HandAction) -> bool {
+        match action {
+            HandAction::Surrender => self.confirm_surrender,
+            HandAction::Double => self.confirm_double,
+            HandAction::Stand | HandAction::Hit | HandAction::Split => false,
+        }
+    }
+
+    /// Returns the actions to offer the player on their current hand:
+    /// those the table allows, less surrendering if the player never surrenders.
+    #[must_use]
+    pub fn offered_actions(&self, table: &Table, player_turn: &PlayerTurn) -> Vec<HandAction> {
+        let mut actions = table.allowed_actions(player_turn);
+        if self.never_surrender {
+            actions.retain(|&action| action != HandAction::Surrender);
+        }
+        actions
+    }
+
+    /// Returns the input to make automatically in the given state, if any.
+    #[must_use]
+    pub fn auto_input(&self, state: &GameState) -> Option<Input> {
+        match state {
+            GameState::Betting { last_bet } if self.auto_rebet => last_bet.map(Input::bet),
+            GameState::OfferInsurance { .. } if self.auto_decline_insurance => Some(Input::bet(0)),
+            GameState::OfferEarlySurrender { .. } if self.never_surrender => {
+                Some(Input::choice(false))
+            }
+            GameState::PlayPlayerTurn { player_turn, .. } if self.auto_stand => {
+                let value = &player_turn.current_hand().value;
+                (!value.soft && value.total >= 20).then_some(Input::action(HandAction::Stand))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Preferences {
+    /// Writes the preferences as a preferences file, one line for each.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut preferences = *self;
+        for (name, value) in preferences.named() {
+            writeln!(f, "{name} = {value}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Preferences {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut preferences = Self::default();
+        for line in s.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("expected preference = true or false: {line}"))?;
+            let (key, value) = (key.trim(), value.trim());
+            let (_, preference) = preferences
+                .named()
+                .into_iter()
+                .find(|(name, _)| *name == key)
+                .ok_or_else(|| format!("unknown preference: {key}"))?;
+            *preference = value
+                .parse()
+                .map_err(|_| format!("invalid {key}: {value}"))?;
+        }
+        Ok(preferences)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let preferences: Preferences =
+            "# Faster play\nauto-stand = true\n\nconfirm-double = true\nauto-rebet = false\n"
+                .parse()
+                .unwrap();
+        assert_eq!(
+            preferences,
+            Preferences {
+                auto_stand: true,
+                confirm_double: true,
+                ..Preferences::default()
+            }
+        );
+        assert_eq!(preferences.to_string().parse(), Ok(preferences));
+        assert!("auto-hit = true".parse::<Preferences>().is_err());
+        assert!("auto-stand = maybe".parse::<Preferences>().is_err());
+        assert!("auto-stand".parse::<Preferences>().is_err());
+    }
+}
@@ -0,0 +1,40 @@
+//! Allocation counting for the benchmarks and the profiler overlay.
+//! A binary counts its allocations by making `CountingAllocator` its global allocator,
+//! which is only built with the profiling feature.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The allocations counted so far.
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// The allocations made since the program started,
+/// or none if they aren't counted because `CountingAllocator` isn't the global allocator.
+pub fn allocations() -> Option<usize> {
+    Some(ALLOCATIONS.load(Ordering::Relaxed)).filter(|&count| count > 0)
+}
+
+/// Counts every allocation the program makes, passing them on to the system allocator.
+#[cfg(feature = "profiling")]
+#[derive(Debug, Clone, Copy)]
+pub struct CountingAllocator;
+
+// SAFETY: every call is passed on unchanged to the system allocator
+#[cfg(feature = "profiling")]
+unsafe impl std::alloc::GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: std::alloc::Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        // SAFETY: the caller upholds `GlobalAlloc::alloc`'s contract, which is the same as `System`'s
+        unsafe { std::alloc::System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: std::alloc::Layout) {
+        // SAFETY: the pointer was allocated by `System` with this layout
+        unsafe { std::alloc::System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: std::alloc::Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        // SAFETY: the pointer was allocated by `System` with this layout
+        unsafe { std::alloc::System.realloc(ptr, layout, new_size) }
+    }
+}
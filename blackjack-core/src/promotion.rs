@@ -0,0 +1,144 @@
+//! Promotional coupons, such as the match-play and blackjack bonus coupons casinos hand out in their fun books.
+//! A coupon is redeemed with a bet, and settled along with the first hand of the round,
+//! so its expected value can be measured by playing with it.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::card::hand::{DealerHand, PlayerHand, RoundOutcome};
+use crate::rules::Rules;
+
+/// A coupon redeemed with a bet, which changes what the round's first hand pays once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Coupon {
+    /// Matches the bet up to the face value. The match wins even money if the hand wins, even with a blackjack,
+    /// and is lost otherwise. On a push, the coupon stays on the table for the next round.
+    MatchPlay(u32),
+    /// Pays a blackjack 2:1 instead of the table's payout, on the bet up to the face value.
+    /// The coupon is used up whether or not the hand is a blackjack.
+    BlackjackBonus(u32),
+}
+
+/// How a coupon turned out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CouponResult {
+    /// The chips the coupon paid on top of the hand's own winnings
+    pub winnings: u32,
+    /// Whether the coupon is still good for another round
+    pub kept: bool,
+}
+
+impl Coupon {
+    /// Settles the coupon with the hand it was redeemed on, once the hand itself is settled.
+    #[must_use]
+    pub fn settle(
+        self,
+        hand: &PlayerHand,
+        dealer_hand: &DealerHand,
+        rules: &Rules,
+    ) -> CouponResult {
+        match (self, hand.outcome) {
+            (Self::MatchPlay(face), Some(RoundOutcome::Win | RoundOutcome::WinBlackjack)) => {
+                CouponResult {
+                    winnings: face.min(hand.bet),
+                    kept: false,
+                }
+            }
+            (Self::MatchPlay(_), Some(RoundOutcome::Push | RoundOutcome::Push22)) => CouponResult {
+                winnings: 0,
+                kept: true,
+            },
+            (Self::BlackjackBonus(face), Some(RoundOutcome::WinBlackjack)) => {
+                let covered = face.min(hand.bet);
+                let paid = hand.calculate_winnings_on(covered, dealer_hand, rules);
+                CouponResult {
+                    winnings: (covered * 3).saturating_sub(paid),
+                    kept: false,
+                }
+            }
+            _ => CouponResult {
+                winnings: 0,
+                kept: false,
+            },
+        }
+    }
+}
+
+impl fmt::Display for Coupon {
+    /// Coupons are displayed as their kind and face value, e.g. "match:25" or "blackjack:25"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MatchPlay(face) => write!(f, "match:{face}"),
+            Self::BlackjackBonus(face) => write!(f, "blackjack:{face}"),
+        }
+    }
+}
+
+impl FromStr for Coupon {
+    type Err = String;
+
+    /// Parses a coupon's kind and face value, e.g. "match:25" for a match-play coupon
+    /// or "blackjack:25" for blackjack paying 2:1.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, face) = s
+            .split_once(':')
+            .ok_or_else(|| format!("expected kind:value: {s}"))?;
+        let face: u32 = face
+            .trim()
+            .parse()
+            .ok()
+            .filter(|face| *face > 0)
+            .ok_or_else(|| format!("invalid coupon value: {face}"))?;
+        match kind.trim().to_ascii_lowercase().as_str() {
+            "match" => Ok(Self::MatchPlay(face)),
+            "blackjack" => Ok(Self::BlackjackBonus(face)),
+            _ => Err(format!("unknown coupon: {kind}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::hand::Status;
+    use crate::card::{Card, Rank, Suit};
+    use crate::rules::DealerDrawing;
+
+    #[test]
+    fn test_coupons() {
+        assert_eq!("match:25".parse(), Ok(Coupon::MatchPlay(25)));
+        assert_eq!("Blackjack:10".parse(), Ok(Coupon::BlackjackBonus(10)));
+        assert!("free:5".parse::<Coupon>().is_err());
+        assert!("match:0".parse::<Coupon>().is_err());
+        assert_eq!(Coupon::BlackjackBonus(10).to_string(), "blackjack:10");
+
+        let card = |rank| Card {
+            rank,
+            suit: Suit::Hearts,
+        };
+        let rules = Rules::default();
+        let hand = |first, second| {
+            let mut hand = PlayerHand::new(card(first), 20);
+            hand += card(second);
+            hand
+        };
+        let mut dealer_hand = DealerHand::new(card(Rank::Ten), DealerDrawing::S17);
+        dealer_hand += card(Rank::Eight);
+
+        let mut blackjack = hand(Rank::Ace, Rank::King);
+        blackjack.settle(&dealer_hand, &rules);
+        // 3:2 pays 30 on the 20 covered, where 2:1 pays 40
+        let bonus = Coupon::BlackjackBonus(25).settle(&blackjack, &dealer_hand, &rules);
+        assert_eq!((bonus.winnings, bonus.kept), (10, false));
+        let matched = Coupon::MatchPlay(25).settle(&blackjack, &dealer_hand, &rules);
+        assert_eq!((matched.winnings, matched.kept), (20, false));
+
+        let mut push = hand(Rank::Ten, Rank::Eight);
+        push.status = Status::Stood;
+        push.settle(&dealer_hand, &rules);
+        let matched = Coupon::MatchPlay(10).settle(&push, &dealer_hand, &rules);
+        assert_eq!((matched.winnings, matched.kept), (0, true));
+        let bonus = Coupon::BlackjackBonus(10).settle(&push, &dealer_hand, &rules);
+        assert_eq!((bonus.winnings, bonus.kept), (0, false));
+    }
+}
@@ -0,0 +1,264 @@
+//! A protobuf encoding of round summaries and game events, for streaming simulations to
+//! consumers in other languages faster than JSON allows. The schema is `proto/blackjack.proto`.
+//! Messages are encoded by hand, so the feature adds no dependencies; nothing is decoded.
+
+use std::io::{self, Write};
+
+use crate::card::hand::{Status, Value};
+use crate::card::{Card, Rank, Suit, Visibility};
+use crate::events::GameEvent;
+use crate::game::HandAction;
+use crate::simulation::RoundSummary;
+
+/// A message being encoded. Fields holding their default value are left out, as in proto3.
+#[derive(Debug, Default)]
+struct Message(Vec<u8>);
+
+impl Message {
+    fn key(&mut self, field: u32, wire_type: u8) {
+        put_varint(&mut self.0, u64::from(field << 3 | u32::from(wire_type)));
+    }
+
+    fn varint(mut self, field: u32, value: u64) -> Self {
+        if value != 0 {
+            self.key(field, 0);
+            put_varint(&mut self.0, value);
+        }
+        self
+    }
+
+    fn sint(self, field: u32, value: i64) -> Self {
+        self.varint(field, ((value << 1) ^ (value >> 63)) as u64)
+    }
+
+    fn float(mut self, field: u32, value: f32) -> Self {
+        if value != 0.0 {
+            self.key(field, 5);
+            self.0.extend(value.to_le_bytes());
+        }
+        self
+    }
+
+    /// Adds an embedded message, which is always written so that a oneof knows which case is set.
+    fn message(mut self, field: u32, message: Self) -> Self {
+        self.key(field, 2);
+        put_varint(&mut self.0, message.0.len() as u64);
+        self.0.extend(message.0);
+        self
+    }
+}
+
+fn put_varint(buffer: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buffer.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    buffer.push(value as u8);
+}
+
+fn card(card: &Card) -> Message {
+    let rank = Rank::ALL.iter().position(|rank| *rank == card.rank);
+    let suit = match card.suit {
+        Suit::Clubs => 0,
+        Suit::Diamonds => 1,
+        Suit::Hearts => 2,
+        Suit::Spades => 3,
+    };
+    Message::default()
+        .varint(1, rank.unwrap_or_default() as u64)
+        .varint(2, suit)
+}
+
+fn value(value: &Value) -> Message {
+    Message::default()
+        .varint(1, u64::from(value.total))
+        .varint(2, u64::from(value.soft))
+}
+
+const fn status(status: &Status) -> u64 {
+    match status {
+        Status::InPlay => 0,
+        Status::Stood => 1,
+        Status::Bust => 2,
+        Status::Blackjack => 3,
+        Status::Surrendered => 4,
+    }
+}
+
+const fn action(action: HandAction) -> u64 {
+    match action {
+        HandAction::Stand => 0,
+        HandAction::Hit => 1,
+        HandAction::Double => 2,
+        HandAction::Split => 3,
+        HandAction::Surrender => 4,
+    }
+}
+
+/// Encodes a round summary as a `RoundSummary` message.
+#[must_use]
+pub fn encode_round(round: &RoundSummary) -> Vec<u8> {
+    Message::default()
+        .varint(1, round.round as u64)
+        .float(2, round.true_count)
+        .varint(3, u64::from(round.played))
+        .varint(4, u64::from(round.bet))
+        .varint(5, round.hands as u64)
+        .varint(6, round.blackjacks as u64)
+        .sint(7, round.net)
+        .varint(8, u64::from(round.chips))
+        .varint(9, u64::from(round.tips))
+        .0
+}
+
+/// Encodes an event as a `GameEvent` message.
+#[must_use]
+pub fn encode_event(event: &GameEvent) -> Vec<u8> {
+    let empty = Message::default;
+    let (field, message) = match event {
+        GameEvent::BetPlaced { bet } => (1, empty().varint(1, u64::from(*bet))),
+        GameEvent::BackBetPlaced { backer, bet } => (
+            2,
+            empty().varint(1, *backer as u64).varint(2, u64::from(*bet)),
+        ),
+        GameEvent::Tipped { tip } => (3, empty().varint(1, u64::from(*tip))),
+        GameEvent::PlayerCard {
+            hand,
+            card: dealt,
+            value: hand_value,
+            status: hand_status,
+            visibility,
+        } => (
+            4,
+            empty()
+                .varint(1, *hand as u64)
+                .message(2, card(dealt))
+                .message(3, value(hand_value))
+                .varint(4, status(hand_status))
+                .varint(5, u64::from(*visibility == Visibility::FaceDown)),
+        ),
+        GameEvent::DealerCard {
+            card: dealt,
+            value: hand_value,
+        } => (
+            5,
+            empty()
+                .message(1, card(dealt))
+                .message(2, value(hand_value)),
+        ),
+        GameEvent::HoleCardDealt => (6, empty()),
+        GameEvent::EarlySurrenderOffered => (7, empty()),
+        GameEvent::InsuranceOffered => (8, empty()),
+        GameEvent::InsurancePlaced { bet } => (9, empty().varint(1, u64::from(*bet))),
+        GameEvent::DealerPeeked { blackjack } => (10, empty().varint(1, u64::from(*blackjack))),
+        GameEvent::PlayerAction {
+            hand,
+            action: taken,
+        } => (
+            11,
+            empty().varint(1, *hand as u64).varint(2, action(*taken)),
+        ),
+        GameEvent::HoleCardRevealed {
+            card: revealed,
+            value: hand_value,
+        } => (
+            12,
+            empty()
+                .message(1, card(revealed))
+                .message(2, value(hand_value)),
+        ),
+        GameEvent::RoundOver {
+            status: hand_status,
+            value: hand_value,
+        } => (
+            13,
+            empty()
+                .varint(1, status(hand_status))
+                .message(2, value(hand_value)),
+        ),
+        GameEvent::Payout {
+            total_bet,
+            total_winnings,
+        } => (
+            14,
+            empty()
+                .varint(1, u64::from(*total_bet))
+                .varint(2, u64::from(*total_winnings)),
+        ),
+        GameEvent::CutCardReached => (15, empty()),
+        GameEvent::Shuffled => (16, empty()),
+        GameEvent::GameOver => (17, empty()),
+        GameEvent::SpotBetPlaced { spot, bet } => (
+            18,
+            empty().varint(1, *spot as u64).varint(2, u64::from(*bet)),
+        ),
+        GameEvent::HoleCardFlashed { card: flashed } => (19, empty().message(1, card(flashed))),
+        GameEvent::Mispaid { hand, amount } => {
+            (20, empty().varint(1, *hand as u64).sint(2, *amount))
+        }
+        GameEvent::BotPlayed {
+            bot,
+            cards,
+            actions,
+            value: hand_value,
+            status: hand_status,
+        } => {
+            let mut message = empty().varint(1, *bot as u64);
+            for dealt in cards {
+                message = message.message(2, card(dealt));
+            }
+            for taken in actions {
+                message = message.varint(3, action(*taken));
+            }
+            (
+                21,
+                message
+                    .message(4, value(hand_value))
+                    .varint(5, status(hand_status)),
+            )
+        }
+        GameEvent::BotPaid { bot, net } => (22, empty().varint(1, *bot as u64).sint(2, *net)),
+    };
+    empty().message(field, message).0
+}
+
+/// Writes an encoded message to a stream, prefixed with its length as a varint.
+pub fn write_delimited(writer: &mut impl Write, message: &[u8]) -> io::Result<()> {
+    let mut length = Vec::new();
+    put_varint(&mut length, message.len() as u64);
+    writer.write_all(&length)?;
+    writer.write_all(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encoding() {
+        let round = RoundSummary {
+            round: 1,
+            true_count: 0.0,
+            played: true,
+            bet: 100,
+            hands: 1,
+            blackjacks: 0,
+            net: -100,
+            tips: 0,
+            chips: 900,
+        };
+        assert_eq!(
+            encode_round(&round),
+            [0x08, 1, 0x18, 1, 0x20, 100, 0x28, 1, 0x38, 0xc7, 0x01, 0x40, 0x84, 0x07]
+        );
+        assert_eq!(
+            encode_event(&GameEvent::BetPlaced { bet: 100 }),
+            [0x0a, 2, 0x08, 100]
+        );
+        assert_eq!(encode_event(&GameEvent::HoleCardDealt), [0x32, 0]);
+
+        let mut stream = Vec::new();
+        write_delimited(&mut stream, &[0x08, 1]).unwrap();
+        assert_eq!(stream, [2, 0x08, 1]);
+    }
+}
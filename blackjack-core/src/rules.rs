@@ -1,10 +1,92 @@
 //! Blackjack table rules.
 
-/// The action the dealer takes on a soft 17.
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::card::hand::Value;
+
+/// When the dealer stops drawing: the lowest hard and soft totals they stand on.
+/// Most tables either stand on all 17s or hit soft 17, but promotions and house variants
+/// move either threshold, e.g. a dealer who hits soft 18, or stands on hard 16.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum DealerSoft17Action {
-    Stand,
-    Hit,
+pub struct DealerDrawing {
+    /// The lowest hard total the dealer stands on
+    pub hard: u8,
+    /// The lowest soft total the dealer stands on
+    pub soft: u8,
+}
+
+impl DealerDrawing {
+    /// The dealer stands on all 17s.
+    pub const S17: Self = Self { hard: 17, soft: 17 };
+    /// The dealer hits soft 17.
+    pub const H17: Self = Self { hard: 17, soft: 18 };
+
+    /// Returns whether the dealer stands on the given value.
+    #[must_use]
+    pub const fn stands_on(self, value: Value) -> bool {
+        value.total >= if value.soft { self.soft } else { self.hard }
+    }
+
+    /// Returns whether the dealer hits soft 17, as basic strategy charts are drawn up for.
+    #[must_use]
+    pub const fn hits_soft_17(self) -> bool {
+        self.soft > 17
+    }
+}
+
+impl FromStr for DealerDrawing {
+    type Err = String;
+
+    /// Reads the shorthand written by `Display`: "S17" stands on all 17s, "H17" hits soft 17,
+    /// "S16" stands on all 16s, "H18" hits soft 18, and "S16/18" stands on hard 16 and soft 18.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_ascii_uppercase();
+        let total = |total: &str| {
+            total
+                .parse::<u8>()
+                .ok()
+                .filter(|total| (12..=21).contains(total))
+                .ok_or_else(|| format!("unknown dealer drawing rule: {s}"))
+        };
+        // A dealer can't hit soft 21
+        if let Some(rest) = s.strip_prefix('H').filter(|rest| *rest != "21") {
+            return Ok(Self {
+                hard: 17,
+                soft: total(rest)? + 1,
+            });
+        }
+        let rest = s
+            .strip_prefix('S')
+            .ok_or_else(|| format!("unknown dealer drawing rule: {s}"))?;
+        match rest.split_once('/') {
+            Some((hard, soft)) => Ok(Self {
+                hard: total(hard)?,
+                soft: total(soft)?,
+            }),
+            None => {
+                let total = total(rest)?;
+                Ok(Self {
+                    hard: total,
+                    soft: total,
+                })
+            }
+        }
+    }
+}
+
+impl fmt::Display for DealerDrawing {
+    /// e.g. "S17", "H17", or "S16/18"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.hard == self.soft {
+            write!(f, "S{}", self.hard)
+        } else if self.hard == 17 && self.soft > 17 {
+            write!(f, "H{}", self.soft - 1)
+        } else {
+            write!(f, "S{}/{}", self.hard, self.soft)
+        }
+    }
 }
 
 /// The payout for a blackjack, either 3:2 or 6:5.
@@ -14,6 +96,161 @@ pub enum BlackjackPayout {
     SixToFive,
 }
 
+/// A card dealt at the start of a round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Deal {
+    /// A card to the player
+    Player,
+    /// The dealer's face-up card
+    DealerUpcard,
+    /// The dealer's face-down hole card
+    DealerHoleCard,
+}
+
+/// The order the opening cards are dealt in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DealOrder {
+    /// The player, the dealer's upcard, the player again, then the hole card
+    Alternating,
+    /// Both of the player's cards, then the dealer's upcard and hole card
+    PlayerFirst,
+}
+
+impl DealOrder {
+    /// The opening cards, in the order they are dealt.
+    #[must_use]
+    pub const fn sequence(self) -> [Deal; 4] {
+        match self {
+            Self::Alternating => [
+                Deal::Player,
+                Deal::DealerUpcard,
+                Deal::Player,
+                Deal::DealerHoleCard,
+            ],
+            Self::PlayerFirst => [
+                Deal::Player,
+                Deal::Player,
+                Deal::DealerUpcard,
+                Deal::DealerHoleCard,
+            ],
+        }
+    }
+}
+
+/// Who wins when the player's total ties the dealer's.
+/// A tie of blackjacks always pushes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PushPolicy {
+    /// Ties push, returning the bet
+    #[default]
+    Push,
+    /// The dealer wins every tie, as in Double Exposure
+    TiesLose,
+    /// The dealer wins ties up to the given total, and higher ties push, e.g. 19 for ties on 17 to 19
+    TiesLoseUpTo(u8),
+}
+
+impl PushPolicy {
+    /// Returns whether the dealer wins a tie on the given total.
+    #[must_use]
+    pub const fn dealer_wins_tie(self, total: u8) -> bool {
+        match self {
+            Self::Push => false,
+            Self::TiesLose => true,
+            Self::TiesLoseUpTo(highest) => total <= highest,
+        }
+    }
+}
+
+impl FromStr for PushPolicy {
+    type Err = String;
+
+    /// Reads the shorthand written by `Display`: "TP" for ties push, "TL" for ties lose,
+    /// and "TL19" for ties losing up to 19.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_uppercase().as_str() {
+            "TP" => Ok(Self::Push),
+            "TL" => Ok(Self::TiesLose),
+            policy => policy
+                .strip_prefix("TL")
+                .and_then(|highest| highest.parse().ok())
+                .filter(|highest| (17..21).contains(highest))
+                .map(Self::TiesLoseUpTo)
+                .ok_or_else(|| format!("unknown push rule: {s}")),
+        }
+    }
+}
+
+impl fmt::Display for PushPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Push => write!(f, "TP"),
+            Self::TiesLose => write!(f, "TL"),
+            Self::TiesLoseUpTo(highest) => write!(f, "TL{highest}"),
+        }
+    }
+}
+
+/// How a payout which comes to a fraction of a chip is rounded to whole chips,
+/// such as 3:2 on an odd bet, or half of a surrendered bet.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PayoutRounding {
+    /// Fractions of a chip go to the house
+    #[default]
+    Down,
+    /// Half a chip or more is paid as a whole chip
+    Nearest,
+    /// Fractions of a chip go to the player
+    Up,
+}
+
+impl PayoutRounding {
+    pub const ALL: [Self; 3] = [Self::Down, Self::Nearest, Self::Up];
+
+    /// Returns the bet multiplied by the fraction, rounded to whole chips.
+    #[must_use]
+    pub const fn apply(self, bet: u32, numerator: u32, denominator: u32) -> u32 {
+        let product = bet * numerator;
+        match self {
+            Self::Down => product / denominator,
+            Self::Nearest => (product + denominator / 2) / denominator,
+            Self::Up => product.div_ceil(denominator),
+        }
+    }
+}
+
+impl FromStr for PayoutRounding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "down" => Ok(Self::Down),
+            "nearest" => Ok(Self::Nearest),
+            "up" => Ok(Self::Up),
+            _ => Err(format!("unknown payout rounding: {s}")),
+        }
+    }
+}
+
+impl fmt::Display for PayoutRounding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Down => f.pad("down"),
+            Self::Nearest => f.pad("nearest"),
+            Self::Up => f.pad("up"),
+        }
+    }
+}
+
+/// What happens when the player runs out of time to make a decision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeoutAction {
+    /// Decline any offers and stand
+    Stand,
+    /// Play the decision according to basic strategy
+    BasicStrategy,
+}
+
 /// Blackjack table rules.
 #[derive(Debug, Clone)]
 pub struct Rules {
@@ -23,8 +260,8 @@ pub struct Rules {
     pub min_bet: Option<u32>,
     /// The payout for a blackjack.
     pub blackjack_payout: BlackjackPayout,
-    /// The action the dealer takes on a soft 17.
-    pub dealer_soft_17: DealerSoft17Action,
+    /// When the dealer stops drawing, e.g. standing on soft 17.
+    pub dealer_drawing: DealerDrawing,
     /// Whether to offer insurance.
     pub insurance: bool,
     /// Whether players are allowed to surrender before the dealer checks for blackjack.
@@ -37,6 +274,84 @@ pub struct Rules {
     pub double_after_split: bool,
     /// Whether players can split aces.
     pub split_aces: bool,
+    /// The time the player has for each decision, if decisions are timed (speed blackjack).
+    pub decision_timer: Option<Duration>,
+    /// What happens to a decision when the timer runs out.
+    pub timeout_action: TimeoutAction,
+    /// Whether new seats, and players returning from sitting out, may only join after a shuffle.
+    pub no_mid_shoe_entry: bool,
+    /// The order the opening cards are dealt in.
+    pub deal_order: DealOrder,
+    /// Whether the player's opening cards and double-down cards are dealt face down,
+    /// as in hand-held (pitch) games.
+    pub face_down: bool,
+    /// Whether a dealer who busts with 22 pushes the hands still standing, instead of losing to them,
+    /// as in Blackjack Switch and Free Bet. A blackjack still wins.
+    pub dealer_push_22: bool,
+    /// Who wins when the player's total ties the dealer's.
+    pub push_policy: PushPolicy,
+    /// The number of seats at the table, taken by the player's spots and by backers.
+    pub max_seats: u8,
+    /// How payouts coming to a fraction of a chip are rounded.
+    pub payout_rounding: PayoutRounding,
+}
+
+impl Rules {
+    /// Returns a hash of the rules which change how the game plays out, which stays the same between builds,
+    /// unlike the standard hasher, to tell apart tables with the same shorthand and key results kept for later runs.
+    /// Table limits, seats and decision timers are left out, so they can change without invalidating results,
+    /// and each rule is written out explicitly, so new rules only change the hash once they are added here.
+    #[must_use]
+    pub fn stable_hash(&self) -> u64 {
+        let option = |value: Option<u8>| value.map_or([0, 0], |value| [1, value]);
+        let push_policy = match self.push_policy {
+            PushPolicy::Push => [0, 0],
+            PushPolicy::TiesLose => [1, 0],
+            PushPolicy::TiesLoseUpTo(highest) => [2, highest],
+        };
+        let rules = [
+            [
+                match self.blackjack_payout {
+                    BlackjackPayout::ThreeToTwo => 0,
+                    BlackjackPayout::SixToFive => 1,
+                },
+                0,
+            ],
+            [self.dealer_drawing.hard, self.dealer_drawing.soft],
+            [u8::from(self.insurance), 0],
+            [
+                u8::from(self.early_surrender),
+                u8::from(self.late_surrender),
+            ],
+            option(self.max_splits),
+            [u8::from(self.double_after_split), u8::from(self.split_aces)],
+            [u8::from(self.no_mid_shoe_entry), 0],
+            [
+                match self.deal_order {
+                    DealOrder::Alternating => 0,
+                    DealOrder::PlayerFirst => 1,
+                },
+                u8::from(self.face_down),
+            ],
+            [u8::from(self.dealer_push_22), 0],
+            push_policy,
+            [
+                match self.payout_rounding {
+                    PayoutRounding::Down => 0,
+                    PayoutRounding::Nearest => 1,
+                    PayoutRounding::Up => 2,
+                },
+                0,
+            ],
+        ];
+        // 64-bit FNV-1a
+        rules
+            .as_flattened()
+            .iter()
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, &byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+            })
+    }
 }
 
 impl Default for Rules {
@@ -45,14 +360,57 @@ impl Default for Rules {
             max_bet: None,
             min_bet: Some(100),
             blackjack_payout: BlackjackPayout::ThreeToTwo,
-            dealer_soft_17: DealerSoft17Action::Stand,
+            dealer_drawing: DealerDrawing::S17,
             insurance: false,
             early_surrender: false,
             late_surrender: true,
             max_splits: Some(5),
             double_after_split: true,
             split_aces: true,
+            decision_timer: None,
+            timeout_action: TimeoutAction::Stand,
+            no_mid_shoe_entry: false,
+            deal_order: DealOrder::Alternating,
+            face_down: false,
+            dealer_push_22: false,
+            push_policy: PushPolicy::Push,
+            max_seats: 7,
+            payout_rounding: PayoutRounding::Down,
         }
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stable_hash() {
+        let rules = Rules::default();
+        // Limits and seats don't change how the game plays out
+        let limits = Rules {
+            min_bet: Some(25),
+            max_bet: Some(500),
+            max_seats: 3,
+            decision_timer: Some(Duration::from_secs(5)),
+            ..Rules::default()
+        };
+        assert_eq!(rules.stable_hash(), limits.stable_hash());
+        let h17 = Rules {
+            dealer_drawing: DealerDrawing::H17,
+            ..Rules::default()
+        };
+        assert_ne!(rules.stable_hash(), h17.stable_hash());
+        let no_resplits = Rules {
+            max_splits: Some(1),
+            ..Rules::default()
+        };
+        let unlimited = Rules {
+            max_splits: None,
+            ..Rules::default()
+        };
+        assert_ne!(no_resplits.stable_hash(), unlimited.stable_hash());
+        // The hash is written down, so it only changes when the rules it covers do
+        assert_eq!(rules.stable_hash(), 0xb454_4eb1_3b44_016c);
+    }
+}
@@ -0,0 +1,243 @@
+//! Sessions of play, and the limits that end them.
+//! A session can be played out to a set number of rounds, or cut short by money-management rules:
+//! a stop-win once the player is up by a target, or a stop-loss once they are down by a limit.
+//! Sessions can carry notes and tags, e.g. the casino they were played at, kept with their round logs
+//! along with when the session started and which rules it was played under.
+
+use std::fmt::Write;
+use std::str::FromStr;
+
+/// How a session came to an end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionEnd {
+    /// The player played every round of the session
+    Completed,
+    /// The player drew too much heat and was asked to stop playing
+    BackedOff,
+    /// The player reached their stop-win and walked away
+    StopWin,
+    /// The player reached their stop-loss and walked away
+    StopLoss,
+}
+
+/// The limits which end a session, if any.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SessionLimits {
+    /// The number of rounds dealt in a session
+    pub rounds: Option<usize>,
+    /// The profit in chips at which the player quits while ahead
+    pub stop_win: Option<u32>,
+    /// The loss in chips at which the player quits
+    pub stop_loss: Option<u32>,
+}
+
+impl SessionLimits {
+    /// Returns how the session ends after the given number of rounds and net result, if it does.
+    /// The stop-win and stop-loss take precedence over the length of the session.
+    #[must_use]
+    pub fn check(&self, rounds: usize, net: i64) -> Option<SessionEnd> {
+        if self
+            .stop_win
+            .is_some_and(|stop_win| net >= i64::from(stop_win))
+        {
+            Some(SessionEnd::StopWin)
+        } else if self
+            .stop_loss
+            .is_some_and(|stop_loss| net <= -i64::from(stop_loss))
+        {
+            Some(SessionEnd::StopLoss)
+        } else if self.rounds.is_some_and(|limit| rounds >= limit) {
+            Some(SessionEnd::Completed)
+        } else {
+            None
+        }
+    }
+}
+
+/// A free-text note and tags attached to a session, such as the casino or a label for its rules,
+/// written as the first line of the session's round log.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SessionNotes {
+    pub note: String,
+    pub tags: Vec<String>,
+    /// When the session started, in seconds since the Unix epoch
+    pub date: Option<u64>,
+    /// The stable hash of the rules the session was played under
+    pub rules: Option<u64>,
+}
+
+impl SessionNotes {
+    /// Whether the session is tagged with every one of the given tags, ignoring case.
+    #[must_use]
+    pub fn has_tags(&self, tags: &[String]) -> bool {
+        tags.iter()
+            .all(|tag| self.tags.iter().any(|own| own.eq_ignore_ascii_case(tag)))
+    }
+
+    /// Whether the session started within the given range, in seconds since the Unix epoch.
+    /// A session with no date is only within an unbounded range.
+    #[must_use]
+    pub fn started_within(&self, since: Option<u64>, until: Option<u64>) -> bool {
+        match self.date {
+            Some(date) => {
+                since.is_none_or(|since| date >= since) && until.is_none_or(|until| date < until)
+            }
+            None => since.is_none() && until.is_none(),
+        }
+    }
+
+    /// Returns the notes as a single-line JSON object, for the head of a JSON Lines round log.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let tags: Vec<String> = self.tags.iter().map(|tag| json_string(tag)).collect();
+        let mut json = format!(
+            r#"{{"note":{},"tags":[{}]"#,
+            json_string(&self.note),
+            tags.join(",")
+        );
+        if let Some(date) = self.date {
+            let _ = write!(json, r#","date":{date}"#);
+        }
+        if let Some(rules) = self.rules {
+            let _ = write!(json, r#","rules":"{rules:016x}""#);
+        }
+        json.push('}');
+        json
+    }
+}
+
+impl FromStr for SessionNotes {
+    type Err = String;
+
+    /// Parses notes written by `to_json`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("expected session notes: {s}");
+        let rest = s.trim().strip_prefix(r#"{"note":"#).ok_or_else(invalid)?;
+        let (note, rest) = parse_json_string(rest).ok_or_else(invalid)?;
+        let mut rest = rest.strip_prefix(r#","tags":["#).ok_or_else(invalid)?;
+        let mut tags = Vec::new();
+        while !rest.starts_with(']') {
+            let (tag, after) = parse_json_string(rest).ok_or_else(invalid)?;
+            tags.push(tag);
+            rest = after.strip_prefix(',').unwrap_or(after);
+        }
+        let mut rest = &rest[1..];
+        let mut date = None;
+        if let Some(after) = rest.strip_prefix(r#","date":"#) {
+            let end = after
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(after.len());
+            date = Some(after[..end].parse().map_err(|_| invalid())?);
+            rest = &after[end..];
+        }
+        let mut rules = None;
+        if let Some(after) = rest.strip_prefix(r#","rules":"#) {
+            let (hash, after) = parse_json_string(after).ok_or_else(invalid)?;
+            rules = Some(u64::from_str_radix(&hash, 16).map_err(|_| invalid())?);
+            rest = after;
+        }
+        if rest != "}" {
+            return Err(invalid());
+        }
+        Ok(Self {
+            note,
+            tags,
+            date,
+            rules,
+        })
+    }
+}
+
+/// Returns the text as a quoted JSON string.
+fn json_string(text: &str) -> String {
+    let mut json = String::from('"');
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            c if c.is_control() => {
+                let _ = write!(json, "\\u{:04x}", u32::from(c));
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Parses the quoted JSON string at the start of the text, returning it and the text after it.
+fn parse_json_string(text: &str) -> Option<(String, &str)> {
+    let mut chars = text.strip_prefix('"')?.char_indices();
+    let mut string = String::new();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Some((string, &text[i + 2..])),
+            '\\' => match chars.next()?.1 {
+                'n' => string.push('\n'),
+                'u' => {
+                    let hex: String = (0..4)
+                        .filter_map(|_| chars.next())
+                        .map(|(_, c)| c)
+                        .collect();
+                    string.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+                }
+                c => string.push(c),
+            },
+            c => string.push(c),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits() {
+        let limits = SessionLimits {
+            rounds: Some(100),
+            stop_win: Some(500),
+            stop_loss: Some(1000),
+        };
+        assert_eq!(limits.check(10, 499), None);
+        assert_eq!(limits.check(10, 500), Some(SessionEnd::StopWin));
+        assert_eq!(limits.check(10, -1000), Some(SessionEnd::StopLoss));
+        assert_eq!(limits.check(100, 0), Some(SessionEnd::Completed));
+        assert_eq!(limits.check(100, 600), Some(SessionEnd::StopWin));
+        assert_eq!(SessionLimits::default().check(usize::MAX, i64::MIN), None);
+    }
+
+    #[test]
+    fn test_notes() {
+        let notes = SessionNotes {
+            note: "Lost track of the count after a \"free\" drink\nStill up".to_string(),
+            tags: vec!["Golden Nugget".to_string(), "6D,H17".to_string()],
+            date: Some(1_700_000_000),
+            rules: Some(0xb454_4eb1_3b44_016c),
+        };
+        let json = notes.to_json();
+        assert!(!json.contains('\n'));
+        assert_eq!(json.parse(), Ok(notes.clone()));
+        assert!(notes.has_tags(&["golden nugget".to_string()]));
+        assert!(!notes.has_tags(&["6D,H17".to_string(), "Bellagio".to_string()]));
+        assert_eq!(
+            SessionNotes::default().to_json().parse(),
+            Ok(SessionNotes::default())
+        );
+        assert!(r#"{"round":1}"#.parse::<SessionNotes>().is_err());
+        // Logs written before sessions were dated still parse
+        assert_eq!(
+            r#"{"note":"old","tags":[]}"#.parse::<SessionNotes>(),
+            Ok(SessionNotes {
+                note: "old".to_string(),
+                ..SessionNotes::default()
+            })
+        );
+        assert!(notes.started_within(Some(1_700_000_000), Some(1_700_000_001)));
+        assert!(!notes.started_within(None, Some(1_700_000_000)));
+        assert!(SessionNotes::default().started_within(None, None));
+        assert!(!SessionNotes::default().started_within(Some(0), None));
+    }
+}
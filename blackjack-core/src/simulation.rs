@@ -0,0 +1,1190 @@
+//! Headless simulation of a table played with a strategy.
+//! The table is fast-forwarded, so every call to `progress` runs until the next player decision.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::mem;
+use std::str::FromStr;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::betting::CountResults;
+use crate::checkpoint::Checkpoint;
+use crate::counting;
+use crate::currency::{self, InCurrency};
+use crate::game::{Input, Table};
+use crate::heat::Heat;
+use crate::promotion::Coupon;
+use crate::session::{SessionEnd, SessionLimits};
+use crate::state::GameState;
+use crate::statistics::Statistics;
+use crate::strategy::{BasicStrategy, Strategy};
+
+/// The results of a simulation.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Report {
+    /// The number of rounds dealt at the table, whether the player sat in or not
+    pub rounds_observed: usize,
+    /// The number of rounds the player actually bet on
+    pub rounds_played: usize,
+    /// The player's net result in chips, including tips
+    pub net: i64,
+    /// The sum of the squares of the player's net result in each round played, for error bars
+    pub net_squared: f64,
+    /// The total amount the player tipped the dealer
+    pub tips: u64,
+    /// The number of rounds dealt per hour, used to express the EV as an hourly rate
+    pub rounds_per_hour: Option<f64>,
+    /// The results of the rounds played at each Hi-Lo true count, rounded down
+    pub by_true_count: BTreeMap<i32, CountResults>,
+    /// The lowest the player's bankroll has been after a round
+    pub lowest_bankroll: u32,
+    /// The highest the player's bankroll has been after a round
+    pub highest_bankroll: u32,
+    /// The number of sessions ended by the session limits or the heat model, if any
+    pub sessions: usize,
+    /// The number of those sessions which ended with the player backed off
+    pub back_offs: usize,
+    /// The number of those sessions which ended at the player's stop-win
+    pub stop_wins: usize,
+    /// The number of those sessions which ended at the player's stop-loss
+    pub stop_losses: usize,
+}
+
+impl Report {
+    /// Recomputes the report of a simulation from the summaries of its rounds.
+    /// As in the simulation, the net includes tips, but the results by true count leave them out.
+    pub fn from_rounds(rounds: impl IntoIterator<Item = RoundSummary>) -> Self {
+        let mut report = Self {
+            lowest_bankroll: u32::MAX,
+            ..Self::default()
+        };
+        for round in rounds {
+            report.rounds_observed += 1;
+            report.lowest_bankroll = report.lowest_bankroll.min(round.chips);
+            report.highest_bankroll = report.highest_bankroll.max(round.chips);
+            if !round.played {
+                continue;
+            }
+            report.rounds_played += 1;
+            report.net += round.net;
+            report.net_squared += (round.net as f64).powi(2);
+            report.tips += u64::from(round.tips);
+            report
+                .by_true_count
+                .entry(round.true_count.floor() as i32)
+                .or_default()
+                .record(round.bet, round.net + i64::from(round.tips));
+        }
+        report.lowest_bankroll = report.lowest_bankroll.min(report.highest_bankroll);
+        report
+    }
+
+    /// Adds the results of another simulation to this one, as if they were one simulation.
+    /// The pace is left as it is.
+    pub fn merge(&mut self, other: &Self) {
+        self.rounds_observed += other.rounds_observed;
+        self.rounds_played += other.rounds_played;
+        self.net += other.net;
+        self.net_squared += other.net_squared;
+        self.tips += other.tips;
+        for (count, results) in &other.by_true_count {
+            self.by_true_count.entry(*count).or_default().merge(results);
+        }
+        self.lowest_bankroll = self.lowest_bankroll.min(other.lowest_bankroll);
+        self.highest_bankroll = self.highest_bankroll.max(other.highest_bankroll);
+        self.sessions += other.sessions;
+        self.back_offs += other.back_offs;
+        self.stop_wins += other.stop_wins;
+        self.stop_losses += other.stop_losses;
+    }
+
+    /// The expected value per round dealt, including the rounds the player sat out.
+    #[must_use]
+    pub fn ev_per_round_observed(&self) -> f64 {
+        if self.rounds_observed == 0 {
+            0.0
+        } else {
+            self.net as f64 / self.rounds_observed as f64
+        }
+    }
+
+    /// The expected value per round the player bet on.
+    #[must_use]
+    pub fn ev_per_round_played(&self) -> f64 {
+        if self.rounds_played == 0 {
+            0.0
+        } else {
+            self.net as f64 / self.rounds_played as f64
+        }
+    }
+
+    /// The standard error of the expected value per round played,
+    /// which is about a third of the width of its 95% confidence interval either way.
+    #[must_use]
+    pub fn standard_error(&self) -> f64 {
+        if self.rounds_played < 2 {
+            return 0.0;
+        }
+        let rounds = self.rounds_played as f64;
+        let mean = self.net as f64 / rounds;
+        let variance = (self.net_squared / rounds - mean * mean).max(0.0);
+        (variance / (rounds - 1.0)).sqrt()
+    }
+
+    /// The expected win (or loss) per hour at the configured pace, if any.
+    #[must_use]
+    pub fn ev_per_hour(&self) -> Option<f64> {
+        self.rounds_per_hour
+            .map(|pace| self.ev_per_round_observed() * pace)
+    }
+
+    /// The proportion of sessions the player played out without being backed off, if any ended.
+    #[must_use]
+    pub fn sessions_survived(&self) -> Option<f64> {
+        (self.sessions > 0).then(|| (self.sessions - self.back_offs) as f64 / self.sessions as f64)
+    }
+
+    /// The expected value per session, if any ended.
+    #[must_use]
+    pub fn ev_per_session(&self) -> Option<f64> {
+        (self.sessions > 0).then(|| self.net as f64 / self.sessions as f64)
+    }
+
+    /// The cost of tipping per round the player bet on.
+    #[must_use]
+    pub fn tips_per_round_played(&self) -> f64 {
+        if self.rounds_played == 0 {
+            0.0
+        } else {
+            self.tips as f64 / self.rounds_played as f64
+        }
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        InCurrency::new(self, None).fmt(f)
+    }
+}
+
+impl fmt::Display for InCurrency<'_, Report> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (report, currency) = (self.value, self.currency);
+        writeln!(f, "Rounds Observed: {}", report.rounds_observed)?;
+        writeln!(f, "Rounds Played: {}", report.rounds_played)?;
+        writeln!(
+            f,
+            "Net Result: {}",
+            currency::chips(report.net as f64, 0, currency)
+        )?;
+        writeln!(
+            f,
+            "EV per Round Observed: {}",
+            currency::chips(report.ev_per_round_observed(), 4, currency)
+        )?;
+        writeln!(
+            f,
+            "EV per Round Played: {} ± {}",
+            currency::amount(report.ev_per_round_played(), 4, currency),
+            currency::chips(report.standard_error(), 4, currency)
+        )?;
+        writeln!(
+            f,
+            "Tips per Round Played: {}",
+            currency::chips(report.tips_per_round_played(), 4, currency)
+        )?;
+        if let Some(ev_per_hour) = report.ev_per_hour() {
+            writeln!(
+                f,
+                "EV per Hour: {}",
+                currency::chips(ev_per_hour, 2, currency)
+            )?;
+        }
+        writeln!(
+            f,
+            "Lowest Bankroll: {}",
+            currency::chips(f64::from(report.lowest_bankroll), 0, currency)
+        )?;
+        writeln!(
+            f,
+            "Highest Bankroll: {}",
+            currency::chips(f64::from(report.highest_bankroll), 0, currency)
+        )?;
+        if let (Some(survived), Some(ev_per_session)) =
+            (report.sessions_survived(), report.ev_per_session())
+        {
+            writeln!(
+                f,
+                "Sessions: {}, {:.2}% Survived",
+                report.sessions,
+                survived * 100.0
+            )?;
+            writeln!(
+                f,
+                "Session Ends: {} Completed, {} Backed Off, {} Stop-Win, {} Stop-Loss",
+                report.sessions - report.back_offs - report.stop_wins - report.stop_losses,
+                report.back_offs,
+                report.stop_wins,
+                report.stop_losses
+            )?;
+            writeln!(
+                f,
+                "EV per Session: {}",
+                currency::chips(ev_per_session, 2, currency)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The distribution of the results of many independent sessions, each started with the same bankroll.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SessionDistribution {
+    /// The net result of each session, sorted from worst to best
+    nets: Vec<i64>,
+    /// The number of sessions in which the player went broke
+    pub busts: usize,
+}
+
+impl SessionDistribution {
+    /// Adds the result of a session.
+    pub fn record(&mut self, net: i64, busted: bool) {
+        let index = self.nets.partition_point(|&other| other <= net);
+        self.nets.insert(index, net);
+        self.busts += usize::from(busted);
+    }
+
+    /// The number of sessions recorded.
+    #[must_use]
+    pub fn sessions(&self) -> usize {
+        self.nets.len()
+    }
+
+    /// The net result which the given proportion of sessions did no better than (nearest rank).
+    #[must_use]
+    pub fn percentile(&self, proportion: f64) -> Option<i64> {
+        let rank = (proportion * self.nets.len() as f64).ceil() as usize;
+        self.nets
+            .get(rank.clamp(1, self.nets.len().max(1)) - 1)
+            .copied()
+    }
+
+    /// The proportion of sessions in which the player went broke.
+    #[must_use]
+    pub fn bust_rate(&self) -> f64 {
+        if self.nets.is_empty() {
+            0.0
+        } else {
+            self.busts as f64 / self.nets.len() as f64
+        }
+    }
+
+    /// The proportion of sessions the player finished ahead.
+    #[must_use]
+    pub fn win_rate(&self) -> f64 {
+        if self.nets.is_empty() {
+            0.0
+        } else {
+            let wins = self.nets.len() - self.nets.partition_point(|&net| net <= 0);
+            wins as f64 / self.nets.len() as f64
+        }
+    }
+}
+
+impl fmt::Display for SessionDistribution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        InCurrency::new(self, None).fmt(f)
+    }
+}
+
+impl fmt::Display for InCurrency<'_, SessionDistribution> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let distribution = self.value;
+        writeln!(f, "Sessions Run: {}", distribution.sessions())?;
+        writeln!(f, "Bust Rate: {:.2}%", distribution.bust_rate() * 100.0)?;
+        writeln!(f, "Sessions Ahead: {:.2}%", distribution.win_rate() * 100.0)?;
+        for proportion in [0.05, 0.25, 0.5, 0.75, 0.95] {
+            if let Some(net) = distribution.percentile(proportion) {
+                writeln!(
+                    f,
+                    "{:>2.0}th Percentile Session: {}",
+                    proportion * 100.0,
+                    currency::chips(net as f64, 0, self.currency)
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How far along a simulation is, and what it has found so far.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Progress {
+    /// The number of rounds dealt so far
+    pub rounds: usize,
+    /// The number of rounds to deal in total
+    pub total_rounds: usize,
+    /// The time spent simulating so far
+    pub elapsed: Duration,
+    /// The current estimate of the expected value per round played
+    pub ev_per_round: f64,
+    /// The standard error of that estimate
+    pub standard_error: f64,
+}
+
+impl Progress {
+    /// The proportion of the rounds dealt so far, between 0 and 1.
+    #[must_use]
+    pub fn fraction(&self) -> f64 {
+        if self.total_rounds == 0 {
+            1.0
+        } else {
+            self.rounds as f64 / self.total_rounds as f64
+        }
+    }
+
+    /// The number of rounds dealt per second so far.
+    #[must_use]
+    pub fn rounds_per_second(&self) -> f64 {
+        let seconds = self.elapsed.as_secs_f64();
+        if seconds > 0.0 {
+            self.rounds as f64 / seconds
+        } else {
+            0.0
+        }
+    }
+}
+
+impl fmt::Display for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:5.1}% {}/{} rounds, {:.0} rounds/s, EV {:+.4} ± {:.4} Chips",
+            self.fraction() * 100.0,
+            self.rounds,
+            self.total_rounds,
+            self.rounds_per_second(),
+            self.ev_per_round,
+            self.standard_error
+        )
+    }
+}
+
+/// What happened in a single round of a simulation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RoundSummary {
+    /// The number of the round, counting every round dealt
+    pub round: usize,
+    /// The Hi-Lo true count before the round was dealt
+    pub true_count: f32,
+    /// Whether the player sat in, rather than watching a bot seat play
+    pub played: bool,
+    /// The player's initial bet
+    pub bet: u32,
+    /// The number of hands the player finished with, including split hands
+    pub hands: usize,
+    /// The number of blackjacks the player was dealt
+    pub blackjacks: usize,
+    /// The player's net result, including tips
+    pub net: i64,
+    /// The tips the player gave the dealer around the round
+    pub tips: u32,
+    /// The player's chips after the round
+    pub chips: u32,
+}
+
+impl RoundSummary {
+    /// Returns the summary as a single-line JSON object, for a JSON Lines stream.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        format!(
+            r#"{{"round":{},"true_count":{:.3},"played":{},"bet":{},"hands":{},"blackjacks":{},"net":{},"tips":{},"chips":{}}}"#,
+            self.round,
+            self.true_count,
+            self.played,
+            self.bet,
+            self.hands,
+            self.blackjacks,
+            self.net,
+            self.tips,
+            self.chips
+        )
+    }
+}
+
+impl FromStr for RoundSummary {
+    type Err = String;
+
+    /// Parses a summary written by `to_json`. Summaries written before tips were recorded have none.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let object = s
+            .trim()
+            .strip_prefix('{')
+            .and_then(|s| s.strip_suffix('}'))
+            .ok_or_else(|| format!("expected a JSON object: {s}"))?;
+        let mut values = HashMap::new();
+        for field in object.split(',') {
+            let (key, value) = field
+                .split_once(':')
+                .ok_or_else(|| format!("expected \"key\":value: {field}"))?;
+            values.insert(key.trim().trim_matches('"'), value.trim());
+        }
+        fn parse<T: FromStr>(values: &HashMap<&str, &str>, key: &str) -> Result<T, String> {
+            let value = values.get(key).ok_or_else(|| format!("missing {key}"))?;
+            value.parse().map_err(|_| format!("invalid {key}: {value}"))
+        }
+        Ok(Self {
+            round: parse(&values, "round")?,
+            true_count: parse(&values, "true_count")?,
+            played: parse(&values, "played")?,
+            bet: parse(&values, "bet")?,
+            hands: parse(&values, "hands")?,
+            blackjacks: parse(&values, "blackjacks")?,
+            net: parse(&values, "net")?,
+            tips: if values.contains_key("tips") {
+                parse(&values, "tips")?
+            } else {
+                0
+            },
+            chips: parse(&values, "chips")?,
+        })
+    }
+}
+
+/// How the simulated player tips the dealer.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TippingPolicy {
+    /// The tip given before every round the player bets on
+    pub per_round: u32,
+    /// The tip given after every blackjack the player is dealt
+    pub per_blackjack: u32,
+}
+
+/// The rounds played before a simulation starts keeping results,
+/// so they aren't skewed by the fresh shoe and bankroll it starts with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WarmUp {
+    /// Discard the given number of rounds
+    Rounds(usize),
+    /// Discard the rounds dealt from the first shoe
+    Shoe,
+}
+
+impl FromStr for WarmUp {
+    type Err = String;
+
+    /// Parses a number of rounds, or "shoe" for the first shoe.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("shoe") {
+            Ok(Self::Shoe)
+        } else {
+            s.parse()
+                .map(Self::Rounds)
+                .map_err(|_| format!("expected a number of rounds or \"shoe\": {s}"))
+        }
+    }
+}
+
+impl fmt::Display for WarmUp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rounds(rounds) => write!(f, "{rounds} rounds"),
+            Self::Shoe => write!(f, "the first shoe"),
+        }
+    }
+}
+
+/// Simulates rounds at a table with a strategy, basic strategy unless told otherwise.
+#[derive(Debug)]
+pub struct Simulator<S = BasicStrategy> {
+    /// The simulated table
+    pub table: Table,
+    /// The strategy the player follows
+    pub strategy: S,
+    /// If set, the player only sits in when the true count is at least this high (Wonging).
+    /// Otherwise, the round is dealt to a bot seat while the player watches the count.
+    pub wong_in: Option<f32>,
+    /// How the player tips the dealer
+    pub tipping: TippingPolicy,
+    /// If set, bet spreads draw heat, which ends the session once the player is backed off
+    pub heat: Option<Heat>,
+    /// The limits which end a session. After each session, the player moves to a fresh table
+    pub limits: SessionLimits,
+    /// If set, the rounds played before the first round counted.
+    /// They are played on the first call to `next_round`, and left out of the report and statistics
+    pub warm_up: Option<WarmUp>,
+    /// If set, the player redeems this coupon on every round they play,
+    /// unless one they pushed with is still on the table
+    pub coupon: Option<Coupon>,
+    /// The results so far
+    pub report: Report,
+    /// A summary of the last round dealt, if any
+    pub last_round: Option<RoundSummary>,
+    /// Whether the player played the last round, rather than sitting it out
+    seated: bool,
+    /// The number of rounds dealt in the current session
+    session_rounds: usize,
+    /// The player's chips at the start of the current session
+    session_chips: u32,
+}
+
+impl Simulator {
+    /// Creates a new simulator playing basic strategy at the given table,
+    /// which is switched to fast-forward.
+    #[must_use]
+    pub const fn new(table: Table) -> Self {
+        Self::with_strategy(table, BasicStrategy)
+    }
+}
+
+impl<S: Strategy> Simulator<S> {
+    /// Creates a new simulator playing the given strategy at the given table,
+    /// which is switched to fast-forward.
+    #[must_use]
+    pub const fn with_strategy(mut table: Table, strategy: S) -> Self {
+        table.fast_forward = true;
+        let chips = table.chips;
+        Self {
+            table,
+            strategy,
+            wong_in: None,
+            tipping: TippingPolicy {
+                per_round: 0,
+                per_blackjack: 0,
+            },
+            heat: None,
+            limits: SessionLimits {
+                rounds: None,
+                stop_win: None,
+                stop_loss: None,
+            },
+            warm_up: None,
+            coupon: None,
+            report: Report {
+                rounds_observed: 0,
+                rounds_played: 0,
+                net: 0,
+                net_squared: 0.0,
+                tips: 0,
+                rounds_per_hour: None,
+                by_true_count: BTreeMap::new(),
+                lowest_bankroll: chips,
+                highest_bankroll: chips,
+                sessions: 0,
+                back_offs: 0,
+                stop_wins: 0,
+                stop_losses: 0,
+            },
+            last_round: None,
+            seated: true,
+            session_rounds: 0,
+            session_chips: chips,
+        }
+    }
+
+    /// Saves the state of the simulation, to resume it later.
+    /// The simulator doesn't know the seed it was started with, so that is left for the caller to fill in.
+    #[must_use]
+    pub fn checkpoint(&self) -> Checkpoint {
+        let mut strategy = BTreeMap::new();
+        self.strategy.save(&mut strategy);
+        Checkpoint {
+            seed: None,
+            chips: self.table.chips,
+            last_bet: self.table.last_bet,
+            last_net: self.table.last_net,
+            coupon: self.table.coupon,
+            heat: self.heat.as_ref().map(Heat::level),
+            heat_last_bet: self.heat.as_ref().and_then(Heat::last_bet),
+            seated: self.seated,
+            session_rounds: self.session_rounds,
+            session_chips: self.session_chips,
+            shoe: self.table.shoe.clone(),
+            statistics: self.table.statistics.clone(),
+            report: Report {
+                rounds_per_hour: None,
+                ..self.report.clone()
+            },
+            strategy,
+            outputs: BTreeMap::new(),
+        }
+    }
+
+    /// Resumes the simulation from a checkpoint.
+    /// The table's rules, the strategy, the heat model and the session limits are kept,
+    /// so they should match the simulation that was saved,
+    /// and the strategy carries on from what it had learned.
+    /// Any warm-up is skipped, as the saved simulation has already played it.
+    /// # Errors
+    /// Returns an error if the strategy can't be restored from what the checkpoint saved of it.
+    pub fn resume(&mut self, checkpoint: Checkpoint) -> Result<(), String> {
+        self.strategy.restore(&checkpoint.strategy)?;
+        self.warm_up = None;
+        self.table.chips = checkpoint.chips;
+        self.table.last_bet = checkpoint.last_bet;
+        self.table.last_net = checkpoint.last_net;
+        self.table.coupon = checkpoint.coupon;
+        if let (Some(heat), Some(level)) = (&mut self.heat, checkpoint.heat) {
+            heat.restore(level, checkpoint.heat_last_bet);
+        }
+        self.seated = checkpoint.seated;
+        self.session_rounds = checkpoint.session_rounds;
+        self.session_chips = checkpoint.session_chips;
+        self.table.shoe = checkpoint.shoe;
+        self.table.statistics = checkpoint.statistics;
+        self.report = Report {
+            rounds_per_hour: self.report.rounds_per_hour,
+            ..checkpoint.report
+        };
+        Ok(())
+    }
+
+    /// Simulates up to the given number of rounds, stopping early if the player goes broke.
+    pub fn run(&mut self, rounds: usize) -> &Report {
+        for _ in 0..rounds {
+            if !self.next_round() {
+                break;
+            }
+        }
+        &self.report
+    }
+
+    /// Simulates up to the given number of rounds like `run`,
+    /// calling back with the progress every `interval` rounds and once more at the end.
+    pub fn run_with_progress(
+        &mut self,
+        rounds: usize,
+        interval: usize,
+        mut on_progress: impl FnMut(Progress),
+    ) -> &Report {
+        let started = Instant::now();
+        for round in 1..=rounds {
+            let playing = self.next_round();
+            if !playing || round == rounds || round % interval.max(1) == 0 {
+                on_progress(self.progress(round, rounds, started.elapsed()));
+            }
+            if !playing {
+                break;
+            }
+        }
+        &self.report
+    }
+
+    /// Simulates a single session of up to the given number of rounds,
+    /// stopping early if the player goes broke or the session limits end it.
+    /// Returns whether the player went broke.
+    pub fn run_session(&mut self, rounds: usize) -> bool {
+        let sessions = self.report.sessions;
+        for _ in 0..rounds {
+            if !self.next_round() {
+                return true;
+            }
+            if self.report.sessions > sessions {
+                break;
+            }
+        }
+        false
+    }
+
+    /// Returns the progress of a run which has dealt the given number of its rounds so far.
+    #[must_use]
+    pub fn progress(&self, rounds: usize, total_rounds: usize, elapsed: Duration) -> Progress {
+        Progress {
+            rounds,
+            total_rounds,
+            elapsed,
+            ev_per_round: self.report.ev_per_round_played(),
+            standard_error: self.report.standard_error(),
+        }
+    }
+
+    /// Deals the next round, either to the player or to a bot seat.
+    /// Returns false if the game is over.
+    pub fn next_round(&mut self) -> bool {
+        self.next_round_watched(&mut |_, _| {})
+    }
+
+    /// Deals the next round like `next_round`, showing the watcher the table
+    /// and each state the player's round passes through.
+    pub fn next_round_watched(&mut self, watch: &mut dyn FnMut(&Table, &GameState)) -> bool {
+        if let Some(warm_up) = self.warm_up.take() {
+            if !self.play_warm_up(warm_up) {
+                return false;
+            }
+        }
+        self.report.rounds_observed += 1;
+        let exact_true_count = counting::true_count(&self.table.shoe);
+        // Without mid-shoe entry, a player who sat out has to wait for the shuffle to come back
+        let sit_in = self
+            .wong_in
+            .is_none_or(|threshold| exact_true_count >= threshold)
+            && (self.seated || self.table.can_join());
+        self.seated = sit_in;
+        let playing = if sit_in {
+            self.report.rounds_played += 1;
+            let chips = self.table.chips;
+            let tips = self.report.tips;
+            let blackjacks = self.table.statistics.blackjacks();
+            let hands = self.table.statistics.hands_played();
+            self.tip(self.tipping.per_round);
+            if self.table.coupon.is_none() {
+                self.table.coupon = self.coupon;
+            }
+            let true_count = exact_true_count.floor() as i32;
+            let chips_before_round = self.table.chips;
+            let (state, bet) = self.play_round(None, watch);
+            self.report
+                .by_true_count
+                .entry(true_count)
+                .or_default()
+                .record(
+                    bet,
+                    i64::from(self.table.chips) - i64::from(chips_before_round),
+                );
+            if matches!(state, GameState::Betting { .. }) {
+                let blackjacks = self.table.statistics.blackjacks() - blackjacks;
+                self.tip(self.tipping.per_blackjack * blackjacks as u32);
+            }
+            let net = i64::from(self.table.chips) - i64::from(chips);
+            self.report.net += net;
+            self.report.net_squared += (net as f64).powi(2);
+            self.report.lowest_bankroll = self.report.lowest_bankroll.min(self.table.chips);
+            self.report.highest_bankroll = self.report.highest_bankroll.max(self.table.chips);
+            self.last_round = Some(RoundSummary {
+                round: self.report.rounds_observed,
+                true_count: exact_true_count,
+                played: true,
+                bet,
+                hands: self.table.statistics.hands_played() - hands,
+                blackjacks: self.table.statistics.blackjacks() - blackjacks,
+                net,
+                tips: (self.report.tips - tips) as u32,
+                chips: self.table.chips,
+            });
+            state != GameState::GameOver
+        } else {
+            self.observe_round();
+            self.last_round = Some(RoundSummary {
+                round: self.report.rounds_observed,
+                true_count: exact_true_count,
+                played: false,
+                bet: 0,
+                hands: 0,
+                blackjacks: 0,
+                net: 0,
+                tips: 0,
+                chips: self.table.chips,
+            });
+            true
+        };
+        self.session_rounds += 1;
+        let bet = self
+            .last_round
+            .filter(|round| round.played)
+            .map(|round| round.bet);
+        let end = if self.heat.as_mut().is_some_and(|heat| heat.observe(bet)) {
+            Some(SessionEnd::BackedOff)
+        } else {
+            let net = i64::from(self.table.chips) - i64::from(self.session_chips);
+            self.limits.check(self.session_rounds, net)
+        };
+        if let Some(end) = end {
+            self.end_session(end);
+        }
+        playing
+    }
+
+    /// Plays the warm-up rounds, then discards their results, starting a new session from there.
+    /// The shoe and the bankroll carry on as the warm-up left them.
+    /// Returns false if the game ended during the warm-up.
+    fn play_warm_up(&mut self, warm_up: WarmUp) -> bool {
+        let shuffles = self.table.statistics.shuffles();
+        let mut rounds = 0;
+        let playing = loop {
+            let done = match warm_up {
+                WarmUp::Rounds(warm_up) => rounds >= warm_up,
+                WarmUp::Shoe => self.table.statistics.shuffles() > shuffles,
+            };
+            if done {
+                break true;
+            }
+            if !self.next_round() {
+                break false;
+            }
+            rounds += 1;
+        };
+        let chips = self.table.chips;
+        self.report = Report {
+            rounds_per_hour: self.report.rounds_per_hour,
+            lowest_bankroll: chips,
+            highest_bankroll: chips,
+            ..Report::default()
+        };
+        self.table.statistics = Statistics::new();
+        self.last_round = None;
+        self.session_rounds = 0;
+        self.session_chips = chips;
+        playing
+    }
+
+    /// Ends the current session, and starts the next at another table with a freshly shuffled shoe.
+    fn end_session(&mut self, end: SessionEnd) {
+        self.report.sessions += 1;
+        match end {
+            SessionEnd::Completed => {}
+            SessionEnd::BackedOff => self.report.back_offs += 1,
+            SessionEnd::StopWin => self.report.stop_wins += 1,
+            SessionEnd::StopLoss => self.report.stop_losses += 1,
+        }
+        if let Some(heat) = &mut self.heat {
+            heat.reset();
+        }
+        self.table.statistics.record_shuffle(
+            usize::from(self.table.shoe.cards_drawn),
+            usize::from(self.table.shoe.decks) * 52,
+        );
+        self.table.shoe.shuffle();
+        self.seated = true;
+        self.session_rounds = 0;
+        self.session_chips = self.table.chips;
+    }
+
+    /// Tips the dealer, as long as the player can afford it.
+    fn tip(&mut self, tip: u32) {
+        if tip > 0
+            && self
+                .table
+                .progress(self.table.betting(), Some(Input::tip(tip)))
+                .is_ok()
+        {
+            self.report.tips += u64::from(tip);
+        }
+    }
+
+    /// Plays one round from betting until the table is ready for the next bet.
+    /// If a bot bet is given, it is placed without asking the strategy, which only plays the hands.
+    /// Returns the state the round ended in and the initial bet.
+    fn play_round(
+        &mut self,
+        bot_bet: Option<u32>,
+        watch: &mut dyn FnMut(&Table, &GameState),
+    ) -> (GameState, u32) {
+        let mut state = self.table.betting();
+        let mut bet = 0;
+        loop {
+            watch(&self.table, &state);
+            let input = match (&state, bot_bet) {
+                (GameState::Betting { .. }, Some(bot_bet)) => Some(Input::bet(bot_bet)),
+                _ => {
+                    let composition = self.table.unseen(&state);
+                    self.strategy.input(&self.table, &state, &composition)
+                }
+            };
+            if let (
+                GameState::Betting { .. },
+                Some(Input::Bet {
+                    bet: initial_bet, ..
+                }),
+            ) = (&state, input)
+            {
+                bet = initial_bet;
+            }
+            state = match self.table.progress(state, input) {
+                Ok(state) | Err((state, _)) => state,
+            };
+            if matches!(state, GameState::Betting { .. } | GameState::GameOver) {
+                return (state, bet);
+            }
+        }
+    }
+
+    /// Deals a round to a bot seat playing the player's strategy while the player sits out.
+    /// The cards still come out of the shoe, but the player's chips and statistics are untouched,
+    /// apart from any shuffle, and the table's observers don't see the round.
+    /// The bot bets the table minimum itself, so the player's betting strategy, last bet and result,
+    /// and coupon carry over to the next round the player plays as if the round had never been dealt.
+    fn observe_round(&mut self) {
+        let chips = mem::replace(&mut self.table.chips, u32::MAX / 2);
+        let last_bet = self.table.last_bet;
+        let last_net = self.table.last_net;
+        let coupon = self.table.coupon.take();
+        let statistics = mem::take(&mut self.table.statistics);
+        let observers = mem::take(&mut self.table.observers);
+        let bot_bet = self.table.rules.min_bet.unwrap_or(1);
+        self.play_round(Some(bot_bet), &mut |_, _| {});
+        self.table.chips = chips;
+        self.table.last_bet = last_bet;
+        self.table.last_net = last_net;
+        self.table.coupon = coupon;
+        self.table.observers = observers;
+        let observed = mem::replace(&mut self.table.statistics, statistics);
+        self.table.statistics.merge_shuffles(&observed);
+    }
+}
+
+/// Simulates independent tables at once, each on its own thread,
+/// until each has dealt its share of the given number of rounds or its player goes broke.
+/// Returns the pooled report; each simulator keeps its own.
+pub fn run_tables<S: Strategy + Send>(simulators: &mut [Simulator<S>], rounds: usize) -> Report {
+    let tables = simulators.len().max(1);
+    thread::scope(|scope| {
+        for (i, simulator) in simulators.iter_mut().enumerate() {
+            // The first tables deal the rounds left over from splitting them evenly
+            let share = rounds / tables + usize::from(i < rounds % tables);
+            scope.spawn(move || simulator.run(share));
+        }
+    });
+    let mut pooled = simulators
+        .first()
+        .map(|simulator| simulator.report.clone())
+        .unwrap_or_default();
+    for simulator in simulators.iter().skip(1) {
+        pooled.merge(&simulator.report);
+    }
+    pooled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::betting::{Martingale, WithBetting};
+    use crate::card::shoe::Shoe;
+    use crate::heat::HeatModel;
+    use crate::rules::Rules;
+
+    #[test]
+    fn test_wonging() {
+        let table = Table::new(u32::MAX / 2, Shoe::new(6, 0.75), Rules::default());
+        let mut simulator = Simulator::new(table);
+        simulator.run(100);
+        assert_eq!(simulator.report.rounds_observed, 100);
+        assert_eq!(simulator.report.rounds_played, 100);
+        let statistics = &simulator.table.statistics;
+        assert!(statistics
+            .cards_per_round()
+            .is_some_and(|cards| (4.0..7.0).contains(&cards)));
+        assert!(statistics
+            .penetration()
+            .is_some_and(|pen| (0.75..0.8).contains(&pen)));
+
+        let table = Table::new(u32::MAX / 2, Shoe::new(6, 0.75), Rules::default());
+        let mut simulator = Simulator::new(table);
+        simulator.wong_in = Some(f32::INFINITY);
+        simulator.run(100);
+        assert_eq!(simulator.report.rounds_observed, 100);
+        assert_eq!(simulator.report.rounds_played, 0);
+        assert_eq!(simulator.report.net, 0);
+        assert!(simulator.table.statistics.shuffles() > 0);
+
+        // Without mid-shoe entry, a player who sits out waits for the shuffle to come back
+        let rules = Rules {
+            no_mid_shoe_entry: true,
+            ..Rules::default()
+        };
+        let table = Table::new(u32::MAX / 2, Shoe::new(6, 0.75), rules);
+        let mut simulator = Simulator::new(table);
+        simulator.wong_in = Some(f32::NEG_INFINITY);
+        simulator.next_round();
+        simulator.wong_in = Some(f32::INFINITY);
+        simulator.next_round();
+        simulator.wong_in = Some(f32::NEG_INFINITY);
+        simulator.next_round();
+        assert_eq!(simulator.report.rounds_played, 1);
+        assert!(!simulator.table.can_join());
+    }
+
+    #[test]
+    fn test_wonging_progression() {
+        // Rounds the player sits out don't settle the progression, so each bet follows the last one played
+        let rules = Rules {
+            min_bet: Some(1),
+            ..Rules::default()
+        };
+        let table = Table::new(1_000_000, Shoe::seeded(6, 0.75, 7), rules);
+        let strategy = WithBetting::new(BasicStrategy, Martingale::new(1));
+        let mut simulator = Simulator::with_strategy(table, strategy);
+        simulator.wong_in = Some(0.0);
+        let mut expected = 1;
+        for _ in 0..1000 {
+            simulator.next_round();
+            let round = simulator.last_round.unwrap();
+            if round.played {
+                assert_eq!(round.bet, expected);
+                expected = match round.net {
+                    ..0 => expected * 2,
+                    0 => expected,
+                    1.. => 1,
+                };
+                assert_eq!(simulator.table.last_bet(), Some(round.bet));
+            }
+        }
+        assert!(simulator.report.rounds_played > 0);
+        assert!(simulator.report.rounds_played < simulator.report.rounds_observed);
+    }
+
+    #[test]
+    fn test_tipped_progression() {
+        // Tips come out of the player's chips, but the progression follows each round's own result
+        let rules = Rules {
+            min_bet: Some(1),
+            ..Rules::default()
+        };
+        let table = Table::new(1_000_000, Shoe::seeded(6, 0.75, 7), rules);
+        let strategy = WithBetting::new(BasicStrategy, Martingale::new(1));
+        let mut simulator = Simulator::with_strategy(table, strategy);
+        simulator.tipping.per_round = 1;
+        let mut expected = 1;
+        for _ in 0..1000 {
+            simulator.next_round();
+            let round = simulator.last_round.unwrap();
+            assert_eq!(round.bet, expected);
+            expected = match round.net + 1 {
+                ..0 => expected * 2,
+                0 => expected,
+                1.. => 1,
+            };
+        }
+    }
+
+    #[test]
+    fn test_sessions() {
+        let table = Table::new(u32::MAX / 2, Shoe::new(6, 0.75), Rules::default());
+        let mut simulator = Simulator::new(table);
+        simulator.heat = Some(Heat::new(HeatModel::default()));
+        simulator.limits.rounds = Some(40);
+        simulator.run(100);
+        assert_eq!(simulator.report.sessions, 2);
+        assert_eq!(simulator.report.back_offs, 0);
+        assert_eq!(simulator.report.sessions_survived(), Some(1.0));
+
+        // A stop-win and stop-loss of a single chip end every session which isn't a push
+        let table = Table::new(u32::MAX / 2, Shoe::new(6, 0.75), Rules::default());
+        let mut simulator = Simulator::new(table);
+        simulator.limits = SessionLimits {
+            rounds: None,
+            stop_win: Some(1),
+            stop_loss: Some(1),
+        };
+        simulator.run(100);
+        let report = &simulator.report;
+        assert_eq!(report.sessions, report.stop_wins + report.stop_losses);
+        assert!(report.stop_wins > 0 && report.stop_losses > 0);
+    }
+
+    #[test]
+    fn test_warm_up() {
+        let table = Table::new(u32::MAX / 2, Shoe::new(6, 0.75), Rules::default());
+        let mut simulator = Simulator::new(table);
+        simulator.warm_up = Some(WarmUp::Rounds(50));
+        simulator.run(100);
+        assert_eq!(simulator.report.rounds_observed, 100);
+        assert_eq!(simulator.table.statistics.turns_played(), 100);
+        assert_eq!(simulator.warm_up, None);
+
+        let table = Table::new(u32::MAX / 2, Shoe::new(1, 0.75), Rules::default());
+        let mut simulator = Simulator::new(table);
+        simulator.warm_up = Some("shoe".parse().unwrap());
+        simulator.run(1);
+        // The first shoe was shuffled away before the round counted
+        assert_eq!(simulator.table.statistics.shuffles(), 0);
+        assert!(simulator.table.shoe.cards_drawn < 20);
+        assert!("soon".parse::<WarmUp>().is_err());
+    }
+
+    #[test]
+    fn test_session_distribution() {
+        let mut distribution = SessionDistribution::default();
+        for net in [300, -1000, 0, 100, -200] {
+            distribution.record(net, net == -1000);
+        }
+        assert_eq!(distribution.percentile(0.05), Some(-1000));
+        assert_eq!(distribution.percentile(0.5), Some(0));
+        assert_eq!(distribution.percentile(0.95), Some(300));
+        assert!((distribution.bust_rate() - 0.2).abs() < f64::EPSILON);
+        assert!((distribution.win_rate() - 0.4).abs() < f64::EPSILON);
+        assert_eq!(SessionDistribution::default().percentile(0.5), None);
+
+        let table = Table::new(1000, Shoe::seeded(6, 0.75, 7), Rules::default());
+        let mut simulator = Simulator::new(table);
+        simulator.limits.stop_loss = Some(200);
+        let busted = simulator.run_session(1000);
+        assert!(!busted);
+        assert_eq!(simulator.report.stop_losses, 1);
+        assert!(simulator.table.chips <= 800);
+    }
+
+    #[test]
+    fn test_progress() {
+        let table = Table::new(u32::MAX / 2, Shoe::new(6, 0.75), Rules::default());
+        let mut simulator = Simulator::new(table);
+        let mut updates = Vec::new();
+        simulator.run_with_progress(1000, 300, |progress| updates.push(progress));
+        let rounds: Vec<usize> = updates.iter().map(|progress| progress.rounds).collect();
+        assert_eq!(rounds, [300, 600, 900, 1000]);
+        let last = updates[3];
+        assert!((last.fraction() - 1.0).abs() < f64::EPSILON);
+        assert!(last.standard_error > 0.0);
+    }
+
+    #[test]
+    fn test_run_tables() {
+        let mut simulators: Vec<_> = (0..3)
+            .map(|_| {
+                Simulator::new(Table::new(
+                    u32::MAX / 2,
+                    Shoe::new(6, 0.75),
+                    Rules::default(),
+                ))
+            })
+            .collect();
+        let report = run_tables(&mut simulators, 1000);
+        assert_eq!(report.rounds_played, 1000);
+        assert_eq!(simulators[0].report.rounds_played, 334);
+        assert_eq!(
+            report.net,
+            simulators.iter().map(|s| s.report.net).sum::<i64>()
+        );
+    }
+
+    #[test]
+    fn test_round_summary() {
+        let table = Table::new(1000, Shoe::new(6, 0.75), Rules::default());
+        let mut simulator = Simulator::new(table);
+        simulator.next_round();
+        let summary = simulator.last_round.unwrap();
+        assert_eq!(summary.round, 1);
+        assert_eq!(summary.bet, 100);
+        assert_eq!(summary.chips, simulator.table.chips);
+        assert!(summary
+            .to_json()
+            .starts_with(r#"{"round":1,"true_count":0.000,"#));
+        assert_eq!(summary.to_json().parse(), Ok(summary));
+        let report = Report::from_rounds([summary]);
+        assert_eq!(report.rounds_played, 1);
+        assert_eq!(report.net, summary.net);
+        assert_eq!(report.lowest_bankroll, summary.chips);
+    }
+
+    #[test]
+    fn test_report_from_tipped_rounds() {
+        // The round log recomputes the same report, tips and results by true count included
+        let table = Table::new(1_000_000, Shoe::seeded(6, 0.75, 7), Rules::default());
+        let mut simulator = Simulator::new(table);
+        simulator.tipping = TippingPolicy {
+            per_round: 5,
+            per_blackjack: 25,
+        };
+        let mut rounds = Vec::new();
+        for _ in 0..1000 {
+            simulator.next_round();
+            let round = simulator.last_round.unwrap();
+            rounds.push(round.to_json().parse().unwrap());
+        }
+        let report = Report::from_rounds(rounds);
+        assert!(report.tips > 0);
+        assert_eq!(report.tips, simulator.report.tips);
+        assert_eq!(report.net, simulator.report.net);
+        assert_eq!(report.by_true_count, simulator.report.by_true_count);
+    }
+}
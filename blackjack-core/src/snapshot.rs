@@ -0,0 +1,280 @@
+//! Stable text snapshots of game states, for golden tests.
+//! A snapshot names the state, then lists its fields one per line in a fixed order.
+//! Cards are written compactly, e.g. "10♠", and nothing depends on the locale or the shoe,
+//! so the same state always gives the same text.
+
+use std::fmt::Write;
+
+use crate::card::hand::{DealerHand, PlayerHand, PlayerTurn};
+use crate::card::{Card, Visibility};
+use crate::state::{BetResult, GameState};
+
+/// Returns the snapshot of a state.
+#[must_use]
+pub fn state(state: &GameState) -> String {
+    let mut snapshot = String::new();
+    let s = &mut snapshot;
+    line(s, "state", name(state));
+    match state {
+        GameState::Betting { last_bet } => {
+            if let Some(last_bet) = last_bet {
+                line(s, "last bet", last_bet);
+            }
+        }
+        GameState::Shuffle | GameState::GameOver => {}
+        GameState::Dealing {
+            bet,
+            dealt,
+            player_hand,
+            dealer_hand,
+        } => {
+            line(s, "bet", bet);
+            line(s, "dealt", dealt);
+            if let Some(dealer_hand) = dealer_hand {
+                line(s, "dealer", dealer(dealer_hand));
+            }
+            if let Some(player_hand) = player_hand {
+                line(s, "hand 1", player(player_hand));
+            }
+        }
+        GameState::OfferEarlySurrender {
+            player_hand,
+            dealer_hand,
+        }
+        | GameState::OfferInsurance {
+            player_hand,
+            dealer_hand,
+        } => {
+            line(s, "dealer", dealer(dealer_hand));
+            line(s, "hand 1", player(player_hand));
+        }
+        GameState::CheckDealerHoleCard {
+            player_hand,
+            dealer_hand,
+            insurance_bet,
+        } => {
+            line(s, "dealer", dealer(dealer_hand));
+            line(s, "hand 1", player(player_hand));
+            line(s, "insurance", insurance_bet);
+        }
+        GameState::PlayPlayerTurn {
+            player_turn,
+            dealer_hand,
+            insurance_bet,
+        }
+        | GameState::PlayerStand {
+            player_turn,
+            dealer_hand,
+            insurance_bet,
+        }
+        | GameState::PlayerHit {
+            player_turn,
+            dealer_hand,
+            insurance_bet,
+        }
+        | GameState::PlayerDouble {
+            player_turn,
+            dealer_hand,
+            insurance_bet,
+        }
+        | GameState::PlayerSplit {
+            player_turn,
+            dealer_hand,
+            insurance_bet,
+        }
+        | GameState::PlayerSurrender {
+            player_turn,
+            dealer_hand,
+            insurance_bet,
+        } => {
+            line(s, "dealer", dealer(dealer_hand));
+            turn(s, player_turn);
+            line(s, "insurance", insurance_bet);
+        }
+        GameState::DealFirstSplitCard {
+            player_turn,
+            new_hand,
+            dealer_hand,
+            insurance_bet,
+        }
+        | GameState::DealSecondSplitCard {
+            player_turn,
+            new_hand,
+            dealer_hand,
+            insurance_bet,
+        } => {
+            line(s, "dealer", dealer(dealer_hand));
+            turn(s, player_turn);
+            line(s, "new hand", player(new_hand));
+            line(s, "insurance", insurance_bet);
+        }
+        GameState::RevealHoleCard {
+            finished_hands,
+            dealer_hand,
+            insurance_bet,
+        }
+        | GameState::PlayDealerTurn {
+            finished_hands,
+            dealer_hand,
+            insurance_bet,
+        }
+        | GameState::RoundOver {
+            finished_hands,
+            dealer_hand,
+            insurance_bet,
+        } => {
+            line(s, "dealer", dealer(dealer_hand));
+            for (i, hand) in finished_hands.iter().enumerate() {
+                line(s, &format!("hand {}", i + 1), player(hand));
+            }
+            line(s, "insurance", insurance_bet);
+        }
+        GameState::Payout { round } => {
+            let settlement = round.settlement();
+            line(s, "total bet", round.total_bet());
+            line(s, "total winnings", round.total_winnings());
+            for (i, result) in settlement.hands.iter().enumerate() {
+                line(s, &format!("result {}", i + 1), bet_result(result));
+            }
+            if let Some(result) = &settlement.insurance {
+                line(s, "insurance result", bet_result(result));
+            }
+        }
+    }
+    snapshot
+}
+
+/// The name of the state's variant.
+pub(crate) const fn name(state: &GameState) -> &'static str {
+    match state {
+        GameState::Betting { .. } => "Betting",
+        GameState::Dealing { .. } => "Dealing",
+        GameState::OfferEarlySurrender { .. } => "OfferEarlySurrender",
+        GameState::OfferInsurance { .. } => "OfferInsurance",
+        GameState::CheckDealerHoleCard { .. } => "CheckDealerHoleCard",
+        GameState::PlayPlayerTurn { .. } => "PlayPlayerTurn",
+        GameState::PlayerStand { .. } => "PlayerStand",
+        GameState::PlayerHit { .. } => "PlayerHit",
+        GameState::PlayerDouble { .. } => "PlayerDouble",
+        GameState::PlayerSplit { .. } => "PlayerSplit",
+        GameState::DealFirstSplitCard { .. } => "DealFirstSplitCard",
+        GameState::DealSecondSplitCard { .. } => "DealSecondSplitCard",
+        GameState::PlayerSurrender { .. } => "PlayerSurrender",
+        GameState::RevealHoleCard { .. } => "RevealHoleCard",
+        GameState::PlayDealerTurn { .. } => "PlayDealerTurn",
+        GameState::RoundOver { .. } => "RoundOver",
+        GameState::Payout { .. } => "Payout",
+        GameState::Shuffle => "Shuffle",
+        GameState::GameOver => "GameOver",
+    }
+}
+
+fn line(snapshot: &mut String, key: &str, value: impl std::fmt::Display) {
+    writeln!(snapshot, "{key}: {value}").unwrap();
+}
+
+/// Writes each of the player's hands, marking the one being played.
+fn turn(snapshot: &mut String, player_turn: &PlayerTurn) {
+    for (i, hand) in player_turn.all_hands().iter().enumerate() {
+        let key = if i == player_turn.current_hand_index() {
+            format!("hand {} (current)", i + 1)
+        } else {
+            format!("hand {}", i + 1)
+        };
+        line(snapshot, &key, player(hand));
+    }
+}
+
+fn cards(cards: &[Card]) -> String {
+    cards
+        .iter()
+        .map(|card| format!("{}{}", card.rank.symbol(), card.suit.symbol()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// e.g. "A♠ 7♥ (Soft 18, Stood)"
+fn dealer(hand: &DealerHand) -> String {
+    format!(
+        "{} ({}, {:?})",
+        cards(hand.cards()),
+        hand.value,
+        hand.status
+    )
+}
+
+/// e.g. "10♣ 6♥ (Hard 16, InPlay), bet 100", followed by how the hand was settled once it is
+fn player(hand: &PlayerHand) -> String {
+    let mut text = format!(
+        "{} ({}, {:?}), bet {}",
+        cards(&hand.cards),
+        hand.value,
+        hand.status,
+        hand.bet
+    );
+    if hand.visibility == Visibility::FaceDown {
+        text += ", face down";
+    }
+    if let Some(outcome) = hand.outcome {
+        write!(text, ", {outcome:?}, winnings {}", hand.winnings).unwrap();
+    }
+    text
+}
+
+/// e.g. "Win, bet 100, winnings 200"
+fn bet_result(result: &BetResult) -> String {
+    format!(
+        "{:?}, bet {}, winnings {}",
+        result.outcome, result.bet, result.winnings
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::basic_strategy;
+    use crate::card::shoe::Shoe;
+    use crate::game::{Input, Table};
+    use crate::narration::Narrator;
+    use crate::rules::Rules;
+
+    const GOLDEN: &str = include_str!("../snapshots/rounds.txt");
+
+    /// Plays a few seeded rounds with basic strategy, snapshotting every state along the way
+    /// with the dealer's narration of it.
+    /// Run with `UPDATE_SNAPSHOTS=1` to write the golden file again after an intended change.
+    #[test]
+    fn test_golden_rounds() {
+        let mut table = Table::new(1000, Shoe::seeded(6, 0.75, 5), Rules::default());
+        table.record_events = true;
+        let narrator = Narrator::default();
+        let mut transcript = String::new();
+        let mut state = GameState::default();
+        let mut rounds = 0;
+        while rounds < 6 {
+            let input = match state {
+                GameState::Betting { .. } => {
+                    rounds += 1;
+                    Some(Input::bet(100))
+                }
+                _ => basic_strategy::input(&table, &state),
+            };
+            state = table.progress(state, input).unwrap();
+            for event in table.drain_events() {
+                if let Some(narration) = narrator.narrate(&event) {
+                    writeln!(transcript, "> {narration}").unwrap();
+                }
+            }
+            transcript += &self::state(&state);
+        }
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            std::fs::write(
+                concat!(env!("CARGO_MANIFEST_DIR"), "/snapshots/rounds.txt"),
+                &transcript,
+            )
+            .unwrap();
+        } else {
+            assert_eq!(transcript, GOLDEN);
+        }
+    }
+}
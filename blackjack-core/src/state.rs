@@ -1,23 +1,127 @@
-use crate::card::hand::{DealerHand, PlayerHand, PlayerTurn};
+use crate::card::hand::{DealerHand, PlayerHand, PlayerTurn, RoundOutcome};
+use crate::card::shoe::Composition;
+use crate::timeline::Timeline;
 
-#[derive(Debug, Default, PartialEq, Eq)]
+/// The result of one of the player's bets at the end of a round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BetResult {
+    pub outcome: RoundOutcome,
+    /// The amount bet, including any double
+    pub bet: u32,
+    /// The amount paid back to the player, including the bet if it was returned
+    pub winnings: u32,
+}
+
+impl BetResult {
+    /// Settles a side bet, such as insurance, given what it paid back.
+    #[must_use]
+    pub fn new(bet: u32, winnings: u32) -> Self {
+        let outcome = match winnings.cmp(&bet) {
+            std::cmp::Ordering::Greater => RoundOutcome::Win,
+            std::cmp::Ordering::Equal => RoundOutcome::Push,
+            std::cmp::Ordering::Less => RoundOutcome::Lose,
+        };
+        Self {
+            outcome,
+            bet,
+            winnings,
+        }
+    }
+
+    /// The player's profit or loss on the bet.
+    #[must_use]
+    pub fn net(&self) -> i64 {
+        i64::from(self.winnings) - i64::from(self.bet)
+    }
+}
+
+impl From<&PlayerHand> for BetResult {
+    /// The result of a settled hand.
+    fn from(hand: &PlayerHand) -> Self {
+        Self {
+            outcome: hand.outcome.expect("hand should be settled"),
+            bet: hand.bet,
+            winnings: hand.winnings,
+        }
+    }
+}
+
+/// The results of each of the player's bets in a round.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Settlement {
+    /// The result of each hand, in the order they were split
+    pub hands: Vec<BetResult>,
+    /// The result of the insurance bet, if one was placed
+    pub insurance: Option<BetResult>,
+}
+
+/// A round as the table settled it, paid out to the player and handed to the statistics
+/// and to every round observer.
+/// Each hand carries its own bet, outcome, and winnings.
+#[derive(Debug, PartialEq, Eq)]
+pub struct FinishedRound {
+    /// The player's settled hands, in the order they were split
+    pub hands: Vec<PlayerHand>,
+    /// The number of spots the player bet on, whose hands come first
+    pub spots: u8,
+    pub dealer_hand: DealerHand,
+    /// The insurance bet, or 0 if the player didn't take insurance
+    pub insurance_bet: u32,
+    /// What the insurance bet paid back, including the bet itself if it won
+    pub insurance_winnings: u32,
+    /// What a coupon redeemed with the bet paid on top of the original hand's winnings
+    pub coupon_winnings: u32,
+    /// The cards left in the shoe once the round was dealt, e.g. for the count
+    pub shoe: Composition,
+    /// When each transition of the round was made, if the table was played with timestamps.
+    /// Boxed to keep the game state small.
+    pub timeline: Option<Box<Timeline>>,
+}
+
+impl FinishedRound {
+    /// The total the player bet in the round, including splits, doubles, and insurance.
+    #[must_use]
+    pub fn total_bet(&self) -> u32 {
+        self.hands.iter().map(|hand| hand.bet).sum::<u32>() + self.insurance_bet
+    }
+
+    /// The total paid back to the player, including every bet that was returned and what a coupon paid.
+    #[must_use]
+    pub fn total_winnings(&self) -> u32 {
+        self.hands.iter().map(|hand| hand.winnings).sum::<u32>()
+            + self.insurance_winnings
+            + self.coupon_winnings
+    }
+
+    /// The player's profit or loss on the round.
+    #[must_use]
+    pub fn net(&self) -> i64 {
+        i64::from(self.total_winnings()) - i64::from(self.total_bet())
+    }
+
+    /// The result of each of the player's bets, to keep once the round itself is gone.
+    #[must_use]
+    pub fn settlement(&self) -> Settlement {
+        Settlement {
+            hands: self.hands.iter().map(BetResult::from).collect(),
+            insurance: (self.insurance_bet > 0)
+                .then(|| BetResult::new(self.insurance_bet, self.insurance_winnings)),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
 pub enum GameState {
     /// The player is placing a bet.
-    #[default]
-    Betting,
-    /// The dealer is dealing the first card to the player.
-    DealFirstPlayerCard { bet: u32 },
-    /// The dealer is dealing the first card to themselves.
-    DealFirstDealerCard { player_hand: PlayerHand },
-    /// The dealer is dealing the second card to the player.
-    DealSecondPlayerCard {
-        player_hand: PlayerHand,
-        dealer_hand: DealerHand,
-    },
-    /// The dealer deals the hole card to themselves.
-    DealHoleCard {
-        player_hand: PlayerHand,
-        dealer_hand: DealerHand,
+    /// `last_bet` is the bet they placed on the last round, if any, so they can bet the same again.
+    Betting { last_bet: Option<u32> },
+    /// The dealer is dealing the opening cards, in the order the rules give.
+    /// `dealt` counts the cards dealt so far; the hands are created with their first cards.
+    Dealing {
+        bet: u32,
+        dealt: usize,
+        player_hand: Option<PlayerHand>,
+        dealer_hand: Option<DealerHand>,
     },
     /// The player has a chance to surrender early (before the dealer checks for blackjack).
     OfferEarlySurrender {
@@ -104,9 +208,94 @@ pub enum GameState {
         insurance_bet: u32,
     },
     /// The dealer is paying out the winnings.
-    Payout { total_bet: u32, total_winnings: u32 },
+    Payout { round: FinishedRound },
     /// The dealer is shuffling the shoe.
     Shuffle,
     /// The game is over.
     GameOver,
 }
+
+impl Default for GameState {
+    fn default() -> Self {
+        Self::Betting { last_bet: None }
+    }
+}
+
+impl GameState {
+    /// Returns the total the player has at risk in the current round:
+    /// the bets on all their hands, including splits and doubles, plus any insurance bet.
+    /// `spot_bets` are the table's bets on extra spots, which the state only holds from the player's turn.
+    #[must_use]
+    pub fn total_wagered(&self, spot_bets: &[u32]) -> u32 {
+        let spot_bets = spot_bets.iter().sum::<u32>();
+        match self {
+            Self::Betting { .. } | Self::Shuffle | Self::GameOver => 0,
+            Self::Dealing { bet, .. } => bet + spot_bets,
+            Self::OfferEarlySurrender { player_hand, .. }
+            | Self::OfferInsurance { player_hand, .. } => player_hand.bet + spot_bets,
+            Self::CheckDealerHoleCard {
+                player_hand,
+                insurance_bet,
+                ..
+            } => player_hand.bet + spot_bets + insurance_bet,
+            Self::PlayPlayerTurn {
+                player_turn,
+                insurance_bet,
+                ..
+            }
+            | Self::PlayerStand {
+                player_turn,
+                insurance_bet,
+                ..
+            }
+            | Self::PlayerHit {
+                player_turn,
+                insurance_bet,
+                ..
+            }
+            | Self::PlayerDouble {
+                player_turn,
+                insurance_bet,
+                ..
+            }
+            | Self::PlayerSplit {
+                player_turn,
+                insurance_bet,
+                ..
+            }
+            | Self::PlayerSurrender {
+                player_turn,
+                insurance_bet,
+                ..
+            } => player_turn.total_bet() + insurance_bet,
+            Self::DealFirstSplitCard {
+                player_turn,
+                new_hand,
+                insurance_bet,
+                ..
+            }
+            | Self::DealSecondSplitCard {
+                player_turn,
+                new_hand,
+                insurance_bet,
+                ..
+            } => player_turn.total_bet() + new_hand.bet + insurance_bet,
+            Self::RevealHoleCard {
+                finished_hands,
+                insurance_bet,
+                ..
+            }
+            | Self::PlayDealerTurn {
+                finished_hands,
+                insurance_bet,
+                ..
+            }
+            | Self::RoundOver {
+                finished_hands,
+                insurance_bet,
+                ..
+            } => finished_hands.iter().map(|hand| hand.bet).sum::<u32>() + insurance_bet,
+            Self::Payout { round } => round.total_bet(),
+        }
+    }
+}
@@ -1,11 +1,34 @@
-use crate::card::hand::{DealerHand, PlayerHand, Status};
-use std::cmp::Ordering;
+use crate::card::hand::{DealerHand, PlayerHand, RoundOutcome, Status};
+use crate::currency::{self, InCurrency};
+use crate::i18n::Catalog;
+use crate::timeline::Timeline;
+use crate::trainer::Decision;
 use std::fmt::Display;
+use std::time::Duration;
 
-#[derive(Debug, Default)]
+/// A group of related statistics, shown together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Section {
+    /// How the player's hands turned out
+    Outcomes,
+    /// The chips bet, won, and tipped
+    Money,
+    /// How the dealer's hands turned out
+    Dealer,
+    /// The cards dealt and how deep into each shoe
+    Count,
+}
+
+impl Section {
+    pub const ALL: [Self; 4] = [Self::Outcomes, Self::Money, Self::Dealer, Self::Count];
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct Statistics {
     turns_played: usize,
     hands_played: usize,
+    /// The spots the player bet on besides their main hand
+    extra_spots: usize,
     total_bet: usize,
     total_won: usize,
     wins: usize,
@@ -15,6 +38,32 @@ pub struct Statistics {
     busts: usize,
     dealer_blackjacks: usize,
     dealer_busts: usize,
+    /// The hands pushed by the dealer busting with 22
+    dealer_22_pushes: usize,
+    tips: usize,
+    insurance_bets: usize,
+    insurance_bet: usize,
+    insurance_won: usize,
+    cards_dealt: usize,
+    shuffles: usize,
+    /// The cards dealt from each shoe before it was shuffled, added up
+    shuffled_cards_dealt: usize,
+    /// The size of each shoe that was shuffled, added up
+    shuffled_shoe_cards: usize,
+    /// The rounds played with a timeline, and how long they took in milliseconds, added up
+    timed_rounds: usize,
+    round_millis: usize,
+    /// The decisions made in timed rounds, and how long they took in milliseconds, added up
+    timed_decisions: usize,
+    decision_millis: usize,
+    /// The decisions logged by the trainer, the mistakes among them,
+    /// and the chips the player expects to have lost to those mistakes in hundredths of a chip, added up
+    logged_decisions: usize,
+    mistakes: usize,
+    mistake_cost: usize,
+    /// The coupons the player used up, and what they paid on top of the hands they were redeemed on
+    coupons: usize,
+    coupon_won: usize,
 }
 
 impl Statistics {
@@ -23,6 +72,7 @@ impl Statistics {
         Self {
             turns_played: 0,
             hands_played: 0,
+            extra_spots: 0,
             total_bet: 0,
             total_won: 0,
             wins: 0,
@@ -32,23 +82,424 @@ impl Statistics {
             busts: 0,
             dealer_blackjacks: 0,
             dealer_busts: 0,
+            dealer_22_pushes: 0,
+            tips: 0,
+            insurance_bets: 0,
+            insurance_bet: 0,
+            insurance_won: 0,
+            cards_dealt: 0,
+            shuffles: 0,
+            shuffled_cards_dealt: 0,
+            shuffled_shoe_cards: 0,
+            timed_rounds: 0,
+            round_millis: 0,
+            timed_decisions: 0,
+            decision_millis: 0,
+            logged_decisions: 0,
+            mistakes: 0,
+            mistake_cost: 0,
+            coupons: 0,
+            coupon_won: 0,
+        }
+    }
+
+    /// The number of rounds the player has played.
+    #[must_use]
+    pub const fn turns_played(&self) -> usize {
+        self.turns_played
+    }
+
+    /// The number of hands the player has played, including split hands.
+    #[must_use]
+    pub const fn hands_played(&self) -> usize {
+        self.hands_played
+    }
+
+    /// The number of spots the player has bet on besides their main hand.
+    #[must_use]
+    pub const fn extra_spots(&self) -> usize {
+        self.extra_spots
+    }
+
+    /// Record the spots the player bet on in a round, including their main hand.
+    pub fn record_spots(&mut self, spots: u8) {
+        self.extra_spots += usize::from(spots.saturating_sub(1));
+    }
+
+    /// The number of blackjacks the player has been dealt.
+    #[must_use]
+    pub const fn blackjacks(&self) -> usize {
+        self.blackjacks
+    }
+
+    /// The total amount the player has tipped the dealer.
+    #[must_use]
+    pub const fn tips(&self) -> usize {
+        self.tips
+    }
+
+    /// Record a tip given to the dealer.
+    pub fn record_tip(&mut self, tip: u32) {
+        self.tips = self.tips.saturating_add(tip as usize);
+    }
+
+    /// The number of insurance bets the player has placed.
+    #[must_use]
+    pub const fn insurance_bets(&self) -> usize {
+        self.insurance_bets
+    }
+
+    /// The player's net result from insurance bets.
+    #[must_use]
+    pub fn insurance_net(&self) -> i64 {
+        self.insurance_won as i64 - self.insurance_bet as i64
+    }
+
+    /// The expected value of insurance per chip bet on it, or None if the player has never taken it.
+    #[must_use]
+    pub fn insurance_ev(&self) -> Option<f64> {
+        (self.insurance_bet > 0).then(|| self.insurance_net() as f64 / self.insurance_bet as f64)
+    }
+
+    /// Record an insurance bet and what it paid back, including the bet itself if it won.
+    pub fn record_insurance(&mut self, bet: u32, winnings: u32) {
+        self.insurance_bets += 1;
+        self.insurance_bet = self.insurance_bet.saturating_add(bet as usize);
+        self.insurance_won = self.insurance_won.saturating_add(winnings as usize);
+    }
+
+    /// The number of coupons the player has used up.
+    #[must_use]
+    pub const fn coupons(&self) -> usize {
+        self.coupons
+    }
+
+    /// The chips a coupon is worth on average, or None if the player has never used one up.
+    #[must_use]
+    pub fn coupon_ev(&self) -> Option<f64> {
+        (self.coupons > 0).then(|| self.coupon_won as f64 / self.coupons as f64)
+    }
+
+    /// Record a coupon used up and what it paid on top of the hand it was redeemed on.
+    pub fn record_coupon(&mut self, winnings: u32) {
+        self.coupons += 1;
+        self.coupon_won = self.coupon_won.saturating_add(winnings as usize);
+    }
+
+    /// Record a card dealt in a round the player played.
+    pub fn record_card(&mut self) {
+        self.cards_dealt += 1;
+    }
+
+    /// Record a shuffle of a shoe with the given number of cards, after the given number were dealt.
+    pub fn record_shuffle(&mut self, cards_dealt: usize, shoe_cards: usize) {
+        self.shuffles += 1;
+        self.shuffled_cards_dealt = self.shuffled_cards_dealt.saturating_add(cards_dealt);
+        self.shuffled_shoe_cards = self.shuffled_shoe_cards.saturating_add(shoe_cards);
+    }
+
+    /// Adds the shuffles recorded in other statistics to these.
+    pub(crate) fn merge_shuffles(&mut self, other: &Self) {
+        self.shuffles += other.shuffles;
+        self.shuffled_cards_dealt += other.shuffled_cards_dealt;
+        self.shuffled_shoe_cards += other.shuffled_shoe_cards;
+    }
+
+    /// The number of times the shoe has been shuffled.
+    #[must_use]
+    pub const fn shuffles(&self) -> usize {
+        self.shuffles
+    }
+
+    /// The average number of cards dealt in each round the player played, or None before the first.
+    #[must_use]
+    pub fn cards_per_round(&self) -> Option<f64> {
+        (self.turns_played > 0).then(|| self.cards_dealt as f64 / self.turns_played as f64)
+    }
+
+    /// The average number of rounds the player played per shoe, or None before the first shuffle.
+    #[must_use]
+    pub fn rounds_per_shoe(&self) -> Option<f64> {
+        (self.shuffles > 0).then(|| self.turns_played as f64 / self.shuffles as f64)
+    }
+
+    /// The average proportion of each shoe dealt before it was shuffled,
+    /// or None before the first shuffle.
+    #[must_use]
+    pub fn penetration(&self) -> Option<f64> {
+        (self.shuffled_shoe_cards > 0)
+            .then(|| self.shuffled_cards_dealt as f64 / self.shuffled_shoe_cards as f64)
+    }
+
+    /// Record how long a round took, and each decision in it.
+    pub fn record_timeline(&mut self, timeline: &Timeline) {
+        let millis =
+            |duration: Duration| usize::try_from(duration.as_millis()).unwrap_or(usize::MAX);
+        self.timed_rounds += 1;
+        self.round_millis = self
+            .round_millis
+            .saturating_add(millis(timeline.duration()));
+        for latency in timeline.decision_latencies() {
+            self.timed_decisions += 1;
+            self.decision_millis = self.decision_millis.saturating_add(millis(latency));
+        }
+    }
+
+    /// Record a decision logged by the trainer, and what it cost if it was a mistake.
+    pub fn record_decision(&mut self, decision: &Decision) {
+        self.logged_decisions += 1;
+        if decision.is_mistake() {
+            self.mistakes += 1;
+            let cost = (decision.ev_cost * 100.0).round() as usize;
+            self.mistake_cost = self.mistake_cost.saturating_add(cost);
+        }
+    }
+
+    /// The chips the player expects to lose per 100 hands by deviating from the best play,
+    /// or None if the trainer has not logged any decisions.
+    #[must_use]
+    pub fn cost_of_errors(&self) -> Option<f64> {
+        (self.logged_decisions > 0 && self.hands_played > 0)
+            .then(|| self.mistake_cost as f64 / self.hands_played as f64)
+    }
+
+    /// The average time the player took over a round, or None if no round was timed.
+    #[must_use]
+    pub fn average_round_duration(&self) -> Option<Duration> {
+        (self.timed_rounds > 0)
+            .then(|| Duration::from_millis((self.round_millis / self.timed_rounds) as u64))
+    }
+
+    /// The average time the player took over a decision, or None if no decision was timed.
+    #[must_use]
+    pub fn average_decision_latency(&self) -> Option<Duration> {
+        (self.timed_decisions > 0)
+            .then(|| Duration::from_millis((self.decision_millis / self.timed_decisions) as u64))
+    }
+
+    /// Returns every counter by name, for saving and restoring the statistics.
+    pub(crate) fn counters_mut(&mut self) -> [(&'static str, &mut usize); 30] {
+        [
+            ("turns_played", &mut self.turns_played),
+            ("hands_played", &mut self.hands_played),
+            ("extra_spots", &mut self.extra_spots),
+            ("total_bet", &mut self.total_bet),
+            ("total_won", &mut self.total_won),
+            ("wins", &mut self.wins),
+            ("pushes", &mut self.pushes),
+            ("losses", &mut self.losses),
+            ("blackjacks", &mut self.blackjacks),
+            ("busts", &mut self.busts),
+            ("dealer_blackjacks", &mut self.dealer_blackjacks),
+            ("dealer_busts", &mut self.dealer_busts),
+            ("dealer_22_pushes", &mut self.dealer_22_pushes),
+            ("tips", &mut self.tips),
+            ("insurance_bets", &mut self.insurance_bets),
+            ("insurance_bet", &mut self.insurance_bet),
+            ("insurance_won", &mut self.insurance_won),
+            ("cards_dealt", &mut self.cards_dealt),
+            ("shuffles", &mut self.shuffles),
+            ("shuffled_cards_dealt", &mut self.shuffled_cards_dealt),
+            ("shuffled_shoe_cards", &mut self.shuffled_shoe_cards),
+            ("timed_rounds", &mut self.timed_rounds),
+            ("round_millis", &mut self.round_millis),
+            ("timed_decisions", &mut self.timed_decisions),
+            ("decision_millis", &mut self.decision_millis),
+            ("logged_decisions", &mut self.logged_decisions),
+            ("mistakes", &mut self.mistakes),
+            ("mistake_cost", &mut self.mistake_cost),
+            ("coupons", &mut self.coupons),
+            ("coupon_won", &mut self.coupon_won),
+        ]
+    }
+
+    /// Adds the statistics of another game to these, as if they were one game.
+    pub fn merge(&mut self, other: &Self) {
+        let mut other = other.clone();
+        for ((_, total), (_, value)) in self.counters_mut().into_iter().zip(other.counters_mut()) {
+            *total = total.saturating_add(*value);
+        }
+    }
+
+    /// Returns the statistics in a section by name, with their values formatted for display,
+    /// in English and in chips.
+    #[must_use]
+    pub fn section(&self, section: Section) -> Vec<(String, String)> {
+        self.section_in(section, &Catalog::default())
+    }
+
+    /// Returns the statistics in a section by name, with their names and values in the catalog's
+    /// language and amounts of chips shown in its currency, if it has one.
+    #[must_use]
+    pub fn section_in(&self, section: Section, catalog: &Catalog) -> Vec<(String, String)> {
+        fn pct(num: usize, div: usize) -> String {
+            if div == 0 {
+                "0.0".to_string()
+            } else {
+                format!("{:.2}", num as f64 / div as f64 * 100.0)
+            }
         }
+        fn div(num: usize, div: usize) -> f64 {
+            if div == 0 {
+                0.0
+            } else {
+                num as f64 / div as f64
+            }
+        }
+        let currency = catalog.currency.as_ref();
+        let chips = |chips: f64, precision: usize| currency::chips(chips, precision, currency);
+        let share = |count: usize| {
+            catalog.format(
+                "statistics.share",
+                &[
+                    ("count", &count),
+                    ("percent", &pct(count, self.hands_played)),
+                ],
+            )
+        };
+        let seconds = |duration: Duration| {
+            let seconds = format!("{:.1}", duration.as_secs_f64());
+            catalog.format("statistics.seconds", &[("seconds", &seconds)])
+        };
+
+        let mut stats = Vec::new();
+        let mut push = |key: &str, value: String| stats.push((catalog.get(key).to_string(), value));
+        match section {
+            Section::Outcomes => {
+                push("statistics.turns-played", self.turns_played.to_string());
+                push("statistics.hands-played", self.hands_played.to_string());
+                if self.extra_spots > 0 {
+                    push("statistics.extra-spots", self.extra_spots.to_string());
+                }
+                push("statistics.wins", share(self.wins));
+                push("statistics.pushes", share(self.pushes));
+                push("statistics.losses", share(self.losses));
+                push("statistics.blackjacks", share(self.blackjacks));
+                push("statistics.busts", share(self.busts));
+                if let Some(cost) = self.cost_of_errors() {
+                    push(
+                        "statistics.mistakes",
+                        catalog.format(
+                            "statistics.mistakes-value",
+                            &[
+                                ("count", &self.mistakes),
+                                ("percent", &pct(self.mistakes, self.logged_decisions)),
+                            ],
+                        ),
+                    );
+                    push(
+                        "statistics.cost-of-errors",
+                        catalog.format(
+                            "statistics.cost-of-errors-value",
+                            &[("cost", &chips(cost, 2))],
+                        ),
+                    );
+                }
+                if let Some(duration) = self.average_round_duration() {
+                    push("statistics.average-round-time", seconds(duration));
+                }
+                if let Some(latency) = self.average_decision_latency() {
+                    push("statistics.average-decision-time", seconds(latency));
+                }
+            }
+            Section::Money => {
+                push("statistics.total-bet", chips(self.total_bet as f64, 0));
+                push(
+                    "statistics.average-bet",
+                    chips(div(self.total_bet, self.hands_played), 2),
+                );
+                push("statistics.total-won", chips(self.total_won as f64, 0));
+                push(
+                    "statistics.average-win",
+                    chips(div(self.total_won, self.hands_played), 2),
+                );
+                push("statistics.tips", chips(self.tips as f64, 0));
+                push("statistics.insurance-bets", self.insurance_bets.to_string());
+                if let Some(ev) = self.insurance_ev() {
+                    push(
+                        "statistics.insurance-net",
+                        catalog.format(
+                            "statistics.insurance-net-value",
+                            &[
+                                ("net", &chips(self.insurance_net() as f64, 0)),
+                                ("ev", &format!("{:+.2}", ev * 100.0)),
+                            ],
+                        ),
+                    );
+                }
+                if let Some(ev) = self.coupon_ev() {
+                    push(
+                        "statistics.coupons",
+                        catalog.format(
+                            "statistics.coupons-value",
+                            &[("count", &self.coupons), ("value", &chips(ev, 2))],
+                        ),
+                    );
+                }
+            }
+            Section::Dealer => {
+                push(
+                    "statistics.dealer-blackjacks",
+                    share(self.dealer_blackjacks),
+                );
+                push("statistics.dealer-busts", share(self.dealer_busts));
+                if self.dealer_22_pushes > 0 {
+                    push("statistics.dealer-22-pushes", share(self.dealer_22_pushes));
+                }
+            }
+            Section::Count => {
+                if let Some(cards) = self.cards_per_round() {
+                    push("statistics.cards-per-round", format!("{cards:.2}"));
+                }
+                push("statistics.shuffles", self.shuffles.to_string());
+                if let (Some(rounds), Some(penetration)) =
+                    (self.rounds_per_shoe(), self.penetration())
+                {
+                    push("statistics.rounds-per-shoe", format!("{rounds:.2}"));
+                    push(
+                        "statistics.average-penetration",
+                        format!("{:.2}%", penetration * 100.0),
+                    );
+                }
+            }
+        }
+        stats
+    }
+
+    /// Returns every section of the statistics, one `name: value` line for each statistic,
+    /// in the catalog's language and currency.
+    #[must_use]
+    pub fn report(&self, catalog: &Catalog) -> String {
+        let mut report = String::new();
+        for section in Section::ALL {
+            for (name, value) in self.section_in(section, catalog) {
+                report.push_str(
+                    &catalog.format("statistics.line", &[("name", &name), ("value", &value)]),
+                );
+                report.push('\n');
+            }
+        }
+        report
     }
 
     /// Update the statistics with the results of a round of blackjack.
-    pub fn update(&mut self, player_hands: Vec<PlayerHand>, dealer_hand: DealerHand) {
+    pub fn update(&mut self, player_hands: &[PlayerHand], dealer_hand: &DealerHand) {
         self.turns_played += 1;
         self.hands_played += player_hands.len();
-        for hand in &player_hands {
+        for hand in player_hands {
             match hand.status {
                 Status::Blackjack => self.blackjacks += 1,
                 Status::Bust => self.busts += 1,
                 _ => {}
             }
-            match hand.winnings.cmp(&hand.bet) {
-                Ordering::Greater => self.wins += 1,
-                Ordering::Equal => self.pushes += 1,
-                Ordering::Less => self.losses += 1,
+            match hand.outcome {
+                Some(outcome) if outcome.is_win() => self.wins += 1,
+                Some(RoundOutcome::Push) => self.pushes += 1,
+                Some(RoundOutcome::Push22) => {
+                    self.pushes += 1;
+                    self.dealer_22_pushes += 1;
+                }
+                _ => self.losses += 1,
             }
             self.total_bet = self.total_bet.saturating_add(hand.bet as usize);
             self.total_won = self.total_won.saturating_add(hand.winnings as usize);
@@ -63,79 +514,15 @@ impl Statistics {
 
 impl Display for Statistics {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        fn pct(num: usize, div: usize) -> String {
-            if div == 0 {
-                "0.0".to_string()
-            } else {
-                format!("{:.2}", num as f64 / div as f64 * 100.0)
-            }
-        }
-        fn div(num: usize, div: usize) -> String {
-            if div == 0 {
-                "0.0".to_string()
-            } else {
-                format!("{:.2}", num as f64 / div as f64)
-            }
-        }
-
-        writeln!(f, "Turns Played: {}", self.turns_played)?;
-        writeln!(f, "Hands Played: {}", self.hands_played)?;
-        writeln!(f, "Total Bet: {} Chips", self.total_bet)?;
-        writeln!(
-            f,
-            "Average Bet: {} Chips",
-            div(self.total_bet, self.hands_played)
-        )?;
-        writeln!(f, "Total Won: {} Chips", self.total_won)?;
-        writeln!(
-            f,
-            "Average Win: {} Chips",
-            div(self.total_won, self.hands_played)
-        )?;
-        writeln!(
-            f,
-            "Wins: {} ({}%)",
-            self.wins,
-            pct(self.wins, self.hands_played)
-        )?;
-        writeln!(
-            f,
-            "Pushes: {} ({}%)",
-            self.pushes,
-            pct(self.pushes, self.hands_played)
-        )?;
-        writeln!(
-            f,
-            "Losses: {} ({}%)",
-            self.losses,
-            pct(self.losses, self.hands_played)
-        )?;
-        writeln!(
-            f,
-            "Blackjacks: {} ({}%)",
-            self.blackjacks,
-            pct(self.blackjacks, self.hands_played)
-        )?;
-        writeln!(
-            f,
-            "Busts: {} ({}%)",
-            self.busts,
-            pct(self.busts, self.hands_played)
-        )?;
-        writeln!(
-            f,
-            "Dealer Blackjacks: {} ({}%)",
-            self.dealer_blackjacks,
-            pct(self.dealer_blackjacks, self.hands_played)
-        )?;
-        writeln!(
-            f,
-            "Dealer Busts: {} ({}%)",
-            self.dealer_busts,
-            pct(self.dealer_busts, self.hands_played)
-        )?;
-
-        Ok(())
+        InCurrency::new(self, None).fmt(f)
     }
 }
 
+impl Display for InCurrency<'_, Statistics> {
+    /// Writes the statistics in English, through the English catalog.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut catalog = Catalog::default();
+        catalog.currency = self.currency.cloned();
+        f.write_str(&self.value.report(&catalog))
+    }
+}
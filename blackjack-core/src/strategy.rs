@@ -0,0 +1,442 @@
+//! Strategies decide the player's input whenever the game needs one.
+//! Besides basic strategy, there are exploring strategies which make random (but legal) decisions,
+//! for generating training data and for stress-testing the state machine.
+
+use std::collections::BTreeMap;
+
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::basic_strategy;
+use crate::betting::{BettingStrategy, WithBetting};
+use crate::card::hand::{PlayerHand, Status};
+use crate::card::shoe::Composition;
+use crate::counting::TrueCount;
+use crate::ev;
+use crate::game::{HandAction, Input, Table};
+use crate::state::GameState;
+
+/// Decides the player's input at a table.
+pub trait Strategy {
+    /// Returns the input for the given state, or None if the state does not require any input.
+    /// The composition of the cards the player has not seen is given for strategies that play on it;
+    /// others can ignore it. This includes the dealer's hole card while it is face down.
+    fn input(
+        &mut self,
+        table: &Table,
+        state: &GameState,
+        composition: &Composition,
+    ) -> Option<Input>;
+
+    /// Saves what the strategy has learned from the rounds so far as `key = value` pairs for a checkpoint,
+    /// e.g. where its betting progression stands.
+    fn save(&self, _state: &mut BTreeMap<String, String>) {}
+
+    /// Restores what the strategy had learned from the pairs it saved.
+    /// # Errors
+    /// Returns an error if a saved value is missing or invalid.
+    fn restore(&mut self, _state: &BTreeMap<String, String>) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Combines this strategy with a betting strategy, which then decides every bet.
+    fn with_betting<B: BettingStrategy>(self, betting: B) -> WithBetting<Self, B>
+    where
+        Self: Sized,
+    {
+        WithBetting::new(self, betting)
+    }
+
+    /// Combines this strategy with an insurance strategy, which then decides every insurance bet.
+    fn with_insurance<I: InsuranceStrategy>(self, insurance: I) -> WithInsurance<Self, I>
+    where
+        Self: Sized,
+    {
+        WithInsurance {
+            strategy: self,
+            insurance,
+        }
+    }
+}
+
+/// Plays basic strategy.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BasicStrategy;
+
+impl Strategy for BasicStrategy {
+    fn input(&mut self, table: &Table, state: &GameState, _: &Composition) -> Option<Input> {
+        basic_strategy::input(table, state)
+    }
+}
+
+/// Makes every decision at random among the legal options, betting flat like basic strategy.
+#[derive(Debug, Clone)]
+pub struct RandomStrategy {
+    rng: StdRng,
+}
+
+impl Default for RandomStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RandomStrategy {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            rng: StdRng::from_os_rng(),
+        }
+    }
+
+    /// Creates a random strategy which makes the same decisions every time for the same seed.
+    #[must_use]
+    pub fn seeded(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Strategy for RandomStrategy {
+    fn input(&mut self, table: &Table, state: &GameState, _: &Composition) -> Option<Input> {
+        match state {
+            GameState::OfferEarlySurrender { .. } => Some(Input::choice(self.rng.random_bool(0.5))),
+            GameState::OfferInsurance { player_hand, .. } => {
+                let insurance = if self.rng.random_bool(0.5) {
+                    table.max_insurance(player_hand)
+                } else {
+                    0
+                };
+                Some(Input::bet(insurance))
+            }
+            GameState::PlayPlayerTurn { player_turn, .. } => table
+                .allowed_actions(player_turn)
+                .choose(&mut self.rng)
+                .copied()
+                .map(Input::action),
+            state => basic_strategy::input(table, state),
+        }
+    }
+}
+
+/// Follows another strategy, but deviates to a random legal decision with probability epsilon.
+/// Bets are always left to the other strategy.
+#[derive(Debug, Clone)]
+pub struct EpsilonGreedy<S = BasicStrategy> {
+    /// The probability of deviating on any decision
+    pub epsilon: f64,
+    /// The strategy followed when not deviating
+    pub strategy: S,
+    /// The number of decisions made at random so far
+    pub deviations: usize,
+    random: RandomStrategy,
+}
+
+impl<S: Strategy> EpsilonGreedy<S> {
+    #[must_use]
+    pub fn new(epsilon: f64, strategy: S) -> Self {
+        Self::with_random(epsilon, strategy, RandomStrategy::new())
+    }
+
+    /// Creates an epsilon-greedy strategy which deviates the same way every time for the same seed.
+    #[must_use]
+    pub fn seeded(epsilon: f64, strategy: S, seed: u64) -> Self {
+        Self::with_random(epsilon, strategy, RandomStrategy::seeded(seed))
+    }
+
+    const fn with_random(epsilon: f64, strategy: S, random: RandomStrategy) -> Self {
+        Self {
+            epsilon,
+            strategy,
+            deviations: 0,
+            random,
+        }
+    }
+}
+
+impl<S: Strategy> Strategy for EpsilonGreedy<S> {
+    fn input(
+        &mut self,
+        table: &Table,
+        state: &GameState,
+        composition: &Composition,
+    ) -> Option<Input> {
+        let input = self.strategy.input(table, state, composition);
+        let decision = input.is_some() && !matches!(*state, GameState::Betting { .. });
+        if decision && self.random.rng.random_bool(self.epsilon) {
+            self.deviations += 1;
+            return self.random.input(table, state, composition);
+        }
+        input
+    }
+}
+
+/// Follows another strategy, but stands on a stiff hard hand instead of hitting it
+/// when the cards left in the shoe make hitting too likely to bust,
+/// e.g. standing on 16 against a 10 when the fours and fives are gone.
+#[derive(Debug, Clone)]
+pub struct CompositionDependent<S = BasicStrategy> {
+    /// The strategy followed when the composition does not call for a deviation
+    pub strategy: S,
+    /// The lowest chance of not busting for which a stiff hand is still hit
+    pub min_safe_hit: f64,
+}
+
+impl<S: Strategy> CompositionDependent<S> {
+    #[must_use]
+    pub const fn new(strategy: S) -> Self {
+        Self {
+            strategy,
+            min_safe_hit: 0.3,
+        }
+    }
+}
+
+impl<S: Strategy> Strategy for CompositionDependent<S> {
+    fn input(
+        &mut self,
+        table: &Table,
+        state: &GameState,
+        composition: &Composition,
+    ) -> Option<Input> {
+        let input = self.strategy.input(table, state, composition);
+        if let (
+            Some(Input::Action {
+                action: HandAction::Hit,
+                ..
+            }),
+            GameState::PlayPlayerTurn { player_turn, .. },
+        ) = (&input, state)
+        {
+            let value = player_turn.current_hand().value;
+            if !value.soft
+                && (12..=16).contains(&value.total)
+                && safe_hit_chance(value.total, composition) < self.min_safe_hit
+            {
+                return Some(Input::action(HandAction::Stand));
+            }
+        }
+        input
+    }
+}
+
+/// Returns the chance that hitting a hard hand with the given total does not bust.
+fn safe_hit_chance(total: u8, composition: &Composition) -> f64 {
+    let cards = composition.total();
+    if cards == 0 {
+        return 0.0;
+    }
+    // Aces count as 1 on a hard hand, so they never bust it
+    let safe: u16 = (2..=21u8.saturating_sub(total).min(10))
+        .map(|worth| composition.remaining_worth(worth))
+        .sum::<u16>()
+        + composition.remaining_worth(11);
+    f64::from(safe) / f64::from(cards)
+}
+
+/// Decides how much insurance to take when the dealer shows an ace.
+pub trait InsuranceStrategy {
+    /// Returns the insurance bet for the hand, which must be at most half its bet
+    /// and no more than the player's chips.
+    fn insurance(
+        &mut self,
+        table: &Table,
+        player_hand: &PlayerHand,
+        composition: &Composition,
+    ) -> u32;
+}
+
+/// Takes full insurance once the Hi-Lo true count reaches a threshold,
+/// when enough tens are left for insurance to be a winning bet,
+/// or whenever the exact chance of dealer blackjack makes it one.
+/// Can also always take even money, which is full insurance on a blackjack.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CountedInsurance {
+    /// The lowest true count insurance is taken at, or None to never take it on the count
+    pub min_true_count: Option<f32>,
+    /// Whether to take even money on a blackjack, regardless of the count
+    pub even_money: bool,
+    /// Whether to take insurance whenever the unseen cards make it a winning bet, regardless of the count
+    pub favorable: bool,
+    /// How the true count is worked out, which is not rounded for the insurance decision
+    pub true_count: TrueCount,
+}
+
+impl Default for CountedInsurance {
+    fn default() -> Self {
+        Self {
+            min_true_count: Some(3.0),
+            even_money: false,
+            favorable: false,
+            true_count: TrueCount::default(),
+        }
+    }
+}
+
+impl InsuranceStrategy for CountedInsurance {
+    fn insurance(
+        &mut self,
+        table: &Table,
+        player_hand: &PlayerHand,
+        composition: &Composition,
+    ) -> u32 {
+        let even_money = self.even_money && player_hand.status == Status::Blackjack;
+        let count = self
+            .min_true_count
+            .is_some_and(|min| self.true_count.of(composition) >= min);
+        let favorable = self.favorable && ev::insurance_ev(composition) > 0.0;
+        if even_money || count || favorable {
+            table.max_insurance(player_hand)
+        } else {
+            0
+        }
+    }
+}
+
+/// Plays another strategy, but leaves the insurance decision to an insurance strategy.
+#[derive(Debug, Clone)]
+pub struct WithInsurance<S, I> {
+    pub strategy: S,
+    pub insurance: I,
+}
+
+impl<S: Strategy, I: InsuranceStrategy> Strategy for WithInsurance<S, I> {
+    fn input(
+        &mut self,
+        table: &Table,
+        state: &GameState,
+        composition: &Composition,
+    ) -> Option<Input> {
+        match state {
+            GameState::OfferInsurance { player_hand, .. } => Some(Input::bet(
+                self.insurance.insurance(table, player_hand, composition),
+            )),
+            state => self.strategy.input(table, state, composition),
+        }
+    }
+
+    fn save(&self, state: &mut BTreeMap<String, String>) {
+        self.strategy.save(state);
+    }
+
+    fn restore(&mut self, state: &BTreeMap<String, String>) -> Result<(), String> {
+        self.strategy.restore(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::hand::{DealerHand, PlayerHand, PlayerTurn};
+    use crate::card::shoe::Shoe;
+    use crate::card::{Card, Rank, Suit};
+    use crate::rules::{DealerDrawing, Rules};
+    use crate::simulation::Simulator;
+
+    #[test]
+    fn test_random_play() {
+        let rules = Rules {
+            insurance: true,
+            early_surrender: true,
+            ..Rules::default()
+        };
+        let table = Table::new(u32::MAX / 2, Shoe::new(6, 0.75), rules.clone());
+        let mut simulator = Simulator::with_strategy(table, RandomStrategy::seeded(7));
+        simulator.run(2000);
+        assert_eq!(simulator.report.rounds_played, 2000);
+
+        let table = Table::new(u32::MAX / 2, Shoe::new(6, 0.75), rules);
+        let strategy = EpsilonGreedy::seeded(0.25, BasicStrategy, 7);
+        let mut simulator = Simulator::with_strategy(table, strategy);
+        simulator.run(2000);
+        assert_eq!(simulator.report.rounds_played, 2000);
+        assert!(simulator.strategy.deviations > 0);
+    }
+
+    #[test]
+    fn test_composition_dependent() {
+        let rules = Rules {
+            late_surrender: false,
+            ..Rules::default()
+        };
+        let table = Table::new(1000, Shoe::new(1, 0.75), rules);
+        let mut player_hand = PlayerHand::new(
+            Card {
+                rank: Rank::Ten,
+                suit: Suit::Clubs,
+            },
+            100,
+        );
+        player_hand += Card {
+            rank: Rank::Six,
+            suit: Suit::Clubs,
+        };
+        let state = GameState::PlayPlayerTurn {
+            player_turn: PlayerTurn::from(player_hand),
+            dealer_hand: DealerHand::new(
+                Card {
+                    rank: Rank::Ten,
+                    suit: Suit::Hearts,
+                },
+                DealerDrawing::S17,
+            ),
+            insurance_bet: 0,
+        };
+        let mut strategy = CompositionDependent::new(BasicStrategy);
+        let full = table.shoe.composition();
+        assert_eq!(
+            strategy.input(&table, &state, &full),
+            Some(Input::action(HandAction::Hit))
+        );
+        let no_fours_or_fives = Composition::from([4, 4, 0, 0, 4, 4, 4, 4, 2, 3, 4, 4, 4]);
+        assert_eq!(
+            strategy.input(&table, &state, &no_fours_or_fives),
+            Some(Input::action(HandAction::Stand))
+        );
+    }
+
+    #[test]
+    fn test_insurance() {
+        let rules = Rules {
+            insurance: true,
+            ..Rules::default()
+        };
+        let table = Table::new(1000, Shoe::new(6, 0.75), rules.clone());
+        let mut blackjack = PlayerHand::new(
+            Card {
+                rank: Rank::Ace,
+                suit: Suit::Clubs,
+            },
+            100,
+        );
+        blackjack += Card {
+            rank: Rank::King,
+            suit: Suit::Clubs,
+        };
+        let composition = table.shoe.composition();
+        let mut insurance = CountedInsurance::default();
+        assert_eq!(insurance.insurance(&table, &blackjack, &composition), 0);
+        insurance.even_money = true;
+        assert_eq!(insurance.insurance(&table, &blackjack, &composition), 50);
+        let tens = Composition::from([0, 0, 0, 0, 0, 0, 0, 0, 4, 4, 4, 4, 4]);
+        let mut favorable = CountedInsurance {
+            min_true_count: None,
+            even_money: false,
+            favorable: true,
+            true_count: TrueCount::default(),
+        };
+        assert_eq!(favorable.insurance(&table, &blackjack, &composition), 0);
+        assert_eq!(favorable.insurance(&table, &blackjack, &tens), 50);
+
+        let table = Table::new(u32::MAX / 2, Shoe::new(6, 0.75), rules);
+        let strategy = BasicStrategy.with_insurance(CountedInsurance {
+            min_true_count: Some(f32::MIN),
+            ..CountedInsurance::default()
+        });
+        let mut simulator = Simulator::with_strategy(table, strategy);
+        simulator.run(2000);
+        assert!(simulator.table.statistics.insurance_bets() > 0);
+    }
+}
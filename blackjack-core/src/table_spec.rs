@@ -0,0 +1,217 @@
+//! Tables written in the usual shorthand, so every front-end builds the same table from the same flags.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::card::shoe::Shoe;
+use crate::game::Table;
+use crate::rules::{BlackjackPayout, PayoutRounding, PushPolicy, Rules};
+
+/// A table written in the usual shorthand, e.g. "6D,H17,DAS,LS" or "1D,S17,NDAS,NS,6:5,80%",
+/// the last number being the penetration.
+/// Anything left out is taken from the default rules with six decks dealt to 75%.
+#[derive(Debug, Clone)]
+pub struct TableSpec {
+    pub decks: u8,
+    /// The proportion of the shoe dealt before shuffling
+    pub penetration: f32,
+    pub rules: Rules,
+}
+
+impl Default for TableSpec {
+    fn default() -> Self {
+        Self {
+            decks: 6,
+            penetration: 0.75,
+            rules: Rules::default(),
+        }
+    }
+}
+
+impl TableSpec {
+    /// Returns the table dealing to the given penetration instead of its own, if one is given.
+    #[must_use]
+    pub fn with_penetration(self, penetration: Option<f32>) -> Self {
+        Self {
+            penetration: penetration.unwrap_or(self.penetration),
+            ..self
+        }
+    }
+
+    /// Returns a freshly shuffled shoe for the table.
+    #[must_use]
+    pub fn shoe(&self) -> Shoe {
+        Shoe::new(self.decks, self.penetration)
+    }
+
+    /// Returns a table with the given bankroll, dealing from a freshly shuffled shoe.
+    #[must_use]
+    pub fn table(&self, chips: u32) -> Table {
+        Table::new(chips, self.shoe(), self.rules.clone())
+    }
+}
+
+impl FromStr for TableSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut spec = Self::default();
+        for token in s.split([',', ' ']).filter(|token| !token.is_empty()) {
+            match token.to_ascii_uppercase().as_str() {
+                "DAS" => spec.rules.double_after_split = true,
+                "NDAS" => spec.rules.double_after_split = false,
+                "LS" => spec.rules.late_surrender = true,
+                "ES" => spec.rules.early_surrender = true,
+                "NS" => {
+                    spec.rules.late_surrender = false;
+                    spec.rules.early_surrender = false;
+                }
+                "RSA" => spec.rules.split_aces = true,
+                "NRSA" => spec.rules.split_aces = false,
+                "INS" => spec.rules.insurance = true,
+                "NINS" => spec.rules.insurance = false,
+                "NMSE" => spec.rules.no_mid_shoe_entry = true,
+                "MSE" => spec.rules.no_mid_shoe_entry = false,
+                "FD" => spec.rules.face_down = true,
+                "FU" => spec.rules.face_down = false,
+                "P22" => spec.rules.dealer_push_22 = true,
+                "3:2" => spec.rules.blackjack_payout = BlackjackPayout::ThreeToTwo,
+                "6:5" => spec.rules.blackjack_payout = BlackjackPayout::SixToFive,
+                "ROUNDDOWN" => spec.rules.payout_rounding = PayoutRounding::Down,
+                "ROUNDNEAREST" => spec.rules.payout_rounding = PayoutRounding::Nearest,
+                "ROUNDUP" => spec.rules.payout_rounding = PayoutRounding::Up,
+                policy if policy.starts_with('T') => {
+                    spec.rules.push_policy = policy.parse()?;
+                }
+                drawing if drawing.starts_with(['S', 'H']) => {
+                    spec.rules.dealer_drawing = drawing.parse()?;
+                }
+                seats if seats.ends_with("SEATS") => {
+                    spec.rules.max_seats = seats
+                        .trim_end_matches("SEATS")
+                        .parse()
+                        .ok()
+                        .filter(|&seats| seats > 0)
+                        .ok_or_else(|| format!("invalid seats: {token}"))?;
+                }
+                penetration if penetration.ends_with('%') => {
+                    spec.penetration = penetration
+                        .trim_end_matches('%')
+                        .parse::<f32>()
+                        .ok()
+                        .filter(|percent| *percent > 0.0 && *percent <= 100.0)
+                        .ok_or_else(|| format!("invalid penetration: {token}"))?
+                        / 100.0;
+                }
+                decks => {
+                    spec.decks = decks
+                        .strip_suffix('D')
+                        .and_then(|decks| decks.parse().ok())
+                        .filter(|&decks| decks > 0)
+                        .ok_or_else(|| format!("unknown rule: {token}"))?;
+                }
+            }
+        }
+        Ok(spec)
+    }
+}
+
+impl fmt::Display for TableSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}D", self.decks)?;
+        write!(f, ",{}", self.rules.dealer_drawing)?;
+        if self.rules.double_after_split {
+            write!(f, ",DAS")?;
+        } else {
+            write!(f, ",NDAS")?;
+        }
+        if self.rules.early_surrender {
+            write!(f, ",ES")?;
+        }
+        if self.rules.late_surrender {
+            write!(f, ",LS")?;
+        }
+        if !self.rules.early_surrender && !self.rules.late_surrender {
+            write!(f, ",NS")?;
+        }
+        if self.rules.insurance {
+            write!(f, ",INS")?;
+        }
+        if self.rules.no_mid_shoe_entry {
+            write!(f, ",NMSE")?;
+        }
+        if self.rules.face_down {
+            write!(f, ",FD")?;
+        }
+        if self.rules.dealer_push_22 {
+            write!(f, ",P22")?;
+        }
+        if self.rules.push_policy != PushPolicy::Push {
+            write!(f, ",{}", self.rules.push_policy)?;
+        }
+        if self.rules.blackjack_payout == BlackjackPayout::SixToFive {
+            write!(f, ",6:5")?;
+        }
+        if self.rules.payout_rounding != PayoutRounding::Down {
+            let rounding = self.rules.payout_rounding.to_string().to_ascii_uppercase();
+            write!(f, ",ROUND{rounding}")?;
+        }
+        if self.rules.max_seats != Rules::default().max_seats {
+            write!(f, ",{}SEATS", self.rules.max_seats)?;
+        }
+        if self.penetration != Self::default().penetration {
+            // Rounded to hundredths of a percent, so "60%" reads back as written
+            write!(f, ",{}%", (self.penetration * 10_000.0).round() / 100.0)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::DealerDrawing;
+
+    #[test]
+    fn test_table_spec() {
+        let spec: TableSpec = "2D,H17,NDAS,6:5,60%".parse().unwrap();
+        assert_eq!(spec.decks, 2);
+        assert_eq!(spec.rules.dealer_drawing, DealerDrawing::H17);
+        assert_eq!(spec.to_string(), "2D,H17,NDAS,LS,6:5,60%");
+        let table = spec.table(1000);
+        assert_eq!(table.shoe.decks, 2);
+        assert!((table.shoe.max_penetration - 0.6).abs() < f32::EPSILON);
+        assert_eq!(table.rules.blackjack_payout, BlackjackPayout::SixToFive);
+        assert_eq!(
+            "6D".parse::<TableSpec>().unwrap().to_string(),
+            "6D,S17,DAS,LS"
+        );
+        assert!("6D,120%".parse::<TableSpec>().is_err());
+
+        let promo: TableSpec = "6D,S16/17".parse().unwrap();
+        let drawing = DealerDrawing { hard: 16, soft: 17 };
+        assert_eq!(promo.rules.dealer_drawing, drawing);
+        assert_eq!(promo.to_string(), "6D,S16/17,DAS,LS");
+        let h18 = DealerDrawing { hard: 17, soft: 19 };
+        assert_eq!("h18".parse(), Ok(h18));
+        assert_eq!(h18.to_string(), "H18");
+        assert!("H21".parse::<DealerDrawing>().is_err());
+        assert!("6D,S22".parse::<TableSpec>().is_err());
+
+        let double_exposure: TableSpec = "6D,P22,TL19".parse().unwrap();
+        assert_eq!(
+            double_exposure.rules.push_policy,
+            PushPolicy::TiesLoseUpTo(19)
+        );
+        assert_eq!(double_exposure.to_string(), "6D,S17,DAS,LS,P22,TL19");
+        assert!("6D,TL22".parse::<TableSpec>().is_err());
+
+        let heads_up: TableSpec = "2D,6:5,1seats".parse().unwrap();
+        assert_eq!(heads_up.rules.max_seats, 1);
+        assert_eq!(heads_up.to_string(), "2D,S17,DAS,LS,6:5,1SEATS");
+        let generous: TableSpec = "6D,roundUp".parse().unwrap();
+        assert_eq!(generous.rules.payout_rounding, PayoutRounding::Up);
+        assert_eq!(generous.to_string(), "6D,S17,DAS,LS,ROUNDUP");
+        assert!("6D,0SEATS".parse::<TableSpec>().is_err());
+    }
+}
@@ -0,0 +1,106 @@
+//! Timelines of rounds, for analyzing how fast the player plays.
+//! A table played with `Table::progress_at` stamps every transition with the time it was made,
+//! as told by the front-end's own clock, and hands the round's timeline over with the round.
+//! Times are only meaningful relative to each other, so any clock will do.
+
+use std::time::Duration;
+
+use crate::state::GameState;
+
+/// A transition of the game, and when it was made.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Transition {
+    pub at: Duration,       // The time of the transition
+    pub from: &'static str, // The name of the state the game progressed from
+    pub decision: bool,     // Whether the player made a decision to progress
+}
+
+impl Transition {
+    /// Stamps the transition from the given state at the given time.
+    #[must_use]
+    pub const fn new(state: &GameState, at: Duration) -> Self {
+        Self {
+            at,
+            from: crate::snapshot::name(state),
+            decision: matches!(
+                state,
+                GameState::OfferEarlySurrender { .. }
+                    | GameState::OfferInsurance { .. }
+                    | GameState::PlayPlayerTurn { .. }
+            ),
+        }
+    }
+}
+
+/// The transitions of a round, from the player's bet onwards.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Timeline {
+    pub transitions: Vec<Transition>,
+}
+
+impl Timeline {
+    /// The time from the first transition of the round to the last.
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        match (self.transitions.first(), self.transitions.last()) {
+            (Some(first), Some(last)) => last.at.saturating_sub(first.at),
+            _ => Duration::ZERO,
+        }
+    }
+
+    /// How long the player took over each decision,
+    /// from the transition which asked for it to the one which made it.
+    pub fn decision_latencies(&self) -> impl Iterator<Item = Duration> + '_ {
+        self.transitions
+            .windows(2)
+            .filter(|pair| pair[1].decision)
+            .map(|pair| pair[1].at.saturating_sub(pair[0].at))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::shoe::Shoe;
+    use crate::game::{HandAction, Input, Table};
+    use crate::rules::Rules;
+
+    #[test]
+    fn test_timeline() {
+        let mut table = Table::new(1000, Shoe::seeded(6, 0.75, 1), Rules::default());
+        let mut state = GameState::default();
+        let mut now = Duration::ZERO;
+        while table.statistics.turns_played() == 0 {
+            let input = match state {
+                GameState::Betting { .. } => Some(Input::bet(100)),
+                GameState::OfferInsurance { .. } => Some(Input::bet(0)),
+                GameState::PlayPlayerTurn { .. } => Some(Input::action(HandAction::Stand)),
+                _ => None,
+            };
+            // The player takes 3 seconds over each decision, and the dealer 1 over each card
+            now += if input.is_some() {
+                Duration::from_secs(3)
+            } else {
+                Duration::from_secs(1)
+            };
+            state = table.progress_at(state, input, now).unwrap();
+        }
+        let GameState::Payout { round } = &state else {
+            panic!("the round should be paid out");
+        };
+        let timeline = round.timeline.as_ref().unwrap();
+        assert_eq!(timeline.transitions[0].from, "Betting");
+        assert_eq!(timeline.duration(), now - timeline.transitions[0].at);
+        assert!(timeline
+            .decision_latencies()
+            .all(|latency| latency == Duration::from_secs(3)));
+        assert_eq!(
+            table.statistics.average_decision_latency(),
+            timeline.decision_latencies().next()
+        );
+        assert_eq!(
+            table.statistics.average_round_duration(),
+            Some(timeline.duration())
+        );
+    }
+}
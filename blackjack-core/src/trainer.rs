@@ -0,0 +1,322 @@
+//! Trainer analytics: a log of the player's decisions, each against the action with the best expected value,
+//! so the most expensive recurring mistakes can be reported after a session, or exported for analysis.
+//! A log can keep only its latest decisions in memory, handing older ones over to be spilled elsewhere,
+//! while its mistakes and their cost still cover every decision.
+
+use std::collections::vec_deque::{Drain, Iter};
+use std::collections::VecDeque;
+use std::fmt;
+use std::fmt::Write;
+
+use crate::card::hand::{DealerHand, PlayerTurn};
+use crate::card::shoe::Composition;
+use crate::chart::{worth_symbol, ChartHand};
+use crate::ev;
+use crate::game::{HandAction, Table};
+
+/// A decision the player made on one of their hands.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Decision {
+    /// The player's hand when they decided
+    pub hand: ChartHand,
+    /// The worth of the dealer's upcard (an ace is 11)
+    pub upcard: u8,
+    /// The bet on the hand when they decided
+    pub bet: u32,
+    /// The action the player took
+    pub action: HandAction,
+    /// The action with the best expected value
+    pub correct: HandAction,
+    /// The chips the player expects to lose by their action compared to the correct one
+    pub ev_cost: f64,
+}
+
+impl Decision {
+    /// Whether the player took an action other than the correct one.
+    #[must_use]
+    pub fn is_mistake(&self) -> bool {
+        self.action != self.correct
+    }
+}
+
+/// A mistake the player made one or more times: the same wrong action in the same situation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mistake {
+    pub hand: ChartHand,
+    pub upcard: u8,
+    pub action: HandAction,
+    pub correct: HandAction,
+    /// How many times the player made this mistake
+    pub count: usize,
+    /// The chips the player expects to have lost to this mistake, over every time they made it
+    pub ev_cost: f64,
+}
+
+impl fmt::Display for Mistake {
+    /// Mistakes are displayed as e.g. "16 vs T: Stand instead of Hit (3x, 42.50 chips)"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} vs {}: {:?} instead of {:?} ({}x, {:.2} chips)",
+            self.hand,
+            worth_symbol(self.upcard),
+            self.action,
+            self.correct,
+            self.count,
+            self.ev_cost
+        )
+    }
+}
+
+/// The header row of the decisions exported as CSV.
+pub const CSV_HEADER: &str = "hand,upcard,bet,action,correct,ev_cost";
+
+/// The decisions the player made while training, in the order they made them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DecisionLog {
+    decisions: VecDeque<Decision>,
+    /// The number of decisions kept in memory once older ones are spilled, or `None` to keep every one
+    retention: Option<usize>,
+    /// The number of decisions recorded, including those spilled
+    recorded: usize,
+    /// The mistakes over every decision recorded, in the order they were first made
+    mistakes: Vec<Mistake>,
+    ev_cost: f64,
+}
+
+impl DecisionLog {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            decisions: VecDeque::new(),
+            retention: None,
+            recorded: 0,
+            mistakes: Vec::new(),
+            ev_cost: 0.0,
+        }
+    }
+
+    /// Creates a log which keeps the given number of latest decisions once older ones are spilled.
+    #[must_use]
+    pub const fn with_retention(retention: usize) -> Self {
+        let mut log = Self::new();
+        log.retention = Some(retention);
+        log
+    }
+
+    /// Records the player taking an action on their current hand, given the cards they have not seen.
+    /// Returns the decision, or `None` if the action is not allowed on the hand.
+    pub fn record(
+        &mut self,
+        table: &Table,
+        player_turn: &PlayerTurn,
+        dealer_hand: &DealerHand,
+        unseen: &Composition,
+        action: HandAction,
+    ) -> Option<&Decision> {
+        let evs = ev::action_evs(table, player_turn, dealer_hand, unseen);
+        self.record_evs(player_turn, dealer_hand, &evs, action)
+    }
+
+    /// Records the player taking an action on their current hand like `record`,
+    /// given the expected value of each action they were allowed to take, e.g. from an `EvCache`.
+    pub fn record_evs(
+        &mut self,
+        player_turn: &PlayerTurn,
+        dealer_hand: &DealerHand,
+        evs: &[(HandAction, f64)],
+        action: HandAction,
+    ) -> Option<&Decision> {
+        let (_, taken) = evs.iter().find(|(allowed, _)| *allowed == action)?;
+        let (best, best_ev) = evs
+            .iter()
+            .copied()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))?;
+        // An action as good as the best one is not a mistake
+        let correct = if best_ev - taken < 1e-9 { action } else { best };
+        let hand = player_turn.current_hand();
+        let decision = Decision {
+            hand: ChartHand::of(hand),
+            upcard: dealer_hand.showing(),
+            bet: hand.bet,
+            action,
+            correct,
+            ev_cost: (best_ev - taken).max(0.0) * f64::from(hand.bet),
+        };
+        self.tally(&decision);
+        self.decisions.push_back(decision);
+        self.decisions.back()
+    }
+
+    /// Counts a decision towards the mistakes and their cost.
+    fn tally(&mut self, decision: &Decision) {
+        self.recorded += 1;
+        self.ev_cost += decision.ev_cost;
+        if !decision.is_mistake() {
+            return;
+        }
+        let same = |mistake: &&mut Mistake| {
+            mistake.hand == decision.hand
+                && mistake.upcard == decision.upcard
+                && mistake.action == decision.action
+        };
+        if let Some(mistake) = self.mistakes.iter_mut().find(same) {
+            mistake.count += 1;
+            mistake.ev_cost += decision.ev_cost;
+        } else {
+            self.mistakes.push(Mistake {
+                hand: decision.hand,
+                upcard: decision.upcard,
+                action: decision.action,
+                correct: decision.correct,
+                count: 1,
+                ev_cost: decision.ev_cost,
+            });
+        }
+    }
+
+    /// The decisions kept in memory, oldest first.
+    #[must_use]
+    pub fn decisions(&self) -> Iter<'_, Decision> {
+        self.decisions.iter()
+    }
+
+    /// The number of decisions recorded, including those spilled.
+    #[must_use]
+    pub const fn recorded(&self) -> usize {
+        self.recorded
+    }
+
+    /// The number of decisions kept in memory beyond the retention, waiting to be spilled.
+    #[must_use]
+    pub fn overflow(&self) -> usize {
+        self.retention.map_or(0, |retention| {
+            self.decisions.len().saturating_sub(retention)
+        })
+    }
+
+    /// Removes the decisions beyond the retention from memory, oldest first, to be kept elsewhere.
+    /// The mistakes and their cost still count them.
+    pub fn spill(&mut self) -> Drain<'_, Decision> {
+        let overflow = self.overflow();
+        self.decisions.drain(..overflow)
+    }
+
+    /// The chips the player expects to have lost to all of their mistakes.
+    #[must_use]
+    pub const fn ev_cost(&self) -> f64 {
+        self.ev_cost
+    }
+
+    /// The player's mistakes, grouped by situation and action, most expensive first.
+    #[must_use]
+    pub fn mistakes(&self) -> Vec<Mistake> {
+        let mut mistakes = self.mistakes.clone();
+        mistakes.sort_by(|a, b| b.ev_cost.total_cmp(&a.ev_cost));
+        mistakes
+    }
+
+    /// Returns the decisions kept in memory as CSV, with a header row.
+    #[must_use]
+    pub fn to_csv(&self) -> String {
+        let mut csv = format!("{CSV_HEADER}\n");
+        for decision in &self.decisions {
+            let _ = writeln!(csv, "{}", csv_row(decision));
+        }
+        csv
+    }
+}
+
+/// Returns a decision as a row of CSV, without the line break.
+#[must_use]
+pub fn csv_row(decision: &Decision) -> String {
+    format!(
+        "{},{},{},{:?},{:?},{:.4}",
+        decision.hand,
+        worth_symbol(decision.upcard),
+        decision.bet,
+        decision.action,
+        decision.correct,
+        decision.ev_cost
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::hand::PlayerHand;
+    use crate::card::shoe::Shoe;
+    use crate::card::{Card, Rank, Suit};
+    use crate::rules::{DealerDrawing, Rules};
+    use crate::statistics::Statistics;
+
+    #[test]
+    fn test_decision_log() {
+        let table = Table::new(1000, Shoe::new(6, 0.75), Rules::default());
+        let full = table.shoe.composition();
+        let card = |rank| Card {
+            rank,
+            suit: Suit::Clubs,
+        };
+        let mut hand = PlayerHand::new(card(Rank::Ten), 100);
+        hand += card(Rank::Six);
+        let player_turn = PlayerTurn::from(hand);
+        let dealer_hand = DealerHand::new(card(Rank::Six), DealerDrawing::S17);
+
+        let mut log = DecisionLog::new();
+        let decision = *log
+            .record(&table, &player_turn, &dealer_hand, &full, HandAction::Stand)
+            .unwrap();
+        assert_eq!(decision.hand, ChartHand::Hard(16));
+        assert!(!decision.is_mistake());
+        assert!(decision.ev_cost.abs() < f64::EPSILON);
+        for _ in 0..2 {
+            log.record(&table, &player_turn, &dealer_hand, &full, HandAction::Hit);
+        }
+        assert_eq!(log.decisions().len(), 3);
+
+        let mistakes = log.mistakes();
+        assert_eq!(mistakes.len(), 1);
+        assert_eq!(mistakes[0].count, 2);
+        assert_eq!(mistakes[0].correct, HandAction::Stand);
+        assert!(mistakes[0].ev_cost > 0.0);
+        assert!((mistakes[0].ev_cost - log.ev_cost()).abs() < 1e-9);
+
+        let mut statistics = Statistics::new();
+        for decision in log.decisions() {
+            statistics.record_decision(decision);
+        }
+        assert_eq!(statistics.cost_of_errors(), None);
+        statistics.update(
+            std::slice::from_ref(player_turn.current_hand()),
+            &dealer_hand,
+        );
+        // One hand played, so the cost per 100 hands is a hundred times the cost, to the hundredth of a chip
+        let cost = statistics.cost_of_errors().unwrap();
+        assert!((cost - log.ev_cost() * 100.0).abs() < 1.0);
+        assert!(mistakes[0]
+            .to_string()
+            .starts_with("16 vs 6: Hit instead of Stand (2x,"));
+
+        let csv = log.to_csv();
+        assert_eq!(csv.lines().count(), 4);
+        assert!(csv
+            .starts_with("hand,upcard,bet,action,correct,ev_cost\n16,6,100,Stand,Stand,0.0000\n"));
+
+        // A log keeping one decision spills the older ones, but still counts them
+        let mut kept = DecisionLog::with_retention(1);
+        kept.record(&table, &player_turn, &dealer_hand, &full, HandAction::Stand);
+        for _ in 0..2 {
+            kept.record(&table, &player_turn, &dealer_hand, &full, HandAction::Hit);
+        }
+        assert_eq!(kept.overflow(), 2);
+        let spilled: Vec<Decision> = kept.spill().collect();
+        assert_eq!(
+            spilled,
+            log.decisions().take(2).copied().collect::<Vec<_>>()
+        );
+        assert_eq!((kept.decisions().len(), kept.recorded()), (1, 3));
+        assert_eq!(kept.mistakes(), mistakes);
+        assert!((kept.ev_cost() - log.ev_cost()).abs() < 1e-9);
+    }
+}
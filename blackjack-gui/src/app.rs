@@ -1,22 +1,83 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::mem;
+use std::num::NonZeroUsize;
+use std::thread;
+
 use crossterm::event::KeyCode;
+use rand::Rng;
+
+use blackjack_core::card::hand::Status;
+use blackjack_core::cues::Cue;
+use blackjack_core::state::GameState;
+
+use crate::game::{Blackjack, Settings};
+use crate::palette::{Command, CommandPalette, PaletteInput};
+use crate::profiler::Profiler;
+use crate::workers::{Report, Step, Workers};
 
-use crate::game::Blackjack;
+/// The fewest games worth a worker thread of their own when stepping them all at once.
+/// Fewer games than this are quicker to step on the render thread than to hand out.
+const GAMES_PER_WORKER: usize = 4;
 
 #[derive(Debug, Default)]
 pub struct App {
     pub games: Vec<Blackjack>,
     pub selected_game: usize,
     pub should_quit: bool,
+    pub settings: Settings,
+    pub demo: bool,
+    pub narration: Option<String>,
+    /// Whether the rules of the selected game are shown in full, rather than collapsed
+    pub show_rules: bool,
+    /// Whether the basic strategy chart for the selected game's table is shown in place of the game
+    pub show_chart: bool,
+    /// The number of lines the statistics pane is scrolled down by
+    pub statistics_scroll: u16,
+    /// Which sections of the statistics pane are collapsed, in the order of `Section::ALL`
+    pub collapsed_sections: [bool; 4],
+    /// The command palette, while it is open
+    pub palette: Option<CommandPalette>,
+    /// The profiler, while its overlay is shown
+    pub profiler: Option<Profiler>,
+    /// The worker threads stepping the games, once there have been enough games to need them
+    workers: Option<Workers>,
+    /// Shares of the games back from the workers, waiting for the rest
+    returned: Vec<Report>,
+    /// Keys pressed while the games were away with the workers, considered once they are back
+    queued_keys: VecDeque<KeyCode>,
 }
 
 impl App {
     #[must_use]
-    pub const fn new() -> Self {
+    pub const fn new(settings: Settings) -> Self {
         Self {
             games: Vec::new(),
             selected_game: 0,
             should_quit: false,
+            settings,
+            demo: false,
+            narration: None,
+            show_rules: true,
+            show_chart: false,
+            statistics_scroll: 0,
+            collapsed_sections: [false; 4],
+            palette: None,
+            profiler: None,
+            workers: None,
+            returned: Vec::new(),
+            queued_keys: VecDeque::new(),
+        }
+    }
+
+    /// Starts the attract mode, where the given number of games play themselves
+    /// and the view follows whichever game has something interesting happening.
+    pub fn start_demo(&mut self, games: usize) {
+        self.demo = true;
+        for _ in 0..games {
+            self.add_game();
         }
+        self.selected_game = 0;
     }
 
     #[must_use]
@@ -25,30 +86,257 @@ impl App {
     }
 
     pub fn simulate(&mut self) {
-        for game in &mut self.games {
+        self.step_games(|game| {
             game.simulate();
-        }
+            None
+        });
     }
 
     pub fn tick(&mut self) {
-        for game in &mut self.games {
+        if self.demo {
+            self.tick_demo();
+            return;
+        }
+        self.step_games(|game| {
             game.tick();
+            None
+        });
+    }
+
+    /// Plays every game with basic strategy, occasionally letting one wait a tick
+    /// so the games do not move in lockstep.
+    fn tick_demo(&mut self) {
+        if self.stepping() {
+            return;
+        }
+        for game in &mut self.games {
+            if game.game_state == GameState::GameOver {
+                *game = Blackjack::new(&self.settings);
+            }
+        }
+        self.step_games(|game| {
+            if rand::rng().random_bool(0.75) {
+                game.simulate();
+                highlight(&game.game_state)
+            } else {
+                None
+            }
+        });
+    }
+
+    /// Steps every game, handing them out to the worker threads when there are enough to be worth it,
+    /// so dozens of autoplaying games don't hold up the render thread one after another.
+    /// Games handed out are away until `collect_steps` finds them all back; a tick while they are away is skipped.
+    fn step_games(&mut self, step: Step) {
+        if self.stepping() {
+            return;
+        }
+        let parallelism = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        let workers = parallelism.min(self.games.len() / GAMES_PER_WORKER);
+        if workers <= 1 {
+            let highlights: Vec<_> = self
+                .games
+                .iter_mut()
+                .enumerate()
+                .filter_map(|(i, game)| step(game).map(|line| (i, line)))
+                .collect();
+            self.follow(&highlights);
+            return;
+        }
+        let games = mem::take(&mut self.games);
+        self.workers
+            .get_or_insert_with(|| Workers::new(parallelism))
+            .hand_out(games, workers, step);
+    }
+
+    /// Whether the games are away with the worker threads.
+    #[must_use]
+    pub fn stepping(&self) -> bool {
+        self.workers.as_ref().is_some_and(Workers::busy)
+    }
+
+    /// Takes back the games the worker threads have finished stepping, without waiting for them.
+    /// Once every game is back, the keys pressed in the meantime are considered in turn.
+    pub fn collect_steps(&mut self) {
+        let Some(workers) = &mut self.workers else {
+            return;
+        };
+        while let Some(report) = workers.try_collect() {
+            self.returned.push(report);
+        }
+        if workers.busy() || self.returned.is_empty() {
+            return;
+        }
+        self.returned.sort_by_key(|report| report.first);
+        let mut highlights = Vec::new();
+        for report in mem::take(&mut self.returned) {
+            self.games.extend(report.games);
+            highlights.extend(report.highlights);
+        }
+        self.follow(&highlights);
+        while let Some(key) = self.queued_keys.pop_front() {
+            self.input(key);
+        }
+    }
+
+    /// Waits for the worker threads to finish stepping the games, and takes them back.
+    #[cfg(test)]
+    pub fn finish_steps(&mut self) {
+        if let Some(workers) = &mut self.workers {
+            while let Some(report) = workers.collect() {
+                self.returned.push(report);
+            }
+        }
+        self.collect_steps();
+    }
+
+    /// In the demo, the view follows the last game with something interesting happening.
+    fn follow(&mut self, highlights: &[(usize, &'static str)]) {
+        if !self.demo {
+            return;
+        }
+        if let Some(&(i, line)) = highlights.iter().max_by_key(|&&(i, _)| i) {
+            let catalog = &self.settings.catalog;
+            self.selected_game = i;
+            self.narration = Some(catalog.format(
+                "demo.highlight",
+                &[("game", &i), ("line", &catalog.get(line))],
+            ));
         }
     }
 
     pub fn input(&mut self, key: KeyCode) {
+        if self.stepping() {
+            self.queued_keys.push_back(key);
+            return;
+        }
+        if let Some(palette) = &mut self.palette {
+            match palette.consider(key) {
+                Some(PaletteInput::Close) => self.palette = None,
+                Some(PaletteInput::Submit(command)) => self.run_command(&command),
+                None => {}
+            }
+            return;
+        }
         match key {
             KeyCode::Esc => self.should_quit = true,
+            KeyCode::Char(':') => self.palette = Some(CommandPalette::default()),
             KeyCode::Char('g') => self.add_game(),
             KeyCode::Char('q') => self.delete_game(),
+            KeyCode::Char('i') => self.show_rules = !self.show_rules,
+            KeyCode::Char('[') => self.move_game_up(),
+            KeyCode::Char(']') => self.move_game_down(),
+            KeyCode::Char('c') => self.duplicate_game(),
+            KeyCode::Char('a') => self.toggle_autoplay_all(),
+            KeyCode::Char('x') => self.toggle_pause_all(),
+            KeyCode::Char('f') => self.delete_finished_games(),
+            KeyCode::Char('.') => {
+                if let Some(game) = self.games.get_mut(self.selected_game) {
+                    game.replay_last_round();
+                }
+            }
+            KeyCode::PageUp => self.statistics_scroll = self.statistics_scroll.saturating_sub(5),
+            KeyCode::PageDown => self.statistics_scroll = self.statistics_scroll.saturating_add(5),
+            KeyCode::F(n @ 1..=4) => {
+                let collapsed = &mut self.collapsed_sections[usize::from(n) - 1];
+                *collapsed = !*collapsed;
+            }
             KeyCode::Up => self.cursor_up(),
             KeyCode::Down => self.cursor_down(),
             key => self.input_current_game(key),
         }
     }
 
+    /// Runs a command from the palette, closing it unless the command failed.
+    fn run_command(&mut self, input: &str) {
+        let result = match Command::parse(input) {
+            Some(command) => self.execute(command),
+            None => Err(self
+                .settings
+                .catalog
+                .format("palette.unknown", &[("command", &input)])),
+        };
+        match result {
+            Ok(()) => self.palette = None,
+            Err(error) => {
+                if let Some(palette) = &mut self.palette {
+                    palette.error = Some(error);
+                }
+            }
+        }
+    }
+
+    fn execute(&mut self, command: Command) -> Result<(), String> {
+        let catalog = &self.settings.catalog;
+        match command {
+            Command::Bet(bet) => {
+                let game = self
+                    .games
+                    .get_mut(self.selected_game)
+                    .ok_or_else(|| catalog.get("ui.no-game").to_string())?;
+                game.bet(bet).map_err(|e| catalog.error(&e).to_string())?;
+            }
+            Command::Hints => {
+                let show = !(self.settings.show_ev && self.settings.show_bust);
+                self.settings.show_ev = show;
+                self.settings.show_bust = show;
+            }
+            Command::Chart => self.show_chart = !self.show_chart,
+            Command::Profiler => {
+                self.profiler = match self.profiler {
+                    Some(_) => None,
+                    None => Some(Profiler::default()),
+                };
+            }
+            Command::Save(path) => {
+                let game = self
+                    .current_game()
+                    .ok_or_else(|| catalog.get("ui.no-game").to_string())?;
+                fs::write(&path, game.summary(catalog))
+                    .map_err(|e| format!("{}: {e}", path.display()))?;
+            }
+            Command::Mistakes(path) => {
+                let game = self
+                    .current_game()
+                    .ok_or_else(|| catalog.get("ui.no-game").to_string())?;
+                fs::write(&path, game.decisions.to_csv())
+                    .map_err(|e| format!("{}: {e}", path.display()))?;
+            }
+            Command::Practice => {
+                let game = self
+                    .games
+                    .get_mut(self.selected_game)
+                    .ok_or_else(|| catalog.get("ui.no-game").to_string())?;
+                game.toggle_practice()
+                    .map_err(|e| catalog.error(&e).to_string())?;
+            }
+            Command::Game(game) if game < self.games.len() => self.selected_game = game,
+            Command::Game(game) => {
+                return Err(catalog.format("palette.no-such-game", &[("game", &game)]));
+            }
+            Command::Quit => self.should_quit = true,
+        }
+        Ok(())
+    }
+
+    /// Removes and returns the cues queued up by every game.
+    pub fn drain_cues(&mut self) -> Vec<Cue> {
+        self.games
+            .iter_mut()
+            .flat_map(|game| game.cues.drain(..))
+            .collect()
+    }
+
+    /// Returns the state transitions the games have made since the last call.
+    pub fn take_transitions(&mut self) -> u64 {
+        self.games
+            .iter_mut()
+            .map(|game| std::mem::take(&mut game.transitions))
+            .sum()
+    }
+
     pub fn add_game(&mut self) {
-        self.games.push(Blackjack::new());
+        self.games.push(Blackjack::new(&self.settings));
         self.selected_game = self.games.len() - 1;
     }
 
@@ -61,6 +349,58 @@ impl App {
         }
     }
 
+    /// Adds a new game with the same configuration as the selected game.
+    pub fn duplicate_game(&mut self) {
+        if let Some(game) = self.current_game() {
+            let game = game.duplicate(&self.settings);
+            self.games.push(game);
+            self.selected_game = self.games.len() - 1;
+        }
+    }
+
+    /// Swaps the selected game with the one above it, keeping it selected.
+    pub fn move_game_up(&mut self) {
+        if self.selected_game > 0 && self.selected_game < self.games.len() {
+            self.games.swap(self.selected_game, self.selected_game - 1);
+            self.selected_game -= 1;
+        }
+    }
+
+    /// Swaps the selected game with the one below it, keeping it selected.
+    pub fn move_game_down(&mut self) {
+        if self.selected_game + 1 < self.games.len() {
+            self.games.swap(self.selected_game, self.selected_game + 1);
+            self.selected_game += 1;
+        }
+    }
+
+    /// Turns autoplay on for every game, or off for every game if they are all autoplaying already.
+    pub fn toggle_autoplay_all(&mut self) {
+        let autoplay = !self.games.iter().all(|game| game.autoplay);
+        for game in &mut self.games {
+            game.autoplay = autoplay;
+        }
+    }
+
+    /// Pauses every game, or resumes every game if they are all paused already.
+    pub fn toggle_pause_all(&mut self) {
+        let paused = !self.games.iter().all(|game| game.paused);
+        for game in &mut self.games {
+            game.paused = paused;
+        }
+    }
+
+    /// Removes every game which is over, keeping the selection on the same game if it remains.
+    pub fn delete_finished_games(&mut self) {
+        let finished_before = self.games[..self.selected_game.min(self.games.len())]
+            .iter()
+            .filter(|game| game.is_finished())
+            .count();
+        self.games.retain(|game| !game.is_finished());
+        self.selected_game =
+            (self.selected_game - finished_before).min(self.games.len().saturating_sub(1));
+    }
+
     pub fn cursor_up(&mut self) {
         self.selected_game = (self.selected_game + self.games.len() - 1) % self.games.len();
     }
@@ -75,3 +415,22 @@ impl App {
         }
     }
 }
+
+/// Returns the catalog key of a line of narration if the state is worth showing off in the demo.
+fn highlight(state: &GameState) -> Option<&'static str> {
+    match state {
+        GameState::PlayerSplit { .. } => Some("demo.split"),
+        GameState::PlayerDouble { .. } => Some("demo.double"),
+        GameState::RoundOver { dealer_hand, .. } if dealer_hand.status == Status::Bust => {
+            Some("demo.dealer-busts")
+        }
+        GameState::RoundOver { finished_hands, .. }
+            if finished_hands
+                .iter()
+                .any(|hand| hand.status == Status::Blackjack) =>
+        {
+            Some("demo.blackjack")
+        }
+        _ => None,
+    }
+}
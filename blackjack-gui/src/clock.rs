@@ -0,0 +1,212 @@
+//! The time the GUI runs on: when ticks are due, how long a decision has left,
+//! and how long a round's summary stays up.
+//! Everything reads the time from one shared `Clock`, so the app can run at any speed,
+//! or with no waiting at all in tests. Clocks are shared with the threads autoplaying the games,
+//! which only ever read them; the render loop is the one to step clocks which don't move on their own.
+
+use std::fmt;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+#[cfg(test)]
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// A source of time. Times are measured from when the clock started.
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// The time since the clock started.
+    fn now(&self) -> Duration;
+
+    /// How long to wait in real time until the clock reaches the given time.
+    /// Clocks which don't follow real time never need to be waited for.
+    fn wait(&self, _until: Duration) -> Duration {
+        Duration::ZERO
+    }
+
+    /// Moves the clock forward by a step, for clocks which only move when the render loop steps them.
+    fn step(&self) {}
+}
+
+/// A clock following real time.
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    started: Instant,
+}
+
+impl SystemClock {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    fn wait(&self, until: Duration) -> Duration {
+        until.saturating_sub(self.now())
+    }
+}
+
+/// A clock running faster or slower than real time, e.g. twice as fast with a speed of 2.
+#[derive(Debug, Clone, Copy)]
+pub struct ScaledClock {
+    started: Instant,
+    speed: f64,
+}
+
+impl ScaledClock {
+    /// # Panics
+    /// Panics if the speed isn't positive.
+    #[must_use]
+    pub fn new(speed: f64) -> Self {
+        assert!(speed > 0.0, "the clock's speed must be positive");
+        Self {
+            started: Instant::now(),
+            speed,
+        }
+    }
+}
+
+impl Clock for ScaledClock {
+    fn now(&self) -> Duration {
+        self.started.elapsed().mul_f64(self.speed)
+    }
+
+    fn wait(&self, until: Duration) -> Duration {
+        until.saturating_sub(self.now()).div_f64(self.speed)
+    }
+}
+
+/// A clock which only moves when it is told to, for stepping through time deterministically in tests.
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    now: Mutex<Duration>,
+}
+
+#[cfg(test)]
+impl ManualClock {
+    /// Moves the clock forward.
+    pub fn advance(&self, duration: Duration) {
+        *self.now.lock().unwrap_or_else(PoisonError::into_inner) += duration;
+    }
+}
+
+#[cfg(test)]
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        *self.now.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A clock which moves a fixed step forward every time the render loop steps it,
+/// so every tick is due as soon as it is checked and nothing is ever waited for.
+/// Reading it doesn't move it, so every game sees the same time within a tick.
+#[derive(Debug)]
+pub struct InstantClock {
+    nanos: AtomicU64,
+    step: u64,
+}
+
+impl InstantClock {
+    #[must_use]
+    pub fn new(step: Duration) -> Self {
+        Self {
+            nanos: AtomicU64::new(0),
+            step: u64::try_from(step.as_nanos()).unwrap_or(u64::MAX),
+        }
+    }
+}
+
+impl Clock for InstantClock {
+    fn now(&self) -> Duration {
+        Duration::from_nanos(self.nanos.load(Ordering::Relaxed))
+    }
+
+    fn step(&self) {
+        self.nanos.fetch_add(self.step, Ordering::Relaxed);
+    }
+}
+
+/// A clock shared by the app and every game in it. Follows real time by default.
+#[derive(Debug, Clone)]
+pub struct SharedClock(Arc<dyn Clock>);
+
+impl Default for SharedClock {
+    fn default() -> Self {
+        Self(Arc::new(SystemClock::new()))
+    }
+}
+
+impl<C: Clock + 'static> From<Arc<C>> for SharedClock {
+    fn from(clock: Arc<C>) -> Self {
+        Self(clock)
+    }
+}
+
+impl Deref for SharedClock {
+    type Target = dyn Clock;
+
+    fn deref(&self) -> &Self::Target {
+        &*self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{Blackjack, Settings};
+    use blackjack_core::rules::Rules;
+    use blackjack_core::state::GameState;
+    use blackjack_core::table_spec::TableSpec;
+
+    #[test]
+    fn test_decision_timer() {
+        let clock = Arc::new(ManualClock::default());
+        let settings = Settings {
+            table: TableSpec {
+                rules: Rules {
+                    decision_timer: Some(Duration::from_secs(10)),
+                    ..Rules::default()
+                },
+                ..TableSpec::default()
+            },
+            clock: Arc::clone(&clock).into(),
+            ..Settings::default()
+        };
+        let mut game = Blackjack::new(&settings);
+        while game.time_left().is_none() {
+            game.round_result = None;
+            if matches!(game.game_state, GameState::Betting { .. }) {
+                game.bet(100).unwrap();
+            }
+            game.tick();
+        }
+        assert_eq!(game.time_left(), Some(Duration::from_secs(10)));
+        clock.advance(Duration::from_secs(4));
+        game.tick();
+        assert_eq!(game.time_left(), Some(Duration::from_secs(6)));
+        clock.advance(Duration::from_secs(6));
+        assert_eq!(game.time_left(), Some(Duration::ZERO));
+        game.tick();
+        assert_ne!(game.time_left(), Some(Duration::ZERO));
+        assert_eq!(game.session_length(), Duration::from_secs(10));
+
+        let clock = InstantClock::new(Duration::from_secs(1));
+        assert_eq!(clock.now(), clock.now());
+        clock.step();
+        assert_eq!(clock.now(), Duration::from_secs(1));
+        assert_eq!(clock.wait(Duration::from_secs(60)), Duration::ZERO);
+    }
+}
@@ -1,47 +1,340 @@
+use std::collections::VecDeque;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use crate::clock::SharedClock;
 use crate::input::InputField;
 
 use blackjack_core::basic_strategy;
-use blackjack_core::card::shoe::Shoe;
-use blackjack_core::game::{Error, Input, Table};
-use blackjack_core::rules::Rules;
-use blackjack_core::state::GameState;
+use blackjack_core::card::hand::{DealerHand, PlayerTurn};
+use blackjack_core::card::shoe::{Composition, Shoe};
+use blackjack_core::counting;
+use blackjack_core::cues::{Cue, CueSettings};
+use blackjack_core::ev;
+use blackjack_core::ev_cache::EvCache;
+use blackjack_core::events::GameEvent;
+use blackjack_core::game::{BotSeats, Error, HandAction, Input, Seat, Table};
+use blackjack_core::i18n::Catalog;
+use blackjack_core::narration::Narrator;
+use blackjack_core::preferences::Preferences;
+use blackjack_core::session::{SessionEnd, SessionLimits};
+use blackjack_core::state::{GameState, Settlement};
+use blackjack_core::statistics::Statistics;
+use blackjack_core::table_spec::TableSpec;
+use blackjack_core::trainer::{self, DecisionLog};
 
 use crossterm::event::KeyCode;
 
+/// The number of lines of narration kept for display.
+const NARRATION_LINES: usize = 6;
+
+/// The number of the most expensive mistakes listed in a session summary.
+const MISTAKES_REPORTED: usize = 10;
+
+/// How long the summary of a round stays up in a game that plays itself.
+const SUMMARY_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// The number of decisions spilled at once beyond the retention, so the spill file is not opened for every one.
+const SPILL_BATCH: usize = 1000;
+
+/// The results of the last round, shown until the player dismisses them.
+#[derive(Debug)]
+pub struct RoundResult {
+    pub settlement: Settlement,
+    /// The player's net result for the round
+    pub net: i64,
+    /// The player's chips once the round is paid out
+    pub chips: u32,
+    shown: Duration,
+}
+
+/// The settings new games are created with.
+#[derive(Debug, Default, Clone)]
+pub struct Settings {
+    pub table: TableSpec,
+    pub preferences: Preferences,
+    pub narrator: Narrator,
+    pub catalog: Catalog,
+    pub cues: Option<CueSettings>,
+    /// Whether to show the expected value of each action on the player's turn, and of insurance
+    pub show_ev: bool,
+    /// Whether to show the chance that the dealer busts while the hole card is face down
+    pub show_bust: bool,
+    /// The stop-win and stop-loss which end each game
+    pub limits: SessionLimits,
+    /// Whether to draw with bold, reversed, and shaped markers instead of colors
+    pub high_contrast: bool,
+    /// The number of practice decisions each game keeps in memory, or `None` to keep every one
+    pub retention: Option<usize>,
+    /// The file older practice decisions are appended to as CSV, or `None` to drop them
+    pub spill: Option<PathBuf>,
+    /// The expected values solved so far, shared by every game
+    pub ev_cache: Arc<Mutex<EvCache>>,
+    /// The clock the games keep time by
+    pub clock: SharedClock,
+    /// The bots seated beside the player at each game, as far as the table's seats allow
+    pub bots: Vec<BotSeats>,
+    /// The chips of each backer seated behind the player at each game, as far as the table's seats allow
+    pub backers: Vec<u32>,
+}
+
 #[derive(Debug)]
 pub struct Blackjack {
     pub table: Table,
     pub game_state: GameState,
     pub input_field: Option<InputField>,
     pub last_error: Option<Error>,
+    /// When the game was started, by its clock
+    pub started: Duration,
+    pub preferences: Preferences,
+    /// When the current decision was put to the player, by the game's clock
+    pub decision_started: Duration,
+    pub narrator: Narrator,
+    pub narration: VecDeque<String>,
+    pub cue_settings: Option<CueSettings>,
+    pub cues: Vec<Cue>,
+    /// A notice about the shoe, shown until the next round starts
+    pub toast: Option<String>,
+    pub limits: SessionLimits,
+    /// The player's chips when the game started
+    pub starting_chips: u32,
+    /// How the game ended, if the session limits ended it
+    pub session_end: Option<SessionEnd>,
+    /// The statistics as they were when the last bet was placed, to highlight what the round changed
+    pub statistics_before_round: Statistics,
+    /// Whether the game plays itself with basic strategy on every tick
+    pub autoplay: bool,
+    /// Whether the game is paused, so ticks neither autoplay nor time out decisions
+    pub paused: bool,
+    /// The summary of the round just paid out, held up until a key is pressed
+    pub round_result: Option<RoundResult>,
+    /// In practice mode, the player's real chips and statistics, set aside until practice ends
+    real: Option<(u32, Statistics)>,
+    /// The decisions made in the latest practice, kept after it ends for reporting and export
+    pub decisions: DecisionLog,
+    retention: Option<usize>,
+    spill: Option<PathBuf>,
+    ev_cache: Arc<Mutex<EvCache>>,
+    /// The inputs made so far this round
+    round_inputs: Vec<Input>,
+    /// The inputs made in the last round to be paid out, to play again
+    pub last_round_inputs: Vec<Input>,
+    /// The inputs still to be replayed, one whenever the game waits for input
+    replay: VecDeque<Input>,
+    /// The state transitions made since the profiler last took them
+    pub transitions: u64,
+    /// The seat the keyboard bets for, switched between the player and the backers while bets are placed
+    pub seat: Seat,
+    clock: SharedClock,
+}
+
+/// Creates a decision log keeping the given number of decisions in memory, or every one.
+fn new_decision_log(retention: Option<usize>) -> DecisionLog {
+    retention.map_or_else(DecisionLog::new, DecisionLog::with_retention)
+}
+
+/// Appends rows of decisions to a CSV file, starting it with a header row if it is new.
+fn append_rows(path: &Path, rows: &str) -> io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    if file.metadata()?.len() == 0 {
+        writeln!(file, "{}", trainer::CSV_HEADER)?;
+    }
+    file.write_all(rows.as_bytes())
 }
 
 impl Default for Blackjack {
     fn default() -> Self {
-        Self::new()
+        Self::new(&Settings::default())
     }
 }
 impl Blackjack {
-    pub fn new() -> Self {
-        let table = Table::new(50000, Shoe::new(4, 0.50), Rules::default());
-        let game_state = GameState::Betting;
-        let input_field = InputField::from_game(&game_state, &table);
+    pub fn new(settings: &Settings) -> Self {
+        let mut table = settings.table.table(50000);
+        table.record_events = true;
+        table.seat_bots(&settings.bots);
+        for &chips in &settings.backers {
+            if table.add_backer(chips).is_err() {
+                break;
+            }
+        }
+        let game_state = table.betting();
+        let preferences = settings.preferences;
+        let input_field = InputField::from_game(&game_state, &table, &preferences);
+        let starting_chips = table.chips;
+        let now = settings.clock.now();
         Self {
             table,
             game_state,
             input_field,
             last_error: None,
+            started: now,
+            preferences,
+            decision_started: now,
+            narrator: settings.narrator.clone(),
+            narration: VecDeque::with_capacity(NARRATION_LINES),
+            cue_settings: settings.cues.clone(),
+            cues: Vec::new(),
+            toast: None,
+            limits: settings.limits,
+            starting_chips,
+            session_end: None,
+            statistics_before_round: Statistics::new(),
+            autoplay: false,
+            paused: false,
+            round_result: None,
+            real: None,
+            decisions: new_decision_log(settings.retention),
+            retention: settings.retention,
+            spill: settings.spill.clone(),
+            ev_cache: Arc::clone(&settings.ev_cache),
+            round_inputs: Vec::new(),
+            last_round_inputs: Vec::new(),
+            replay: VecDeque::new(),
+            transitions: 0,
+            seat: Seat::Player,
+            clock: settings.clock.clone(),
+        }
+    }
+
+    /// Whether the game is in practice mode, where the player rebuys whenever they run low
+    /// and nothing counts towards their real chips or statistics.
+    pub const fn is_practice(&self) -> bool {
+        self.real.is_some()
+    }
+
+    /// Starts or ends practice mode between rounds.
+    /// Practice starts with the starting chips, fresh statistics, and a fresh decision log;
+    /// ending it brings back the real chips and statistics.
+    pub fn toggle_practice(&mut self) -> Result<(), Error> {
+        if !matches!(self.game_state, GameState::Betting { .. }) {
+            return Err(Error::WrongInput);
+        }
+        let (chips, statistics) = match self.real.take() {
+            Some(real) => real,
+            None => {
+                self.real = Some((self.table.chips, self.table.statistics.clone()));
+                self.decisions = new_decision_log(self.retention);
+                (self.starting_chips, Statistics::new())
+            }
+        };
+        self.table.chips = chips;
+        self.table.statistics = statistics;
+        self.statistics_before_round = self.table.statistics.clone();
+        Ok(())
+    }
+
+    /// Starts a new game with the same configuration as this one:
+    /// its rules, shoe size, preferences, session limits, and autoplay.
+    pub fn duplicate(&self, settings: &Settings) -> Self {
+        let mut game = Self::new(settings);
+        game.table.rules = self.table.rules.clone();
+        game.table.shoe = Shoe::new(self.table.shoe.decks, self.table.shoe.max_penetration);
+        game.preferences = self.preferences;
+        game.limits = self.limits;
+        game.autoplay = self.autoplay;
+        game.input_field = InputField::from_game(&game.game_state, &game.table, &game.preferences);
+        game
+    }
+
+    /// Whether the game is over, whether the player went broke or reached a session limit.
+    pub fn is_finished(&self) -> bool {
+        self.game_state == GameState::GameOver
+    }
+
+    /// The time since this game was started, by its clock.
+    pub fn session_length(&self) -> Duration {
+        self.clock.now().saturating_sub(self.started)
+    }
+
+    /// The session length as hours, minutes, and seconds, e.g. "01:02:03".
+    pub fn session_time(&self) -> String {
+        let session = self.session_length().as_secs();
+        format!(
+            "{:02}:{:02}:{:02}",
+            session / 3600,
+            session / 60 % 60,
+            session % 60
+        )
+    }
+
+    /// The number of rounds played per hour of session time.
+    pub fn hands_per_hour(&self) -> f64 {
+        let hours = self.session_length().as_secs_f64() / 3600.0;
+        if hours > 0.0 {
+            self.table.statistics.turns_played() as f64 / hours
+        } else {
+            0.0
+        }
+    }
+
+    /// The time the player has left for the current decision, if decisions are timed.
+    pub fn time_left(&self) -> Option<Duration> {
+        let timer = self.table.rules.decision_timer?;
+        if self.input_field.is_none() || matches!(self.game_state, GameState::Betting { .. }) {
+            return None;
         }
+        let elapsed = self.clock.now().saturating_sub(self.decision_started);
+        Some(timer.saturating_sub(elapsed))
     }
 
     pub fn tick(&mut self) {
-        if self.try_progress(None).is_ok() {
-            self.last_error = None;
+        if self.paused {
+            return;
+        }
+        if self.autoplay {
+            self.simulate();
+            return;
+        }
+        if self.round_result.is_some() {
+            return;
+        }
+        let timed_out = self.time_left() == Some(Duration::ZERO);
+        let replaying = !timed_out && self.input_field.is_some() && !self.replay.is_empty();
+        let input = if timed_out {
+            Some(Input::Timeout)
+        } else if replaying {
+            self.replay.pop_front()
+        } else {
+            self.preferences.auto_input(&self.game_state)
+        };
+        match self.try_progress(input) {
+            Ok(()) => self.last_error = None,
+            // An input from the last round which isn't legal this time ends the replay
+            Err(transition_error) if replaying => {
+                self.replay.clear();
+                self.last_error = Some(transition_error);
+            }
+            Err(_) => {}
+        }
+    }
+
+    /// Plays the inputs of the last round again, one per tick, for as long as they stay legal.
+    pub fn replay_last_round(&mut self) {
+        if matches!(self.game_state, GameState::Betting { .. }) {
+            self.round_result = None;
+            self.replay = self.last_round_inputs.iter().copied().collect();
         }
     }
 
     pub fn input(&mut self, key: KeyCode) {
-        let input = self.input_field.as_mut().and_then(|f| f.consider(key));
+        // Any key dismisses the summary of the last round
+        if self.round_result.take().is_some() {
+            return;
+        }
+        if key == KeyCode::Tab {
+            self.switch_seat();
+            return;
+        }
+        let preferences = &self.preferences;
+        let seat = self.active_seat();
+        let input = self
+            .input_field
+            .as_mut()
+            .and_then(|f| f.consider(key, preferences))
+            .map(|input| input.with_seat(seat));
         if input.is_some() {
             if let Err(transition_error) = self.try_progress(input) {
                 self.last_error = Some(transition_error);
@@ -51,7 +344,83 @@ impl Blackjack {
         }
     }
 
+    /// Returns the seat the keyboard plays for: the one chosen while bets are placed,
+    /// and the player's once the round is dealt, since backers make no decisions.
+    pub const fn active_seat(&self) -> Seat {
+        match self.game_state {
+            GameState::Betting { .. } => self.seat,
+            _ => Seat::Player,
+        }
+    }
+
+    /// Switches the keyboard to the next seat at the table, from the player to each backer in turn.
+    fn switch_seat(&mut self) {
+        let backers = self.table.backers.len();
+        self.seat = match self.seat {
+            Seat::Player if backers > 0 => Seat::Backer(0),
+            Seat::Backer(backer) if usize::from(backer) + 1 < backers => Seat::Backer(backer + 1),
+            _ => Seat::Player,
+        };
+    }
+
+    /// Places a bet, as if it were typed in, if the game is waiting for one.
+    pub fn bet(&mut self, bet: u32) -> Result<(), Error> {
+        if !matches!(self.game_state, GameState::Betting { .. }) {
+            return Err(Error::WrongInput);
+        }
+        self.try_progress(Some(Input::bet(bet)))
+    }
+
+    /// Summarizes the session so far, for saving to a file.
+    /// After practice, the summary ends with the most expensive mistakes made in it.
+    pub fn summary(&self, catalog: &Catalog) -> String {
+        let rate = format!("{:.1}", self.hands_per_hour());
+        let mut summary = format!(
+            "{}\n{}\n{}\n{}",
+            catalog.format("ui.session", &[("time", &self.session_time())]),
+            catalog.format(
+                "ui.chips",
+                &[("chips", &catalog.money(f64::from(self.table.chips)))]
+            ),
+            catalog.format("ui.hands-per-hour", &[("rate", &rate)]),
+            self.table.statistics.report(catalog)
+        );
+        if self.decisions.recorded() > 0 {
+            let mistakes = self.decisions.mistakes();
+            let cost = catalog.chips((self.decisions.ev_cost() * 100.0).round() / 100.0);
+            summary.push_str(&catalog.format(
+                "ui.mistakes",
+                &[
+                    ("decisions", &self.decisions.recorded()),
+                    ("mistakes", &mistakes.iter().map(|m| m.count).sum::<usize>()),
+                    ("cost", &cost),
+                ],
+            ));
+            for mistake in mistakes.iter().take(MISTAKES_REPORTED) {
+                summary.push_str(&format!("\n  {mistake}"));
+            }
+        }
+        summary
+    }
+
+    /// A single line of `key=value` pairs describing the game, for status bars to show.
+    pub fn status_line(&self) -> String {
+        format!(
+            "chips={} hands={} running={} true={:.1}",
+            self.table.chips,
+            self.table.statistics.hands_played(),
+            counting::running_count(&self.table.shoe),
+            counting::true_count(&self.table.shoe)
+        )
+    }
+
     pub fn simulate(&mut self) {
+        if let Some(result) = &self.round_result {
+            if self.clock.now().saturating_sub(result.shown) < SUMMARY_TIMEOUT {
+                return;
+            }
+            self.round_result = None;
+        }
         let input = self.basic_strategy_input();
         if let Err(transition_error) = self.try_progress(input) {
             self.last_error = Some(transition_error);
@@ -61,10 +430,47 @@ impl Blackjack {
     }
 
     fn try_progress(&mut self, input: Option<Input>) -> Result<(), Error> {
-        let current_state = core::mem::replace(&mut self.game_state, GameState::Betting);
-        match self.table.progress(current_state, input) {
+        if self.is_practice() {
+            self.log_decision(input);
+        }
+        let current_state = core::mem::take(&mut self.game_state);
+        let statistics = self.table.statistics.clone();
+        let placing_bet = matches!(
+            (&current_state, &input),
+            (
+                GameState::Betting { .. },
+                Some(Input::Bet {
+                    seat: Seat::Player,
+                    ..
+                })
+            )
+        );
+        match self
+            .table
+            .progress_at(current_state, input, self.clock.now())
+        {
             Ok(next_state) => {
-                self.input_field = InputField::from_game(&next_state, &self.table);
+                self.transitions += 1;
+                if let Some(input) = input.filter(|&input| input != Input::Timeout) {
+                    self.round_inputs.push(input);
+                }
+                if placing_bet {
+                    self.statistics_before_round = statistics;
+                }
+                self.decision_started = self.clock.now();
+                self.narrate();
+                let next_state = self.check_limits(next_state);
+                if let GameState::Payout { round } = &next_state {
+                    self.last_round_inputs = std::mem::take(&mut self.round_inputs);
+                    self.round_result = Some(RoundResult {
+                        settlement: round.settlement(),
+                        net: round.net(),
+                        chips: self.table.chips + round.total_winnings(),
+                        shown: self.clock.now(),
+                    });
+                }
+                self.input_field =
+                    InputField::from_game(&next_state, &self.table, &self.preferences);
                 self.game_state = next_state;
                 Ok(())
             }
@@ -75,29 +481,161 @@ impl Blackjack {
         }
     }
 
-    pub fn basic_strategy_input(&self) -> Option<Input> {
-        match &self.game_state {
-            GameState::Betting => Some(Input::Bet(basic_strategy::bet())),
-            GameState::OfferEarlySurrender {
-                player_hand,
-                dealer_hand,
-            } => Some(Input::Choice(basic_strategy::surrender_early(
-                &self.table,
-                player_hand,
-                dealer_hand,
-            ))),
-            GameState::OfferInsurance { .. } => Some(Input::Bet(basic_strategy::bet_insurance())),
+    /// Logs the player's action on their current hand, if the input is one,
+    /// and counts it towards the cost of their errors.
+    fn log_decision(&mut self, input: Option<Input>) {
+        let (
+            Some(Input::Action { action, .. }),
             GameState::PlayPlayerTurn {
                 player_turn,
                 dealer_hand,
                 ..
-            } => Some(Input::Action(basic_strategy::play_hand(
-                &self.table,
-                player_turn,
-                dealer_hand,
-            ))),
-            _ => None,
+            },
+        ) = (input, &self.game_state)
+        else {
+            return;
+        };
+        let unseen = self.table.unseen(&self.game_state);
+        let evs = self.solve(player_turn, dealer_hand, &unseen);
+        if let Some(decision) = self
+            .decisions
+            .record_evs(player_turn, dealer_hand, &evs, action)
+        {
+            self.table.statistics.record_decision(decision);
+        }
+        self.spill_decisions();
+    }
+
+    /// Moves the decisions beyond the retention out of memory, a batch at a time,
+    /// appending them to the spill file if there is one.
+    fn spill_decisions(&mut self) {
+        if self.decisions.overflow() < SPILL_BATCH {
+            return;
+        }
+        let rows: String = self
+            .decisions
+            .spill()
+            .map(|decision| trainer::csv_row(&decision) + "\n")
+            .collect();
+        if let Some(path) = &self.spill {
+            if let Err(e) = append_rows(path, &rows) {
+                self.toast = Some(format!("{}: {e}", path.display()));
+            }
         }
     }
-}
 
+    /// The player's net result since the game started.
+    pub fn net(&self) -> i64 {
+        i64::from(self.table.chips) - i64::from(self.starting_chips)
+    }
+
+    /// Ends the game between rounds once the player reaches one of the session limits.
+    /// In practice mode, the player rebuys instead of going broke, and there are no limits.
+    fn check_limits(&mut self, state: GameState) -> GameState {
+        if self.is_practice() && matches!(state, GameState::Betting { .. } | GameState::GameOver) {
+            self.table.chips = self.table.chips.max(self.starting_chips);
+            return self.table.betting();
+        }
+        if !matches!(state, GameState::Betting { .. }) {
+            return state;
+        }
+        self.session_end = self
+            .limits
+            .check(self.table.statistics.turns_played(), self.net());
+        if self.session_end.is_some() {
+            self.table.withdraw_bets();
+            GameState::GameOver
+        } else {
+            state
+        }
+    }
+
+    /// Narrates the events since the last transition, keeping only the most recent lines,
+    /// and queues up the cues for any notable moments.
+    fn narrate(&mut self) {
+        for event in self.table.drain_events() {
+            match event {
+                GameEvent::CutCardReached | GameEvent::Shuffled => {
+                    self.toast = self.narrator.narrate(&event);
+                }
+                GameEvent::BetPlaced { .. } => self.toast = None,
+                _ => {}
+            }
+            if let Some(cue) = self.cue_settings.as_ref().and_then(|s| s.cue(&event)) {
+                self.cues.push(cue);
+            }
+            if let Some(line) = self.narrator.narrate(&event) {
+                if self.narration.len() == NARRATION_LINES {
+                    self.narration.pop_front();
+                }
+                self.narration.push_back(line);
+            }
+        }
+    }
+
+    /// The expected value of each action the player can take on their current hand,
+    /// given the cards they have not seen, or `None` outside the player's turn.
+    pub fn action_evs(&self) -> Option<Vec<(HandAction, f64)>> {
+        let GameState::PlayPlayerTurn {
+            player_turn,
+            dealer_hand,
+            ..
+        } = &self.game_state
+        else {
+            return None;
+        };
+        let unseen = self.table.unseen(&self.game_state);
+        Some(self.solve(player_turn, dealer_hand, &unseen))
+    }
+
+    /// The expected value of each action allowed on the player's current hand, from the cache if it has been solved.
+    fn solve(
+        &self,
+        player_turn: &PlayerTurn,
+        dealer_hand: &DealerHand,
+        unseen: &Composition,
+    ) -> Vec<(HandAction, f64)> {
+        self.ev_cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .action_evs(&self.table, player_turn, dealer_hand, unseen)
+    }
+
+    /// The chance that the dealer busts given their up card and the cards the player has not seen,
+    /// or `None` unless the player is making a decision with the hole card face down.
+    pub fn dealer_bust_chance(&self) -> Option<f64> {
+        let (dealer_hand, peeked) = match &self.game_state {
+            GameState::OfferEarlySurrender { dealer_hand, .. }
+            | GameState::OfferInsurance { dealer_hand, .. } => (dealer_hand, false),
+            GameState::PlayPlayerTurn { dealer_hand, .. } => {
+                (dealer_hand, dealer_hand.showing() >= 10)
+            }
+            _ => return None,
+        };
+        let unseen = self.table.unseen(&self.game_state);
+        let outcomes = ev::DealerOutcomes::new(
+            dealer_hand.showing(),
+            &unseen,
+            dealer_hand.drawing(),
+            peeked,
+        );
+        Some(outcomes.bust())
+    }
+
+    /// The exact chance that the dealer has blackjack and the expected value of insurance per unit bet,
+    /// given the cards the player has not seen, or `None` unless insurance is on offer.
+    pub fn insurance_ev(&self) -> Option<(f64, f64)> {
+        let GameState::OfferInsurance { dealer_hand, .. } = &self.game_state else {
+            return None;
+        };
+        let unseen = self.table.unseen(&self.game_state);
+        Some((
+            ev::dealer_blackjack_chance(dealer_hand.showing(), &unseen),
+            ev::insurance_ev(&unseen),
+        ))
+    }
+
+    pub fn basic_strategy_input(&self) -> Option<Input> {
+        basic_strategy::input(&self.table, &self.game_state)
+    }
+}
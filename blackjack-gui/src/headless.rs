@@ -0,0 +1,260 @@
+//! Drives the app's loop without a real terminal, for tests.
+//! Frames are drawn to a ratatui `TestBackend`, keys come from a script,
+//! and time only passes when the script waits for a tick.
+
+use std::collections::VecDeque;
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crossterm::event::KeyCode;
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+
+use blackjack_core::cues::{Cue, CuePlayer};
+
+use crate::app::App;
+use crate::clock::ManualClock;
+use crate::game::Settings;
+use crate::status::StatusLine;
+
+const TICK_RATE: Duration = Duration::from_secs(1);
+
+/// A step of a script played to the app.
+#[derive(Debug, Clone, Copy)]
+pub enum Step {
+    /// Presses a key
+    Key(KeyCode),
+    /// Types each character of the text in turn
+    Type(&'static str),
+    /// Lets the given number of ticks pass without pressing a key
+    Ticks(usize),
+}
+
+/// Collects the cues the app plays.
+struct Cues<'a>(&'a mut Vec<Cue>);
+
+impl CuePlayer for Cues<'_> {
+    fn play(&mut self, cue: Cue) {
+        self.0.push(cue);
+    }
+}
+
+/// An app running on a test terminal and a manual clock.
+pub struct Headless {
+    pub app: App,
+    pub terminal: Terminal<TestBackend>,
+    /// Every cue played so far
+    pub cues: Vec<Cue>,
+    clock: Arc<ManualClock>,
+}
+
+impl Headless {
+    /// Starts the app on a terminal of the given size, keeping time with a manual clock.
+    pub fn new(settings: Settings, width: u16, height: u16) -> Self {
+        let clock = Arc::new(ManualClock::default());
+        let settings = Settings {
+            clock: Arc::clone(&clock).into(),
+            ..settings
+        };
+        Self {
+            app: App::new(settings),
+            terminal: Terminal::new(TestBackend::new(width, height)).unwrap(),
+            cues: Vec::new(),
+            clock,
+        }
+    }
+
+    /// Runs the app's loop through the script, stopping once the script runs out of keys
+    /// with the frame after the last key drawn, and every game back from the worker threads.
+    pub fn run(&mut self, script: &[Step]) {
+        let mut keys: VecDeque<Option<KeyCode>> = script
+            .iter()
+            .flat_map(|step| match *step {
+                Step::Key(key) => vec![Some(key)],
+                Step::Type(text) => text.chars().map(|c| Some(KeyCode::Char(c))).collect(),
+                Step::Ticks(ticks) => vec![None; ticks],
+            })
+            .collect();
+        let clock = Arc::clone(&self.clock);
+        let next_key = |_timeout| -> io::Result<Option<KeyCode>> {
+            match keys.pop_front() {
+                Some(Some(key)) => Ok(Some(key)),
+                Some(None) => {
+                    clock.advance(TICK_RATE);
+                    Ok(None)
+                }
+                None => Err(io::ErrorKind::UnexpectedEof.into()),
+            }
+        };
+        let result = crate::run_app(
+            &mut self.terminal,
+            &mut self.app,
+            TICK_RATE,
+            next_key,
+            &mut Cues(&mut self.cues),
+            &mut StatusLine::new(false, None),
+        );
+        if let Err(error) = result {
+            let io_error = error.downcast_ref::<io::Error>();
+            assert!(
+                io_error.is_some_and(|e| e.kind() == io::ErrorKind::UnexpectedEof),
+                "{error}"
+            );
+        }
+        self.app.finish_steps();
+    }
+
+    /// The last frame drawn, one line of text per row.
+    pub fn screen(&self) -> String {
+        let buffer = self.terminal.backend().buffer();
+        let width = usize::from(buffer.area.width);
+        buffer
+            .content
+            .chunks(width)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+mod tests {
+    use super::*;
+    use blackjack_core::card::Rank;
+    use blackjack_core::state::GameState;
+
+    #[test]
+    fn test_betting() {
+        let mut headless = Headless::new(Settings::default(), 160, 50);
+        headless.run(&[Step::Key(KeyCode::Char('g')), Step::Type("25")]);
+        assert_eq!(headless.app.games.len(), 1);
+        assert!(headless
+            .screen()
+            .contains("or (.) to replay the last round: 25"));
+
+        headless.run(&[Step::Key(KeyCode::Enter)]);
+        assert!(headless.screen().contains("Bet too low"));
+
+        headless.run(&[
+            Step::Key(KeyCode::Backspace),
+            Step::Key(KeyCode::Backspace),
+            Step::Type("100"),
+            Step::Key(KeyCode::Enter),
+        ]);
+        let game = &headless.app.games[0];
+        assert_eq!(game.table.last_bet(), Some(100));
+        assert_eq!(game.table.chips, 49900);
+        assert!(!matches!(game.game_state, GameState::Betting { .. }));
+    }
+
+    #[test]
+    fn test_autoplay() {
+        let mut headless = Headless::new(Settings::default(), 160, 50);
+        headless.run(&[Step::Key(KeyCode::Char('g')), Step::Key(KeyCode::Char('a'))]);
+        assert_eq!(headless.app.games[0].table.statistics.hands_played(), 0);
+        headless.run(&[Step::Ticks(200)]);
+        assert!(headless.app.games[0].table.statistics.hands_played() > 0);
+        assert!(headless.app.games[0].session_length() >= Duration::from_secs(200));
+
+        headless.run(&[
+            Step::Type(":profiler"),
+            Step::Key(KeyCode::Enter),
+            Step::Ticks(5),
+        ]);
+        assert!(headless.app.profiler.is_some());
+        let screen = headless.screen();
+        assert!(screen.contains("Profiler"));
+        assert!(screen.contains("Transitions: "));
+    }
+
+    #[test]
+    fn test_autoplay_many() {
+        let mut headless = Headless::new(Settings::default(), 160, 50);
+        headless.run(&[Step::Type("gggggggggggg"), Step::Key(KeyCode::Char('a'))]);
+        headless.run(&[Step::Ticks(100)]);
+        assert_eq!(headless.app.games.len(), 12);
+        assert!(headless
+            .app
+            .games
+            .iter()
+            .all(|game| game.table.statistics.hands_played() > 0));
+    }
+
+    #[test]
+    fn test_spots() {
+        let mut headless = Headless::new(Settings::default(), 160, 50);
+        headless.run(&[Step::Key(KeyCode::Char('g'))]);
+        headless.app.games[0].table.shoe.force([
+            Rank::Ten,
+            Rank::Five,
+            Rank::Seven,
+            Rank::Nine,
+            Rank::Ten,
+            Rank::Six,
+        ]);
+        headless.run(&[
+            Step::Type("100s"),
+            Step::Type("100"),
+            Step::Key(KeyCode::Enter),
+            Step::Ticks(10),
+        ]);
+        let screen = headless.screen();
+        assert!(screen.contains("You"));
+        assert!(screen.contains("1. Hard 17, Bet: 100"));
+        assert!(screen.contains("2. Hard 16, Bet: 100"));
+    }
+
+    #[test]
+    fn test_seats() {
+        let settings = Settings {
+            bots: vec!["1:mimic".parse().unwrap()],
+            backers: vec![500],
+            ..Settings::default()
+        };
+        let mut headless = Headless::new(settings, 160, 50);
+        headless.run(&[Step::Key(KeyCode::Char('g'))]);
+        let screen = headless.screen();
+        assert!(screen.contains("Backer 1"));
+        assert!(screen.contains("Bot 1"));
+        assert!(screen.contains("Betting for You"));
+
+        // The keyboard bets for the backer after switching to them, and for the player again after that
+        headless.run(&[
+            Step::Key(KeyCode::Tab),
+            Step::Type("200"),
+            Step::Key(KeyCode::Enter),
+        ]);
+        let game = &headless.app.games[0];
+        assert_eq!(game.table.backers[0].chips, 300);
+        assert!(headless.screen().contains("Behind spot 1"));
+        headless.run(&[
+            Step::Key(KeyCode::Tab),
+            Step::Type("100"),
+            Step::Key(KeyCode::Enter),
+            Step::Ticks(10),
+        ]);
+        let game = &headless.app.games[0];
+        assert_eq!(game.table.chips, 49900);
+        assert!(!matches!(game.game_state, GameState::Betting { .. }));
+    }
+
+    #[test]
+    fn test_palette() {
+        let mut headless = Headless::new(Settings::default(), 160, 50);
+        headless.run(&[Step::Key(KeyCode::Char(':')), Step::Type("game 3")]);
+        headless.run(&[Step::Key(KeyCode::Enter)]);
+        assert!(headless.screen().contains("There is no game 3"));
+        headless.run(&[Step::Key(KeyCode::Esc), Step::Type("gg")]);
+        assert!(headless.app.palette.is_none());
+        assert_eq!(headless.app.selected_game, 1);
+        headless.run(&[Step::Type(":game 0"), Step::Key(KeyCode::Enter)]);
+        assert_eq!(headless.app.selected_game, 0);
+        headless.run(&[Step::Type(":chart"), Step::Key(KeyCode::Enter)]);
+        assert!(headless.screen().contains("Basic Strategy"));
+        assert!(headless
+            .screen()
+            .contains("A7:  S  D  D  D  D  S  S  H  H  H"));
+        headless.run(&[Step::Key(KeyCode::Esc)]);
+        assert!(headless.app.should_quit);
+    }
+}
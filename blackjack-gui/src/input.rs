@@ -1,50 +1,85 @@
 use crossterm::event::KeyCode;
 
 use blackjack_core::game::{HandAction, Input, Table};
+use blackjack_core::preferences::Preferences;
 use blackjack_core::state::GameState;
 
 #[derive(Debug)]
 pub enum InputField {
-    PlaceBet(String),
-    PlaceInsuranceBet(String),
+    /// The bet typed so far, and the last bet, placed again if nothing is typed
+    PlaceBet(String, Option<u32>),
+    /// The insurance bet typed so far, and the most the player may bet
+    PlaceInsuranceBet(String, u32),
     ChooseSurrender,
     PlayHand(Vec<HandAction>),
+    /// An irreversible action waiting to be confirmed, and the field to return to if it is not
+    Confirm(HandAction, Box<InputField>),
 }
 
 impl InputField {
-    pub fn from_game(state: &GameState, table: &Table) -> Option<Self> {
+    pub fn from_game(state: &GameState, table: &Table, preferences: &Preferences) -> Option<Self> {
         match state {
-            GameState::Betting => Some(Self::PlaceBet(String::new())),
-            GameState::OfferInsurance { .. } => Some(Self::PlaceInsuranceBet(String::new())),
-            GameState::OfferEarlySurrender { .. } => Some(Self::ChooseSurrender),
-            GameState::PlayPlayerTurn { player_turn, .. } => {
-                let mut allowed_actions = Vec::with_capacity(5);
-                allowed_actions.push(HandAction::Hit);
-                allowed_actions.push(HandAction::Stand);
-                if table.check_double_allowed(player_turn).is_ok() {
-                    allowed_actions.push(HandAction::Double);
-                }
-                if table.check_split_allowed(player_turn).is_ok() {
-                    allowed_actions.push(HandAction::Split);
-                }
-                if table
-                    .check_surrender_allowed(player_turn.current_hand())
-                    .is_ok()
-                {
-                    allowed_actions.push(HandAction::Surrender);
-                }
-                Some(Self::PlayHand(allowed_actions))
+            GameState::Betting { last_bet } => Some(Self::PlaceBet(String::new(), *last_bet)),
+            GameState::OfferInsurance { player_hand, .. } => Some(Self::PlaceInsuranceBet(
+                String::new(),
+                table.max_insurance(player_hand),
+            )),
+            GameState::OfferEarlySurrender { .. } if !preferences.never_surrender => {
+                Some(Self::ChooseSurrender)
             }
+            GameState::PlayPlayerTurn { player_turn, .. } => Some(Self::PlayHand(
+                preferences.offered_actions(table, player_turn),
+            )),
             _ => None,
         }
     }
 
-    pub fn consider(&mut self, key_code: KeyCode) -> Option<Input> {
-        match self {
-            Self::PlaceBet(s) => parse_bet_from_string(key_code, s),
-            Self::PlaceInsuranceBet(s) => parse_bet_from_string(key_code, s),
+    /// Considers a key press, returning the input it completes, if any.
+    /// Actions the preferences require confirmation for are held back until they are confirmed.
+    pub fn consider(&mut self, key_code: KeyCode, preferences: &Preferences) -> Option<Input> {
+        let input = match self {
+            Self::PlaceBet(s, last_bet) => match key_code {
+                KeyCode::Char('t' | 'T') => s.parse().ok().map(Input::tip),
+                KeyCode::Char('s' | 'S') => s.parse().ok().map(Input::spot),
+                KeyCode::Enter if s.is_empty() => last_bet.map(Input::bet),
+                key_code => parse_bet_from_string(key_code, s),
+            },
+            Self::PlaceInsuranceBet(s, max) => match key_code {
+                KeyCode::Char('m' | 'M') => Some(Input::bet(*max)),
+                KeyCode::Char('h' | 'H') => Some(Input::bet(*max / 2)),
+                KeyCode::Char('n' | 'N') => Some(Input::bet(0)),
+                key_code => parse_bet_from_string(key_code, s),
+            },
             Self::ChooseSurrender => select_choice(key_code),
             Self::PlayHand(_) => select_action(key_code),
+            Self::Confirm(action, previous) => {
+                return match select_choice(key_code) {
+                    Some(Input::Choice { choice: true, .. }) => Some(match **previous {
+                        Self::ChooseSurrender => Input::choice(true),
+                        _ => Input::action(*action),
+                    }),
+                    Some(_) => {
+                        let previous = std::mem::replace(&mut **previous, Self::ChooseSurrender);
+                        *self = previous;
+                        None
+                    }
+                    None => None,
+                };
+            }
+        };
+        let action = match input {
+            Some(Input::Action { action, .. }) => action,
+            Some(Input::Choice { choice: true, .. }) if matches!(self, Self::ChooseSurrender) => {
+                HandAction::Surrender
+            }
+            _ => return input,
+        };
+        if preferences.confirm_required(action) {
+            let previous = std::mem::replace(self, Self::ChooseSurrender);
+            *self = Self::Confirm(action, Box::new(previous));
+            None
+        } else {
+            input
         }
     }
 }
@@ -52,7 +87,7 @@ impl InputField {
 fn parse_bet_from_string(key: KeyCode, field: &mut String) -> Option<Input> {
     if key == KeyCode::Enter {
         if let Ok(bet) = field.parse() {
-            return Some(Input::Bet(bet));
+            return Some(Input::bet(bet));
         }
     }
     match key {
@@ -67,19 +102,19 @@ fn parse_bet_from_string(key: KeyCode, field: &mut String) -> Option<Input> {
 
 const fn select_choice(key: KeyCode) -> Option<Input> {
     match key {
-        KeyCode::Char('y' | 'Y') => Some(Input::Choice(true)),
-        KeyCode::Char('n' | 'N') => Some(Input::Choice(false)),
+        KeyCode::Char('y' | 'Y') => Some(Input::choice(true)),
+        KeyCode::Char('n' | 'N') => Some(Input::choice(false)),
         _ => None,
     }
 }
 
 fn select_action(key: KeyCode) -> Option<Input> {
     match key {
-        KeyCode::Char('h' | 'H') => Some(Input::Action(HandAction::Hit)),
-        KeyCode::Char('s' | 'S') => Some(Input::Action(HandAction::Stand)),
-        KeyCode::Char('d' | 'D') => Some(Input::Action(HandAction::Double)),
-        KeyCode::Char('p' | 'P') => Some(Input::Action(HandAction::Split)),
-        KeyCode::Char('r' | 'R') => Some(Input::Action(HandAction::Surrender)),
+        KeyCode::Char('h' | 'H') => Some(Input::action(HandAction::Hit)),
+        KeyCode::Char('s' | 'S') => Some(Input::action(HandAction::Stand)),
+        KeyCode::Char('d' | 'D') => Some(Input::action(HandAction::Double)),
+        KeyCode::Char('p' | 'P') => Some(Input::action(HandAction::Split)),
+        KeyCode::Char('r' | 'R') => Some(Input::action(HandAction::Surrender)),
         _ => None,
     }
 }
@@ -1,81 +1,360 @@
 use std::error::Error;
+use std::fs;
 use std::io;
-use std::io::Stdout;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, PoisonError};
 use std::time::{Duration, Instant};
 
 use clap::Parser;
-use crossterm::event::{DisableMouseCapture, EnableMouseCapture, Event, KeyEvent};
+use crossterm::event::{DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent};
 use crossterm::terminal::{
     disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
 };
 use crossterm::{event, execute};
-use ratatui::backend::CrosstermBackend;
+use ratatui::backend::{Backend, CrosstermBackend};
 use ratatui::Terminal;
 
+use blackjack_core::cues::{Bell, CuePlayer, CueSettings};
+use blackjack_core::currency::Currency;
+use blackjack_core::ev_cache::EvCache;
+use blackjack_core::game::BotSeats;
+use blackjack_core::i18n::{Catalog, Locale, SuitStyle};
+use blackjack_core::narration::{Narrator, Personality};
+use blackjack_core::rules::TimeoutAction;
+use blackjack_core::session::SessionLimits;
+use blackjack_core::table_spec::TableSpec;
+
 use crate::app::App;
+use crate::clock::{InstantClock, ScaledClock, SharedClock};
+use crate::game::Settings;
+use crate::preferences::PreferenceArgs;
+use crate::profiler::Profiler;
+use crate::remote::RemoteTable;
+use crate::status::StatusLine;
 
 pub mod app;
+mod clock;
 mod game;
+#[cfg(test)]
+mod headless;
 mod input;
+mod palette;
+mod preferences;
+mod profiler;
+mod remote;
+mod status;
 pub mod ui;
+mod workers;
+
+/// Counts every allocation for the profiler overlay.
+#[cfg(feature = "profiling")]
+#[global_allocator]
+static GLOBAL: blackjack_core::profiling::CountingAllocator =
+    blackjack_core::profiling::CountingAllocator;
 
+/// Play and simulate many games of Blackjack at once in the terminal.
 #[derive(Debug, Parser)]
 #[command(author, about, version)]
 pub struct AppConfiguration {
+    /// the table new games are dealt at, e.g. "6D,H17,DAS,LS,75%".
+    #[arg(long, default_value = "4D,50%")]
+    table: TableSpec,
     /// time in ms between two ticks.
     #[arg(short, long, default_value_t = 1000)]
     tick_rate: u64,
+    /// how fast time passes, e.g. 2 to play ticks, timers, and summaries at double speed,
+    /// or max to play every tick without waiting.
+    #[arg(long, default_value_t = 1.0, value_parser = parse_speed)]
+    speed: f64,
+    /// time in seconds for each decision (speed blackjack).
+    #[arg(short, long)]
+    decision_time: Option<u64>,
+    /// play timed-out decisions with basic strategy instead of standing.
+    #[arg(long)]
+    timeout_basic_strategy: bool,
+    /// number of games to autoplay in demo mode.
+    #[arg(long)]
+    demo: Option<usize>,
+    /// the dealer's personality: professional, friendly, or grumpy.
+    #[arg(long, default_value_t = Personality::Professional)]
+    dealer: Personality,
+    /// the language of the interface: en or es.
+    #[arg(long, default_value_t = Locale::English)]
+    locale: Locale,
+    /// a message catalog file overriding the built-in messages.
+    #[arg(long)]
+    messages: Option<PathBuf>,
+    /// ring the terminal bell on blackjacks, busts, and big wins.
+    #[arg(long)]
+    bell: bool,
+    /// the profit in chips on a round that counts as a big win.
+    #[arg(long, default_value_t = 500)]
+    big_win: u32,
+    /// show the expected value of each action on the player's turn, and of insurance when offered.
+    #[arg(long)]
+    show_ev: bool,
+    /// show the chance that the dealer busts while the hole card is face down.
+    #[arg(long)]
+    show_bust: bool,
+    /// end the game once the player is up by this many chips.
+    #[arg(long)]
+    stop_win: Option<u32>,
+    /// end the game once the player is down by this many chips.
+    #[arg(long)]
+    stop_loss: Option<u32>,
+    /// how cards are named: names, letters, or symbols.
+    #[arg(long, default_value_t = SuitStyle::Names)]
+    suits: SuitStyle,
+    /// show chips as money: a currency symbol and the worth of a chip, e.g. "$0.25" or "€5".
+    #[arg(long)]
+    currency: Option<Currency>,
+    /// draw with bold and reversed text instead of colors.
+    #[arg(long)]
+    high_contrast: bool,
+    /// show the selected game's chips, hands played, and count in the terminal's title.
+    #[arg(long)]
+    status_title: bool,
+    /// keep the selected game's chips, hands played, and count in this file, on a single line.
+    #[arg(long)]
+    status_file: Option<PathBuf>,
+    /// show the profiler overlay from the start, which the "profiler" command toggles.
+    /// Allocations are only counted when built with the profiling feature.
+    #[arg(long)]
+    profiler: bool,
+    /// the number of practice decisions each game keeps in memory for its mistakes export;
+    /// older ones are appended to the spill file, or dropped. The summary still counts every one.
+    #[arg(long, default_value_t = 10_000)]
+    retain_decisions: usize,
+    /// the CSV file practice decisions beyond those kept in memory are appended to.
+    #[arg(long)]
+    spill_file: Option<PathBuf>,
+    /// keep the expected values solved for hints and practice in this file,
+    /// loading it at the start and saving it on quitting, so later sessions don't solve them again.
+    #[arg(long)]
+    ev_cache: Option<PathBuf>,
+    /// bots seated beside the player, playing hands of their own, as a number and optionally their strategy:
+    /// basic (the default), mimic (hits below 17 like the dealer), or never-bust.
+    /// May be given more than once, e.g. "--bots 2 --bots 1:mimic".
+    #[arg(long)]
+    bots: Vec<BotSeats>,
+    /// seat a backer with this many chips behind the player, betting from the keyboard after (tab) switches to them.
+    /// May be given more than once.
+    #[arg(long = "backer")]
+    backers: Vec<u32>,
+    /// play at a table served by `blackjack serve` at this address, e.g. "localhost:2323", as a thin client.
+    #[arg(long)]
+    connect: Option<String>,
+    #[command(flatten)]
+    preferences: PreferenceArgs,
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
+    let config = AppConfiguration::parse();
+    // The player takes a seat of their own
+    let seats = usize::from(config.table.rules.max_seats).saturating_sub(1);
+    if config.bots.iter().map(|bots| bots.count).sum::<usize>() + config.backers.len() > seats {
+        return Err(format!("at most {seats} bots and backers fit beside the player").into());
+    }
+    let mut catalog = Catalog::new(config.locale);
+    if let Some(path) = &config.messages {
+        catalog.extend(&fs::read_to_string(path)?);
+    }
+    catalog.suit_style = config.suits;
+    catalog.currency = config.currency;
+    let preferences = config.preferences.load()?;
+    let ev_cache = match &config.ev_cache {
+        Some(path) if path.exists() => fs::read_to_string(path)?
+            .parse()
+            .map_err(|e| format!("{}: {e}", path.display()))?,
+        _ => EvCache::new(),
+    };
+    let ev_cache = Arc::new(Mutex::new(ev_cache));
+    let mut remote = config
+        .connect
+        .as_deref()
+        .map(RemoteTable::connect)
+        .transpose()?;
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
 
-    let mut app = App::new();
-    let tick_rate = Duration::from_secs(1);
-    let result = run_app(&mut terminal, &mut app, tick_rate);
+    let mut table = config.table;
+    table.rules.decision_timer = config.decision_time.map(Duration::from_secs);
+    table.rules.timeout_action = if config.timeout_basic_strategy {
+        TimeoutAction::BasicStrategy
+    } else {
+        TimeoutAction::Stand
+    };
+    let mut app = App::new(Settings {
+        table,
+        preferences,
+        narrator: Narrator {
+            personality: config.dealer,
+            catalog: catalog.clone(),
+        },
+        catalog,
+        cues: config.bell.then(|| CueSettings {
+            big_win: Some(config.big_win),
+            ..CueSettings::default()
+        }),
+        show_ev: config.show_ev,
+        show_bust: config.show_bust,
+        limits: SessionLimits {
+            rounds: None,
+            stop_win: config.stop_win,
+            stop_loss: config.stop_loss,
+        },
+        high_contrast: config.high_contrast,
+        retention: Some(config.retain_decisions),
+        spill: config.spill_file,
+        ev_cache: Arc::clone(&ev_cache),
+        clock: if config.speed.is_infinite() {
+            Arc::new(InstantClock::new(Duration::from_millis(config.tick_rate))).into()
+        } else if (config.speed - 1.0).abs() < f64::EPSILON {
+            SharedClock::default()
+        } else {
+            Arc::new(ScaledClock::new(config.speed)).into()
+        },
+        bots: config.bots,
+        backers: config.backers,
+    });
+    if config.profiler {
+        app.profiler = Some(Profiler::default());
+    }
+    if let Some(games) = config.demo {
+        app.start_demo(games);
+    }
+    let tick_rate = Duration::from_millis(config.tick_rate);
+    let mut bell = Bell::new(io::stdout());
+    let mut status = StatusLine::new(config.status_title, config.status_file);
+    let result = match &mut remote {
+        Some(remote) => run_remote(&mut terminal, remote, &app, read_key),
+        None => run_app(
+            &mut terminal,
+            &mut app,
+            tick_rate,
+            read_key,
+            &mut bell,
+            &mut status,
+        ),
+    };
 
     disable_raw_mode()?;
     execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
     terminal.show_cursor()?;
 
-    println!("{app:#?}");
+    if remote.is_none() {
+        println!("{app:#?}");
+    }
     if let Err(err) = result {
         println!("{err:#?}");
     }
+    if let Some(path) = &config.ev_cache {
+        let ev_cache = ev_cache.lock().unwrap_or_else(PoisonError::into_inner);
+        fs::write(path, ev_cache.to_string())?;
+    }
 
     Ok(())
 }
 
-fn run_app(
-    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+/// Runs the app until it quits: drawing each frame, handing it each key press,
+/// and ticking it whenever a tick's worth of time has passed on its clock without one.
+/// `next_key` waits up to the given time for a key press, returning it if there was one.
+/// Games stepped by worker threads are taken back between frames, without waiting for them.
+fn run_app<B: Backend>(
+    terminal: &mut Terminal<B>,
     app: &mut App,
     tick_rate: Duration,
+    mut next_key: impl FnMut(Duration) -> io::Result<Option<KeyCode>>,
+    cue_player: &mut impl CuePlayer,
+    status: &mut StatusLine,
 ) -> Result<(), Box<dyn Error>> {
-    let mut last_tick = Instant::now();
+    let clock = app.settings.clock.clone();
+    let mut last_tick = clock.now();
     loop {
-        terminal.draw(|f| ui::display(f, app))?;
-        let timeout = tick_rate.saturating_sub(last_tick.elapsed());
-        if event::poll(timeout)? {
-            if let Event::Key(KeyEvent {
-                kind: event::KeyEventKind::Press,
-                code,
-                ..
-            }) = event::read()?
-            {
-                app.input(code);
-                last_tick = Instant::now();
+        clock.step();
+        app.collect_steps();
+        // While the games are away with the workers, the last frame stays up and keys wait for them
+        let mut wait = clock.wait(last_tick + tick_rate);
+        if app.stepping() {
+            wait = wait.min(workers::POLL);
+        } else {
+            let drawing = Instant::now();
+            terminal.draw(|f| ui::display(f, app))?;
+            if let Some(profiler) = &mut app.profiler {
+                profiler.record_frame(drawing.elapsed());
             }
         }
+        if let Some(key) = next_key(wait)? {
+            app.input(key);
+            last_tick = clock.now();
+        }
         if app.should_quit {
             break;
         }
-        if last_tick.elapsed() >= tick_rate {
+        if !app.stepping() && clock.now() >= last_tick + tick_rate {
+            let ticking = Instant::now();
             app.tick();
-            last_tick = Instant::now();
+            if let Some(profiler) = &mut app.profiler {
+                profiler.record_tick(ticking.elapsed());
+            }
+            last_tick = clock.now();
+        }
+        let transitions = app.take_transitions();
+        if let Some(profiler) = &mut app.profiler {
+            profiler.record_transitions(transitions);
+        }
+        for cue in app.drain_cues() {
+            cue_player.play(cue);
+        }
+        if let Some(game) = app.current_game() {
+            status.publish(&game.status_line())?;
         }
     }
     Ok(())
 }
+
+/// Runs a table played on a server until the player quits: drawing each frame,
+/// handing it each key press, and taking in what the server sends in between.
+fn run_remote<B: Backend>(
+    terminal: &mut Terminal<B>,
+    remote: &mut RemoteTable,
+    app: &App,
+    mut next_key: impl FnMut(Duration) -> io::Result<Option<KeyCode>>,
+) -> Result<(), Box<dyn Error>> {
+    while !remote.should_quit {
+        remote.receive();
+        terminal.draw(|f| ui::display_remote(f, remote, app))?;
+        if let Some(key) = next_key(remote::POLL)? {
+            remote.input(key);
+        }
+    }
+    Ok(())
+}
+
+/// Waits up to the timeout for a key to be pressed in the terminal.
+fn read_key(timeout: Duration) -> io::Result<Option<KeyCode>> {
+    if event::poll(timeout)? {
+        if let Event::Key(KeyEvent {
+            kind: event::KeyEventKind::Press,
+            code,
+            ..
+        }) = event::read()?
+        {
+            return Ok(Some(code));
+        }
+    }
+    Ok(None)
+}
+
+/// Parses the speed of the clock, which must be positive, or "max" for no waiting.
+fn parse_speed(s: &str) -> Result<f64, String> {
+    if s == "max" {
+        return Ok(f64::INFINITY);
+    }
+    match s.parse() {
+        Ok(speed) if speed > 0.0 => Ok(speed),
+        _ => Err(format!("expected a positive speed: {s}")),
+    }
+}
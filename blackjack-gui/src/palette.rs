@@ -0,0 +1,79 @@
+use std::path::PathBuf;
+
+use crossterm::event::KeyCode;
+
+/// A command typed into the command palette, for operations without a key of their own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Bet this amount on the selected game
+    Bet(u32),
+    /// Show or hide the expected values and the dealer's bust chance
+    Hints,
+    /// Show the basic strategy chart for the selected game's table in place of the game, or hide it
+    Chart,
+    /// Write a summary of the selected game's session to a file
+    Save(PathBuf),
+    /// Write every decision logged in the selected game's latest practice to a CSV file
+    Mistakes(PathBuf),
+    /// Select the game with this number
+    Game(usize),
+    /// Start or end practice mode in the selected game
+    Practice,
+    /// Show or hide the profiler overlay
+    Profiler,
+    Quit,
+}
+
+impl Command {
+    /// Parses a command, e.g. "bet 200" or "game 3". Returns `None` if it isn't one.
+    pub fn parse(input: &str) -> Option<Self> {
+        let mut words = input.split_whitespace();
+        let command = match (words.next()?, words.next()) {
+            ("bet", Some(bet)) => Self::Bet(bet.parse().ok()?),
+            ("hints", None) => Self::Hints,
+            ("chart", None) => Self::Chart,
+            ("practice", None) => Self::Practice,
+            ("profiler", None) => Self::Profiler,
+            ("save", Some(path)) => Self::Save(PathBuf::from(path)),
+            ("mistakes", Some(path)) => Self::Mistakes(PathBuf::from(path)),
+            ("game", Some(game)) => Self::Game(game.parse().ok()?),
+            ("quit" | "q", None) => Self::Quit,
+            _ => return None,
+        };
+        words.next().is_none().then_some(command)
+    }
+}
+
+/// The command palette, opened with `:`.
+#[derive(Debug, Default)]
+pub struct CommandPalette {
+    /// The command typed so far
+    pub input: String,
+    /// Why the last command could not be run, if it couldn't
+    pub error: Option<String>,
+}
+
+impl CommandPalette {
+    /// Considers a key press, returning what it asks of the palette, if anything.
+    pub fn consider(&mut self, key: KeyCode) -> Option<PaletteInput> {
+        match key {
+            KeyCode::Esc => return Some(PaletteInput::Close),
+            KeyCode::Enter => return Some(PaletteInput::Submit(std::mem::take(&mut self.input))),
+            KeyCode::Char(c) => self.input.push(c),
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            _ => {}
+        }
+        self.error = None;
+        None
+    }
+}
+
+/// What a key press in the command palette asks for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PaletteInput {
+    Close,
+    /// Run the command typed so far
+    Submit(String),
+}
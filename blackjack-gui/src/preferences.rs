@@ -0,0 +1,49 @@
+use std::path::PathBuf;
+
+use clap::Args;
+
+use blackjack_core::preferences::Preferences;
+
+/// The player's preferences for manual play: those in a preferences file, shared with
+/// `blackjack serve`, and any more turned on by flags.
+#[derive(Debug, Default, Clone, Args)]
+pub struct PreferenceArgs {
+    /// A preferences file, holding one "preference = true" per line, e.g. "auto-stand = true".
+    #[arg(long = "preferences")]
+    pub file: Option<PathBuf>,
+    /// Automatically stand on hard 20 or more.
+    #[arg(long)]
+    pub auto_stand: bool,
+    /// Automatically decline insurance.
+    #[arg(long)]
+    pub auto_decline_insurance: bool,
+    /// Automatically repeat the last bet.
+    #[arg(long)]
+    pub auto_rebet: bool,
+    /// Never offer to surrender.
+    #[arg(long)]
+    pub never_surrender: bool,
+    /// Ask for confirmation before surrendering.
+    #[arg(long)]
+    pub confirm_surrender: bool,
+    /// Ask for confirmation before doubling down.
+    #[arg(long)]
+    pub confirm_double: bool,
+}
+
+impl PreferenceArgs {
+    /// Loads the preferences file, if there is one, and turns on the preferences given as flags.
+    pub fn load(&self) -> Result<Preferences, String> {
+        let mut preferences = match &self.file {
+            Some(path) => Preferences::load(path)?,
+            None => Preferences::default(),
+        };
+        preferences.auto_stand |= self.auto_stand;
+        preferences.auto_decline_insurance |= self.auto_decline_insurance;
+        preferences.auto_rebet |= self.auto_rebet;
+        preferences.never_surrender |= self.never_surrender;
+        preferences.confirm_surrender |= self.confirm_surrender;
+        preferences.confirm_double |= self.confirm_double;
+        Ok(preferences)
+    }
+}
@@ -0,0 +1,123 @@
+//! A profiler for the main loop, shown as an overlay: how long frames and ticks take,
+//! how many state transitions the games make, and, when built with the profiling feature,
+//! how many allocations are made, all over the last second.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use blackjack_core::profiling::allocations;
+
+/// How far back the profiler looks.
+pub const WINDOW: Duration = Duration::from_secs(1);
+
+/// The timings and counts recorded over the last second.
+#[derive(Debug, Default)]
+pub struct Profiler {
+    /// When each frame was drawn, and how long drawing it took
+    frames: VecDeque<(Instant, Duration)>,
+    /// When each tick was made, and how long it took
+    ticks: VecDeque<(Instant, Duration)>,
+    /// The state transitions made by the games since the last sample
+    transitions: VecDeque<(Instant, u64)>,
+    /// The allocations made since the last frame, and the running total they were counted from
+    allocations: VecDeque<(Instant, usize)>,
+    allocations_counted: usize,
+}
+
+/// A summary of the profiler's last second.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Profile {
+    pub frames: Timings,
+    pub ticks: Timings,
+    pub transitions_per_second: f64,
+    /// The allocations made per second, if they are counted
+    pub allocations_per_second: Option<f64>,
+}
+
+/// How often something happened in the last second, and how long it took.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Timings {
+    pub per_second: f64,
+    pub average: Duration,
+    pub max: Duration,
+}
+
+impl Timings {
+    /// Summarizes the samples taken since the cutoff.
+    fn of(samples: &VecDeque<(Instant, Duration)>, cutoff: Option<Instant>) -> Self {
+        let durations: Vec<Duration> = samples
+            .iter()
+            .filter(|(at, _)| cutoff.is_none_or(|cutoff| *at >= cutoff))
+            .map(|&(_, duration)| duration)
+            .collect();
+        let Some(&max) = durations.iter().max() else {
+            return Self::default();
+        };
+        Self {
+            per_second: durations.len() as f64 / WINDOW.as_secs_f64(),
+            average: durations.iter().sum::<Duration>() / durations.len() as u32,
+            max,
+        }
+    }
+}
+
+impl Profiler {
+    /// Records a frame that took the given time to draw.
+    pub fn record_frame(&mut self, duration: Duration) {
+        let now = Instant::now();
+        push(&mut self.frames, now, duration);
+        if let Some(total) = allocations() {
+            let made = total.saturating_sub(self.allocations_counted);
+            self.allocations_counted = total;
+            push(&mut self.allocations, now, made);
+        }
+    }
+
+    /// Records a tick that took the given time.
+    pub fn record_tick(&mut self, duration: Duration) {
+        push(&mut self.ticks, Instant::now(), duration);
+    }
+
+    /// Records state transitions made by the games.
+    pub fn record_transitions(&mut self, transitions: u64) {
+        if transitions > 0 {
+            push(&mut self.transitions, Instant::now(), transitions);
+        }
+    }
+
+    /// Summarizes the last second.
+    #[must_use]
+    pub fn profile(&self) -> Profile {
+        let cutoff = Instant::now().checked_sub(WINDOW);
+        let recent = |at: &Instant| cutoff.is_none_or(|cutoff| *at >= cutoff);
+        let transitions: u64 = self
+            .transitions
+            .iter()
+            .filter(|(at, _)| recent(at))
+            .map(|&(_, count)| count)
+            .sum();
+        let allocated: usize = self
+            .allocations
+            .iter()
+            .filter(|(at, _)| recent(at))
+            .map(|&(_, count)| count)
+            .sum();
+        Profile {
+            frames: Timings::of(&self.frames, cutoff),
+            ticks: Timings::of(&self.ticks, cutoff),
+            transitions_per_second: transitions as f64 / WINDOW.as_secs_f64(),
+            allocations_per_second: allocations().map(|_| allocated as f64 / WINDOW.as_secs_f64()),
+        }
+    }
+}
+
+/// Adds a sample, dropping those which have fallen out of the window.
+fn push<T>(samples: &mut VecDeque<(Instant, T)>, now: Instant, sample: T) {
+    while samples
+        .front()
+        .is_some_and(|(at, _)| now.duration_since(*at) > WINDOW)
+    {
+        samples.pop_front();
+    }
+    samples.push_back((now, sample));
+}
@@ -0,0 +1,244 @@
+//! A thin client for a table served by `blackjack serve`: the server deals and narrates each round,
+//! and the GUI draws the table from the snapshots it sends, alongside its narration and prompts,
+//! and sends back the player's answers.
+//! The two speak the text protocol of the `blackjack-protocol` crate.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Shutdown, TcpStream};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::Duration;
+
+use crossterm::event::KeyCode;
+
+use blackjack_protocol::answer::{action, action_name};
+use blackjack_protocol::{Greeting, Message, Prompt, Snapshot};
+
+/// How long to wait for a key press before taking in what the server has sent.
+pub const POLL: Duration = Duration::from_millis(50);
+
+/// The most lines of narration kept.
+const MAX_LOG: usize = 1000;
+
+/// A table played on a server.
+#[derive(Debug)]
+pub struct RemoteTable {
+    /// The server's address
+    pub address: String,
+    /// The server's narration and errors, oldest first
+    pub log: Vec<String>,
+    /// The table as the server last sent it
+    pub snapshot: Snapshot,
+    /// The decision the table is waiting for
+    pub prompt: Option<Prompt>,
+    /// The answer typed so far
+    pub answer: String,
+    /// Whether the server has closed the connection
+    pub closed: bool,
+    pub should_quit: bool,
+    messages: Receiver<Message>,
+    output: TcpStream,
+}
+
+impl RemoteTable {
+    /// Connects to a server and greets it, reading its messages on a thread of their own from then on.
+    /// # Errors
+    /// Returns an error if the server can't be reached, doesn't greet back,
+    /// or speaks another version of the protocol.
+    pub fn connect(address: &str) -> io::Result<Self> {
+        let stream = TcpStream::connect(address)?;
+        let mut output = stream.try_clone()?;
+        writeln!(output, "{}", Greeting::CURRENT)?;
+        let mut input = BufReader::new(stream);
+        // Lines before the server's greeting, like its question for people typing at a terminal, are skipped
+        let mut last = String::new();
+        loop {
+            let mut line = String::new();
+            if input.read_line(&mut line)? == 0 {
+                let reason = if last.is_empty() {
+                    format!("{address} closed the connection without greeting")
+                } else {
+                    format!("{address}: {last}")
+                };
+                return Err(io::Error::new(io::ErrorKind::UnexpectedEof, reason));
+            }
+            match line.parse() {
+                Ok(Message::Greeting(greeting)) => {
+                    greeting.check().map_err(io::Error::other)?;
+                    break;
+                }
+                Ok(Message::Error(error)) => return Err(io::Error::other(error)),
+                _ => last = line.trim().to_string(),
+            }
+        }
+        let (sender, messages) = mpsc::channel();
+        thread::spawn(move || {
+            for line in input.lines().map_while(Result::ok) {
+                if let Ok(message) = line.parse() {
+                    if sender.send(message).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+        Ok(Self {
+            address: address.to_string(),
+            log: Vec::new(),
+            snapshot: Snapshot::default(),
+            prompt: None,
+            answer: String::new(),
+            closed: false,
+            should_quit: false,
+            messages,
+            output,
+        })
+    }
+
+    /// Takes in the messages the server has sent since the last call, without waiting for more.
+    pub fn receive(&mut self) {
+        loop {
+            match self.messages.try_recv() {
+                Ok(Message::Say(line)) => self.log.push(line),
+                Ok(Message::Error(error)) => self.log.push(format!("{error}!")),
+                Ok(Message::Snapshot(snapshot)) => self.snapshot = snapshot,
+                Ok(Message::Prompt(prompt)) => self.prompt = Some(prompt),
+                // Events are for programs; the narration tells people the same
+                Ok(Message::Greeting(_) | Message::Event(_)) => {}
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.closed = true;
+                    self.prompt = None;
+                    break;
+                }
+            }
+        }
+        let excess = self.log.len().saturating_sub(MAX_LOG);
+        self.log.drain(..excess);
+    }
+
+    /// Considers a key press. Actions and surrender choices are answered with a key,
+    /// like at a local table, and other prompts with what is typed before enter;
+    /// nothing at all bets the last bet again.
+    /// Escape leaves the table, giving up the seat if the player is betting,
+    /// and otherwise keeping it for them to return to with their seat code.
+    pub fn input(&mut self, key: KeyCode) {
+        match (key, &self.prompt) {
+            (KeyCode::Esc, prompt) => {
+                if matches!(prompt, Some(Prompt::Bet { .. })) {
+                    self.send("q");
+                }
+                self.should_quit = true;
+            }
+            (KeyCode::Char(c), Some(Prompt::Turn { allowed, .. })) => {
+                if let Some(action) = action(&c.to_ascii_lowercase().to_string())
+                    .filter(|action| allowed.contains(action))
+                {
+                    self.send(action_name(action));
+                }
+            }
+            (KeyCode::Char(c @ ('y' | 'Y' | 'n' | 'N')), Some(Prompt::Surrender { .. })) => {
+                self.send(&c.to_ascii_lowercase().to_string());
+            }
+            (KeyCode::Char(c), Some(_)) => self.answer.push(c),
+            (KeyCode::Backspace, _) => {
+                self.answer.pop();
+            }
+            (KeyCode::Enter, Some(prompt)) => {
+                let mut answer = std::mem::take(&mut self.answer);
+                if answer.is_empty() && matches!(prompt, Prompt::Bet { last: Some(_), .. }) {
+                    answer = "same".to_string();
+                }
+                self.send(&answer);
+            }
+            _ => {}
+        }
+    }
+
+    /// Answers the prompt, which waits for the server to ask again.
+    fn send(&mut self, answer: &str) {
+        self.answer.clear();
+        self.prompt = None;
+        if writeln!(self.output, "{answer}").is_err() {
+            self.closed = true;
+        }
+    }
+}
+
+impl Drop for RemoteTable {
+    fn drop(&mut self) {
+        let _ = self.output.shutdown(Shutdown::Both);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    use crate::app::App;
+    use crate::game::Settings;
+    use crate::ui;
+
+    #[test]
+    fn test_remote_table() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut input = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            input.read_line(&mut line).unwrap();
+            assert_eq!(line.trim().parse(), Ok(Greeting::CURRENT));
+            write!(stream, "Seat code? \r\n{}\r\n", Greeting::CURRENT).unwrap();
+            writeln!(stream, "say The dealer shuffles.\r").unwrap();
+            writeln!(stream, "event bet seat=player spot=0 bet=100\r").unwrap();
+            writeln!(
+                stream,
+                "snapshot dealer=A,? seat=player:900 seat=bot.0:-10 \
+                 hand=player:100:in-play:hard-16:10,6 hand=bot.0:100:stood:hard-20:10,K turn=player:0\r"
+            )
+            .unwrap();
+            writeln!(
+                stream,
+                "turn hand=10,6 value=hard-16 dealer=A allowed=hit,stand\r"
+            )
+            .unwrap();
+            let answers: Vec<_> = input.lines().map_while(Result::ok).collect();
+            assert_eq!(answers, ["stand"]);
+        });
+        let mut remote = RemoteTable::connect(&address).unwrap();
+        while remote.prompt.is_none() {
+            remote.receive();
+        }
+        assert_eq!(remote.log, ["The dealer shuffles."]);
+        assert_eq!(remote.snapshot.seats.len(), 2);
+
+        // The table is drawn from the snapshot, with the seats a local table has
+        let app = App::new(Settings::default());
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal
+            .draw(|frame| ui::display_remote(frame, &remote, &app))
+            .unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains("Dealer showing: 11"));
+        assert!(screen.contains("1. Hard 16, Bet: 100"));
+        assert!(screen.contains("Bot 1"));
+        assert!(screen.contains("Net: -10"));
+        // Splitting isn't allowed, so only standing is answered
+        remote.input(KeyCode::Char('p'));
+        assert!(remote.prompt.is_some());
+        remote.input(KeyCode::Char('S'));
+        assert!(remote.prompt.is_none());
+        drop(remote);
+        server.join().unwrap();
+    }
+}
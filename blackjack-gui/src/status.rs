@@ -0,0 +1,40 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crossterm::execute;
+use crossterm::terminal::SetTitle;
+
+/// Publishes the selected game's status line for status bars outside the GUI,
+/// in the terminal's title (through an OSC sequence) or in a file that is rewritten as it changes.
+#[derive(Debug)]
+pub struct StatusLine {
+    pub title: bool,
+    pub file: Option<PathBuf>,
+    last: Option<String>,
+}
+
+impl StatusLine {
+    pub const fn new(title: bool, file: Option<PathBuf>) -> Self {
+        Self {
+            title,
+            file,
+            last: None,
+        }
+    }
+
+    /// Publishes the line, unless it is the same as the last one.
+    pub fn publish(&mut self, line: &str) -> io::Result<()> {
+        if self.last.as_deref() == Some(line) {
+            return Ok(());
+        }
+        if self.title {
+            execute!(io::stdout(), SetTitle(line))?;
+        }
+        if let Some(file) = &self.file {
+            fs::write(file, format!("{line}\n"))?;
+        }
+        self.last = Some(line.to_string());
+        Ok(())
+    }
+}
@@ -1,13 +1,28 @@
 use std::fmt::Write;
 
 use ratatui::prelude::*;
-use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::widgets::{Block, BorderType, Borders, Clear, Paragraph};
 
-use blackjack_core::card::hand::Status;
+use blackjack_core::card::hand::{DealerHand, PlayerHand, Status, Value};
+use blackjack_core::card::Visibility;
+use blackjack_core::chart::{
+    action_symbol, worth_symbol, ChartHand, ChartRow, StrategyChart, UPCARDS,
+};
+use blackjack_core::counting;
+use blackjack_core::game::HandAction;
+use blackjack_core::i18n::Catalog;
+use blackjack_core::narration;
+use blackjack_core::session::SessionEnd;
 use blackjack_core::state::GameState;
+use blackjack_core::statistics::Section;
+use blackjack_protocol::{Occupant, Prompt, Snapshot};
 
 use crate::app::App;
+use crate::game::{Blackjack, RoundResult};
 use crate::input::InputField;
+use crate::palette::CommandPalette;
+use crate::profiler::{Profiler, Timings};
+use crate::remote::RemoteTable;
 
 pub fn display(frame: &mut Frame, app: &App) {
     let columns =
@@ -15,25 +30,260 @@ pub fn display(frame: &mut Frame, app: &App) {
     draw_games_list(frame, app, columns[0]);
     draw_middle_zone(frame, app, columns[1]);
     draw_statistics_section(frame, app, columns[2]);
+    if let Some(profiler) = &app.profiler {
+        draw_profiler(frame, app, profiler);
+    }
+    if let Some(palette) = &app.palette {
+        draw_palette(frame, app, palette);
+    }
+}
+
+/// Draws a table played on a server from the last snapshot it sent, with the same seats as a local table:
+/// the dealer's hand and the server's narration above them, and below it the prompt it is waiting on.
+pub fn display_remote(frame: &mut Frame, remote: &RemoteTable, app: &App) {
+    let catalog = &app.settings.catalog;
+    let rows = Layout::vertical([Constraint::Min(3), Constraint::Length(4)]).split(frame.area());
+    let block = Block::default()
+        .title_top(
+            Line::styled("BLACKJACK", highlight(app, Color::Red).bold())
+                .alignment(Alignment::Center),
+        )
+        .title_top(Line::from(format!(" {} ", remote.address)).alignment(Alignment::Right))
+        .borders(Borders::ALL);
+    let snapshot = &remote.snapshot;
+    let mut lines = dealer_lines(snapshot, catalog);
+    let seats = if snapshot.seats.is_empty() {
+        0
+    } else {
+        seats_height(snapshot)
+    };
+    let shown = usize::from(rows[0].height.saturating_sub(2 + seats)).saturating_sub(lines.len());
+    lines.extend_from_slice(&remote.log[remote.log.len().saturating_sub(shown)..]);
+    frame.render_widget(Paragraph::new(lines.join("\n")).block(block), rows[0]);
+    if seats > 0 {
+        let active = snapshot
+            .turn
+            .map_or(Occupant::Player, |(occupant, _)| occupant);
+        draw_seats(frame, app, snapshot, active, rows[0]);
+    }
+
+    let input = &remote.answer;
+    let text = match &remote.prompt {
+        None if remote.closed => catalog.get("ui.remote-closed").to_string(),
+        None => String::new(),
+        Some(Prompt::Bet {
+            chips,
+            last,
+            running,
+            true_count,
+            ..
+        }) => {
+            let prompt = last.map_or_else(
+                || catalog.format("prompt.remote-bet", &[("input", input)]),
+                |last| catalog.format("prompt.remote-rebet", &[("input", input), ("bet", &last)]),
+            );
+            let true_count = format!("{true_count:.1}");
+            format!(
+                "{prompt}\n{}  {}  {}",
+                catalog.format("ui.chips", &[("chips", &catalog.money(f64::from(*chips)))]),
+                catalog.format("ui.running-count", &[("count", running)]),
+                catalog.format("ui.true-count", &[("count", &true_count)])
+            )
+        }
+        Some(Prompt::Insurance { max, .. }) => {
+            catalog.format("prompt.insurance", &[("input", input), ("max", max)])
+        }
+        Some(Prompt::Surrender { .. }) => catalog.get("prompt.surrender").to_string(),
+        Some(Prompt::Turn {
+            hand,
+            value,
+            dealer,
+            allowed,
+        }) => {
+            let hand: Vec<_> = hand.iter().map(|rank| rank.symbol()).collect();
+            let actions: Vec<_> = allowed
+                .iter()
+                .map(|&action| catalog.action(action))
+                .collect();
+            format!(
+                "{}\n{}",
+                catalog.format(
+                    "prompt.remote-hand",
+                    &[
+                        ("hand", &hand.join(" ")),
+                        ("value", &catalog.value(value)),
+                        ("dealer", &dealer.symbol())
+                    ]
+                ),
+                actions.join(", ")
+            )
+        }
+        Some(Prompt::Text(question)) => format!("{question} {input}"),
+    };
+    let content = Paragraph::new(text).block(
+        Block::default()
+            .title(catalog.get("ui.input"))
+            .borders(Borders::ALL),
+    );
+    frame.render_widget(content, rows[1]);
+}
+
+/// Draws the command palette as a popup across the middle of the screen.
+fn draw_palette(frame: &mut Frame, app: &App, palette: &CommandPalette) {
+    let [area] = Layout::horizontal([Constraint::Percentage(60)])
+        .flex(layout::Flex::Center)
+        .areas(frame.area());
+    let [area] = Layout::vertical([Constraint::Length(4)])
+        .flex(layout::Flex::Center)
+        .areas(area);
+    let mut text = format!(":{}", palette.input);
+    if let Some(error) = &palette.error {
+        write!(text, "\n{error}").unwrap();
+    }
+    let content = Paragraph::new(text).block(
+        Block::default()
+            .title(app.settings.catalog.get("ui.palette"))
+            .borders(Borders::ALL),
+    );
+    frame.render_widget(Clear, area);
+    frame.render_widget(content, area);
+}
+
+/// Draws the profiler overlay in the top right corner.
+fn draw_profiler(frame: &mut Frame, app: &App, profiler: &Profiler) {
+    let catalog = &app.settings.catalog;
+    let profile = profiler.profile();
+    let timings = |key, timings: Timings| {
+        catalog.format(
+            key,
+            &[
+                ("rate", &format!("{:.0}", timings.per_second)),
+                ("average", &millis(timings.average)),
+                ("max", &millis(timings.max)),
+            ],
+        )
+    };
+    let rate = |key, rate: f64| catalog.format(key, &[("rate", &format!("{rate:.0}"))]);
+    let lines = [
+        timings("ui.profiler-frames", profile.frames),
+        timings("ui.profiler-ticks", profile.ticks),
+        rate("ui.profiler-transitions", profile.transitions_per_second),
+        profile.allocations_per_second.map_or_else(
+            || catalog.get("ui.profiler-no-allocations").to_string(),
+            |allocations| rate("ui.profiler-allocations", allocations),
+        ),
+    ];
+    let width = lines
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0) as u16
+        + 2;
+    let [area] = Layout::horizontal([Constraint::Length(width)])
+        .flex(layout::Flex::End)
+        .areas(frame.area());
+    let [area] = Layout::vertical([Constraint::Length(lines.len() as u16 + 2)]).areas(area);
+    let content = Paragraph::new(lines.join("\n")).block(
+        Block::default()
+            .title(catalog.get("ui.profiler"))
+            .borders(Borders::ALL),
+    );
+    frame.render_widget(Clear, area);
+    frame.render_widget(content, area);
+}
+
+/// Formats a duration in milliseconds, e.g. "1.25ms".
+fn millis(duration: std::time::Duration) -> String {
+    format!("{:.2}ms", duration.as_secs_f64() * 1000.0)
 }
 
 fn draw_games_list(frame: &mut Frame, app: &App, area: Rect) {
+    let catalog = &app.settings.catalog;
     let list = app.games.iter().enumerate().fold(
         String::with_capacity(5 * app.games.len()),
-        |mut output, (i, _)| {
+        |mut output, (i, game)| {
             let prefix = if i == app.selected_game { " > " } else { "   " };
-            writeln!(output, "{prefix}{i}").unwrap();
+            write!(output, "{prefix}{i}").unwrap();
+            for (flag, key) in [
+                (game.autoplay, "ui.autoplay"),
+                (game.paused, "ui.paused"),
+                (game.is_practice(), "ui.practice"),
+                (game.is_finished(), "ui.finished"),
+            ] {
+                if flag {
+                    write!(output, " {}", catalog.get(key)).unwrap();
+                }
+            }
+            output.push('\n');
             output
         },
     );
-    let content = Paragraph::new(list).block(Block::default().title("Games").borders(Borders::ALL));
+    let title = catalog.get("ui.games");
+    let content = Paragraph::new(list).block(Block::default().title(title).borders(Borders::ALL));
     frame.render_widget(content, area);
 }
 
 fn draw_statistics_section(frame: &mut Frame, app: &App, area: Rect) {
-    let block = Block::default().title("Statistics").borders(Borders::ALL);
+    let catalog = &app.settings.catalog;
+    let block = Block::default()
+        .title(catalog.get("ui.statistics"))
+        .borders(Borders::ALL);
     if let Some(current_game) = app.current_game() {
-        let content = Paragraph::new(format!("{}", current_game.table.statistics)).block(block);
+        let time = current_game.session_time();
+        let rate = format!("{:.1}", current_game.hands_per_hour());
+        let mut lines = vec![
+            Line::raw(catalog.format("ui.session", &[("time", &time)])),
+            Line::raw(catalog.format("ui.hands-per-hour", &[("rate", &rate)])),
+        ];
+        let statistics = &current_game.table.statistics;
+        let before = &current_game.statistics_before_round;
+        for (section, collapsed) in Section::ALL.into_iter().zip(app.collapsed_sections) {
+            let title = catalog.get(match section {
+                Section::Outcomes => "ui.section-outcomes",
+                Section::Money => "ui.section-money",
+                Section::Dealer => "ui.section-dealer",
+                Section::Count => "ui.section-count",
+            });
+            let marker = if collapsed { '▸' } else { '▾' };
+            lines.push(Line::default());
+            lines.push(Line::styled(
+                format!("{marker} {title}"),
+                Style::default().bold(),
+            ));
+            if collapsed {
+                continue;
+            }
+            // Values the last round changed are highlighted until the next bet
+            let previous = before.section_in(section, catalog);
+            for (name, value) in statistics.section_in(section, catalog) {
+                let changed = !previous.contains(&(name.clone(), value.clone()));
+                let style = if changed {
+                    highlight(app, Color::Yellow)
+                } else {
+                    Style::default()
+                };
+                lines.push(Line::styled(format!("{name}: {value}"), style));
+            }
+            if section == Section::Count {
+                let shoe = &current_game.table.shoe;
+                let running = counting::running_count(shoe);
+                let true_count = format!("{:+.1}", counting::true_count(shoe));
+                let decks = format!("{:.1}", shoe.decks_remaining());
+                lines.push(Line::raw(
+                    catalog.format("ui.running-count", &[("count", &running)]),
+                ));
+                lines.push(Line::raw(
+                    catalog.format("ui.true-count", &[("count", &true_count)]),
+                ));
+                lines.push(Line::raw(
+                    catalog.format("ui.decks-remaining", &[("decks", &decks)]),
+                ));
+            }
+        }
+        let scroll = app
+            .statistics_scroll
+            .min(u16::try_from(lines.len().saturating_sub(1)).unwrap_or(u16::MAX));
+        let content = Paragraph::new(lines).scroll((scroll, 0)).block(block);
         frame.render_widget(content, area);
     } else {
         frame.render_widget(block, area);
@@ -41,99 +291,510 @@ fn draw_statistics_section(frame: &mut Frame, app: &App, area: Rect) {
 }
 
 fn draw_middle_zone(frame: &mut Frame, app: &App, area: Rect) {
-    let middle_rows = Layout::vertical(Constraint::from_percentages([75, 25])).split(area);
-    draw_game(frame, app, middle_rows[0]);
-    draw_input_area(frame, app, middle_rows[1]);
+    let rules = app
+        .current_game()
+        .filter(|_| app.show_rules)
+        .map(|game| {
+            app.settings
+                .catalog
+                .summarize_rules(&game.table.rules, &game.table.shoe)
+        })
+        .unwrap_or_default();
+    // Collapsed, the rules panel is just its title; expanded, the rules fill two columns
+    let rules_height = if rules.is_empty() {
+        1
+    } else {
+        rules.len().div_ceil(2) as u16 + 2
+    };
+    let middle_rows = Layout::vertical([
+        Constraint::Fill(3),
+        Constraint::Length(rules_height),
+        Constraint::Fill(1),
+    ])
+    .split(area);
+    if app.show_chart {
+        draw_chart(frame, app, middle_rows[0]);
+    } else {
+        draw_game(frame, app, middle_rows[0]);
+    }
+    draw_rules(frame, app, &rules, middle_rows[1]);
+    draw_input_area(frame, app, middle_rows[2]);
+}
+
+fn draw_rules(frame: &mut Frame, app: &App, rules: &[String], area: Rect) {
+    let title = app.settings.catalog.get("rules.title");
+    if rules.is_empty() {
+        let block = Block::default().title(title).borders(Borders::TOP);
+        frame.render_widget(block, area);
+        return;
+    }
+    let block = Block::default().title(title).borders(Borders::ALL);
+    let columns =
+        Layout::horizontal(Constraint::from_percentages([50, 50])).split(block.inner(area));
+    frame.render_widget(block, area);
+    let (left, right) = rules.split_at(rules.len().div_ceil(2));
+    frame.render_widget(Paragraph::new(left.join("\n")), columns[0]);
+    frame.render_widget(Paragraph::new(right.join("\n")), columns[1]);
+}
+
+/// Draws the basic strategy chart for the selected game's table, with the hard totals on the left
+/// and the soft totals and pairs on the right, each cell colored by its action.
+fn draw_chart(frame: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default()
+        .title(app.settings.catalog.get("ui.chart"))
+        .borders(Borders::ALL);
+    let Some(game) = app.current_game() else {
+        frame.render_widget(block, area);
+        return;
+    };
+    let chart = StrategyChart::generate(&game.table.rules, game.table.shoe.decks);
+    let header = UPCARDS
+        .iter()
+        .fold("    ".to_string(), |mut header, &upcard| {
+            write!(header, " {} ", worth_symbol(upcard)).unwrap();
+            header
+        });
+    let lines = |rows: Vec<&ChartRow>| {
+        let mut lines = vec![Line::raw(header.clone())];
+        lines.extend(rows.into_iter().map(|row| {
+            let mut spans = vec![Span::raw(format!("{:<4}", format!("{}:", row.hand)))];
+            spans.extend(row.actions.map(|action| {
+                Span::styled(
+                    format!(" {} ", action_symbol(action)),
+                    label(app, action_color(action)),
+                )
+            }));
+            Line::from(spans)
+        }));
+        lines
+    };
+    let (hard, other): (Vec<_>, Vec<_>) = chart
+        .rows
+        .iter()
+        .partition(|row| matches!(row.hand, ChartHand::Hard(_)));
+    let columns =
+        Layout::horizontal(Constraint::from_percentages([50, 50])).split(block.inner(area));
+    frame.render_widget(block, area);
+    frame.render_widget(Paragraph::new(lines(hard)), columns[0]);
+    frame.render_widget(Paragraph::new(lines(other)), columns[1]);
+}
+
+/// Returns the color a chart cell with the action is drawn in, as on a printed chart.
+const fn action_color(action: HandAction) -> Color {
+    match action {
+        HandAction::Hit => Color::LightRed,
+        HandAction::Stand => Color::LightYellow,
+        HandAction::Double => Color::LightGreen,
+        HandAction::Split => Color::LightCyan,
+        HandAction::Surrender => Color::Gray,
+    }
 }
 
 fn draw_input_area(frame: &mut Frame, app: &App, area: Rect) {
+    let catalog = &app.settings.catalog;
     let content = app.current_game().map_or_else(
-        || "No game selected".to_string(),
+        || catalog.get("ui.no-game").to_string(),
         |current_game| {
             let text = current_game
                 .input_field
                 .as_ref()
                 .map_or_else(String::new, |input_field| match input_field {
-                    InputField::PlaceBet(s) => format!("Enter your bet: {s}"),
-                    InputField::PlaceInsuranceBet(s) => {
-                        format!("Place an insurance bet? Enter bet or 0: {s}")
+                    InputField::PlaceBet(s, None) => catalog.format("prompt.bet", &[("input", s)]),
+                    InputField::PlaceBet(s, Some(last_bet)) => {
+                        catalog.format("prompt.rebet", &[("input", s), ("bet", last_bet)])
+                    }
+                    InputField::PlaceInsuranceBet(s, max) => {
+                        catalog.format("prompt.insurance", &[("input", s), ("max", max)])
+                    }
+                    InputField::ChooseSurrender => catalog.get("prompt.surrender").to_string(),
+                    InputField::Confirm(action, _) => {
+                        catalog.format("prompt.confirm", &[("action", &catalog.action(*action))])
                     }
-                    InputField::ChooseSurrender => "Surrender? (y) or (n)".to_string(),
                     InputField::PlayHand(actions) => {
-                        let mut output = String::with_capacity(actions.len() * 7);
+                        let evs = app
+                            .settings
+                            .show_ev
+                            .then(|| current_game.action_evs())
+                            .flatten()
+                            .unwrap_or_default();
+                        let mut output = String::with_capacity(actions.len() * 24);
                         for action in actions {
-                            write!(output, "{action:?}, ").unwrap();
+                            let name = catalog.action(*action);
+                            match evs.iter().find(|(a, _)| a == action) {
+                                Some((_, ev)) => {
+                                    let ev = format!("{ev:+.3}");
+                                    let text = catalog.format(
+                                        "prompt.action-ev",
+                                        &[("action", &name), ("ev", &ev)],
+                                    );
+                                    write!(output, "{text}, ").unwrap();
+                                }
+                                None => write!(output, "{name}, ").unwrap(),
+                            }
                         }
                         output
                     }
                 });
+            let timer = current_game.time_left().map_or_else(String::new, |left| {
+                let seconds = left.as_secs_f32().ceil();
+                format!(
+                    " {}",
+                    catalog.format("prompt.time-left", &[("seconds", &seconds)])
+                )
+            });
             let last_error = current_game
                 .last_error
                 .as_ref()
-                .map_or_else(String::new, |e| format!("{e}!"));
+                .map_or_else(String::new, |e| format!("{}!", catalog.error(e)));
+            // With backers at the table, the keyboard may bet for any of them
+            let betting_for = if !current_game.table.backers.is_empty()
+                && matches!(current_game.game_state, GameState::Betting { .. })
+            {
+                let seat = seat_name(current_game.active_seat().into(), catalog);
+                format!("  {}", catalog.format("ui.betting-for", &[("seat", &seat)]))
+            } else {
+                String::new()
+            };
+            let wagered = current_game
+                .game_state
+                .total_wagered(current_game.table.spot_bets());
+            let wagered = if wagered > 0 {
+                let wagered = catalog.money(f64::from(wagered));
+                format!(" {}", catalog.format("ui.wagered", &[("chips", &wagered)]))
+            } else {
+                String::new()
+            };
             format!(
-                "{text}\nChips: {chips}\n{last_error}",
-                chips = current_game.table.chips
+                "{text}{timer}\n{}{wagered}{betting_for}\n{last_error}",
+                catalog.format(
+                    "ui.chips",
+                    &[("chips", &catalog.money(f64::from(current_game.table.chips)))]
+                )
             )
         },
     );
-    let content =
-        Paragraph::new(content).block(Block::default().title("Input").borders(Borders::ALL));
+    let content = Paragraph::new(content).block(
+        Block::default()
+            .title(catalog.get("ui.input"))
+            .borders(Borders::ALL),
+    );
     frame.render_widget(content, area);
 }
 
 fn draw_game(frame: &mut Frame, app: &App, area: Rect) {
-    let block = Block::default()
+    let mut block = Block::default()
         .title_top(
-            (Line::styled("BLACKJACK", Style::default().bold().red())).alignment(Alignment::Center),
+            (Line::styled("BLACKJACK", highlight(app, Color::Red).bold()))
+                .alignment(Alignment::Center),
         )
         .borders(Borders::ALL);
+    if let Some(narration) = &app.narration {
+        block = block.title_bottom(Line::from(narration.as_str()).alignment(Alignment::Center));
+    }
+    if app.current_game().is_some_and(Blackjack::is_practice) {
+        block = block.title_top(
+            Line::styled(
+                format!(" {} ", app.settings.catalog.get("ui.practice-title")),
+                label(app, Color::Magenta),
+            )
+            .alignment(Alignment::Left),
+        );
+    }
+    if let Some(toast) = app.current_game().and_then(|game| game.toast.as_ref()) {
+        block = block.title_top(
+            Line::styled(format!(" {toast} "), label(app, Color::Yellow))
+                .alignment(Alignment::Right),
+        );
+    }
     if let Some(current_game) = app.current_game() {
-        let content = Paragraph::new(game_text(&current_game.game_state)).block(block);
+        let catalog = &app.settings.catalog;
+        let mut text = game_text(&current_game.game_state, catalog);
+        if let Some(chance) = app
+            .settings
+            .show_bust
+            .then(|| current_game.dealer_bust_chance())
+            .flatten()
+        {
+            let chance = format!("{:.1}", chance * 100.0);
+            text.push('\n');
+            text.push_str(&catalog.format("ui.dealer-bust-chance", &[("chance", &chance)]));
+        }
+        if let Some((chance, ev)) = app
+            .settings
+            .show_ev
+            .then(|| current_game.insurance_ev())
+            .flatten()
+        {
+            let chance = format!("{:.1}", chance * 100.0);
+            let ev = format!("{ev:+.3}");
+            text.push('\n');
+            text.push_str(&catalog.format("ui.insurance-ev", &[("chance", &chance), ("ev", &ev)]));
+        }
+        if let Some(end) = current_game.session_end {
+            let key = match end {
+                SessionEnd::Completed => "ui.session-completed",
+                SessionEnd::BackedOff => "ui.session-backed-off",
+                SessionEnd::StopWin => "ui.stop-win",
+                SessionEnd::StopLoss => "ui.stop-loss",
+            };
+            let net = signed(current_game.net(), |net| catalog.chips(net));
+            text.push('\n');
+            text.push_str(&catalog.format(key, &[("net", &net)]));
+        }
+        text.push('\n');
+        for line in &current_game.narration {
+            text.push('\n');
+            text.push_str(line);
+        }
+        let mut content = Paragraph::new(text).block(block);
+        if app.settings.high_contrast {
+            content = content.style(Style::default().white().on_black().bold());
+        }
         frame.render_widget(content, area);
+        let snapshot = Snapshot::new(&current_game.table, &current_game.game_state);
+        let hands = snapshot.seats.first().map_or(0, |seat| seat.hands.len());
+        if snapshot.seats.len() > 1 || hands > 1 {
+            let active = snapshot.turn.map_or_else(
+                || current_game.active_seat().into(),
+                |(occupant, _)| occupant,
+            );
+            draw_seats(frame, app, &snapshot, active, area);
+        }
+        if let Some(result) = &current_game.round_result {
+            draw_round_result(frame, app, result, area);
+        }
     } else {
         frame.render_widget(block, area);
     }
 }
 
-#[allow(clippy::too_many_lines)]
-fn game_text(game_state: &GameState) -> String {
-    match game_state {
-        GameState::Betting => "Place your bet!".to_string(),
-        GameState::DealFirstPlayerCard { bet } => {
-            format!("DealFirstPlayerCard\nBet: {bet}\n")
-        }
-        GameState::DealFirstDealerCard { player_hand } => {
-            format!("DealFirstDealerCard\nPlayer: {}\n", player_hand.value)
+/// Draws each occupied seat in a column of its own along the bottom of the game, when the player
+/// shares the table or plays more than one hand: the player's hands, the backers' bets behind them,
+/// and the bots' hands, each with its bankroll. The seat the keyboard plays for is highlighted,
+/// as is the hand being played.
+fn draw_seats(frame: &mut Frame, app: &App, snapshot: &Snapshot, active: Occupant, area: Rect) {
+    let catalog = &app.settings.catalog;
+    let columns: Vec<_> = snapshot
+        .seats
+        .iter()
+        .map(|seat| {
+            let chips = match seat.occupant {
+                Occupant::Bot(_) => catalog.format(
+                    "ui.bot-net",
+                    &[("net", &signed(seat.chips, |net| catalog.money(net)))],
+                ),
+                _ => catalog.format("ui.chips", &[("chips", &catalog.money(seat.chips as f64))]),
+            };
+            let mut lines = vec![Line::from(chips)];
+            for (i, hand) in seat.hands.iter().enumerate() {
+                let mut value = catalog.value(&hand.value);
+                if hand.face_down {
+                    value = catalog.format("ui.face-down", &[("hand", &value)]);
+                }
+                let bet = catalog.format("ui.bet", &[("bet", &catalog.money(f64::from(hand.bet)))]);
+                let line = catalog.format(
+                    "ui.seat-hand",
+                    &[("hand", &(i + 1)), ("value", &value), ("bet", &bet)],
+                );
+                lines.push(if snapshot.turn == Some((seat.occupant, i)) {
+                    Line::styled(line, highlight(app, Color::Yellow))
+                } else {
+                    Line::from(line)
+                });
+            }
+            for back_bet in &snapshot.back_bets {
+                if seat.occupant == Occupant::Backer(back_bet.backer) {
+                    let bet = catalog.money(f64::from(back_bet.bet));
+                    lines.push(Line::from(catalog.format(
+                        "ui.back-bet",
+                        &[("spot", &(back_bet.spot + 1)), ("bet", &bet)],
+                    )));
+                }
+            }
+            (seat.occupant, lines)
+        })
+        .collect();
+    let height = columns
+        .iter()
+        .map(|(_, lines)| lines.len())
+        .max()
+        .unwrap_or(0) as u16
+        + 2;
+    let [_, row] = Layout::vertical([Constraint::Fill(1), Constraint::Length(height)])
+        .areas(area.inner(Margin::new(1, 1)));
+    let areas = Layout::horizontal(vec![Constraint::Fill(1); columns.len()]).split(row);
+    for ((occupant, lines), &column) in columns.into_iter().zip(areas.iter()) {
+        let mut block = Block::default()
+            .title(seat_name(occupant, catalog))
+            .borders(Borders::ALL);
+        if occupant == active {
+            block = block.border_style(highlight(app, Color::Yellow));
         }
-        GameState::DealSecondPlayerCard {
-            player_hand,
-            dealer_hand,
-        } => {
-            format!(
-                "DealSecondPlayerCard\nPlayer: {}\nDealer showing: {}\n",
-                player_hand.value,
-                dealer_hand.showing()
-            )
+        frame.render_widget(Clear, column);
+        frame.render_widget(Paragraph::new(lines).block(block), column);
+    }
+}
+
+/// Returns the height of the seats drawn for a snapshot: the longest column, with its borders.
+fn seats_height(snapshot: &Snapshot) -> u16 {
+    let longest = snapshot
+        .seats
+        .iter()
+        .map(|seat| {
+            let back_bets = snapshot
+                .back_bets
+                .iter()
+                .filter(|back_bet| seat.occupant == Occupant::Backer(back_bet.backer))
+                .count();
+            1 + seat.hands.len() + back_bets
+        })
+        .max()
+        .unwrap_or(0);
+    u16::try_from(longest).unwrap_or(u16::MAX).saturating_add(2)
+}
+
+/// Describes the dealer's hand in a snapshot, e.g. "Dealer showing: 9", with a line for a hole card
+/// still face down, or nothing between rounds.
+fn dealer_lines(snapshot: &Snapshot, catalog: &Catalog) -> Vec<String> {
+    if let Some(value) = &snapshot.dealer_value {
+        return vec![catalog.format("ui.dealer", &[("value", &catalog.value(value))])];
+    }
+    let Some(Some(up_card)) = snapshot.dealer.first() else {
+        return Vec::new();
+    };
+    let mut lines = vec![catalog.format("ui.dealer-showing", &[("value", &up_card.worth())])];
+    if snapshot.dealer.contains(&None) {
+        lines.push(catalog.get("ui.hole-card").to_string());
+    }
+    lines
+}
+
+/// Returns the name of whoever sits in a seat, e.g. "Backer 1".
+fn seat_name(occupant: Occupant, catalog: &Catalog) -> String {
+    match occupant {
+        Occupant::Player => catalog.get("ui.seat-player").to_string(),
+        Occupant::Backer(backer) => {
+            catalog.format("ui.seat-backer", &[("backer", &(usize::from(backer) + 1))])
         }
-        GameState::DealHoleCard {
+        Occupant::Bot(bot) => catalog.format("ui.seat-bot", &[("bot", &(bot + 1))]),
+    }
+}
+
+/// Formats an amount of chips with its sign, e.g. "+50 chips" or "-$12.50".
+fn signed(amount: i64, format: impl Fn(f64) -> String) -> String {
+    let sign = if amount >= 0 { "+" } else { "" };
+    format!("{sign}{}", format(amount as f64))
+}
+
+/// Draws the summary of the last round as a popup over the game.
+fn draw_round_result(frame: &mut Frame, app: &App, result: &RoundResult, area: Rect) {
+    let catalog = &app.settings.catalog;
+    let mut lines = Vec::new();
+    for (i, hand) in result.settlement.hands.iter().enumerate() {
+        let hand_result = catalog.bet_result(hand);
+        lines.push(catalog.format(
+            "ui.hand-result",
+            &[("hand", &(i + 1)), ("result", &hand_result)],
+        ));
+    }
+    if let Some(insurance) = &result.settlement.insurance {
+        let insurance = catalog.bet_result(insurance);
+        lines.push(catalog.format("ui.insurance-result", &[("result", &insurance)]));
+    }
+    let net = signed(result.net, |net| catalog.money(net));
+    lines.push(catalog.format("ui.round-net", &[("net", &net)]));
+    let chips = catalog.money(f64::from(result.chips));
+    lines.push(catalog.format("ui.chips", &[("chips", &chips)]));
+    lines.push(String::new());
+    lines.push(catalog.get("ui.press-any-key").to_string());
+    let [popup] = Layout::horizontal([Constraint::Percentage(60)])
+        .flex(layout::Flex::Center)
+        .areas(area);
+    let [popup] = Layout::vertical([Constraint::Length(lines.len() as u16 + 2)])
+        .flex(layout::Flex::Center)
+        .areas(popup);
+    // In high contrast, wins and losses are told apart by the border's shape rather than its color
+    let (style, border_type) = if result.net > 0 {
+        (highlight(app, Color::Green), BorderType::Double)
+    } else if result.net < 0 {
+        (highlight(app, Color::Red), BorderType::Thick)
+    } else {
+        (Style::default(), BorderType::Plain)
+    };
+    let mut block = Block::default()
+        .title(catalog.get("ui.round-summary"))
+        .borders(Borders::ALL)
+        .border_style(style);
+    if app.settings.high_contrast {
+        block = block.border_type(border_type);
+    }
+    let content = Paragraph::new(lines.join("\n")).block(block);
+    frame.render_widget(Clear, popup);
+    frame.render_widget(content, popup);
+}
+
+/// Returns the style that draws attention to some text: the color, or bold in high contrast.
+fn highlight(app: &App, color: Color) -> Style {
+    if app.settings.high_contrast {
+        Style::default().bold()
+    } else {
+        Style::default().fg(color)
+    }
+}
+
+/// Returns the style of a label in the game's title: black on the color, or reversed in high contrast.
+fn label(app: &App, color: Color) -> Style {
+    if app.settings.high_contrast {
+        Style::default().reversed().bold()
+    } else {
+        Style::default().black().bg(color)
+    }
+}
+
+/// Lists the values of the player's finished hands, e.g. "Hard 18, Soft 20, ".
+fn finished_values(finished_hands: &[PlayerHand], catalog: &Catalog) -> String {
+    finished_hands.iter().fold(
+        String::with_capacity(finished_hands.len() * 9),
+        |mut output, h| {
+            write!(output, "{}, ", catalog.value(&h.value)).unwrap();
+            output
+        },
+    )
+}
+
+#[allow(clippy::too_many_lines)]
+fn game_text(game_state: &GameState, catalog: &Catalog) -> String {
+    let player = |value: &Value| catalog.format("ui.player", &[("value", &catalog.value(value))]);
+    let dealer = |value: &Value| catalog.format("ui.dealer", &[("value", &catalog.value(value))]);
+    let showing = |dealer_hand: &DealerHand| {
+        catalog.format("ui.dealer-showing", &[("value", &dealer_hand.showing())])
+    };
+    match game_state {
+        GameState::Betting { .. } => catalog.get("ui.place-bet").to_string(),
+        GameState::Dealing {
+            bet,
             player_hand,
             dealer_hand,
+            ..
         } => {
-            format!(
-                "DealHoleCard\nPlayer: {}\nDealer showing: {}\n",
-                player_hand.value,
-                dealer_hand.showing()
-            )
+            let bet = catalog.money(f64::from(*bet));
+            let mut text = format!("Dealing\n{}\n", catalog.format("ui.bet", &[("bet", &bet)]));
+            if let Some(player_hand) = player_hand {
+                writeln!(text, "{}", player(&player_hand.value)).unwrap();
+            }
+            if let Some(dealer_hand) = dealer_hand {
+                writeln!(text, "{}", showing(dealer_hand)).unwrap();
+            }
+            text
         }
         GameState::OfferEarlySurrender {
             player_hand,
             dealer_hand,
         } => {
             format!(
-                "OfferEarlySurrender\nPlayer: {}\nDealer showing: {}\n",
-                player_hand.value,
-                dealer_hand.showing()
+                "OfferEarlySurrender\n{}\n{}\n",
+                player(&player_hand.value),
+                showing(dealer_hand)
             )
         }
         GameState::OfferInsurance {
@@ -141,9 +802,9 @@ fn game_text(game_state: &GameState) -> String {
             dealer_hand,
         } => {
             format!(
-                "OfferInsurance\nPlayer: {}\nDealer showing: {}\n",
-                player_hand.value,
-                dealer_hand.showing(),
+                "OfferInsurance\n{}\n{}\n",
+                player(&player_hand.value),
+                showing(dealer_hand)
             )
         }
         GameState::CheckDealerHoleCard {
@@ -152,11 +813,14 @@ fn game_text(game_state: &GameState) -> String {
             insurance_bet: insurance,
         } => {
             format!(
-                "CheckDealerHoleCard\nPlayer: {}\nDealer showing: {}\n{}\n",
-                player_hand.value,
-                dealer_hand.showing(),
+                "CheckDealerHoleCard\n{}\n{}\n{}\n",
+                player(&player_hand.value),
+                showing(dealer_hand),
                 if *insurance > 0 {
-                    format!("Insurance: {insurance}")
+                    catalog.format(
+                        "ui.insurance",
+                        &[("bet", &catalog.money(f64::from(*insurance)))],
+                    )
                 } else {
                     String::new()
                 },
@@ -167,10 +831,26 @@ fn game_text(game_state: &GameState) -> String {
             dealer_hand,
             ..
         } => {
+            let bets = player_turn
+                .all_hands()
+                .iter()
+                .map(|hand| catalog.money(f64::from(hand.bet)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let hand = player_turn.current_hand();
+            let mut hand_line = player(&hand.value);
+            if hand.visibility == Visibility::FaceDown {
+                hand_line = catalog.format("ui.face-down", &[("hand", &hand_line)]);
+            }
+            let hole_card = if dealer_hand.hole_card().is_some() {
+                catalog.get("ui.hole-card")
+            } else {
+                ""
+            };
             format!(
-                "PlayPlayerTurn\nPlayer: {}\nDealer showing: {}",
-                player_turn.current_hand().value,
-                dealer_hand.showing(),
+                "PlayPlayerTurn\n{hand_line}\n{}\n{hole_card}\n{}",
+                showing(dealer_hand),
+                catalog.format("ui.hand-bets", &[("bets", &bets)])
             )
         }
         GameState::PlayerStand {
@@ -179,9 +859,9 @@ fn game_text(game_state: &GameState) -> String {
             ..
         } => {
             format!(
-                "Stand\nPlayer: {}\nDealer showing: {}",
-                player_turn.current_hand().value,
-                dealer_hand.showing(),
+                "Stand\n{}\n{}",
+                player(&player_turn.current_hand().value),
+                showing(dealer_hand)
             )
         }
         GameState::PlayerHit {
@@ -190,9 +870,9 @@ fn game_text(game_state: &GameState) -> String {
             ..
         } => {
             format!(
-                "Hit\nPlayer: {}\nDealer showing: {}",
-                player_turn.current_hand().value,
-                dealer_hand.showing(),
+                "Hit\n{}\n{}",
+                player(&player_turn.current_hand().value),
+                showing(dealer_hand)
             )
         }
         GameState::PlayerDouble {
@@ -201,9 +881,9 @@ fn game_text(game_state: &GameState) -> String {
             ..
         } => {
             format!(
-                "Double\nPlayer: {}\nDealer showing: {}",
-                player_turn.current_hand().value,
-                dealer_hand.showing(),
+                "Double\n{}\n{}",
+                player(&player_turn.current_hand().value),
+                showing(dealer_hand)
             )
         }
         GameState::PlayerSplit {
@@ -212,9 +892,9 @@ fn game_text(game_state: &GameState) -> String {
             ..
         } => {
             format!(
-                "Split\nPlayer: {}\nDealer showing: {}",
-                player_turn.current_hand().value,
-                dealer_hand.showing(),
+                "Split\n{}\n{}",
+                player(&player_turn.current_hand().value),
+                showing(dealer_hand)
             )
         }
         GameState::DealFirstSplitCard {
@@ -224,10 +904,10 @@ fn game_text(game_state: &GameState) -> String {
             ..
         } => {
             format!(
-                "DealFirstSplitCard\nPlayer: {}\nNew Hand: {}\nDealer showing: {}",
-                player_turn.current_hand().value,
-                new_hand.value,
-                dealer_hand.showing(),
+                "DealFirstSplitCard\n{}\n{}\n{}",
+                player(&player_turn.current_hand().value),
+                catalog.format("ui.new-hand", &[("value", &catalog.value(&new_hand.value))]),
+                showing(dealer_hand)
             )
         }
         GameState::DealSecondSplitCard {
@@ -237,10 +917,10 @@ fn game_text(game_state: &GameState) -> String {
             ..
         } => {
             format!(
-                "DealSecondSplitCard\nPlayer: {}\nNew Hand: {}\nDealer showing: {}",
-                player_turn.current_hand().value,
-                new_hand.value,
-                dealer_hand.showing(),
+                "DealSecondSplitCard\n{}\n{}\n{}",
+                player(&player_turn.current_hand().value),
+                catalog.format("ui.new-hand", &[("value", &catalog.value(&new_hand.value))]),
+                showing(dealer_hand)
             )
         }
         GameState::PlayerSurrender {
@@ -249,9 +929,9 @@ fn game_text(game_state: &GameState) -> String {
             ..
         } => {
             format!(
-                "Surrender\nPlayer: {}\nDealer showing: {}",
-                player_turn.current_hand().value,
-                dealer_hand.showing(),
+                "Surrender\n{}\n{}",
+                player(&player_turn.current_hand().value),
+                showing(dealer_hand)
             )
         }
         GameState::RevealHoleCard {
@@ -260,16 +940,13 @@ fn game_text(game_state: &GameState) -> String {
             ..
         } => {
             format!(
-                "The dealer reveals his hole card...\nPlayer: {}\nDealer showing: {}",
-                finished_hands.iter().fold(
-                    String::with_capacity(finished_hands.len() * 7),
-                    |mut output, h| {
-                        let value = &h.value;
-                        write!(output, "{value}, ").unwrap();
-                        output
-                    }
+                "{}\n{}\n{}",
+                catalog.get("ui.dealer-reveals"),
+                catalog.format(
+                    "ui.player",
+                    &[("value", &finished_values(finished_hands, catalog))]
                 ),
-                dealer_hand.showing(),
+                showing(dealer_hand)
             )
         }
         GameState::PlayDealerTurn {
@@ -278,16 +955,12 @@ fn game_text(game_state: &GameState) -> String {
             ..
         } => {
             format!(
-                "PlayDealerTurn\nPlayer: {}\nDealer: {}",
-                finished_hands.iter().fold(
-                    String::with_capacity(finished_hands.len() * 7),
-                    |mut output, h| {
-                        let value = &h.value;
-                        write!(output, "{value}, ").unwrap();
-                        output
-                    }
+                "PlayDealerTurn\n{}\n{}",
+                catalog.format(
+                    "ui.player",
+                    &[("value", &finished_values(finished_hands, catalog))]
                 ),
-                dealer_hand.value,
+                dealer(&dealer_hand.value)
             )
         }
         GameState::RoundOver {
@@ -296,40 +969,40 @@ fn game_text(game_state: &GameState) -> String {
             ..
         } => {
             let announcement = match &dealer_hand.status {
-                Status::Blackjack => "Dealer has blackjack!".to_string(),
-                Status::Bust => "Dealer busts!".to_string(),
-                Status::Stood => format!("Dealer has {}.", dealer_hand.value.total),
+                Status::Blackjack => catalog.get("ui.dealer-blackjack").to_string(),
+                Status::Bust => catalog.get("ui.dealer-busts").to_string(),
+                Status::Stood => {
+                    catalog.format("ui.dealer-has", &[("total", &dealer_hand.value.total)])
+                }
                 _ => unreachable!("dealer hand should not be in play or surrendered"),
             };
             format!(
-                "{}\nPlayer: {}\nDealer: {}",
+                "{}\n{}\n{}",
                 announcement,
-                finished_hands.iter().fold(
-                    String::with_capacity(finished_hands.len() * 7),
-                    |mut output, h| {
-                        let value = &h.value;
-                        write!(output, "{value}, ").unwrap();
-                        output
-                    }
+                catalog.format(
+                    "ui.player",
+                    &[("value", &finished_values(finished_hands, catalog))]
                 ),
-                dealer_hand.value,
+                dealer(&dealer_hand.value)
             )
         }
-        GameState::Payout {
-            total_bet,
-            total_winnings,
-        } => {
-            let difference = i64::from(*total_winnings) - i64::from(*total_bet);
-            match difference {
-                1.. => format!("You win {total_winnings} chips (+{difference})!"),
-                0 => format!("You make back {total_winnings} chips. You push!"),
-                _ if *total_winnings > 0 => {
-                    format!("You make back {total_winnings} out of {total_bet} chips!")
-                }
-                _ => format!("You lose {} chips!", difference.abs()),
+        GameState::Payout { round } => {
+            let settlement = round.settlement();
+            let mut text = narration::payout(catalog, round.total_bet(), round.total_winnings());
+            for (i, result) in settlement.hands.iter().enumerate() {
+                let result = catalog.bet_result(result);
+                let line =
+                    catalog.format("ui.hand-result", &[("hand", &(i + 1)), ("result", &result)]);
+                write!(text, "\n{line}").unwrap();
+            }
+            if let Some(insurance) = &settlement.insurance {
+                let result = catalog.bet_result(insurance);
+                let line = catalog.format("ui.insurance-result", &[("result", &result)]);
+                write!(text, "\n{line}").unwrap();
             }
+            text
         }
-        GameState::Shuffle => "Shuffling the shoe...".to_string(),
-        GameState::GameOver => "Game Over!".to_string(),
+        GameState::Shuffle => catalog.get("ui.shuffling").to_string(),
+        GameState::GameOver => catalog.get("ui.game-over").to_string(),
     }
 }
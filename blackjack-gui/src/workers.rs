@@ -0,0 +1,132 @@
+//! Worker threads which step the games while the render thread carries on.
+//! The workers are started once and live as long as the app. On each tick, the app hands every
+//! worker a share of its games, and takes them back as the workers report in over a channel,
+//! without waiting for them.
+
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread;
+use std::time::Duration;
+
+use crate::game::Blackjack;
+
+/// How long to wait for a key press before looking for games back from the workers.
+pub const POLL: Duration = Duration::from_millis(5);
+
+/// What a tick does to a game, returning the catalog key of a line worth showing off, if any.
+pub type Step = fn(&mut Blackjack) -> Option<&'static str>;
+
+/// A share of the games, starting at the given index, and the step to take with each.
+struct Job {
+    first: usize,
+    games: Vec<Blackjack>,
+    step: Step,
+}
+
+/// A share of the games once stepped, with the lines worth showing off by game index.
+#[derive(Debug)]
+pub struct Report {
+    pub first: usize,
+    pub games: Vec<Blackjack>,
+    pub highlights: Vec<(usize, &'static str)>,
+}
+
+/// Worker threads, each waiting for games to step.
+#[derive(Debug)]
+pub struct Workers {
+    jobs: Vec<Sender<Job>>,
+    reports: Receiver<Report>,
+    /// The number of shares handed out and not yet reported back
+    pending: usize,
+}
+
+impl Workers {
+    /// Starts the given number of workers, which stop once the workers are dropped.
+    pub fn new(count: usize) -> Self {
+        let (reporter, reports) = mpsc::channel();
+        let jobs = (0..count)
+            .map(|_| {
+                let (sender, jobs) = mpsc::channel::<Job>();
+                let reporter = reporter.clone();
+                thread::spawn(move || {
+                    for Job {
+                        first,
+                        mut games,
+                        step,
+                    } in jobs
+                    {
+                        let highlights = games
+                            .iter_mut()
+                            .enumerate()
+                            .filter_map(|(i, game)| step(game).map(|line| (first + i, line)))
+                            .collect();
+                        let report = Report {
+                            first,
+                            games,
+                            highlights,
+                        };
+                        if reporter.send(report).is_err() {
+                            break;
+                        }
+                    }
+                });
+                sender
+            })
+            .collect();
+        Self {
+            jobs,
+            reports,
+            pending: 0,
+        }
+    }
+
+    /// Whether any games are still away with the workers.
+    pub const fn busy(&self) -> bool {
+        self.pending > 0
+    }
+
+    /// Shares the games out between as many workers as asked for, to be stepped once each.
+    pub fn hand_out(&mut self, mut games: Vec<Blackjack>, workers: usize, step: Step) {
+        let share = games
+            .len()
+            .div_ceil(workers.clamp(1, self.jobs.len()))
+            .max(1);
+        let mut first = 0;
+        for sender in &self.jobs {
+            if games.is_empty() {
+                break;
+            }
+            let rest = games.split_off(share.min(games.len()));
+            let job = Job {
+                first,
+                games: std::mem::replace(&mut games, rest),
+                step,
+            };
+            first += job.games.len();
+            // A worker only stops once its sender is dropped
+            sender.send(job).expect("the workers outlive their senders");
+            self.pending += 1;
+        }
+    }
+
+    /// Returns a share of the games which has been stepped, if one has come back, without waiting.
+    pub fn try_collect(&mut self) -> Option<Report> {
+        match self.reports.try_recv() {
+            Ok(report) => {
+                self.pending -= 1;
+                Some(report)
+            }
+            Err(TryRecvError::Empty | TryRecvError::Disconnected) => None,
+        }
+    }
+
+    /// Waits for a share of the games to come back, unless none are away.
+    #[cfg(test)]
+    pub fn collect(&mut self) -> Option<Report> {
+        if self.pending == 0 {
+            return None;
+        }
+        let report = self.reports.recv().ok()?;
+        self.pending -= 1;
+        Some(report)
+    }
+}
@@ -0,0 +1,179 @@
+//! Answers: the player's replies to the table's prompts, and how the table reads them.
+
+use std::fmt;
+
+use blackjack_core::betting::BetPresets;
+use blackjack_core::game::{Error, HandAction, Input, Table};
+use blackjack_core::state::GameState;
+
+/// The player's reply to a prompt, e.g. "200", "yes" or "hit".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    /// A bet, or an insurance bet, which may be nothing
+    Bet(u32),
+    /// Whether to surrender early
+    Choice(bool),
+    /// An action on the hand being played
+    Action(HandAction),
+}
+
+impl Answer {
+    /// Returns the answer an input gives to the prompt it was made for.
+    /// Inputs the protocol has no answer for, like tips and timeouts, give None.
+    #[must_use]
+    pub const fn from_input(input: &Input) -> Option<Self> {
+        match *input {
+            Input::Bet { bet, .. } => Some(Self::Bet(bet)),
+            Input::Choice { choice, .. } => Some(Self::Choice(choice)),
+            Input::Action { action, .. } => Some(Self::Action(action)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bet(bet) => write!(f, "{bet}"),
+            Self::Choice(choice) => write!(f, "{}", if *choice { "yes" } else { "no" }),
+            Self::Action(action) => write!(f, "{}", action_name(*action)),
+        }
+    }
+}
+
+/// Why an answer was rejected.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum AnswerError {
+    /// The answer doesn't answer the question
+    #[error("\"{0}\" isn't an answer to the question")]
+    Unrecognized(String),
+    /// The action isn't allowed on the current hand
+    #[error(transparent)]
+    NotAllowed(Error),
+}
+
+/// Parses an answer to the prompt asked in a state, with bets named by the given presets.
+/// Actions are checked against the current hand, but bets are left for the table to check,
+/// so it can say what is wrong with them.
+/// # Errors
+/// Returns an error if the answer doesn't answer the prompt, or names an action that isn't allowed.
+pub fn parse_answer(
+    table: &Table,
+    state: &GameState,
+    answer: &str,
+    presets: &BetPresets,
+) -> Result<Input, AnswerError> {
+    let answer = answer.trim().to_ascii_lowercase();
+    let unrecognized = || AnswerError::Unrecognized(answer.clone());
+    match state {
+        GameState::Betting { .. } => presets
+            .input(&answer, table, state)
+            .ok_or_else(unrecognized),
+        GameState::OfferEarlySurrender { .. } => match answer.as_str() {
+            "y" | "yes" | "surrender" => Ok(Input::choice(true)),
+            "n" | "no" => Ok(Input::choice(false)),
+            _ => Err(unrecognized()),
+        },
+        GameState::OfferInsurance { player_hand, .. } => {
+            let max = table.max_insurance(player_hand);
+            match answer.as_str() {
+                "m" | "max" => Ok(Input::bet(max)),
+                "h" | "half" => Ok(Input::bet(max / 2)),
+                "n" | "no" => Ok(Input::bet(0)),
+                bet => bet.parse().map(Input::bet).map_err(|_| unrecognized()),
+            }
+        }
+        GameState::PlayPlayerTurn { .. } => {
+            let input = Input::action(action(&answer).ok_or_else(unrecognized)?);
+            table
+                .validate(state, &input)
+                .map_err(AnswerError::NotAllowed)?;
+            Ok(input)
+        }
+        _ => Err(unrecognized()),
+    }
+}
+
+/// Returns the name of an action, as it is written in prompts and answers, e.g. "double".
+#[must_use]
+pub const fn action_name(action: HandAction) -> &'static str {
+    match action {
+        HandAction::Stand => "stand",
+        HandAction::Hit => "hit",
+        HandAction::Double => "double",
+        HandAction::Split => "split",
+        HandAction::Surrender => "surrender",
+    }
+}
+
+/// Returns the action with the given name, or the letter it is chosen with on the table's prompts,
+/// e.g. "hit" or "h".
+#[must_use]
+pub fn action(name: &str) -> Option<HandAction> {
+    match name {
+        "s" => Some(HandAction::Stand),
+        "h" => Some(HandAction::Hit),
+        "d" => Some(HandAction::Double),
+        "p" => Some(HandAction::Split),
+        "r" => Some(HandAction::Surrender),
+        name => [
+            HandAction::Stand,
+            HandAction::Hit,
+            HandAction::Double,
+            HandAction::Split,
+            HandAction::Surrender,
+        ]
+        .into_iter()
+        .find(|&action| action_name(action) == name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use blackjack_core::card::shoe::Shoe;
+    use blackjack_core::card::Rank;
+    use blackjack_core::rules::Rules;
+
+    use super::*;
+
+    #[test]
+    fn test_answers() {
+        let mut table = Table::new(1000, Shoe::new(6, 0.75), Rules::default());
+        let betting = table.betting();
+        let presets = BetPresets::default();
+        assert_eq!(
+            parse_answer(&table, &betting, "MAX", &presets),
+            Ok(Input::bet(1000))
+        );
+        assert!(parse_answer(&table, &betting, "hit", &presets).is_err());
+
+        table
+            .shoe
+            .force([Rank::Ten, Rank::Five, Rank::Seven, Rank::Nine]);
+        table.fast_forward = true;
+        let state = table.progress(betting, Some(Input::bet(100))).unwrap();
+        assert!(matches!(state, GameState::PlayPlayerTurn { .. }));
+        let GameState::PlayPlayerTurn { player_turn, .. } = &state else {
+            unreachable!()
+        };
+        for action in table.allowed_actions(player_turn) {
+            let input = Input::action(action);
+            let answer = Answer::from_input(&input).unwrap().to_string();
+            assert_eq!(parse_answer(&table, &state, &answer, &presets), Ok(input));
+        }
+        assert_eq!(Answer::Choice(true).to_string(), "yes");
+        assert_eq!(Answer::from_input(&Input::Timeout), None);
+        assert_eq!(
+            parse_answer(&table, &state, "h", &presets),
+            Ok(Input::action(HandAction::Hit))
+        );
+        assert!(matches!(
+            parse_answer(&table, &state, "p", &presets),
+            Err(AnswerError::NotAllowed(_))
+        ));
+        assert!(matches!(
+            parse_answer(&table, &state, "fold", &presets),
+            Err(AnswerError::Unrecognized(_))
+        ));
+    }
+}
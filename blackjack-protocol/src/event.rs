@@ -0,0 +1,185 @@
+//! Events: what happens at the table, for programs to follow without reading the narration.
+
+use std::fmt;
+use std::str::FromStr;
+
+use blackjack_core::events::GameEvent;
+use blackjack_core::game::HandAction;
+
+use crate::answer::{action, action_name};
+use crate::snapshot::Occupant;
+
+/// Something that happened at the table, e.g. "event bet seat=player spot=0 bet=100".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    /// A seat bet on a spot, where 0 is the player's main hand.
+    /// Backers bet behind the player's main hand.
+    Bet {
+        seat: Occupant,
+        spot: usize,
+        bet: u32,
+    },
+    /// A seat made a decision on the hand being played
+    Action { seat: Occupant, action: HandAction },
+    /// A seat's bets were settled, winning it this many chips, or losing them if negative
+    Paid { seat: Occupant, net: i64 },
+    /// The shoe was shuffled
+    Shuffled,
+    /// The player can no longer afford to play
+    GameOver,
+}
+
+impl Event {
+    /// Returns the events the protocol sends for an event at the table, which are none for most
+    /// of them, since the snapshot shows the cards.
+    #[must_use]
+    pub fn from_game(event: &GameEvent) -> Vec<Self> {
+        match event {
+            &GameEvent::BetPlaced { bet } => vec![Self::Bet {
+                seat: Occupant::Player,
+                spot: 0,
+                bet,
+            }],
+            &GameEvent::SpotBetPlaced { spot, bet } => vec![Self::Bet {
+                seat: Occupant::Player,
+                spot,
+                bet,
+            }],
+            &GameEvent::BackBetPlaced { backer, bet } => u8::try_from(backer)
+                .map(|backer| Self::Bet {
+                    seat: Occupant::Backer(backer),
+                    spot: 0,
+                    bet,
+                })
+                .into_iter()
+                .collect(),
+            &GameEvent::PlayerAction { action, .. } => vec![Self::Action {
+                seat: Occupant::Player,
+                action,
+            }],
+            GameEvent::BotPlayed { bot, actions, .. } => actions
+                .iter()
+                .map(|&action| Self::Action {
+                    seat: Occupant::Bot(*bot),
+                    action,
+                })
+                .collect(),
+            &GameEvent::Payout {
+                total_bet,
+                total_winnings,
+            } => vec![Self::Paid {
+                seat: Occupant::Player,
+                net: i64::from(total_winnings) - i64::from(total_bet),
+            }],
+            &GameEvent::BotPaid { bot, net } => vec![Self::Paid {
+                seat: Occupant::Bot(bot),
+                net,
+            }],
+            GameEvent::Shuffled => vec![Self::Shuffled],
+            GameEvent::GameOver => vec![Self::GameOver],
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bet { seat, spot, bet } => {
+                write!(f, "event bet seat={seat} spot={spot} bet={bet}")
+            }
+            Self::Action { seat, action } => {
+                write!(
+                    f,
+                    "event action seat={seat} action={}",
+                    action_name(*action)
+                )
+            }
+            Self::Paid { seat, net } => write!(f, "event paid seat={seat} net={net}"),
+            Self::Shuffled => write!(f, "event shuffled"),
+            Self::GameOver => write!(f, "event game-over"),
+        }
+    }
+}
+
+impl FromStr for Event {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rest = s
+            .trim()
+            .strip_prefix("event ")
+            .ok_or_else(|| format!("expected an event, got {s}"))?;
+        let (kind, fields) = rest.split_once(' ').unwrap_or((rest, ""));
+        let fields = crate::fields(fields);
+        let field = |key: &str| {
+            fields
+                .iter()
+                .find(|(name, _)| *name == key)
+                .map(|&(_, value)| value)
+                .ok_or_else(|| format!("missing {key} in event: {s}"))
+        };
+        let number = |key: &str| {
+            field(key)?
+                .parse()
+                .map_err(|_| format!("invalid {key} in event: {s}"))
+        };
+        let seat = || field("seat")?.parse::<Occupant>();
+        match kind {
+            "bet" => Ok(Self::Bet {
+                seat: seat()?,
+                spot: field("spot")?
+                    .parse()
+                    .map_err(|_| format!("invalid spot in event: {s}"))?,
+                bet: number("bet")?,
+            }),
+            "action" => Ok(Self::Action {
+                seat: seat()?,
+                action: action(field("action")?)
+                    .ok_or_else(|| format!("invalid action in event: {s}"))?,
+            }),
+            "paid" => Ok(Self::Paid {
+                seat: seat()?,
+                net: field("net")?
+                    .parse()
+                    .map_err(|_| format!("invalid net in event: {s}"))?,
+            }),
+            "shuffled" => Ok(Self::Shuffled),
+            "game-over" => Ok(Self::GameOver),
+            _ => Err(format!("unknown event: {s}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events() {
+        let events = [
+            "event bet seat=backer.1 spot=0 bet=50",
+            "event action seat=bot.0 action=hit",
+            "event paid seat=player net=-100",
+            "event shuffled",
+            "event game-over",
+        ];
+        for event in events {
+            assert_eq!(event.parse::<Event>().unwrap().to_string(), event);
+        }
+        assert!("event fold seat=player".parse::<Event>().is_err());
+
+        let paid = GameEvent::Payout {
+            total_bet: 100,
+            total_winnings: 250,
+        };
+        assert_eq!(
+            Event::from_game(&paid),
+            [Event::Paid {
+                seat: Occupant::Player,
+                net: 150
+            }]
+        );
+        assert!(Event::from_game(&GameEvent::HoleCardDealt).is_empty());
+    }
+}
@@ -0,0 +1,115 @@
+//! The versioned text protocol spoken between a table and a player in another process or across the network,
+//! shared by `blackjack remote`, the server and the programs playing on it, and the chat tables.
+//! Every message is one line of text. On connecting, each side greets the other with the protocol's
+//! name and version, e.g. "blackjack 2", and a side speaking another version is turned away.
+//!
+//! The table then sends what happens at the table, as narration for people to read and as events
+//! for programs, a snapshot of the table whenever it changes, and a prompt whenever it needs a decision, e.g.
+//!
+//! ```text
+//! say The dealer draws a Five of Clubs (Hard 5).
+//! event bet seat=player spot=0 bet=100
+//! snapshot dealer=9,? seat=player:900 seat=bot.0:-10 hand=player:100:in-play:soft-18:A,7 turn=player:0
+//! bet chips=100000 min=100 max=none last=none running=0 true=0.0
+//! turn hand=A,7 value=soft-18 dealer=9 allowed=hit,stand,double
+//! insurance hand=10,10 dealer=A max=50
+//! surrender hand=10,6 dealer=A
+//! ask What's your name?
+//! error "x" isn't an answer to the question
+//! ```
+//!
+//! and the player answers each prompt with a line of its own, e.g. "200", "hit", "25", or "no" (see [`Answer`]).
+//! Answers are read the same way from programs and people: bets may be named ("same", "max"),
+//! and answers may be shortened to the letters on the table's prompts, e.g. "h" to hit or "p" to split.
+
+use std::fmt;
+use std::str::FromStr;
+
+pub mod answer;
+pub mod event;
+pub mod message;
+pub mod prompt;
+pub mod snapshot;
+
+pub use answer::{parse_answer, Answer, AnswerError};
+pub use event::Event;
+pub use message::Message;
+pub use prompt::Prompt;
+pub use snapshot::{Occupant, Snapshot};
+
+/// The protocol's name, which starts every greeting.
+pub const NAME: &str = "blackjack";
+
+/// The version of the protocol spoken by this build.
+/// It is raised whenever a message is added or changed in a way older peers can't read.
+pub const VERSION: u32 = 2;
+
+/// The first line each side sends, naming the version of the protocol it speaks, e.g. "blackjack 2".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Greeting {
+    pub version: u32,
+}
+
+impl Greeting {
+    /// The greeting of this build.
+    pub const CURRENT: Self = Self { version: VERSION };
+
+    /// Checks that a peer greeting with this can talk to this build.
+    /// # Errors
+    /// Returns an error naming both versions if they differ.
+    pub fn check(self) -> Result<(), String> {
+        if self.version == VERSION {
+            Ok(())
+        } else {
+            Err(format!(
+                "the peer speaks version {} of the protocol, but this build speaks version {VERSION}",
+                self.version
+            ))
+        }
+    }
+}
+
+impl fmt::Display for Greeting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{NAME} {}", self.version)
+    }
+}
+
+impl FromStr for Greeting {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.trim()
+            .strip_prefix(NAME)
+            .and_then(|version| version.strip_prefix(' '))
+            .and_then(|version| version.parse().ok())
+            .map(|version| Self { version })
+            .ok_or_else(|| format!("expected a greeting like \"{}\", got {s}", Self::CURRENT))
+    }
+}
+
+/// Splits the fields of a message, e.g. "chips=100 min=10", into their keys and values.
+/// A field with no "=" has an empty value.
+fn fields(s: &str) -> Vec<(&str, &str)> {
+    s.split_whitespace()
+        .map(|field| field.split_once('=').unwrap_or((field, "")))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_greeting() {
+        assert_eq!("blackjack 2".parse(), Ok(Greeting { version: 2 }));
+        assert_eq!(
+            Greeting::CURRENT.to_string(),
+            format!("blackjack {VERSION}")
+        );
+        assert!(Greeting::CURRENT.check().is_ok());
+        assert!(Greeting { version: 1 }.check().is_err());
+        assert!("blackjack".parse::<Greeting>().is_err());
+        assert!("poker 2".parse::<Greeting>().is_err());
+    }
+}
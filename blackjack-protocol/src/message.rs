@@ -0,0 +1,81 @@
+//! Messages: the lines the table sends to the player.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::event::Event;
+use crate::prompt::Prompt;
+use crate::snapshot::Snapshot;
+use crate::{Greeting, NAME};
+
+/// A line the table sends to the player.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+    /// The table's greeting, sent before anything else
+    Greeting(Greeting),
+    /// Narration of the game, for the player to read
+    Say(String),
+    /// Everything on the table, sent whenever it changes
+    Snapshot(Snapshot),
+    /// Something that happened at the table
+    Event(Event),
+    /// A decision the table is waiting for
+    Prompt(Prompt),
+    /// Why the player's last answer was rejected; the prompt is asked again
+    Error(String),
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Greeting(greeting) => write!(f, "{greeting}"),
+            Self::Say(line) => write!(f, "say {line}"),
+            Self::Snapshot(snapshot) => write!(f, "{snapshot}"),
+            Self::Event(event) => write!(f, "{event}"),
+            Self::Prompt(prompt) => write!(f, "{prompt}"),
+            Self::Error(error) => write!(f, "error {error}"),
+        }
+    }
+}
+
+impl FromStr for Message {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim_end_matches(['\r', '\n']);
+        let (kind, rest) = s.split_once(' ').unwrap_or((s, ""));
+        match kind {
+            NAME => s.parse().map(Self::Greeting),
+            "say" => Ok(Self::Say(rest.to_string())),
+            "snapshot" => s.parse().map(Self::Snapshot),
+            "event" => s.parse().map(Self::Event),
+            "error" => Ok(Self::Error(rest.to_string())),
+            _ => s.parse().map(Self::Prompt),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_messages() {
+        let lines = [
+            "blackjack 2",
+            "say You win!",
+            "snapshot dealer=9,? seat=player:900 hand=player:100:in-play:hard-15:10,5 turn=player:0",
+            "event paid seat=bot.0 net=10",
+            "surrender hand=10,6 dealer=A",
+            "error \"x\" isn't an answer",
+        ];
+        for line in lines {
+            assert_eq!(line.parse::<Message>().unwrap().to_string(), line);
+        }
+        assert!(matches!(
+            "turn hand=A,7 value=soft-18 dealer=9 allowed=hit".parse(),
+            Ok(Message::Prompt(Prompt::Turn { .. }))
+        ));
+        assert!("shuffle now".parse::<Message>().is_err());
+    }
+}
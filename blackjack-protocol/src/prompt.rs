@@ -0,0 +1,280 @@
+//! Prompts: the decisions the table asks the player for.
+
+use std::fmt;
+use std::str::FromStr;
+
+use blackjack_core::card::hand::{PlayerHand, Value};
+use blackjack_core::card::Rank;
+use blackjack_core::counting;
+use blackjack_core::game::{HandAction, Table};
+use blackjack_core::state::GameState;
+
+use crate::answer::{action, action_name};
+
+/// A decision the table asks the player for.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Prompt {
+    /// The player bets on the next round
+    Bet {
+        chips: u32,
+        min: u32,
+        max: Option<u32>,
+        /// The bet on the last round, which may be repeated with "same"
+        last: Option<u32>,
+        running: i32,
+        true_count: f32,
+    },
+    /// The player may surrender their hand before the dealer checks for blackjack
+    Surrender { hand: Vec<Rank>, dealer: Rank },
+    /// The player may insure their hand against the dealer's blackjack
+    Insurance {
+        hand: Vec<Rank>,
+        dealer: Rank,
+        max: u32,
+    },
+    /// The player plays their current hand
+    Turn {
+        hand: Vec<Rank>,
+        value: Value,
+        dealer: Rank,
+        allowed: Vec<HandAction>,
+    },
+    /// A question answered in words, such as the player's name
+    Text(String),
+}
+
+impl Prompt {
+    /// Returns the prompt for the decision the table needs in the given state,
+    /// or None if the state progresses without one.
+    #[must_use]
+    pub fn new(table: &Table, state: &GameState) -> Option<Self> {
+        let ranks = |hand: &PlayerHand| hand.cards.iter().map(|card| card.rank.clone()).collect();
+        Some(match state {
+            GameState::Betting { last_bet } => Self::Bet {
+                chips: table.chips,
+                min: table.rules.min_bet.unwrap_or(1),
+                max: table.rules.max_bet,
+                last: *last_bet,
+                running: counting::running_count(&table.shoe),
+                true_count: counting::true_count(&table.shoe),
+            },
+            GameState::OfferEarlySurrender {
+                player_hand,
+                dealer_hand,
+            } => Self::Surrender {
+                hand: ranks(player_hand),
+                dealer: dealer_hand.cards()[0].rank.clone(),
+            },
+            GameState::OfferInsurance {
+                player_hand,
+                dealer_hand,
+            } => Self::Insurance {
+                hand: ranks(player_hand),
+                dealer: dealer_hand.cards()[0].rank.clone(),
+                max: table.max_insurance(player_hand),
+            },
+            GameState::PlayPlayerTurn {
+                player_turn,
+                dealer_hand,
+                ..
+            } => {
+                let hand = player_turn.current_hand();
+                Self::Turn {
+                    hand: ranks(hand),
+                    value: hand.value,
+                    dealer: dealer_hand.cards()[0].rank.clone(),
+                    allowed: table.allowed_actions(player_turn),
+                }
+            }
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for Prompt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let optional =
+            |value: Option<u32>| value.map_or("none".to_string(), |value| value.to_string());
+        match self {
+            Self::Bet {
+                chips,
+                min,
+                max,
+                last,
+                running,
+                true_count,
+            } => write!(
+                f,
+                "bet chips={chips} min={min} max={} last={} running={running} true={true_count:.1}",
+                optional(*max),
+                optional(*last)
+            ),
+            Self::Surrender { hand, dealer } => {
+                write!(
+                    f,
+                    "surrender hand={} dealer={}",
+                    cards(hand),
+                    dealer.symbol()
+                )
+            }
+            Self::Insurance { hand, dealer, max } => write!(
+                f,
+                "insurance hand={} dealer={} max={max}",
+                cards(hand),
+                dealer.symbol()
+            ),
+            Self::Turn {
+                hand,
+                value,
+                dealer,
+                allowed,
+            } => {
+                let allowed: Vec<_> = allowed.iter().map(|&action| action_name(action)).collect();
+                write!(
+                    f,
+                    "turn hand={} value={} dealer={} allowed={}",
+                    cards(hand),
+                    value_name(value),
+                    dealer.symbol(),
+                    allowed.join(",")
+                )
+            }
+            Self::Text(question) => write!(f, "ask {question}"),
+        }
+    }
+}
+
+impl FromStr for Prompt {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, fields) = s.trim().split_once(' ').unwrap_or((s.trim(), ""));
+        if kind == "ask" {
+            return Ok(Self::Text(fields.to_string()));
+        }
+        let fields = crate::fields(fields);
+        let field = |key: &str| {
+            fields
+                .iter()
+                .find(|(name, _)| *name == key)
+                .map(|&(_, value)| value)
+                .ok_or_else(|| format!("missing {key} in prompt: {s}"))
+        };
+        let number = |key: &str| {
+            field(key)?
+                .parse()
+                .map_err(|_| format!("invalid {key} in prompt: {s}"))
+        };
+        let optional = |key: &str| match field(key)? {
+            "none" => Ok(None),
+            value => value
+                .parse()
+                .map(Some)
+                .map_err(|_| format!("invalid {key} in prompt: {s}")),
+        };
+        let hand =
+            || -> Result<Vec<Rank>, String> { field("hand")?.split(',').map(str::parse).collect() };
+        let dealer = || field("dealer")?.parse::<Rank>();
+        match kind {
+            "bet" => Ok(Self::Bet {
+                chips: number("chips")?,
+                min: number("min")?,
+                max: optional("max")?,
+                last: optional("last")?,
+                running: field("running")?
+                    .parse()
+                    .map_err(|_| format!("invalid running in prompt: {s}"))?,
+                true_count: field("true")?
+                    .parse()
+                    .map_err(|_| format!("invalid true in prompt: {s}"))?,
+            }),
+            "surrender" => Ok(Self::Surrender {
+                hand: hand()?,
+                dealer: dealer()?,
+            }),
+            "insurance" => Ok(Self::Insurance {
+                hand: hand()?,
+                dealer: dealer()?,
+                max: number("max")?,
+            }),
+            "turn" => {
+                let value = parse_value(field("value")?)
+                    .ok_or_else(|| format!("invalid value in prompt: {s}"))?;
+                let allowed = field("allowed")?
+                    .split(',')
+                    .filter(|name| !name.is_empty())
+                    .map(|name| action(name).ok_or_else(|| format!("unknown action: {name}")))
+                    .collect::<Result<_, _>>()?;
+                Ok(Self::Turn {
+                    hand: hand()?,
+                    value,
+                    dealer: dealer()?,
+                    allowed,
+                })
+            }
+            _ => Err(format!("unknown prompt: {s}")),
+        }
+    }
+}
+
+/// Lists the ranks of a hand as written on the cards, e.g. "A,7".
+fn cards(hand: &[Rank]) -> String {
+    hand.iter().map(Rank::symbol).collect::<Vec<_>>().join(",")
+}
+
+/// Writes the value of a hand, e.g. "soft-18".
+pub(crate) fn value_name(value: &Value) -> String {
+    let kind = if value.soft { "soft" } else { "hard" };
+    format!("{kind}-{}", value.total)
+}
+
+/// Parses the value of a hand written by `value_name`.
+pub(crate) fn parse_value(s: &str) -> Option<Value> {
+    let (kind, total) = s.split_once('-')?;
+    Some(Value {
+        soft: kind == "soft",
+        total: total.parse().ok()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use blackjack_core::card::shoe::Shoe;
+    use blackjack_core::game::Input;
+    use blackjack_core::rules::Rules;
+
+    use super::*;
+
+    #[test]
+    fn test_prompts() {
+        let prompts = [
+            "bet chips=100000 min=100 max=none last=200 running=-3 true=-0.5",
+            "surrender hand=10,6 dealer=A",
+            "insurance hand=10,K dealer=A max=50",
+            "turn hand=A,7 value=soft-18 dealer=9 allowed=stand,hit,double",
+            "ask What's your name?",
+        ];
+        for prompt in prompts {
+            assert_eq!(prompt.parse::<Prompt>().unwrap().to_string(), prompt);
+        }
+        assert!("turn hand=A,7 value=18 dealer=9 allowed=hit"
+            .parse::<Prompt>()
+            .is_err());
+
+        let mut table = Table::new(1000, Shoe::new(6, 0.75), Rules::default());
+        let betting = table.betting();
+        let Some(Prompt::Bet { chips, min, .. }) = Prompt::new(&table, &betting) else {
+            panic!("expected a bet prompt");
+        };
+        assert_eq!((chips, min), (1000, 100));
+        table
+            .shoe
+            .force([Rank::Ten, Rank::Five, Rank::Seven, Rank::Nine]);
+        table.fast_forward = true;
+        let state = table.progress(betting, Some(Input::bet(100))).unwrap();
+        let Some(Prompt::Turn { value, .. }) = Prompt::new(&table, &state) else {
+            panic!("expected a turn prompt");
+        };
+        assert_eq!(value.total, 17);
+    }
+}
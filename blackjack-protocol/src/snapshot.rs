@@ -0,0 +1,499 @@
+//! Snapshots: what everyone at the table can see, sent whenever it changes,
+//! so a client can draw the whole table rather than only the prompts put to it.
+
+use std::fmt;
+use std::str::FromStr;
+
+use blackjack_core::card::hand::{DealerHand, PlayerHand, Status, Value};
+use blackjack_core::card::{Rank, Visibility};
+use blackjack_core::game::{Seat, Table};
+use blackjack_core::state::GameState;
+
+use crate::prompt::{parse_value, value_name};
+
+/// Whoever sits in a seat: the player, one of the backers betting behind them, or a bot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Occupant {
+    Player,
+    /// A backer, by their index at the table
+    Backer(u8),
+    /// A bot, by its index at the table
+    Bot(usize),
+}
+
+impl Occupant {
+    /// Returns the seat the occupant makes their inputs from, or None for bots, which make none.
+    #[must_use]
+    pub const fn seat(self) -> Option<Seat> {
+        match self {
+            Self::Player => Some(Seat::Player),
+            Self::Backer(backer) => Some(Seat::Backer(backer)),
+            Self::Bot(_) => None,
+        }
+    }
+}
+
+impl From<Seat> for Occupant {
+    fn from(seat: Seat) -> Self {
+        match seat {
+            Seat::Player => Self::Player,
+            Seat::Backer(backer) => Self::Backer(backer),
+        }
+    }
+}
+
+impl fmt::Display for Occupant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Player => write!(f, "player"),
+            Self::Backer(backer) => write!(f, "backer.{backer}"),
+            Self::Bot(bot) => write!(f, "bot.{bot}"),
+        }
+    }
+}
+
+impl FromStr for Occupant {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid seat: {s}");
+        match s.split_once('.') {
+            None if s == "player" => Ok(Self::Player),
+            Some(("backer", backer)) => backer.parse().map(Self::Backer).map_err(|_| invalid()),
+            Some(("bot", bot)) => bot.parse().map(Self::Bot).map_err(|_| invalid()),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// A hand on the table as everyone sees it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandSnapshot {
+    pub bet: u32,
+    pub status: Status,
+    pub value: Value,
+    pub cards: Vec<Rank>,
+    /// Whether the hand's opening cards lie face down
+    pub face_down: bool,
+}
+
+impl HandSnapshot {
+    fn new(hand: &PlayerHand) -> Self {
+        Self {
+            bet: hand.bet,
+            status: hand.status.clone(),
+            value: hand.value,
+            cards: hand.cards.iter().map(|card| card.rank.clone()).collect(),
+            face_down: hand.visibility == Visibility::FaceDown,
+        }
+    }
+}
+
+/// An occupied seat and the hands played from it in the current round.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeatSnapshot {
+    pub occupant: Occupant,
+    /// The chips of the player or a backer, or what a bot has won, less its bets,
+    /// since bots never run out of chips
+    pub chips: i64,
+    /// The seat's hands: the player's spots come first, then any hands split from them
+    pub hands: Vec<HandSnapshot>,
+}
+
+/// A bet placed by a backer behind one of the player's spots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackBetSnapshot {
+    pub backer: u8,
+    /// The spot backed, where 0 is the player's main hand
+    pub spot: usize,
+    pub bet: u32,
+}
+
+/// Everything on the table, as everyone at it can see it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Snapshot {
+    /// The dealer's cards, starting with the up card, with None for the hole card while it lies face down
+    pub dealer: Vec<Option<Rank>>,
+    /// The value of the dealer's hand, once the hole card is turned over
+    pub dealer_value: Option<Value>,
+    /// Every occupied seat: the player first, then the backers and the bots
+    pub seats: Vec<SeatSnapshot>,
+    pub back_bets: Vec<BackBetSnapshot>,
+    /// The seat and hand being played, if any
+    pub turn: Option<(Occupant, usize)>,
+}
+
+impl Snapshot {
+    /// Returns the snapshot of a table in the given state.
+    #[must_use]
+    pub fn new(table: &Table, state: &GameState) -> Self {
+        let (hands, dealer_hand, turn) = hands(state);
+        let revealed = matches!(
+            state,
+            GameState::RevealHoleCard { .. }
+                | GameState::PlayDealerTurn { .. }
+                | GameState::RoundOver { .. }
+                | GameState::Payout { .. }
+        );
+        let (dealer, dealer_value) = dealer_hand.map_or_else(Default::default, |dealer_hand| {
+            let cards = dealer_hand
+                .cards()
+                .iter()
+                .enumerate()
+                .map(|(i, card)| {
+                    (i == 0 || revealed || dealer_hand.flashed).then(|| card.rank.clone())
+                })
+                .collect();
+            (cards, revealed.then_some(dealer_hand.value))
+        });
+
+        let mut player_hands: Vec<_> = hands.iter().map(|hand| HandSnapshot::new(hand)).collect();
+        if turn.is_none() {
+            // Until the player's turn takes them up, the extra spots' hands wait on the table
+            player_hands.extend(table.spot_hands().iter().map(HandSnapshot::new));
+        }
+        let mut seats = vec![SeatSnapshot {
+            occupant: Occupant::Player,
+            chips: i64::from(table.chips),
+            hands: player_hands,
+        }];
+        seats.extend(
+            table
+                .backers
+                .iter()
+                .zip(0..=u8::MAX)
+                .map(|(backer, i)| SeatSnapshot {
+                    occupant: Occupant::Backer(i),
+                    chips: i64::from(backer.chips),
+                    hands: Vec::new(),
+                }),
+        );
+        seats.extend(table.bots.iter().enumerate().map(|(i, bot)| SeatSnapshot {
+            occupant: Occupant::Bot(i),
+            chips: bot.net,
+            hands: table.bot_hands(i).iter().map(HandSnapshot::new).collect(),
+        }));
+        let back_bets = table
+            .back_bets()
+            .iter()
+            .filter_map(|back_bet| {
+                Some(BackBetSnapshot {
+                    backer: u8::try_from(back_bet.backer).ok()?,
+                    spot: back_bet.spot,
+                    bet: back_bet.bet,
+                })
+            })
+            .collect();
+        Self {
+            dealer,
+            dealer_value,
+            seats,
+            back_bets,
+            turn: turn.map(|hand| (Occupant::Player, hand)),
+        }
+    }
+
+    /// Returns the seat of the given occupant, if they are at the table.
+    #[must_use]
+    pub fn seat(&self, occupant: Occupant) -> Option<&SeatSnapshot> {
+        self.seats.iter().find(|seat| seat.occupant == occupant)
+    }
+}
+
+/// Returns the player's hands in a state, the dealer's hand, and the index of the hand being played.
+fn hands(state: &GameState) -> (Vec<&PlayerHand>, Option<&DealerHand>, Option<usize>) {
+    match state {
+        GameState::Dealing {
+            player_hand,
+            dealer_hand,
+            ..
+        } => (player_hand.iter().collect(), dealer_hand.as_ref(), None),
+        GameState::OfferEarlySurrender {
+            player_hand,
+            dealer_hand,
+        }
+        | GameState::OfferInsurance {
+            player_hand,
+            dealer_hand,
+        }
+        | GameState::CheckDealerHoleCard {
+            player_hand,
+            dealer_hand,
+            ..
+        } => (vec![player_hand], Some(dealer_hand), None),
+        GameState::PlayPlayerTurn {
+            player_turn,
+            dealer_hand,
+            ..
+        }
+        | GameState::PlayerStand {
+            player_turn,
+            dealer_hand,
+            ..
+        }
+        | GameState::PlayerHit {
+            player_turn,
+            dealer_hand,
+            ..
+        }
+        | GameState::PlayerDouble {
+            player_turn,
+            dealer_hand,
+            ..
+        }
+        | GameState::PlayerSplit {
+            player_turn,
+            dealer_hand,
+            ..
+        }
+        | GameState::PlayerSurrender {
+            player_turn,
+            dealer_hand,
+            ..
+        } => (
+            player_turn.all_hands().iter().collect(),
+            Some(dealer_hand),
+            Some(player_turn.current_hand_index()),
+        ),
+        GameState::DealFirstSplitCard {
+            player_turn,
+            new_hand,
+            dealer_hand,
+            ..
+        }
+        | GameState::DealSecondSplitCard {
+            player_turn,
+            new_hand,
+            dealer_hand,
+            ..
+        } => (
+            player_turn.all_hands().iter().chain([new_hand]).collect(),
+            Some(dealer_hand),
+            Some(player_turn.current_hand_index()),
+        ),
+        GameState::RevealHoleCard {
+            finished_hands,
+            dealer_hand,
+            ..
+        }
+        | GameState::PlayDealerTurn {
+            finished_hands,
+            dealer_hand,
+            ..
+        }
+        | GameState::RoundOver {
+            finished_hands,
+            dealer_hand,
+            ..
+        } => (finished_hands.iter().collect(), Some(dealer_hand), None),
+        GameState::Payout { round } => {
+            (round.hands.iter().collect(), Some(&round.dealer_hand), None)
+        }
+        GameState::Betting { .. } | GameState::Shuffle | GameState::GameOver => {
+            (Vec::new(), None, None)
+        }
+    }
+}
+
+/// Writes the status of a hand, e.g. "in-play".
+const fn status_name(status: &Status) -> &'static str {
+    match status {
+        Status::InPlay => "in-play",
+        Status::Stood => "stood",
+        Status::Bust => "bust",
+        Status::Blackjack => "blackjack",
+        Status::Surrendered => "surrendered",
+    }
+}
+
+/// Parses the status of a hand written by `status_name`.
+fn parse_status(s: &str) -> Option<Status> {
+    [
+        Status::InPlay,
+        Status::Stood,
+        Status::Bust,
+        Status::Blackjack,
+        Status::Surrendered,
+    ]
+    .into_iter()
+    .find(|status| status_name(status) == s)
+}
+
+impl fmt::Display for Snapshot {
+    /// Writes the snapshot on one line, e.g.
+    /// "snapshot dealer=9,? seat=player:900 seat=bot.0:-10 hand=player:100:in-play:soft-18:A,7 turn=player:0".
+    /// Hands are listed in order under the seat they were played from, and face-down hands end in ":down".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ranks = |ranks: &mut dyn Iterator<Item = &str>| ranks.collect::<Vec<_>>().join(",");
+        write!(f, "snapshot")?;
+        if !self.dealer.is_empty() {
+            let mut cards = self
+                .dealer
+                .iter()
+                .map(|rank| rank.as_ref().map_or("?", Rank::symbol));
+            write!(f, " dealer={}", ranks(&mut cards))?;
+            if let Some(value) = &self.dealer_value {
+                write!(f, ":{}", value_name(value))?;
+            }
+        }
+        for seat in &self.seats {
+            write!(f, " seat={}:{}", seat.occupant, seat.chips)?;
+        }
+        for seat in &self.seats {
+            for hand in &seat.hands {
+                write!(
+                    f,
+                    " hand={}:{}:{}:{}:{}",
+                    seat.occupant,
+                    hand.bet,
+                    status_name(&hand.status),
+                    value_name(&hand.value),
+                    ranks(&mut hand.cards.iter().map(Rank::symbol))
+                )?;
+                if hand.face_down {
+                    write!(f, ":down")?;
+                }
+            }
+        }
+        for back_bet in &self.back_bets {
+            write!(
+                f,
+                " back={}:{}:{}",
+                Occupant::Backer(back_bet.backer),
+                back_bet.spot,
+                back_bet.bet
+            )?;
+        }
+        if let Some((occupant, hand)) = self.turn {
+            write!(f, " turn={occupant}:{hand}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Snapshot {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |key: &str| format!("invalid {key} in snapshot: {s}");
+        let fields = s
+            .trim()
+            .strip_prefix("snapshot")
+            .ok_or_else(|| format!("expected a snapshot, got {s}"))?;
+        let mut snapshot = Self::default();
+        for (key, value) in crate::fields(fields) {
+            let parts: Vec<&str> = value.split(':').collect();
+            match (key, &parts[..]) {
+                ("dealer", [cards, rest @ ..]) => {
+                    snapshot.dealer = cards
+                        .split(',')
+                        .map(|rank| match rank {
+                            "?" => Ok(None),
+                            rank => rank.parse().map(Some),
+                        })
+                        .collect::<Result<_, _>>()?;
+                    snapshot.dealer_value = match rest {
+                        [] => None,
+                        [value] => Some(parse_value(value).ok_or_else(|| invalid(key))?),
+                        _ => return Err(invalid(key)),
+                    };
+                }
+                ("seat", [occupant, chips]) => snapshot.seats.push(SeatSnapshot {
+                    occupant: occupant.parse()?,
+                    chips: chips.parse().map_err(|_| invalid(key))?,
+                    hands: Vec::new(),
+                }),
+                ("hand", [occupant, bet, status, value, cards, rest @ ..]) => {
+                    let occupant: Occupant = occupant.parse()?;
+                    let hand = HandSnapshot {
+                        bet: bet.parse().map_err(|_| invalid(key))?,
+                        status: parse_status(status).ok_or_else(|| invalid(key))?,
+                        value: parse_value(value).ok_or_else(|| invalid(key))?,
+                        cards: cards.split(',').map(str::parse).collect::<Result<_, _>>()?,
+                        face_down: match rest {
+                            [] => false,
+                            ["down"] => true,
+                            _ => return Err(invalid(key)),
+                        },
+                    };
+                    snapshot
+                        .seats
+                        .iter_mut()
+                        .find(|seat| seat.occupant == occupant)
+                        .ok_or_else(|| format!("hand for a seat not at the table: {occupant}"))?
+                        .hands
+                        .push(hand);
+                }
+                ("back", [backer, spot, bet]) => {
+                    let Ok(Occupant::Backer(backer)) = backer.parse() else {
+                        return Err(invalid(key));
+                    };
+                    snapshot.back_bets.push(BackBetSnapshot {
+                        backer,
+                        spot: spot.parse().map_err(|_| invalid(key))?,
+                        bet: bet.parse().map_err(|_| invalid(key))?,
+                    });
+                }
+                ("turn", [occupant, hand]) => {
+                    snapshot.turn =
+                        Some((occupant.parse()?, hand.parse().map_err(|_| invalid(key))?));
+                }
+                _ => return Err(invalid(key)),
+            }
+        }
+        Ok(snapshot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use blackjack_core::card::shoe::Shoe;
+    use blackjack_core::game::{BotStrategy, Input};
+    use blackjack_core::rules::Rules;
+
+    use super::*;
+
+    #[test]
+    fn test_snapshots() {
+        let snapshots = [
+            "snapshot seat=player:1000",
+            "snapshot dealer=9,? seat=player:900 seat=backer.0:450 seat=bot.0:-10 \
+             hand=player:100:in-play:soft-18:A,7 hand=bot.0:10:stood:hard-20:10,K \
+             back=backer.0:0:50 turn=player:0",
+            "snapshot dealer=9,7:hard-16 seat=player:900 hand=player:100:stood:hard-15:10,5:down",
+        ];
+        for snapshot in snapshots {
+            assert_eq!(snapshot.parse::<Snapshot>().unwrap().to_string(), snapshot);
+        }
+        assert!("snapshot hand=player:100:in-play:soft-18:A,7"
+            .parse::<Snapshot>()
+            .is_err());
+        assert!("snapshot seat=dealer:0".parse::<Snapshot>().is_err());
+
+        let mut table = Table::new(1000, Shoe::new(6, 0.75), Rules::default());
+        table.add_bot(BotStrategy::Mimic).unwrap();
+        let betting = table.betting();
+        let snapshot = Snapshot::new(&table, &betting);
+        assert_eq!(snapshot.seats.len(), 2);
+        assert!(snapshot.dealer.is_empty());
+
+        table.shoe.force([
+            Rank::Ten,
+            Rank::Ten,
+            Rank::Five,
+            Rank::Seven,
+            Rank::Seven,
+            Rank::Nine,
+        ]);
+        table.fast_forward = true;
+        let state = table.progress(betting, Some(Input::bet(100))).unwrap();
+        assert!(matches!(state, GameState::PlayPlayerTurn { .. }));
+        let snapshot = Snapshot::new(&table, &state);
+        assert_eq!(snapshot.dealer.len(), 2);
+        assert_eq!(snapshot.dealer[1], None);
+        assert_eq!(snapshot.dealer_value, None);
+        assert_eq!(snapshot.turn, Some((Occupant::Player, 0)));
+        assert_eq!(snapshot.seat(Occupant::Player).unwrap().hands.len(), 1);
+        assert_eq!(snapshot.to_string().parse(), Ok(snapshot));
+    }
+}